@@ -0,0 +1,274 @@
+use std::{
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use bigdecimal::{BigDecimal, Zero};
+use regex::Regex;
+use slotted_pig_lib::{
+    categorizer::{Categorizer, TransactionMatcher},
+    edit::add_transaction_matcher,
+    report::{UncategorizedPayee, UncategorizedPayees},
+    transaction::Transaction,
+    util::payee_regex,
+};
+
+use crate::Args;
+
+/// Number of uncategorized payees offered to start a matcher from
+const PAYEES: usize = 10;
+/// Number of example transactions shown for each kind of change
+const EXAMPLES: usize = 5;
+
+/// Prompt for a transaction matcher, show how it changes the categorization of the transactions,
+/// and append it to the categorizer config once confirmed
+///
+/// Prompts are written to stderr and answers are read from stdin. An encrypted categorizer config
+/// stays encrypted.
+pub fn run(args: &Args, transactions: &[Transaction]) -> Result<()> {
+    let categorizer_path = &args.categorizer_path;
+    let yaml = args
+        .read_config_yaml(categorizer_path)
+        .with_context(|| format!("failed to read categorizer {}", categorizer_path.display()))?;
+    let categorizer =
+        Categorizer::from_yaml_buffer(&yaml).context("failed to parse categorizer")?;
+    let mut prompter = Prompter {
+        input: io::stdin().lock(),
+        output: io::stderr(),
+    };
+
+    let uncategorized = transactions.iter().filter(|t| {
+        categorizer
+            .assign_transaction(t)
+            .is_some_and(|a| !a.is_categorized())
+    });
+    let payees = UncategorizedPayees::new(uncategorized).payees;
+    let payee = prompter.payee(&payees)?;
+
+    let categories = categorizer
+        .leaf_category_paths()
+        .into_iter()
+        .map(|path| path.join(":"))
+        .collect::<Vec<_>>();
+    if categories.is_empty() {
+        bail!("the categorizer has no leaf categories to add a transaction matcher to");
+    }
+    let category = prompter.category(&categories)?;
+    let matcher = prompter.matcher(payee)?;
+
+    let path = category.split(':').collect::<Vec<_>>();
+    let edited = add_transaction_matcher(&yaml, &path, &matcher)
+        .context("failed to add the transaction matcher")?;
+    let edited_categorizer =
+        Categorizer::from_yaml_buffer(&edited).context("failed to parse edited categorizer")?;
+    prompter.dry_run(&categorizer, &edited_categorizer, &matcher, transactions)?;
+
+    let confirm = prompter.ask(
+        &format!(
+            "Append `{matcher}` to {category} in {}? [y/N]",
+            categorizer_path.display()
+        ),
+        None,
+    )?;
+    if !matches!(confirm.to_lowercase().as_str(), "y" | "yes") {
+        writeln!(prompter.output, "nothing was written")?;
+        return Ok(());
+    }
+    args.write_config_yaml(categorizer_path, &edited)
+        .with_context(|| format!("failed to write categorizer {}", categorizer_path.display()))?;
+    println!("added `{matcher}` to {category}");
+    Ok(())
+}
+
+struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Ask a question returning the trimmed answer, or the default if the answer is empty
+    fn ask(&mut self, question: &str, default: Option<&str>) -> Result<String> {
+        match default {
+            Some(default) if !default.is_empty() => {
+                write!(self.output, "{question} [{default}]: ")?
+            }
+            _ => write!(self.output, "{question}: ")?,
+        }
+        self.output.flush()?;
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            bail!("input ended before the transaction matcher was complete");
+        }
+        let answer = answer.trim();
+        Ok(match default {
+            Some(default) if answer.is_empty() => default.to_string(),
+            _ => answer.to_string(),
+        })
+    }
+
+    /// Offer the uncategorized payees with the most transactions to start the matcher from
+    fn payee<'a>(
+        &mut self,
+        payees: &'a [UncategorizedPayee],
+    ) -> Result<Option<&'a UncategorizedPayee>> {
+        if payees.is_empty() {
+            writeln!(self.output, "every transaction is categorized")?;
+            return Ok(None);
+        }
+        writeln!(self.output, "Uncategorized payees")?;
+        for (i, payee) in payees.iter().take(PAYEES).enumerate() {
+            writeln!(
+                self.output,
+                "  {:>2}. {}  ({} transactions, total {})",
+                i + 1,
+                payee.payee,
+                payee.count,
+                payee.total
+            )?;
+        }
+        loop {
+            let answer = self.ask("Payee to start from (number, empty for none)", None)?;
+            if answer.is_empty() {
+                return Ok(None);
+            }
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=payees.len().min(PAYEES)).contains(&n) => {
+                    return Ok(Some(&payees[n - 1]))
+                }
+                _ => writeln!(self.output, "`{answer}` is not a listed payee")?,
+            }
+        }
+    }
+
+    /// Pick a leaf category by its number or colon separated path
+    fn category(&mut self, categories: &[String]) -> Result<String> {
+        writeln!(self.output, "Categories")?;
+        for (i, category) in categories.iter().enumerate() {
+            writeln!(self.output, "  {:>2}. {category}", i + 1)?;
+        }
+        loop {
+            let answer = self.ask("Category (number or colon separated path)", None)?;
+            let category = match answer.parse::<usize>() {
+                Ok(n) => n.checked_sub(1).and_then(|i| categories.get(i)),
+                Err(_) => categories.iter().find(|c| **c == answer),
+            };
+            match category {
+                Some(category) => return Ok(category.clone()),
+                None => writeln!(self.output, "`{answer}` is not a leaf category")?,
+            }
+        }
+    }
+
+    /// Prompt for the description pattern and amount bounds, defaulting to the payee's pattern
+    /// and the sign of its transactions
+    fn matcher(&mut self, payee: Option<&UncategorizedPayee>) -> Result<TransactionMatcher> {
+        let pattern = payee.map(|p| payee_regex(&p.payee));
+        let description = loop {
+            let answer = self.ask(
+                "Description regex (- to match any description)",
+                pattern.as_deref(),
+            )?;
+            if answer.is_empty() || answer == "-" {
+                break Vec::new();
+            }
+            match Regex::new(&answer) {
+                Ok(regex) => break vec![regex],
+                Err(e) => writeln!(self.output, "invalid regex: {e}")?,
+            }
+        };
+        let zero = BigDecimal::zero();
+        let min = payee
+            .filter(|p| p.min_amount >= zero)
+            .map(|_| String::from("0"));
+        let max = payee
+            .filter(|p| p.max_amount <= zero)
+            .map(|_| String::from("0"));
+        let min = self.amount("Minimum amount (- for none)", min.as_deref())?;
+        let max = self.amount("Maximum amount (- for none)", max.as_deref())?;
+        Ok(TransactionMatcher {
+            min,
+            max,
+            account: None,
+            description,
+            begin: None,
+            end: None,
+        })
+    }
+
+    fn amount(&mut self, question: &str, default: Option<&str>) -> Result<Option<BigDecimal>> {
+        loop {
+            let answer = self.ask(question, default)?;
+            if answer.is_empty() || answer == "-" {
+                return Ok(None);
+            }
+            match BigDecimal::from_str(&answer) {
+                Ok(amount) => return Ok(Some(amount)),
+                Err(_) => writeln!(self.output, "`{answer}` is not an amount")?,
+            }
+        }
+    }
+
+    /// Show the transactions the matcher categorizes and those it moves from another category
+    fn dry_run(
+        &mut self,
+        categorizer: &Categorizer,
+        edited: &Categorizer,
+        matcher: &TransactionMatcher,
+        transactions: &[Transaction],
+    ) -> Result<()> {
+        let mut matches = 0;
+        let mut categorized = Vec::new();
+        let mut moved = Vec::new();
+        for transaction in transactions.iter().filter(|t| categorizer.filter(t)) {
+            if matcher.matches(transaction) {
+                matches += 1;
+            }
+            let before = categorizer
+                .assign_transaction(transaction)
+                .and_then(|a| a.category_path_joined(":"));
+            let after = edited
+                .assign_transaction(transaction)
+                .and_then(|a| a.category_path_joined(":"));
+            match (before, after) {
+                (None, Some(after)) => categorized.push((transaction, after)),
+                (Some(before), Some(after)) if before != after => {
+                    moved.push((transaction, format!("{before} -> {after}")))
+                }
+                _ => {}
+            }
+        }
+        writeln!(
+            self.output,
+            "The matcher matches {matches} transactions, categorizing {} uncategorized \
+             transactions and moving {} from another category",
+            categorized.len(),
+            moved.len()
+        )?;
+        for (title, changes) in [("Categorized", &categorized), ("Moved", &moved)] {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(self.output, "{title}")?;
+            for (transaction, change) in changes.iter().take(EXAMPLES) {
+                writeln!(
+                    self.output,
+                    "  {}  {}  {}  {change}",
+                    transaction.time.format("%Y-%m-%d"),
+                    transaction.description,
+                    transaction.amount
+                )?;
+            }
+            if changes.len() > EXAMPLES {
+                writeln!(self.output, "  and {} more", changes.len() - EXAMPLES)?;
+            }
+        }
+        if categorized.is_empty() && moved.is_empty() {
+            writeln!(
+                self.output,
+                "warning: the matcher does not change the category of any transaction"
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use std::{collections::BTreeMap, env, fs, io, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Command;
+use serde_yaml::Value;
+
+/// Path of the user defaults file, `$XDG_CONFIG_HOME/slotted-pig/config.yaml` falling back to
+/// `~/.config/slotted-pig/config.yaml`
+pub fn path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("slotted-pig").join("config.yaml"))
+}
+
+/// Replace the default values of the command's arguments with the values in the defaults file
+///
+/// The file maps argument names (ie `categorizer_path`) to values. Only the arguments which can
+/// also be set by a `SLOTTED_PIG_*` environment variable can be set, so the precedence is command
+/// line, then environment variable, then defaults file.
+pub fn apply(mut command: Command) -> Result<Command> {
+    let Some(path) = path() else {
+        return Ok(command);
+    };
+    let yaml = match fs::read_to_string(&path) {
+        Ok(yaml) => yaml,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(command),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let defaults = serde_yaml::from_str::<Option<BTreeMap<String, Value>>>(&yaml)
+        .with_context(|| format!("failed to parse defaults {}", path.display()))?
+        .unwrap_or_default();
+    for (name, value) in defaults {
+        if !command
+            .get_arguments()
+            .any(|a| a.get_id() == name.as_str() && a.get_env().is_some())
+        {
+            bail!("unknown argument `{name}` in defaults {}", path.display());
+        }
+        let value = match value {
+            Value::String(value) => value,
+            Value::Bool(value) => value.to_string(),
+            Value::Number(value) => value.to_string(),
+            _ => bail!(
+                "argument `{name}` in defaults {} must be a string, number, or bool",
+                path.display()
+            ),
+        };
+        command = command.mut_arg(name, |a| a.default_value(value));
+    }
+    Ok(command)
+}
@@ -0,0 +1,116 @@
+use std::{fmt, process::ExitCode};
+
+use clap::ValueEnum;
+use miette::GraphicalReportHandler;
+use serde::Serialize;
+use slotted_pig_lib::diagnostic::YamlDiagnostic;
+
+/// Class of a failure, each class exits with its own stable code so scripts can react to it
+///
+/// Exit code 2 is used when the arguments are invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Any other failure, exit code 1
+    Other,
+    /// A config could not be read or parsed, exit code 3
+    Config,
+    /// A transaction file could not be read or parsed, exit code 4
+    TransactionParse,
+    /// `validate` found problems in the configs, `test` had failing matcher tests, or
+    /// `rules --fail-on-findings` found problems with the rules, exit code 5
+    Validation,
+    /// `budget --fail-over-budget` found a category over its budget, exit code 6
+    OverBudget,
+}
+
+impl ErrorClass {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Other => 1,
+            Self::Config => 3,
+            Self::TransactionParse => 4,
+            Self::Validation => 5,
+            Self::OverBudget => 6,
+        }
+    }
+
+    /// Error message marked with this class, used as the context of an error
+    pub fn message(self, message: impl fmt::Display) -> Classified {
+        Classified {
+            class: self,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Error message marked with the class of the error
+#[derive(Debug)]
+pub struct Classified {
+    class: ErrorClass,
+    message: String,
+}
+
+impl fmt::Display for Classified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Classified {}
+
+/// How errors are printed
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human readable message and causes
+    #[default]
+    Text,
+    /// A JSON object with the `class`, `exit_code`, `message`, and `causes` of the error
+    Json,
+}
+
+#[derive(Serialize)]
+struct JsonError {
+    class: ErrorClass,
+    exit_code: u8,
+    message: String,
+    causes: Vec<String>,
+}
+
+/// Print the error to stderr in the format and return the exit code of its class
+///
+/// The class of the outermost classified context is used, errors without one are `Other`. Yaml
+/// config errors are rendered with the lines around the problem.
+pub fn report(error: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let class = error
+        .downcast_ref::<Classified>()
+        .map_or(ErrorClass::Other, |classified| classified.class);
+    match format {
+        ErrorFormat::Text => match error
+            .chain()
+            .find_map(|e| e.downcast_ref::<YamlDiagnostic>())
+        {
+            Some(diagnostic) => {
+                let mut rendered = String::new();
+                match GraphicalReportHandler::new().render_report(&mut rendered, diagnostic) {
+                    Ok(()) => eprintln!("Error: {error}\n\n{rendered}"),
+                    Err(_) => eprintln!("Error: {error:?}"),
+                }
+            }
+            None => eprintln!("Error: {error:?}"),
+        },
+        ErrorFormat::Json => {
+            let json = JsonError {
+                class,
+                exit_code: class.exit_code(),
+                message: error.to_string(),
+                causes: error.chain().skip(1).map(ToString::to_string).collect(),
+            };
+            match serde_json::to_string(&json) {
+                Ok(json) => eprintln!("{json}"),
+                Err(_) => eprintln!("Error: {error:?}"),
+            }
+        }
+    }
+    ExitCode::from(class.exit_code())
+}
@@ -1,48 +1,553 @@
-use std::{io, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::{self, BufWriter, IsTerminal},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, SystemTime},
+};
 
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use csv::Writer;
-use sloggers::{
-    terminal::TerminalLoggerBuilder,
-    types::{Severity, SourceLocation},
-    Build,
+use anyhow::{bail, Context, Result};
+use bigdecimal::{BigDecimal, Signed};
+use chrono::{DateTime, Local, Months, NaiveDate, SecondsFormat, Utc};
+use clap::{
+    error::ErrorKind, parser::ValueSource, CommandFactory, FromArgMatches, Parser, Subcommand,
+    ValueEnum,
 };
+use csv::Writer;
+use serde::Serialize;
+#[cfg(feature = "email")]
+use slotted_pig_lib::email::EmailConfig;
+#[cfg(feature = "encryption")]
+use slotted_pig_lib::encryption::{self, Passphrase};
+#[cfg(feature = "parquet")]
+use slotted_pig_lib::export::ParquetExporter;
+#[cfg(feature = "sqlite")]
+use slotted_pig_lib::store::Store;
 use slotted_pig_lib::{
-    categorizer::{Categorizer, CategorySort, TransactionSort},
+    categorizer::{CategorizedList, Categorizer, CategorySort, MatcherTest, TransactionSort},
+    config::{Config, ConfigKind},
+    export::{
+        BeancountExporter, GnucashExporter, GnucashFormat, IcalExporter, JsonLinesWriter,
+        LedgerExporter, OfxExporter, QifExporter, SqlExporter,
+    },
+    generate::{write_csv, Generator, CATEGORIZER_YAML, TRANSACTION_PARSER_YAML},
+    query::Query,
+    redact::Redactor,
+    report::{
+        dot, Balances, BalancesFormat, BarChart, Bucket, BurnStatus, Burndown, BurndownFormat,
+        Changes, ChangesFormat, DateRange, Digest, DigestPeriod, FiscalYear, Forecast,
+        ForecastFormat, HtmlReport, NetWorth, NetWorthFormat, Period, Pivot, PivotFormat,
+        RecurringDetector, RecurringFormat, RecurringList, RecurringSort, Rules, RulesFormat,
+        Sankey, SankeyFormat, StartingBalances, Statement, StatementFormat, Stats, StatsFormat,
+        TaxYears, TaxYearsFormat, Top, TopFormat, Totals, TotalsFormat, TransactionTable,
+        TransactionTableFormat, UncategorizedFormat, UncategorizedPayees,
+    },
+    source::{CommandSource, TransactionSource, TransactionSources},
     transaction::{Transaction, TransactionParser},
+    util::{Formatter, NegativeStyle},
+    validate::{validate_categorizer, validate_config, validate_transaction_parser, Issue},
 };
 
+mod add_rule;
+mod defaults;
+mod error;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(test)]
+mod tests;
+#[cfg(feature = "tui")]
+mod tui;
+
+use error::{ErrorClass, ErrorFormat};
+
 /// The simple finance tracker
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 struct Args {
+    /// Path to a combined config file with the transaction parser, categorizer, transaction path
+    /// pattern, and default sorts (ie `slotted-pig.yaml`) instead of the separate config files
+    ///
+    /// The separate config files are used instead when their paths are given with a higher
+    /// precedence (ie on the command line while the combined config is in the defaults file).
+    #[arg(long, env = "SLOTTED_PIG_CONFIG")]
+    config: Option<PathBuf>,
     /// Path to config file for reading transactions from files
-    #[arg(long)]
+    #[arg(
+        long,
+        env = "SLOTTED_PIG_TRANSACTION_PARSER_PATH",
+        default_value = "transaction_parser.yaml"
+    )]
     transaction_parser_path: PathBuf,
-    /// File glob pattern of transaction files to parse
-    #[arg(long)]
-    transaction_path_pattern: String,
+    /// File glob pattern of transaction files to parse, required by every command but `validate`
+    #[arg(long, env = "SLOTTED_PIG_TRANSACTION_PATH_PATTERN")]
+    transaction_path_pattern: Option<String>,
     /// Path to config file categorizing transactions
-    #[arg(long)]
+    #[arg(
+        long,
+        env = "SLOTTED_PIG_CATEGORIZER_PATH",
+        default_value = "categorizer.yaml"
+    )]
     categorizer_path: PathBuf,
+    /// Path to a SQLite store of imported transactions to read instead of the transaction files
+    ///
+    /// A store with an `.age` extension is encrypted at rest. It is not a SQLite file, the
+    /// transactions are kept as encrypted JSON, loaded into an in-memory SQLite database, and the
+    /// whole file is rewritten on every import.
+    #[cfg(feature = "sqlite")]
+    #[arg(long, env = "SLOTTED_PIG_STORE")]
+    store: Option<PathBuf>,
+    /// Passphrase of the store and config files encrypted at rest (ending in `.age`), prefer the
+    /// environment variable so it is not saved in the shell history
+    #[cfg(feature = "encryption")]
+    #[arg(long, env = "SLOTTED_PIG_PASSPHRASE", hide_env_values = true)]
+    passphrase: Option<String>,
+    /// Shell command writing transactions to stdout as csv (amount, account, description, and
+    /// time columns), read in addition to the store or transaction files, can be repeated
+    #[arg(long = "source-command")]
+    source_commands: Vec<String>,
     /// Log level
-    #[arg(long)]
-    log_level: Option<Severity>,
+    #[arg(long, env = "SLOTTED_PIG_LOG_LEVEL")]
+    log_level: Option<LogLevel>,
+    /// Format of the error printed to stderr when a command fails
+    #[arg(long, env = "SLOTTED_PIG_ERROR_FORMAT", default_value = "text")]
+    error_format: ErrorFormat,
+    // Amount formatting
+    #[command(flatten)]
+    format: FormatArgs,
+    // Transaction filters applied before any command, accepted before or after the subcommand
+    #[command(flatten)]
+    filter: FilterArgs,
+    // Redaction of personal details
+    #[command(flatten)]
+    redact: RedactArgs,
     // Subcommands
     #[command(subcommand)]
     command: Command,
 }
 
+impl Args {
+    /// Combined config, read from `--config` or the separate config files
+    ///
+    /// A relative transaction path pattern in a combined config is relative to the config file so
+    /// it works from any directory. `--transaction-path-pattern` overrides the pattern.
+    fn config(&self) -> Result<Config> {
+        let mut config = match &self.config {
+            Some(path) => {
+                let mut config = self
+                    .read_config(path, Config::from_yaml_file, Config::from_yaml_buffer)
+                    .with_context(|| {
+                        ErrorClass::Config
+                            .message(format!("failed to parse config {}", path.display()))
+                    })?;
+                if let (Some(pattern), Some(directory)) =
+                    (&config.transaction_path_pattern, path.parent())
+                {
+                    config.transaction_path_pattern =
+                        Some(directory.join(pattern).to_string_lossy().into_owned());
+                }
+                config
+            }
+            None => Config {
+                transaction_parser: self
+                    .read_config(
+                        &self.transaction_parser_path,
+                        TransactionParser::from_yaml_file,
+                        TransactionParser::from_yaml_buffer,
+                    )
+                    .context(ErrorClass::Config.message("failed to parse transaction parser"))?,
+                categorizer: self.categorizer()?,
+                transaction_path_pattern: None,
+                category_sort: None,
+                transaction_sort: None,
+            },
+        };
+        if let Some(pattern) = &self.transaction_path_pattern {
+            config.transaction_path_pattern = Some(pattern.clone());
+        }
+        Ok(config)
+    }
+
+    /// Transaction parser, without reading the categorizer unless it is in the combined config,
+    /// and the files to parse, defaulting to the files matching the transaction path pattern
+    fn transaction_parser_and_files(
+        &self,
+        files: &[PathBuf],
+    ) -> Result<(TransactionParser, Vec<PathBuf>)> {
+        let (transaction_parser, transaction_path_pattern) = match &self.config {
+            Some(_) => {
+                let config = self.config()?;
+                (config.transaction_parser, config.transaction_path_pattern)
+            }
+            None => (
+                self.read_config(
+                    &self.transaction_parser_path,
+                    TransactionParser::from_yaml_file,
+                    TransactionParser::from_yaml_buffer,
+                )
+                .context(ErrorClass::Config.message("failed to parse transaction parser"))?,
+                self.transaction_path_pattern.clone(),
+            ),
+        };
+        let files = if files.is_empty() {
+            transaction_files(transaction_path_pattern.as_deref())?
+        } else {
+            files.to_vec()
+        };
+        Ok((transaction_parser, files))
+    }
+
+    /// Categorizer read from `--categorizer-path`
+    fn categorizer(&self) -> Result<Categorizer> {
+        self.read_config(
+            &self.categorizer_path,
+            Categorizer::from_yaml_file,
+            Categorizer::from_yaml_buffer,
+        )
+        .context(ErrorClass::Config.message("failed to parse categorizer"))
+    }
+
+    /// Read a config file, decrypting it with the passphrase first when it is encrypted at rest
+    /// (ie `categorizer.yaml.age`)
+    fn read_config<'a, T, E, F, B>(
+        &self,
+        path: &'a Path,
+        from_yaml_file: F,
+        #[cfg_attr(not(feature = "encryption"), allow(unused_variables))] from_yaml_buffer: B,
+    ) -> Result<T>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+        F: FnOnce(&'a Path) -> Result<T, E>,
+        B: FnOnce(Vec<u8>) -> Result<T, E>,
+    {
+        #[cfg(feature = "encryption")]
+        if encryption::is_encrypted(path) {
+            let yaml = encryption::read(path, &self.passphrase()?)
+                .with_context(|| format!("failed to decrypt {}", path.display()))?;
+            return Ok(from_yaml_buffer(yaml)?);
+        }
+        Ok(from_yaml_file(path)?)
+    }
+
+    /// Read the yaml of a config file to validate or edit it, decrypting it like `read_config`
+    fn read_config_yaml(&self, path: &Path) -> Result<String> {
+        self.read_config(path, fs::read_to_string, |yaml| {
+            String::from_utf8(yaml).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    /// Write the yaml of an edited config file, encrypting it with the passphrase when it is
+    /// encrypted at rest
+    fn write_config_yaml(&self, path: &Path, yaml: &str) -> Result<()> {
+        #[cfg(feature = "encryption")]
+        if encryption::is_encrypted(path) {
+            return encryption::write(path, &self.passphrase()?, yaml.as_bytes())
+                .with_context(|| format!("failed to encrypt {}", path.display()));
+        }
+        fs::write(path, yaml).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Passphrase of the files encrypted at rest
+    #[cfg(feature = "encryption")]
+    fn passphrase(&self) -> Result<Passphrase> {
+        self.passphrase
+            .as_deref()
+            .map(Passphrase::new)
+            .context("--passphrase is required to read and write encrypted files")
+    }
+
+    /// Open the store, decrypting it when it is encrypted at rest (ie `ledger.age`)
+    #[cfg(feature = "sqlite")]
+    fn open_store(&self, path: &Path) -> Result<Store> {
+        #[cfg(feature = "encryption")]
+        if encryption::is_encrypted(path) {
+            return Ok(Store::open_encrypted(path, self.passphrase()?)?);
+        }
+        Ok(Store::open(path)?)
+    }
+
+    /// Files the config is read from
+    fn config_paths(&self) -> Vec<&Path> {
+        match &self.config {
+            Some(path) => vec![path],
+            None => vec![&self.transaction_parser_path, &self.categorizer_path],
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct FormatArgs {
+    /// Locale for thousands and decimal separators of amounts (ie `en-US` or `de-DE`)
+    #[arg(long, env = "SLOTTED_PIG_LOCALE")]
+    locale: Option<String>,
+    /// Currency symbol of amounts (ie `$`)
+    #[arg(long, env = "SLOTTED_PIG_CURRENCY_SYMBOL")]
+    currency_symbol: Option<String>,
+    /// Place the currency symbol after amounts
+    #[arg(long, env = "SLOTTED_PIG_CURRENCY_SUFFIX")]
+    currency_suffix: bool,
+    /// Number of decimal places of amounts
+    #[arg(long, env = "SLOTTED_PIG_DECIMAL_PLACES")]
+    decimal_places: Option<u8>,
+    /// How negative amounts are displayed
+    #[arg(long, env = "SLOTTED_PIG_NEGATIVE_STYLE")]
+    negative_style: Option<NegativeStyle>,
+    /// `strftime` style format of dates (ie `%Y-%m-%d` or `%m/%d/%Y`), csv transaction times are
+    /// written as full RFC 3339 timestamps unless set
+    #[arg(long, env = "SLOTTED_PIG_DATE_FORMAT")]
+    date_format: Option<String>,
+}
+
+impl FormatArgs {
+    fn formatter(&self) -> Result<Formatter> {
+        let mut formatter = match &self.locale {
+            Some(locale) => Formatter::from_locale(locale)
+                .with_context(|| format!("unknown locale {locale}"))?,
+            None => Formatter::default(),
+        };
+        if let Some(currency_symbol) = &self.currency_symbol {
+            formatter.currency_symbol.clone_from(currency_symbol);
+        }
+        formatter.currency_suffix = self.currency_suffix;
+        if let Some(decimal_places) = self.decimal_places {
+            formatter.decimal_places = decimal_places;
+        }
+        if let Some(negative_style) = self.negative_style {
+            formatter.negative_style = negative_style;
+        }
+        if let Some(date_format) = &self.date_format {
+            if !Formatter::is_valid_date_format(date_format) {
+                bail!("invalid date format {date_format}");
+            }
+            formatter.date_format.clone_from(date_format);
+        }
+        Ok(formatter)
+    }
+
+    /// Formatter for csv transaction times, `None` to keep RFC 3339 timestamps
+    fn csv_time_formatter(&self) -> Result<Option<Formatter>> {
+        Ok(match self.date_format {
+            Some(_) => Some(self.formatter()?),
+            None => None,
+        })
+    }
+}
+
+#[derive(Debug, Parser)]
+struct FilterArgs {
+    /// Only include transactions on or after this day (ie `2024-03-01`)
+    #[arg(long, global = true)]
+    begin: Option<NaiveDate>,
+    /// Only include transactions on or before this day (ie `2024-03-31`)
+    #[arg(long, global = true)]
+    end: Option<NaiveDate>,
+    /// Only include transactions of this account
+    #[arg(long, global = true)]
+    account: Option<String>,
+    /// Only include transactions with at least this amount
+    #[arg(long, global = true, allow_negative_numbers = true)]
+    min: Option<BigDecimal>,
+    /// Only include transactions with at most this amount
+    #[arg(long, global = true, allow_negative_numbers = true)]
+    max: Option<BigDecimal>,
+}
+
+impl FilterArgs {
+    /// Check if a transaction passes every filter
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let date = transaction.time.date_naive();
+        self.begin.is_none_or(|begin| begin <= date)
+            && self.end.is_none_or(|end| date <= end)
+            && self
+                .account
+                .as_ref()
+                .is_none_or(|account| account == &transaction.account)
+            && self
+                .min
+                .as_ref()
+                .is_none_or(|min| min <= &transaction.amount)
+            && self
+                .max
+                .as_ref()
+                .is_none_or(|max| max >= &transaction.amount)
+    }
+}
+
+#[derive(Debug, Parser)]
+struct RedactArgs {
+    /// Replace descriptions and accounts in all outputs with hashed pseudonyms so reports can be
+    /// shared without leaking personal details
+    #[arg(long)]
+    redact: bool,
+    /// Secret mixed into the redaction hashes so pseudonyms can not be reversed
+    #[arg(long, requires = "redact", default_value = "")]
+    redact_salt: String,
+    /// Factor to multiply amounts by when redacting
+    #[arg(long, requires = "redact")]
+    redact_scale: Option<BigDecimal>,
+    /// Round amounts to the nearest multiple of this size when redacting (ie `10`)
+    #[arg(long, requires = "redact")]
+    redact_bucket: Option<BigDecimal>,
+}
+
+impl RedactArgs {
+    fn redactor(&self) -> Option<Redactor> {
+        self.redact.then(|| Redactor {
+            salt: self.redact_salt.clone(),
+            amount_scale: self.redact_scale.clone(),
+            amount_bucket: self.redact_bucket.clone(),
+        })
+    }
+}
+
+/// Level of the log messages written to stderr
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => Self::TRACE,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Warning => Self::WARN,
+            LogLevel::Error | LogLevel::Critical => Self::ERROR,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 #[command()]
 enum Command {
     /// Output the categorized yaml
     #[command()]
     Categorize(Categorize),
-    /// Output the transactions csv
+    /// Output the transactions
+    #[command()]
+    Transactions(Transactions),
+    /// Output the transactions matching a filter expression (ie
+    /// `amount < -100 && description =~ "AMZN" && time >= 2024-01-01`)
+    #[command()]
+    Query(QueryArgs),
+    /// Normalize transaction files read by the transaction parser into another format, no
+    /// categorizer is needed
+    ///
+    /// Csv, OFX, and QIF files are read. Spreadsheets (ie XLSX) are not supported, export them as
+    /// csv first.
+    #[command()]
+    Convert(Convert),
+    /// Write copies of transaction files with scrambled descriptions and accounts and perturbed
+    /// amounts which parse the same way, for sharing realistic data in bug reports
+    #[command()]
+    Anonymize(Anonymize),
+    /// Export the categorized transactions for use in other tools
+    #[command(subcommand)]
+    Export(Export),
+    /// Output a summary statement of a single month compared to the prior month
+    #[command()]
+    Statement(StatementArgs),
+    /// Output month to date spending compared to the prorated budget of each category
+    #[command()]
+    Budget(BudgetArgs),
+    /// Output the expected income, expenses, and net of the coming months from recurring
+    /// transactions and the average monthly total of every category
+    #[command()]
+    Forecast(ForecastArgs),
+    /// Output the balance of every account and their total at the end of each time period
+    #[command()]
+    NetWorth(NetWorthArgs),
+    /// Output the transaction count, inflow, outflow, and net total of every account over a
+    /// period, with opening and closing balances for accounts with a starting balance
+    #[command()]
+    Balances(BalancesArgs),
+    /// Output detected subscriptions and bills with their cadence, last amount, yearly cost, and
+    /// next expected date
+    #[command()]
+    Recurring(RecurringArgs),
+    /// Output category totals of each fiscal year and their tax relevant total
+    #[command()]
+    TaxYears(TaxYearsArgs),
+    /// Output or email a summary of recent transactions, budget alerts, and unusual transactions
+    #[command()]
+    Digest(DigestArgs),
+    /// Output the date range, transaction counts per account and file, total inflow and outflow,
+    /// uncategorized percentage, and hits of every transaction matcher
+    #[command()]
+    Stats(StatsArgs),
+    /// List every transaction matcher with its category, hits, and lint findings (never matched,
+    /// shadowed by an earlier matcher, or overly broad) along with the config problems
+    #[command()]
+    Rules(RulesArgs),
+    /// Output the largest transactions and the merchants with the largest totals, a quick view of
+    /// where the money went
+    #[command()]
+    Top(TopArgs),
+    /// Output a table of category totals for each month, quarter, or year
+    #[command()]
+    Monthly(MonthlyArgs),
+    /// Rewrite an output whenever the transaction files or configs change
+    #[command()]
+    Watch(WatchArgs),
+    /// Output uncategorized transactions grouped by payee with their counts and totals, or
+    /// suggested transaction matchers for them
+    #[command()]
+    Uncategorized(UncategorizedArgs),
+    /// Check the categorizer and transaction parser configs for problems without parsing any
+    /// transactions
+    #[command()]
+    Validate,
+    /// Run the matcher tests embedded in the categorizer and in fixture files, printing whether
+    /// each passed, failing if any failed
+    #[command()]
+    Test(TestArgs),
+    /// Write starter transaction parser and categorizer configs
+    #[command()]
+    Init(Init),
+    /// Write synthetic transaction csv files with a matching transaction parser and categorizer
+    /// config, for demos, benchmarks, and trying the UI without real financial data
+    #[command()]
+    Generate(Generate),
+    /// Output the JSON schema of a config for editors to autocomplete and validate the yaml (ie
+    /// with a `# yaml-language-server: $schema=categorizer.schema.json` comment)
+    #[command()]
+    Schema(SchemaArgs),
+    /// Explain which transaction matcher assigned a transaction its category and why every other
+    /// matcher rejected it
+    #[command()]
+    Explain(Explain),
+    /// Output the change of every category total between two periods or two saved categorize
+    /// outputs, biggest changes first
+    #[command()]
+    Diff(Diff),
+    /// Output a statement of a period compared to the prior period, optionally with charts
     #[command()]
-    Transactions,
+    Report(Report),
+    /// Interactively build a transaction matcher, starting from an uncategorized payee, preview
+    /// the transactions it categorizes, and append it to the categorizer config
+    #[command()]
+    AddRule,
+    /// Interactively categorize uncategorized transactions, adding a transaction matcher to the
+    /// categorizer config for each
+    #[cfg(feature = "tui")]
+    #[command()]
+    Tui,
+    /// Parse transaction files and add the transactions which are not already in the --store
+    #[cfg(feature = "sqlite")]
+    #[command()]
+    Import(Import),
+    /// Serve a JSON API over the transactions and the built web UI
+    #[cfg(feature = "serve")]
+    #[command()]
+    Serve(ServeArgs),
 }
 
 #[derive(Debug, Parser)]
@@ -53,54 +558,2281 @@ struct Categorize {
     /// How to sort the transactions
     #[arg(long)]
     transaction_sort: Option<TransactionSort>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = CategorizeFormat::Yaml)]
+    format: CategorizeFormat,
+    /// Maximum depth of categories to chart or list, top level categories have a depth of 1
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Colon separated path of a category to limit the output to along with its subcategories (ie
+    /// `expenses:food`), parent totals only include the selected categories
+    #[arg(long)]
+    only: Vec<String>,
+    /// Colon separated path of a category to leave out of the output along with its
+    /// subcategories, parent totals do not include the excluded categories
+    #[arg(long)]
+    exclude: Vec<String>,
+    /// Path to write the categorization to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+#[derive(Debug, Parser)]
+struct Transactions {
+    /// How to sort the transactions, defaults to the order of the transaction files
+    #[arg(long)]
+    sort: Option<TransactionSort>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = TransactionsFormat::Csv)]
+    format: TransactionsFormat,
+    /// Add a `category` column with the colon separated category path to csv, markdown, and html
+    /// output
+    #[arg(long)]
+    category_column: bool,
+    /// Path to write the transactions to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
 
-    let _logger = TerminalLoggerBuilder::new()
-        .level(args.log_level.unwrap_or_default())
-        .source_location(SourceLocation::None)
-        .build()?;
+#[derive(Debug, Parser)]
+struct QueryArgs {
+    /// Comparisons of `amount`, `account`, `description`, `time`, or `category` to values
+    /// combined with `&&`, `||`, `!`, and parentheses, strings are double quoted and `=~` matches a
+    /// regex
+    expression: String,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = TransactionsFormat::Csv)]
+    format: TransactionsFormat,
+    /// Add a `category` column with the colon separated category path to csv, markdown, and html
+    /// output
+    #[arg(long)]
+    category_column: bool,
+    /// Path to write the matching transactions to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
 
-    let categorizer = Categorizer::from_yaml_file(args.categorizer_path)
-        .context("failed to parse categorizer")?;
-    let transaction_parser = TransactionParser::from_yaml_file(args.transaction_parser_path)
-        .context("failed to parse transaction parser")?;
-    let transaction_files = glob::glob(&args.transaction_path_pattern)?
-        .collect::<Result<Vec<_>, _>>()
-        .context("failed to find transaction files")?;
-    let transaction_files = transaction_files
-        .iter()
-        .filter(|f| f.is_file())
-        .map(|f| f.as_path());
-    let transactions = transaction_parser
-        .parse_csvs(transaction_files)
-        .context("failed to parse transaction files")?;
+#[derive(Debug, Parser)]
+struct Convert {
+    /// Transaction files (csv, OFX or QIF) to convert, defaults to the files matching the
+    /// transaction path pattern
+    files: Vec<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = TransactionsFormat::Csv)]
+    format: TransactionsFormat,
+    /// Path to write the transactions to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Anonymize {
+    /// Transaction files to anonymize, defaults to the files matching the transaction path
+    /// pattern
+    files: Vec<PathBuf>,
+    /// Directory to write the anonymized files to with their original names
+    #[arg(long)]
+    output_dir: PathBuf,
+    /// Secret mixed into the scrambling so it can not be reversed by guessing values
+    #[arg(long, default_value = "")]
+    salt: String,
+}
+
+#[derive(Debug, Parser)]
+struct TestArgs {
+    /// Csv files of transactions with their expected `category` column (ie written by
+    /// `transactions --category-column`), an empty category expects no category
+    fixtures: Vec<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Init {
+    /// Bank csv export format the transaction parser is written for
+    #[arg(long, default_value = "generic")]
+    preset: BankPreset,
+    /// Write a sample category tree with common categories instead of a minimal one
+    #[arg(long)]
+    sample_categories: bool,
+    /// Overwrite existing config files
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SchemaArgs {
+    /// Kind of config, one of `transaction_parser`, `categorizer`, or `config`
+    kind: ConfigKind,
+    /// Path to write the schema to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Generate {
+    /// Number of months of transactions, at most 100 years
+    #[arg(long, default_value_t = 12, value_parser = clap::value_parser!(u32).range(..=1200))]
+    months: u32,
+    /// Number of accounts, the first is checking, the third is savings, and the rest are credit
+    /// cards
+    #[arg(long, default_value_t = 3)]
+    accounts: usize,
+    /// First day of the transactions (ie `2024-01-01`), defaults to `--months` months before the
+    /// current month
+    #[arg(long)]
+    start: Option<NaiveDate>,
+    /// Seed of the random amounts, days, and descriptions, the same seed generates the same files
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Directory to write the transaction files and configs to
+    #[arg(long)]
+    output_dir: PathBuf,
+    /// Overwrite existing files
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Debug, Parser)]
+struct Explain {
+    /// Description of a transaction to explain without parsing transaction files
+    #[arg(long, required_unless_present = "transaction_index")]
+    description: Option<String>,
+    /// Amount of the transaction to explain
+    #[arg(
+        long,
+        requires = "description",
+        default_value = "0",
+        allow_negative_numbers = true
+    )]
+    amount: BigDecimal,
+    /// Account of the transaction to explain
+    #[arg(long, requires = "description", default_value = "")]
+    account: String,
+    /// Time of the transaction to explain (ie `2024-02-24T12:00:00Z`), defaults to now
+    #[arg(long, requires = "description")]
+    time: Option<DateTime<Utc>>,
+    /// Index of a parsed transaction to explain, in the order `transactions` outputs them
+    #[arg(long, conflicts_with = "description")]
+    transaction_index: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum BankPreset {
+    /// Csv with `amount`, `account`, `description`, and `time` headers
+    Generic,
+    /// Chase credit card activity
+    Chase,
+    /// American Express activity
+    Amex,
+    /// Bank of America credit card
+    BankOfAmerica,
+}
+
+impl BankPreset {
+    fn transaction_parser(self) -> &'static str {
+        match self {
+            Self::Generic => include_str!("../templates/transaction_parser/generic.yaml"),
+            Self::Chase => include_str!("../templates/transaction_parser/chase.yaml"),
+            Self::Amex => include_str!("../templates/transaction_parser/amex.yaml"),
+            Self::BankOfAmerica => {
+                include_str!("../templates/transaction_parser/bank_of_america.yaml")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TransactionsFormat {
+    /// Comma separated values
+    Csv,
+    /// Open Financial Exchange bank statements
+    Ofx,
+    /// Quicken Interchange Format
+    Qif,
+    /// JSON array of transactions
+    Json,
+    /// YAML list of transactions
+    Yaml,
+    /// Markdown table with formatted amounts and dates
+    Markdown,
+    /// HTML document with a table with formatted amounts and dates
+    Html,
+    /// Plain text table with aligned columns and formatted amounts and dates
+    Table,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CategorizeFormat {
+    /// Categorized hierarchy as yaml
+    Yaml,
+    /// Categorized hierarchy as JSON
+    Json,
+    /// Comma separated category totals in hierarchy order
+    Csv,
+    /// Markdown table of category totals
+    Markdown,
+    /// HTML document with a table of category totals
+    Html,
+    /// Bar chart of category totals for the terminal
+    Chart,
+    /// A JSON line per transaction with its category, streamed as transactions are parsed
+    JsonLines,
+}
+
+#[derive(Debug, Parser)]
+struct StatementArgs {
+    /// Month of the statement (ie `2024-02`), defaults to the month of the latest transaction
+    #[arg(long, value_parser = parse_month)]
+    month: Option<NaiveDate>,
+    /// Names of the top level categories that are income
+    #[arg(long = "income-category", default_value = "Income")]
+    income_categories: Vec<String>,
+    /// Number of the largest transactions to list
+    #[arg(long, default_value_t = 5)]
+    biggest: usize,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: StatementFormat,
+    /// Path to write the statement to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct BudgetArgs {
+    /// Last day of the month to date spending (ie `2024-02-15`), defaults to today
+    #[arg(long)]
+    as_of: Option<NaiveDate>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: BurndownFormat,
+    /// Path to write the burndown to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Exit with the over budget exit code if any category has spent more than its full budget
+    #[arg(long)]
+    fail_over_budget: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ForecastArgs {
+    /// Number of months after the month of `--as-of` to forecast, at most 100 years
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(..=1200))]
+    months: u32,
+    /// Number of whole months before `--as-of` to average category totals over, at most 100 years
+    #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u32).range(..=1200))]
+    history: u32,
+    /// Last day of history to forecast from (ie `2024-02-29`), defaults to the day of the latest
+    /// transaction
+    #[arg(long)]
+    as_of: Option<NaiveDate>,
+    /// Minimum number of transactions for a payee to be considered recurring
+    #[arg(long, default_value_t = 3)]
+    min_occurrences: usize,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: ForecastFormat,
+    /// Path to write the forecast to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct DigestArgs {
+    /// Length of time to summarize, defaults to the period of the email config or weekly
+    #[arg(long)]
+    period: Option<DigestPeriod>,
+    /// Last day to summarize (ie `2024-02-24`), defaults to today
+    #[arg(long)]
+    end: Option<NaiveDate>,
+    /// Transactions more than this many times the typical amount of their category are unusual
+    #[arg(long, default_value = "3")]
+    anomaly_factor: BigDecimal,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: StatementFormat,
+    /// Path to write the digest to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Path to config file of the SMTP server and recipients to email the digest to instead of
+    /// outputting it
+    #[cfg(feature = "email")]
+    #[arg(long, conflicts_with_all = ["format", "output"])]
+    email_config_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct BalancesArgs {
+    /// Period to total (ie `2024`, `2024-03`, `last-month`, or `ytd`), defaults to all
+    /// transactions
+    #[arg(long)]
+    period: Option<String>,
+    /// Path to config file of account balances before their first transaction
+    #[arg(long)]
+    starting_balances_path: Option<PathBuf>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: BalancesFormat,
+    /// Path to write the balances to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct RecurringArgs {
+    /// How to sort the recurring transactions
+    #[arg(long, default_value = "payee")]
+    sort: RecurringSort,
+    /// Minimum number of transactions for a payee to be considered recurring
+    #[arg(long, default_value_t = 3)]
+    min_occurrences: usize,
+    /// Include recurring deposits (ie paychecks) in addition to charges
+    #[arg(long)]
+    include_deposits: bool,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: RecurringFormat,
+    /// Path to write the recurring transactions to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct NetWorthArgs {
+    /// Path to config file of account balances before their first transaction
+    #[arg(long)]
+    starting_balances_path: Option<PathBuf>,
+    /// Length of the time periods
+    #[arg(long, default_value = "month")]
+    bucket: Bucket,
+    /// Data format to output
+    #[arg(long, default_value = "csv")]
+    format: NetWorthFormat,
+    /// Path to write the data to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Path to also write a line chart to, svg or png depending on the extension
+    #[cfg(feature = "charts")]
+    #[arg(long)]
+    chart: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct TaxYearsArgs {
+    /// Month the fiscal year starts in, 1 for calendar years
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=12))]
+    fiscal_year_start: u32,
+    /// Only output tax relevant categories (ie a deductible summary)
+    #[arg(long)]
+    tax_relevant_only: bool,
+    /// Maximum depth of category paths, deeper categories roll up into their ancestor
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: TaxYearsFormat,
+    /// Path to write the years to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct StatsArgs {
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: StatsFormat,
+    /// Path to write the stats to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct RulesArgs {
+    /// Matchers matching more than this percent of the transactions are overly broad
+    #[arg(long, default_value = "50")]
+    broad_percent: BigDecimal,
+    /// Exit with the validation exit code if there are any findings or config problems
+    #[arg(long)]
+    fail_on_findings: bool,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: RulesFormat,
+    /// Path to write the rules to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct TopArgs {
+    /// Number of transactions and merchants to output
+    #[arg(long, default_value_t = 20)]
+    n: usize,
+    /// Category to limit transactions to, either a colon separated path including its
+    /// subcategories (ie `expenses:dining`) or the name of any category in the path (ie `dining`)
+    #[arg(long)]
+    category: Option<String>,
+    /// Period to limit transactions to (ie `2024`, `2024-03`, `last-month`, or `ytd`)
+    #[arg(long)]
+    period: Option<String>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: TopFormat,
+    /// Path to write the top transactions and merchants to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct MonthlyArgs {
+    /// Length of the time periods
+    #[arg(long, default_value = "month")]
+    by: Bucket,
+    /// Maximum depth of category paths, deeper categories roll up into their ancestor
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Names of the top level categories to include, defaults to all categories
+    #[arg(long = "category")]
+    categories: Vec<String>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: PivotFormat,
+    /// Path to write the totals to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct WatchArgs {
+    /// Path to rewrite on every change
+    #[arg(long)]
+    output: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = WatchFormat::Yaml)]
+    format: WatchFormat,
+    /// Seconds between checks for changed files
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+    /// Names of the top level categories that are income, for the html statement
+    #[arg(long = "income-category", default_value = "Income")]
+    income_categories: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum WatchFormat {
+    /// Categorized hierarchy as yaml
+    Yaml,
+    /// Categorized hierarchy as JSON
+    Json,
+    /// Statement of the month of the latest transaction as html
+    Html,
+}
+
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Parser)]
+struct Import {
+    /// Transaction files to import, defaults to the files matching the transaction path pattern
+    files: Vec<PathBuf>,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Parser)]
+struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    address: String,
+    /// Directory of the built web UI (ie `slotted-pig-ui/dist`), only the API is served without it
+    #[arg(long)]
+    ui_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct UncategorizedArgs {
+    /// Output format, `matchers` outputs categorizer yaml with a category per payee
+    #[arg(long, default_value = "text")]
+    format: UncategorizedFormat,
+    /// Path to write the payees to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Diff {
+    /// Earlier period (ie `2024-02` or `last-month`) or saved categorize yaml output
+    #[arg(long)]
+    from: String,
+    /// Later period (ie `2024-03` or `this-month`) or saved categorize yaml output
+    #[arg(long)]
+    to: String,
+    /// Leave out categories deeper than this, top level categories have a depth of 1
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: ChangesFormat,
+    /// Path to write the changes to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+impl Diff {
+    /// Check if both sides are saved categorize outputs so no transactions are needed
+    fn reads_files(&self) -> bool {
+        Path::new(&self.from).is_file() && Path::new(&self.to).is_file()
+    }
+
+    /// Write the changes from the `from` categorization to the `to` categorization
+    ///
+    /// Periods are categorized with `categorize`, which is only called for sides which are not
+    /// files.
+    fn write(
+        &self,
+        formatter: &Formatter,
+        categorize: impl Fn(&DateRange) -> CategorizedList,
+    ) -> Result<()> {
+        let side = |side: &str| -> Result<(String, CategorizedList)> {
+            if Path::new(side).is_file() {
+                let categorized = serde_yaml::from_reader(File::open(side)?)
+                    .with_context(|| format!("failed to parse categorized {side}"))?;
+                Ok((side.to_string(), categorized))
+            } else {
+                let period = Period::parse(side, Local::now().date_naive())?;
+                let categorized = categorize(&period.current);
+                Ok((period.current.label, categorized))
+            }
+        };
+        let (from_label, from) = side(&self.from)?;
+        let (to_label, to) = side(&self.to)?;
+        Changes::new(&from, &to, from_label, to_label, self.depth)
+            .write(self.format, formatter, output_writer(self.output.as_ref())?)
+            .context("failed to write changes")
+    }
+}
+
+#[derive(Debug, Parser)]
+struct Report {
+    /// Period to report (ie `2024-03`, `2024-Q1`, `2024`, `last-month`, `ytd`, or
+    /// `last-30-days`), defaults to the month of the latest transaction
+    #[arg(long)]
+    period: Option<String>,
+    /// Names of the top level categories that are income
+    #[arg(long = "income-category", default_value = "Income")]
+    income_categories: Vec<String>,
+    /// Number of the largest transactions to list
+    #[arg(long, default_value_t = 5)]
+    biggest: usize,
+    /// Output format
+    #[arg(long, default_value = "text")]
+    format: StatementFormat,
+    /// Path to write the report to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Directory to write chart images of the period to
+    #[cfg(feature = "charts")]
+    #[arg(long)]
+    charts: Option<PathBuf>,
+    /// Image format of the charts
+    #[cfg(feature = "charts")]
+    #[arg(long, value_enum, default_value_t = ChartFormat::Svg)]
+    chart_format: ChartFormat,
+}
+
+#[cfg(feature = "charts")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ChartFormat {
+    /// Scalable vector graphics
+    Svg,
+    /// Portable network graphics
+    Png,
+}
+
+#[derive(Subcommand, Debug)]
+#[command()]
+enum Export {
+    /// Output a ledger/hledger journal
+    #[command()]
+    Ledger(Ledger),
+    /// Output a Beancount ledger
+    #[command()]
+    Beancount(Beancount),
+    /// Output a file GnuCash can import
+    #[command()]
+    Gnucash(Gnucash),
+    /// Output Sankey diagram data of money flowing from income to categories
+    #[command()]
+    Sankey(SankeyArgs),
+    /// Output a matrix of category totals for each time period
+    #[command()]
+    Pivot(PivotArgs),
+    /// Output a calendar of upcoming recurring charges
+    #[command()]
+    Ical(Ical),
+    /// Output a SQL script creating and filling tables of categorized transactions
+    #[command()]
+    Sql(Sql),
+    /// Output a Graphviz DOT graph of the category hierarchy
+    #[command()]
+    Dot(Dot),
+    /// Output a Parquet file of categorized transactions
+    #[cfg(feature = "parquet")]
+    #[command()]
+    Parquet(ParquetArgs),
+}
+
+#[derive(Debug, Parser)]
+struct Ledger {
+    /// Account prefix for category postings (ie `Expenses`)
+    #[arg(long)]
+    category_prefix: Option<String>,
+    /// Account prefix for source account postings (ie `Assets`)
+    #[arg(long)]
+    account_prefix: Option<String>,
+    /// Account used for uncategorized transactions
+    #[arg(long)]
+    uncategorized_account: Option<String>,
+    /// Commodity appended to every amount (ie `USD`)
+    #[arg(long)]
+    commodity: Option<String>,
+    /// Path to write the journal to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Beancount {
+    /// Path to config file mapping categories and accounts to Beancount accounts
+    #[arg(long)]
+    config_path: Option<PathBuf>,
+    /// Path to write the ledger to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Gnucash {
+    /// File format to output
+    #[arg(long, default_value = "csv")]
+    format: GnucashFormat,
+    /// Path to config file mapping categories and accounts to GnuCash accounts
+    #[arg(long)]
+    config_path: Option<PathBuf>,
+    /// Path to write the file to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct SankeyArgs {
+    /// Names of the top level categories that are income
+    #[arg(long = "income-category", default_value = "Income")]
+    income_categories: Vec<String>,
+    /// Data format to output
+    #[arg(long, default_value = "json")]
+    format: SankeyFormat,
+    /// Path to write the data to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct PivotArgs {
+    /// Length of the time periods
+    #[arg(long, default_value = "month")]
+    bucket: Bucket,
+    /// Maximum depth of category paths, deeper categories roll up into their ancestor
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Data format to output
+    #[arg(long, default_value = "csv")]
+    format: PivotFormat,
+    /// Path to write the data to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Ical {
+    /// Number of days before each expected charge to be reminded
+    #[arg(long, default_value_t = 3)]
+    reminder_days: u32,
+    /// Minimum number of transactions for a payee to be considered recurring
+    #[arg(long, default_value_t = 3)]
+    min_occurrences: usize,
+    /// Include recurring deposits (ie paychecks) in addition to charges
+    #[arg(long)]
+    include_deposits: bool,
+    /// Path to write the calendar to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct Sql {
+    /// Keep existing tables instead of dropping them before they are created
+    #[arg(long)]
+    keep_existing: bool,
+    /// Path of a SQLite database to write the tables to instead of outputting a script
+    #[cfg(feature = "sqlite")]
+    #[arg(long, conflicts_with = "output")]
+    sqlite: Option<PathBuf>,
+    /// Path to write the script to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[cfg(feature = "parquet")]
+#[derive(Debug, Parser)]
+struct ParquetArgs {
+    /// Time period of the period column
+    #[arg(long, default_value = "month")]
+    bucket: Bucket,
+    /// Number of decimal places amounts are stored with
+    #[arg(long, default_value_t = 2)]
+    amount_scale: u8,
+    /// Path to write the Parquet file to
+    #[arg(long)]
+    output: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+struct Dot {
+    /// Label categories with their totals from categorizing the transactions
+    #[arg(long)]
+    totals: bool,
+    /// Path to write the graph to, defaults to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let command = match defaults::apply(Args::command()) {
+        Ok(command) => command,
+        Err(e) => return error::report(&e, ErrorFormat::default()),
+    };
+    let args = parse_args(command, std::env::args_os()).unwrap_or_else(|e| e.exit());
+    let error_format = args.error_format;
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => error::report(&e, error_format),
+    }
+}
+
+/// Parse the arguments, choosing between the combined config and the separate config paths by
+/// the precedence of where they were set (command line, then environment variable, then
+/// defaults)
+fn parse_args<I, T>(mut command: clap::Command, arguments: I) -> Result<Args, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let matches = command.try_get_matches_from_mut(arguments)?;
+    let mut args = Args::from_arg_matches(&matches)?;
+    let Some(config) = matches.value_source("config") else {
+        return Ok(args);
+    };
+    let paths = ["transaction_parser_path", "categorizer_path"]
+        .into_iter()
+        .filter_map(|id| matches.value_source(id))
+        .max();
+    match paths {
+        Some(ValueSource::CommandLine) if config == ValueSource::CommandLine => {
+            return Err(command.error(
+                ErrorKind::ArgumentConflict,
+                "--config cannot be used with --transaction-parser-path or --categorizer-path",
+            ));
+        }
+        Some(paths) if paths > config => args.config = None,
+        _ => {}
+    }
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<()> {
+    // The cli logs and the diagnostics of the library are both `tracing` events
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::from(args.log_level.unwrap_or_default()))
+        .with_target(false)
+        .with_writer(io::stderr)
+        .init();
+
+    match &args.command {
+        Command::Validate => return validate(&args),
+        Command::Test(test_args) => return test(&args, test_args),
+        Command::Init(init_args) => return init(&args, init_args),
+        Command::Generate(generate_args) => return generate(generate_args),
+        Command::Schema(schema_args) => return schema(schema_args),
+        Command::Watch(watch_args) => return watch(&args, watch_args),
+        Command::Convert(convert_args) => return convert(&args, convert_args),
+        Command::Anonymize(anonymize_args) => return anonymize(&args, anonymize_args),
+        Command::Diff(diff) if diff.reads_files() => {
+            return diff.write(&args.format.formatter()?, |_| {
+                unreachable!("both sides of the diff are files")
+            })
+        }
+        #[cfg(feature = "serve")]
+        Command::Serve(serve_args) => return serve(&args, serve_args),
+        _ => {}
+    }
+
+    let Config {
+        transaction_parser,
+        categorizer,
+        transaction_path_pattern,
+        category_sort,
+        transaction_sort,
+    } = args.config()?;
+    if let Command::Explain(Explain {
+        description: Some(description),
+        amount,
+        account,
+        time,
+        ..
+    }) = &args.command
+    {
+        let transaction = Transaction {
+            amount: amount.clone(),
+            account: account.clone(),
+            description: description.clone(),
+            time: time.unwrap_or_else(Utc::now),
+            source: None,
+        };
+        print!("{}", categorizer.explain(&transaction));
+        return Ok(());
+    }
+    #[cfg(feature = "sqlite")]
+    if let Command::Import(import_args) = &args.command {
+        return import(
+            &args,
+            import_args,
+            &transaction_parser,
+            transaction_path_pattern.as_deref(),
+        );
+    }
+    let redactor = args.redact.redactor();
+    if let (
+        Command::Categorize(Categorize {
+            format: CategorizeFormat::JsonLines,
+            output,
+            only,
+            exclude,
+            ..
+        }),
+        None,
+        true,
+    ) = (
+        &args.command,
+        &redactor,
+        args.reads_only_transaction_files(),
+    ) {
+        // Stream without collecting the transactions so memory use does not grow with history
+        let transaction_files = transaction_files(transaction_path_pattern.as_deref())?;
+        let mut writer = JsonLinesWriter::new(output_writer(output.as_ref())?);
+        for transaction in transaction_parser.files(transaction_files).iter() {
+            let transaction = transaction.context(
+                ErrorClass::TransactionParse.message("failed to parse transaction files"),
+            )?;
+            if !args.filter.matches(&transaction) {
+                continue;
+            }
+            if let Some(assignment) = categorizer
+                .assign_transaction(&transaction)
+                .filter(|a| category_selected(a.category_path_joined(":"), only, exclude))
+            {
+                writer
+                    .write(&assignment)
+                    .context("failed to write json lines")?;
+            }
+        }
+        writer.flush().context("failed to write json lines")?;
+        return Ok(());
+    }
+    let transactions = read_transactions(
+        &args,
+        &transaction_parser,
+        transaction_path_pattern.as_deref(),
+    )?;
+    for warning in categorizer.warnings(&transactions) {
+        tracing::warn!("{warning}");
+    }
+    let (categorizer, transactions) = match &redactor {
+        Some(redactor) => redactor.redact(&categorizer, &transactions),
+        None => (categorizer, transactions),
+    };
 
     match args.command {
         Command::Categorize(categorize) => {
             let (mut categorized, uncategorized) = categorizer.categorize(&transactions);
-            write_transactions(&uncategorized, io::stderr())?;
-            if let Some(sort) = categorize.category_sort {
+            write_transactions(
+                &uncategorized,
+                args.format.csv_time_formatter()?.as_ref(),
+                io::stderr(),
+            )?;
+            if !categorize.only.is_empty() || !categorize.exclude.is_empty() {
+                categorized.prune(&categorize.only, &categorize.exclude);
+            }
+            if let Some(sort) = categorize.category_sort.or(category_sort) {
                 categorized.sort_subcategories(sort);
             }
-            if let Some(sort) = categorize.transaction_sort {
+            if let Some(sort) = categorize.transaction_sort.or(transaction_sort) {
                 categorized.sort_transactions(sort);
             }
-            println!("{}", serde_yaml::to_string(&categorized)?);
+            categorizer.round_totals(&mut categorized);
+            let mut writer = output_writer(categorize.output.as_ref())?;
+            let totals = |format, writer| {
+                Totals::new(&categorized, categorize.depth)
+                    .write(format, &args.format.formatter()?, writer)
+                    .context("failed to write category totals")
+            };
+            match categorize.format {
+                CategorizeFormat::Yaml => serde_yaml::to_writer(&mut writer, &categorized)?,
+                CategorizeFormat::Json => {
+                    serde_json::to_writer_pretty(&mut writer, &categorized)?;
+                    writeln!(writer)?;
+                }
+                CategorizeFormat::Csv => totals(TotalsFormat::Csv, &mut writer)?,
+                CategorizeFormat::Markdown => totals(TotalsFormat::Markdown, &mut writer)?,
+                CategorizeFormat::Html => totals(TotalsFormat::Html, &mut writer)?,
+                CategorizeFormat::Chart => {
+                    let chart = BarChart {
+                        depth: categorize.depth,
+                        color: categorize.output.is_none() && io::stdout().is_terminal(),
+                        formatter: args.format.formatter()?,
+                        ..Default::default()
+                    };
+                    chart.write(&categorized, &mut writer)?;
+                }
+                CategorizeFormat::JsonLines => {
+                    // Only reached when redacting or reading the store as the redacted
+                    // categorizer needs every transaction up front
+                    let mut writer = JsonLinesWriter::new(writer);
+                    for assignment in categorizer.assign(&transactions).into_iter().filter(|a| {
+                        category_selected(
+                            a.category_path_joined(":"),
+                            &categorize.only,
+                            &categorize.exclude,
+                        )
+                    }) {
+                        writer
+                            .write(&assignment)
+                            .context("failed to write json lines")?;
+                    }
+                    writer.flush().context("failed to write json lines")?;
+                }
+            }
+        }
+        Command::Transactions(transactions_args) => {
+            let mut transactions = transactions;
+            if let Some(sort) = transactions_args.sort {
+                sort.sort(&mut transactions);
+            }
+            write_transactions_format(
+                &transactions,
+                transactions_args.format,
+                transactions_args.category_column.then_some(&categorizer),
+                &args.format,
+                output_writer(transactions_args.output.as_ref())?,
+            )?;
+        }
+        Command::Query(query_args) => {
+            let query = Query::parse(&query_args.expression)
+                .with_context(|| format!("invalid query `{}`", query_args.expression))?;
+            let matching = transactions
+                .iter()
+                .filter(|transaction| {
+                    let category = categorizer
+                        .assign_transaction(transaction)
+                        .and_then(|assignment| assignment.category_path_joined(":"));
+                    query.matches(transaction, category.as_deref())
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            write_transactions_format(
+                &matching,
+                query_args.format,
+                query_args.category_column.then_some(&categorizer),
+                &args.format,
+                output_writer(query_args.output.as_ref())?,
+            )?;
+        }
+        Command::Statement(statement) => {
+            let assignments = categorizer.assign(&transactions);
+            let Some(month) = statement.month.or_else(|| {
+                assignments
+                    .iter()
+                    .map(|a| a.transaction.time.date_naive())
+                    .max()
+            }) else {
+                bail!("no transactions to create a statement from");
+            };
+            let income_categories = statement
+                .income_categories
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            Statement::new(
+                &categorizer,
+                &assignments,
+                month,
+                &income_categories,
+                statement.biggest,
+            )
+            .write(
+                statement.format,
+                &args.format.formatter()?,
+                output_writer(statement.output.as_ref())?,
+            )
+            .context("failed to write statement")?;
+        }
+        Command::Budget(budget) => {
+            let assignments = categorizer.assign(&transactions);
+            let as_of = budget.as_of.unwrap_or_else(|| Local::now().date_naive());
+            let burndown = Burndown::new(&categorizer, &assignments, as_of);
+            burndown
+                .write(
+                    budget.format,
+                    &args.format.formatter()?,
+                    output_writer(budget.output.as_ref())?,
+                )
+                .context("failed to write budget burndown")?;
+            let over = burndown
+                .rows
+                .iter()
+                .filter(|row| row.status == BurnStatus::OverBudget)
+                .map(|row| row.category.as_str())
+                .collect::<Vec<_>>();
+            if budget.fail_over_budget && !over.is_empty() {
+                return Err(ErrorClass::OverBudget
+                    .message(format!("over budget in {}", over.join(", ")))
+                    .into());
+            }
+        }
+        Command::Forecast(forecast_args) => {
+            let assignments = categorizer.assign(&transactions);
+            let Some(as_of) = forecast_args.as_of.or_else(|| {
+                assignments
+                    .iter()
+                    .map(|a| a.transaction.time.date_naive())
+                    .max()
+            }) else {
+                bail!("no transactions to create a forecast from");
+            };
+            if as_of
+                .checked_sub_months(Months::new(forecast_args.history + 1))
+                .and_then(|d| {
+                    d.checked_add_months(Months::new(
+                        forecast_args.history + forecast_args.months + 2,
+                    ))
+                })
+                .is_none()
+            {
+                bail!("the forecast of {as_of} is outside of the supported dates");
+            }
+            let detector = RecurringDetector {
+                min_occurrences: forecast_args.min_occurrences,
+                ..Default::default()
+            };
+            let recurring = detector.detect(assignments.iter().map(|a| a.transaction));
+            Forecast::new(
+                &assignments,
+                recurring,
+                as_of,
+                forecast_args.months,
+                forecast_args.history,
+            )
+            .write(
+                forecast_args.format,
+                &args.format.formatter()?,
+                output_writer(forecast_args.output.as_ref())?,
+            )
+            .context("failed to write forecast")?;
+        }
+        Command::Digest(digest_args) => {
+            let assignments = categorizer.assign(&transactions);
+            let end = digest_args.end.unwrap_or_else(|| Local::now().date_naive());
+            let formatter = args.format.formatter()?;
+            #[cfg(feature = "email")]
+            if let Some(path) = &digest_args.email_config_path {
+                let email_config = EmailConfig::from_yaml_file(path)
+                    .context(ErrorClass::Config.message("failed to parse email config"))?;
+                let digest = Digest::new(
+                    &categorizer,
+                    &assignments,
+                    digest_args.period.unwrap_or(email_config.period),
+                    end,
+                    &digest_args.anomaly_factor,
+                );
+                email_config
+                    .send(&digest, &formatter)
+                    .context("failed to email digest")?;
+                return Ok(());
+            }
+            Digest::new(
+                &categorizer,
+                &assignments,
+                digest_args.period.unwrap_or_default(),
+                end,
+                &digest_args.anomaly_factor,
+            )
+            .write(
+                digest_args.format,
+                &formatter,
+                output_writer(digest_args.output.as_ref())?,
+            )
+            .context("failed to write digest")?;
+        }
+        Command::NetWorth(net_worth_args) => {
+            let starting_balances = match &net_worth_args.starting_balances_path {
+                Some(path) => StartingBalances::from_yaml_file(path)
+                    .context(ErrorClass::Config.message("failed to parse starting balances"))?,
+                None => StartingBalances::default(),
+            };
+            let starting_balances = match &redactor {
+                Some(redactor) => redactor.redact_starting_balances(&starting_balances),
+                None => starting_balances,
+            };
+            let net_worth = NetWorth::new(&transactions, &starting_balances, net_worth_args.bucket);
+            net_worth
+                .write(
+                    net_worth_args.format,
+                    output_writer(net_worth_args.output.as_ref())?,
+                )
+                .context("failed to write net worth")?;
+            #[cfg(feature = "charts")]
+            if let Some(path) = &net_worth_args.chart {
+                slotted_pig_lib::charts::Chart::Lines(&net_worth.to_pivot())
+                    .save(path, (1024, 768))
+                    .with_context(|| format!("failed to write chart {}", path.display()))?;
+            }
+        }
+        Command::Balances(balances_args) => {
+            let starting_balances = match &balances_args.starting_balances_path {
+                Some(path) => StartingBalances::from_yaml_file(path)
+                    .context(ErrorClass::Config.message("failed to parse starting balances"))?,
+                None => StartingBalances::default(),
+            };
+            let starting_balances = match &redactor {
+                Some(redactor) => redactor.redact_starting_balances(&starting_balances),
+                None => starting_balances,
+            };
+            let period = balances_args
+                .period
+                .as_deref()
+                .map(|period| Period::parse(period, Local::now().date_naive()))
+                .transpose()?;
+            Balances::new(
+                &transactions,
+                &starting_balances,
+                period.as_ref().map(|period| &period.current),
+            )
+            .write(
+                balances_args.format,
+                &args.format.formatter()?,
+                output_writer(balances_args.output.as_ref())?,
+            )
+            .context("failed to write balances")?;
+        }
+        Command::Recurring(recurring_args) => {
+            let detector = RecurringDetector {
+                min_occurrences: recurring_args.min_occurrences,
+                ..Default::default()
+            };
+            let mut recurring =
+                detector.detect(transactions.iter().filter(|t| categorizer.filter(t)));
+            if !recurring_args.include_deposits {
+                recurring.retain(|r| r.average_amount.is_negative());
+            }
+            RecurringList::new(recurring, recurring_args.sort)
+                .write(
+                    recurring_args.format,
+                    &args.format.formatter()?,
+                    output_writer(recurring_args.output.as_ref())?,
+                )
+                .context("failed to write recurring transactions")?;
+        }
+        Command::TaxYears(tax_years_args) => {
+            let assignments = categorizer.assign(&transactions);
+            let fiscal_year = FiscalYear {
+                start_month: tax_years_args.fiscal_year_start,
+            };
+            let mut tax_years = TaxYears::new(
+                &categorizer,
+                &assignments,
+                fiscal_year,
+                tax_years_args.depth,
+            );
+            if tax_years_args.tax_relevant_only {
+                tax_years = tax_years.tax_relevant_only();
+            }
+            tax_years
+                .write(
+                    tax_years_args.format,
+                    &args.format.formatter()?,
+                    output_writer(tax_years_args.output.as_ref())?,
+                )
+                .context("failed to write tax years")?;
+        }
+        Command::Stats(stats_args) => {
+            Stats::new(&categorizer, &transactions)
+                .write(
+                    stats_args.format,
+                    &args.format.formatter()?,
+                    output_writer(stats_args.output.as_ref())?,
+                )
+                .context("failed to write stats")?;
+        }
+        Command::Rules(ref rules_args) => {
+            let (path, validate) = match &args.config {
+                Some(path) => (path, validate_config as fn(&str) -> Vec<Issue>),
+                None => (
+                    &args.categorizer_path,
+                    validate_categorizer as fn(&str) -> Vec<Issue>,
+                ),
+            };
+            let yaml = args.read_config_yaml(path).with_context(|| {
+                ErrorClass::Config.message(format!("failed to read {}", path.display()))
+            })?;
+            let rules = Rules::new(
+                &categorizer,
+                &transactions,
+                &rules_args.broad_percent,
+                &validate(&yaml),
+            );
+            rules
+                .write(
+                    rules_args.format,
+                    output_writer(rules_args.output.as_ref())?,
+                )
+                .context("failed to write rules")?;
+            if rules_args.fail_on_findings && rules.has_findings() {
+                return Err(ErrorClass::Validation
+                    .message("found problems with the rules")
+                    .into());
+            }
+        }
+        Command::Top(top_args) => {
+            let period = top_args
+                .period
+                .as_deref()
+                .map(|period| Period::parse(period, Local::now().date_naive()))
+                .transpose()?;
+            Top::new(
+                &categorizer.assign(&transactions),
+                top_args.n,
+                top_args.category.as_deref(),
+                period.as_ref().map(|period| &period.current),
+            )
+            .write(
+                top_args.format,
+                &args.format.formatter()?,
+                output_writer(top_args.output.as_ref())?,
+            )
+            .context("failed to write top transactions")?;
+        }
+        Command::Monthly(monthly_args) => {
+            let mut pivot = Pivot::new(
+                &categorizer.assign(&transactions),
+                monthly_args.by,
+                monthly_args.depth,
+            );
+            if !monthly_args.categories.is_empty() {
+                pivot.retain_top_level(&monthly_args.categories);
+            }
+            pivot
+                .write(
+                    monthly_args.format,
+                    &args.format.formatter()?,
+                    output_writer(monthly_args.output.as_ref())?,
+                )
+                .context("failed to write monthly totals")?;
+        }
+        Command::Uncategorized(uncategorized_args) => {
+            let (_categorized, uncategorized) = categorizer.categorize(&transactions);
+            UncategorizedPayees::new(uncategorized)
+                .write(
+                    uncategorized_args.format,
+                    &args.format.formatter()?,
+                    output_writer(uncategorized_args.output.as_ref())?,
+                )
+                .context("failed to write uncategorized payees")?;
+        }
+        Command::Export(export) => {
+            let assignments = categorizer.assign(&transactions);
+            match export {
+                Export::Ledger(ledger) => {
+                    let default = LedgerExporter::default();
+                    let exporter = LedgerExporter {
+                        category_prefix: ledger.category_prefix,
+                        account_prefix: ledger.account_prefix,
+                        uncategorized_account: ledger
+                            .uncategorized_account
+                            .unwrap_or(default.uncategorized_account),
+                        commodity: ledger.commodity,
+                    };
+                    exporter
+                        .write(&assignments, output_writer(ledger.output.as_ref())?)
+                        .context("failed to write ledger journal")?;
+                }
+                Export::Beancount(beancount) => {
+                    let exporter = match beancount.config_path {
+                        Some(path) => BeancountExporter::from_yaml_file(path).context(
+                            ErrorClass::Config.message("failed to parse beancount config"),
+                        )?,
+                        None => BeancountExporter::default(),
+                    };
+                    exporter
+                        .write(&assignments, output_writer(beancount.output.as_ref())?)
+                        .context("failed to write beancount ledger")?;
+                }
+                Export::Gnucash(gnucash) => {
+                    let exporter = match gnucash.config_path {
+                        Some(path) => GnucashExporter::from_yaml_file(path).context(
+                            ErrorClass::Config.message("failed to parse gnucash config"),
+                        )?,
+                        None => GnucashExporter::default(),
+                    };
+                    exporter
+                        .write(
+                            &assignments,
+                            gnucash.format,
+                            output_writer(gnucash.output.as_ref())?,
+                        )
+                        .context("failed to write gnucash file")?;
+                }
+                Export::Sankey(sankey) => {
+                    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+                    let income_categories = sankey
+                        .income_categories
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    Sankey::new(&categorized, &income_categories)
+                        .write(sankey.format, output_writer(sankey.output.as_ref())?)
+                        .context("failed to write sankey data")?;
+                }
+                Export::Pivot(pivot) => {
+                    Pivot::new(&assignments, pivot.bucket, pivot.depth)
+                        .write(
+                            pivot.format,
+                            &args.format.formatter()?,
+                            output_writer(pivot.output.as_ref())?,
+                        )
+                        .context("failed to write pivot")?;
+                }
+                Export::Ical(ical) => {
+                    let detector = RecurringDetector {
+                        min_occurrences: ical.min_occurrences,
+                        ..Default::default()
+                    };
+                    let recurring =
+                        detector.detect(transactions.iter().filter(|t| categorizer.filter(t)));
+                    let exporter = IcalExporter {
+                        reminder_days: ical.reminder_days,
+                        include_deposits: ical.include_deposits,
+                        formatter: args.format.formatter()?,
+                    };
+                    exporter
+                        .write(&recurring, output_writer(ical.output.as_ref())?)
+                        .context("failed to write calendar")?;
+                }
+                Export::Sql(sql) => {
+                    let exporter = SqlExporter {
+                        drop_existing: !sql.keep_existing,
+                    };
+                    #[cfg(feature = "sqlite")]
+                    if let Some(path) = &sql.sqlite {
+                        exporter
+                            .write_sqlite(&categorizer, &assignments, path)
+                            .context("failed to write sqlite database")?;
+                        return Ok(());
+                    }
+                    exporter
+                        .write(
+                            &categorizer,
+                            &assignments,
+                            output_writer(sql.output.as_ref())?,
+                        )
+                        .context("failed to write sql script")?;
+                }
+                Export::Dot(dot_args) => {
+                    let categorized = dot_args
+                        .totals
+                        .then(|| categorizer.categorize(&transactions).0);
+                    dot(
+                        &categorizer,
+                        categorized.as_ref(),
+                        &args.format.formatter()?,
+                        output_writer(dot_args.output.as_ref())?,
+                    )
+                    .context("failed to write dot graph")?;
+                }
+                #[cfg(feature = "parquet")]
+                Export::Parquet(parquet) => {
+                    let exporter = ParquetExporter {
+                        bucket: parquet.bucket,
+                        amount_scale: parquet.amount_scale,
+                    };
+                    let file = File::create(&parquet.output).with_context(|| {
+                        format!("failed to create output file {}", parquet.output.display())
+                    })?;
+                    exporter
+                        .write(&assignments, BufWriter::new(file))
+                        .context("failed to write parquet")?;
+                }
+            }
+        }
+        Command::Explain(explain) => {
+            let index = explain
+                .transaction_index
+                .expect("explaining a description does not parse transactions");
+            let transaction = transactions.get(index).with_context(|| {
+                format!(
+                    "transaction index {index} is out of range of {} transactions",
+                    transactions.len()
+                )
+            })?;
+            print!("{}", categorizer.explain(transaction));
+        }
+        Command::Validate
+        | Command::Test(_)
+        | Command::Init(_)
+        | Command::Generate(_)
+        | Command::Schema(_)
+        | Command::Watch(_)
+        | Command::Convert(_)
+        | Command::Anonymize(_) => {
+            unreachable!("commands without categorizing are handled before parsing transactions")
+        }
+        Command::Diff(diff) => diff.write(&args.format.formatter()?, |range| {
+            let in_range = transactions
+                .iter()
+                .filter(|t| range.contains(t.time.date_naive()))
+                .cloned()
+                .collect::<Vec<_>>();
+            categorizer.categorize(&in_range).0
+        })?,
+        Command::Report(report) => {
+            let period = match &report.period {
+                Some(period) => Period::parse(period, Local::now().date_naive())?,
+                None => {
+                    let Some(latest) = transactions.iter().map(|t| t.time.date_naive()).max()
+                    else {
+                        bail!("no transactions to create a report from");
+                    };
+                    Period::bucket(Bucket::Month, latest)
+                }
+            };
+            let assignments = categorizer.assign(&transactions);
+            let income_categories = report
+                .income_categories
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            if let StatementFormat::Html = report.format {
+                let html = HtmlReport::new(
+                    &categorizer,
+                    &transactions,
+                    &period,
+                    &income_categories,
+                    report.biggest,
+                );
+                #[cfg(feature = "charts")]
+                let html = HtmlReport {
+                    charts: html_charts(&categorizer, &transactions, &period)?,
+                    ..html
+                };
+                html.write(
+                    &args.format.formatter()?,
+                    output_writer(report.output.as_ref())?,
+                )
+                .context("failed to write report")?;
+            } else {
+                Statement::for_period(
+                    &categorizer,
+                    &assignments,
+                    &period,
+                    &income_categories,
+                    report.biggest,
+                )
+                .write(
+                    report.format,
+                    &args.format.formatter()?,
+                    output_writer(report.output.as_ref())?,
+                )
+                .context("failed to write report")?;
+            }
+            #[cfg(feature = "charts")]
+            if let Some(charts) = &report.charts {
+                let transactions = transactions
+                    .into_iter()
+                    .filter(|t| period.current.contains(t.time.date_naive()))
+                    .collect::<Vec<_>>();
+                write_charts(&categorizer, &transactions, charts, report.chart_format)?;
+            }
+        }
+        Command::AddRule => {
+            if redactor.is_some() {
+                bail!("the categorizer config cannot be edited while redacting");
+            }
+            if args.config.is_some() {
+                bail!("add-rule edits --categorizer-path, it cannot edit a combined --config");
+            }
+            add_rule::run(&args, &transactions)?;
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui => {
+            if redactor.is_some() {
+                bail!("the categorizer config cannot be edited while redacting");
+            }
+            if args.config.is_some() {
+                bail!("the tui edits --categorizer-path, it cannot edit a combined --config");
+            }
+            tui::run(&args, transactions)?;
+        }
+        #[cfg(feature = "serve")]
+        Command::Serve(_) => unreachable!("serve is handled before parsing transactions"),
+        #[cfg(feature = "sqlite")]
+        Command::Import(_) => unreachable!("import is handled before reading transactions"),
+    }
+    Ok(())
+}
+
+/// Write the donut, monthly stacked bars, and trend line charts to the charts directory
+#[cfg(feature = "charts")]
+fn write_charts(
+    categorizer: &Categorizer,
+    transactions: &[Transaction],
+    directory: &Path,
+    format: ChartFormat,
+) -> Result<()> {
+    use slotted_pig_lib::charts::Chart;
+
+    const SIZE: (u32, u32) = (1024, 768);
+
+    let (categorized, _uncategorized) = categorizer.categorize(transactions);
+    let pivot = Pivot::new(&categorizer.assign(transactions), Bucket::Month, Some(1));
+    let extension = match format {
+        ChartFormat::Svg => "svg",
+        ChartFormat::Png => "png",
+    };
+    std::fs::create_dir_all(directory).context("failed to create charts directory")?;
+    let charts = [
+        ("categories", Chart::Donut(&categorized.categorized)),
+        ("monthly", Chart::StackedBars(&pivot)),
+        ("trends", Chart::Lines(&pivot)),
+    ];
+    for (name, chart) in charts {
+        let path = directory.join(name).with_extension(extension);
+        chart
+            .save(&path, SIZE)
+            .with_context(|| format!("failed to write chart {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// SVG charts of the period embedded in the HTML report
+#[cfg(feature = "charts")]
+fn html_charts(
+    categorizer: &Categorizer,
+    transactions: &[Transaction],
+    period: &Period,
+) -> Result<Vec<slotted_pig_lib::report::HtmlChart>> {
+    use slotted_pig_lib::{charts::Chart, report::HtmlChart};
+
+    const SIZE: (u32, u32) = (800, 600);
+
+    let transactions = transactions
+        .iter()
+        .filter(|t| period.current.contains(t.time.date_naive()))
+        .cloned()
+        .collect::<Vec<_>>();
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let pivot = Pivot::new(&categorizer.assign(&transactions), Bucket::Month, Some(1));
+    [
+        ("Categories", Chart::Donut(&categorized.categorized)),
+        ("Monthly", Chart::StackedBars(&pivot)),
+    ]
+    .into_iter()
+    .map(|(title, chart)| {
+        Ok(HtmlChart {
+            title: title.to_string(),
+            svg: chart.to_svg(SIZE).context("failed to render chart")?,
+        })
+    })
+    .collect()
+}
+
+impl Args {
+    /// Check if transactions are read from a store instead of transaction files
+    fn reads_store(&self) -> bool {
+        #[cfg(feature = "sqlite")]
+        return self.store.is_some();
+        #[cfg(not(feature = "sqlite"))]
+        return false;
+    }
+
+    /// Check if transactions are only read from transaction files, not a store or source commands
+    fn reads_only_transaction_files(&self) -> bool {
+        !self.reads_store() && self.source_commands.is_empty()
+    }
+}
+
+/// Transaction files matching the pattern
+fn transaction_files(transaction_path_pattern: Option<&str>) -> Result<Vec<PathBuf>> {
+    let transaction_path_pattern = transaction_path_pattern
+        .context("--transaction-path-pattern or a config transaction_path_pattern is required")?;
+    Ok(glob::glob(transaction_path_pattern)?
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to find transaction files")?
+        .into_iter()
+        .filter(|f| f.is_file())
+        .collect())
+}
+
+/// Transactions passing the filters from the store or the transaction files, and the source
+/// commands
+///
+/// The transaction files are optional when there are source commands and no transaction path
+/// pattern.
+fn read_transactions(
+    args: &Args,
+    transaction_parser: &TransactionParser,
+    transaction_path_pattern: Option<&str>,
+) -> Result<Vec<Transaction>> {
+    let mut sources = TransactionSources::default();
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &args.store {
+        sources.register(
+            args.open_store(path)
+                .with_context(|| format!("failed to read store {}", path.display()))?,
+        );
+    }
+    if !args.reads_store()
+        && (transaction_path_pattern.is_some() || args.source_commands.is_empty())
+    {
+        sources.register(transaction_parser.files(transaction_files(transaction_path_pattern)?));
+    }
+    for command in &args.source_commands {
+        sources.register(CommandSource::new(command));
+    }
+    let mut transactions = sources
+        .fetch()
+        .context(ErrorClass::TransactionParse.message("failed to read transactions"))?;
+    transactions.retain(|t| args.filter.matches(t));
+    Ok(transactions)
+}
+
+/// Parse the transaction files and write them in the convert format
+fn convert(args: &Args, convert_args: &Convert) -> Result<()> {
+    if args.redact.redact {
+        bail!("convert cannot redact without a categorizer, use `transactions --redact` instead");
+    }
+    let (transaction_parser, files) = args.transaction_parser_and_files(&convert_args.files)?;
+    // Read like every other command so OFX and QIF statements convert as well as csv files
+    let mut transactions = transaction_parser
+        .files(files)
+        .fetch()
+        .context(ErrorClass::TransactionParse.message("failed to parse transaction files"))?;
+    transactions.retain(|t| args.filter.matches(t));
+    write_transactions_format(
+        &transactions,
+        convert_args.format,
+        None,
+        &args.format,
+        output_writer(convert_args.output.as_ref())?,
+    )
+}
+
+/// Write the JSON schema of a config file kind
+fn schema(schema_args: &SchemaArgs) -> Result<()> {
+    let mut writer = output_writer(schema_args.output.as_ref())?;
+    serde_json::to_writer_pretty(&mut writer, &schema_args.kind.json_schema())
+        .context("failed to write the schema")?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write anonymized copies of the transaction files to the output directory
+fn anonymize(args: &Args, anonymize_args: &Anonymize) -> Result<()> {
+    let (transaction_parser, files) = args.transaction_parser_and_files(&anonymize_args.files)?;
+    let redactor = Redactor {
+        salt: anonymize_args.salt.clone(),
+        ..Default::default()
+    };
+    fs::create_dir_all(&anonymize_args.output_dir).with_context(|| {
+        format!(
+            "failed to create output directory {}",
+            anonymize_args.output_dir.display()
+        )
+    })?;
+    let mut written = BTreeSet::new();
+    for file in files {
+        let name = file
+            .file_name()
+            .with_context(|| format!("{} is not a file", file.display()))?;
+        let output = anonymize_args.output_dir.join(name);
+        if !written.insert(output.clone()) {
+            bail!("more than one file is named {}", name.to_string_lossy());
+        }
+        if output.canonicalize().ok() == file.canonicalize().ok() {
+            bail!("anonymizing {} would overwrite it", file.display());
         }
-        Command::Transactions => {
-            write_transactions(&transactions.iter().collect::<Vec<_>>(), io::stdout())?
+        let mut anonymized = Vec::new();
+        transaction_parser
+            .map_csv(&file, &mut anonymized, |column, value| {
+                redactor.anonymize(column, value)
+            })
+            .with_context(|| format!("failed to anonymize {}", file.display()))?;
+        fs::write(&output, anonymized)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        println!("wrote {}", output.display());
+    }
+    Ok(())
+}
+
+/// Write the transactions in a format, csv times are formatted by the formatter if given
+fn write_transactions_format<W: io::Write>(
+    transactions: &[Transaction],
+    format: TransactionsFormat,
+    categorizer: Option<&Categorizer>,
+    format_args: &FormatArgs,
+    mut writer: W,
+) -> Result<()> {
+    let table = |format, writer| {
+        TransactionTable::new(transactions, categorizer)
+            .write(format, &format_args.formatter()?, writer)
+            .context("failed to write transaction table")
+    };
+    match format {
+        TransactionsFormat::Csv => match categorizer {
+            Some(categorizer) => write_categorized_transactions(
+                categorizer,
+                transactions,
+                format_args.csv_time_formatter()?.as_ref(),
+                writer,
+            )?,
+            None => write_transactions(
+                &transactions.iter().collect::<Vec<_>>(),
+                format_args.csv_time_formatter()?.as_ref(),
+                writer,
+            )?,
+        },
+        TransactionsFormat::Ofx => OfxExporter::default()
+            .write(transactions, writer)
+            .context("failed to write ofx")?,
+        TransactionsFormat::Qif => QifExporter::default()
+            .write(transactions, writer)
+            .context("failed to write qif")?,
+        TransactionsFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, transactions)?;
+            writeln!(writer)?;
         }
+        TransactionsFormat::Yaml => serde_yaml::to_writer(writer, transactions)?,
+        TransactionsFormat::Markdown => table(TransactionTableFormat::Markdown, writer)?,
+        TransactionsFormat::Html => table(TransactionTableFormat::Html, writer)?,
+        TransactionsFormat::Table => table(TransactionTableFormat::Text, writer)?,
+    }
+    Ok(())
+}
+
+/// Add the transactions of the files which are not already in the store
+#[cfg(feature = "sqlite")]
+fn import(
+    args: &Args,
+    import_args: &Import,
+    transaction_parser: &TransactionParser,
+    transaction_path_pattern: Option<&str>,
+) -> Result<()> {
+    let path = args
+        .store
+        .as_ref()
+        .context("--store is required to import")?;
+    if args.redact.redact {
+        bail!("redacted transactions cannot be imported");
     }
+    let files = if import_args.files.is_empty() {
+        transaction_files(transaction_path_pattern)?
+    } else {
+        import_args.files.clone()
+    };
+    // Read like every other command so OFX and QIF statements import as well as csv files
+    let mut transactions = transaction_parser
+        .files(files)
+        .fetch()
+        .context(ErrorClass::TransactionParse.message("failed to parse transaction files"))?;
+    transactions.retain(|t| args.filter.matches(t));
+    let imported = args
+        .open_store(path)
+        .and_then(|mut store| Ok(store.import(&transactions)?))
+        .with_context(|| format!("failed to import into store {}", path.display()))?;
+    println!(
+        "added {} transactions, skipped {} already in {}",
+        imported.added,
+        imported.skipped,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Parse a month (ie `2024-02`) as its first day
+fn parse_month(month: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .with_context(|| format!("invalid month {month}, expected YYYY-MM"))
+}
+
+/// Check if a colon separated category path, `None` if uncategorized, is within the `only`
+/// category paths and not within the `exclude` category paths
+///
+/// Every category path, including uncategorized, is within `only` if it is empty.
+fn category_selected(category: Option<String>, only: &[String], exclude: &[String]) -> bool {
+    let within = |paths: &[String]| {
+        category.as_ref().is_some_and(|category| {
+            paths.iter().any(|path| {
+                category == path
+                    || category
+                        .strip_prefix(path.as_str())
+                        .is_some_and(|rest| rest.starts_with(':'))
+            })
+        })
+    };
+    (only.is_empty() || within(only)) && !within(exclude)
+}
+
+/// Open a buffered writer to the path or stdout if no path is given
+fn output_writer(path: Option<&PathBuf>) -> Result<Box<dyn io::Write>> {
+    Ok(match path {
+        Some(path) => {
+            if let Some(directory) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(directory).with_context(|| {
+                    format!("failed to create output directory {}", directory.display())
+                })?;
+            }
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("failed to create output file {}", path.display())
+            })?))
+        }
+        None => Box::new(io::stdout()),
+    })
+}
+
+/// Rewrite the output whenever the transaction files matching the pattern or the configs change
+///
+/// Files are checked by modification time every interval. Only changed files are parsed again,
+/// unless the transaction parser changed. Problems (ie a file which is still downloading) are
+/// logged and retried on the next change instead of stopping the watch.
+fn watch(args: &Args, watch_args: &WatchArgs) -> Result<()> {
+    let formatter = args.format.formatter()?;
+    let redactor = args.redact.redactor();
+    let mut config_times = None;
+    let mut config = None;
+    let mut files = BTreeMap::<PathBuf, (SystemTime, Vec<Transaction>)>::new();
+    loop {
+        let mut changed = false;
+        let times = args
+            .config_paths()
+            .into_iter()
+            .map(modified)
+            .collect::<Result<Vec<_>>>()?;
+        if config_times.as_ref() != Some(&times) {
+            config_times = Some(times);
+            changed = true;
+            // Every file must be parsed again with a new transaction parser
+            files.clear();
+            config = match args.config() {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    tracing::warn!("failed to load configs: {e:#}");
+                    None
+                }
+            };
+        }
+        let Some(Config {
+            transaction_parser,
+            categorizer,
+            transaction_path_pattern,
+            ..
+        }) = &config
+        else {
+            thread::sleep(Duration::from_secs(watch_args.interval));
+            continue;
+        };
+        let transaction_path_pattern = transaction_path_pattern.as_deref().context(
+            "--transaction-path-pattern or a config transaction_path_pattern is required",
+        )?;
+
+        let paths = glob::glob(transaction_path_pattern)?
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file())
+            .collect::<BTreeSet<_>>();
+        let before = files.len();
+        files.retain(|path, _| paths.contains(path));
+        changed |= files.len() != before;
+        for path in paths {
+            let time = modified(&path)?;
+            if files.get(&path).is_some_and(|(t, _)| *t == time) {
+                continue;
+            }
+            match transaction_parser.parse_csv(&path) {
+                Ok(transactions) => {
+                    tracing::info!("parsed {}", path.display());
+                    files.insert(path, (time, transactions));
+                    changed = true;
+                }
+                Err(e) => tracing::warn!("failed to parse {}: {e}", path.display()),
+            }
+        }
+
+        if changed {
+            let transactions = files
+                .values()
+                .flat_map(|(_, transactions)| transactions.iter())
+                .filter(|t| args.filter.matches(t))
+                .cloned()
+                .collect::<Vec<_>>();
+            let result = match &redactor {
+                Some(redactor) => {
+                    let (categorizer, transactions) = redactor.redact(categorizer, &transactions);
+                    write_watch_output(&categorizer, &transactions, watch_args, &formatter)
+                }
+                None => write_watch_output(categorizer, &transactions, watch_args, &formatter),
+            };
+            match result {
+                Ok(()) => tracing::info!("wrote {}", watch_args.output.display()),
+                Err(e) => tracing::warn!("failed to write output: {e:#}"),
+            }
+        }
+        thread::sleep(Duration::from_secs(watch_args.interval));
+    }
+}
+
+/// Serve the API over the configs and transaction files, which are reloaded on request
+#[cfg(feature = "serve")]
+fn serve(args: &Args, serve_args: &ServeArgs) -> Result<()> {
+    let redactor = args.redact.redactor();
+    let load = || {
+        let Config {
+            transaction_parser,
+            categorizer,
+            transaction_path_pattern,
+            ..
+        } = args.config()?;
+        let transactions = read_transactions(
+            args,
+            &transaction_parser,
+            transaction_path_pattern.as_deref(),
+        )?;
+        let (categorizer, transactions) = match &redactor {
+            Some(redactor) => redactor.redact(&categorizer, &transactions),
+            None => (categorizer, transactions),
+        };
+        Ok(serve::Data {
+            categorizer,
+            transactions,
+        })
+    };
+    serve::run(&serve_args.address, serve_args.ui_dir.as_deref(), load)
+}
+
+fn modified(path: &Path) -> Result<SystemTime> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .with_context(|| format!("failed to read modification time of {}", path.display()))
+}
+
+/// Write to a temporary file and rename it so readers never see a partial output
+fn write_watch_output(
+    categorizer: &Categorizer,
+    transactions: &[Transaction],
+    watch_args: &WatchArgs,
+    formatter: &Formatter,
+) -> Result<()> {
+    let mut output = Vec::new();
+    match watch_args.format {
+        WatchFormat::Yaml => {
+            let (mut categorized, _uncategorized) = categorizer.categorize(transactions);
+            categorizer.round_totals(&mut categorized);
+            serde_yaml::to_writer(&mut output, &categorized)?;
+        }
+        WatchFormat::Json => {
+            let (mut categorized, _uncategorized) = categorizer.categorize(transactions);
+            categorizer.round_totals(&mut categorized);
+            serde_json::to_writer_pretty(&mut output, &categorized)?;
+        }
+        WatchFormat::Html => {
+            let assignments = categorizer.assign(transactions);
+            let month = assignments
+                .iter()
+                .map(|a| a.transaction.time.date_naive())
+                .max()
+                .context("no transactions to create a statement from")?;
+            let income_categories = watch_args
+                .income_categories
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            Statement::new(categorizer, &assignments, month, &income_categories, 5).write(
+                StatementFormat::Html,
+                formatter,
+                &mut output,
+            )?;
+        }
+    }
+    let mut temporary = watch_args.output.clone().into_os_string();
+    temporary.push(".tmp");
+    fs::write(&temporary, output)
+        .and_then(|()| fs::rename(&temporary, &watch_args.output))
+        .with_context(|| format!("failed to write {}", watch_args.output.display()))
+}
+
+/// Write the starter configs to the config paths
+fn init(args: &Args, init_args: &Init) -> Result<()> {
+    let categorizer = if init_args.sample_categories {
+        include_str!("../templates/categorizer/sample.yaml")
+    } else {
+        include_str!("../templates/categorizer/minimal.yaml")
+    };
+    let transaction_parser = init_args.preset.transaction_parser();
+    let combined;
+    let configs = match &args.config {
+        Some(path) => {
+            combined = format!(
+                "transaction_path_pattern: transactions/*.csv\ntransaction_parser:\n{}categorizer:\n{}",
+                indent_yaml(transaction_parser),
+                indent_yaml(categorizer)
+            );
+            vec![(path, combined.as_str())]
+        }
+        None => vec![
+            (&args.transaction_parser_path, transaction_parser),
+            (&args.categorizer_path, categorizer),
+        ],
+    };
+    if !init_args.force {
+        for (path, _) in &configs {
+            if path.exists() {
+                bail!(
+                    "{} already exists, use --force to overwrite it",
+                    path.display()
+                );
+            }
+        }
+    }
+    for (path, contents) in configs {
+        fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn generate(generate_args: &Generate) -> Result<()> {
+    let start = generate_args.start.unwrap_or_else(|| {
+        Bucket::Month.start(Local::now().date_naive()) - Months::new(generate_args.months)
+    });
+    let generator = Generator {
+        months: generate_args.months,
+        accounts: generate_args.accounts,
+        start,
+        seed: generate_args.seed,
+    };
+    if start
+        .checked_add_months(Months::new(generator.months))
+        .is_none()
+    {
+        bail!(
+            "{} months after {start} is past the last supported date",
+            generator.months
+        );
+    }
+    let transactions = generator.transactions();
+    let directory = &generate_args.output_dir;
+    let mut files = generator
+        .account_names()
+        .into_iter()
+        .map(|account| {
+            let mut csv = Vec::new();
+            write_csv(
+                transactions.iter().filter(|t| t.account == account),
+                &mut csv,
+            )
+            .with_context(|| format!("failed to write transactions of {account}"))?;
+            Ok((
+                directory.join(format!("{}.csv", account.replace(' ', "_"))),
+                csv,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    files.push((
+        directory.join("transaction_parser.yaml"),
+        TRANSACTION_PARSER_YAML.as_bytes().to_vec(),
+    ));
+    files.push((
+        directory.join("categorizer.yaml"),
+        CATEGORIZER_YAML.as_bytes().to_vec(),
+    ));
+    if !generate_args.force {
+        for (path, _) in &files {
+            if path.exists() {
+                bail!(
+                    "{} already exists, use --force to overwrite it",
+                    path.display()
+                );
+            }
+        }
+    }
+    fs::create_dir_all(directory)
+        .with_context(|| format!("failed to create output directory {}", directory.display()))?;
+    for (path, contents) in files {
+        fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Nest a yaml document under a key
+fn indent_yaml(yaml: &str) -> String {
+    yaml.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::from("\n")
+            } else {
+                format!("  {line}\n")
+            }
+        })
+        .collect()
+}
+
+/// Print every problem in the configs prefixed by its file and location, failing if there are any
+fn validate(args: &Args) -> Result<()> {
+    let configs = match &args.config {
+        Some(path) => vec![(path, validate_config as fn(&str) -> Vec<Issue>)],
+        None => vec![
+            (
+                &args.categorizer_path,
+                validate_categorizer as fn(&str) -> Vec<Issue>,
+            ),
+            (&args.transaction_parser_path, validate_transaction_parser),
+        ],
+    };
+    let mut problems = 0;
+    for (path, validate) in configs {
+        let yaml = args.read_config_yaml(path).with_context(|| {
+            ErrorClass::Config.message(format!("failed to read {}", path.display()))
+        })?;
+        let issues = validate(&yaml);
+        if issues.is_empty() {
+            println!("{}: ok", path.display());
+        }
+        for issue in &issues {
+            let separator = if issue.line.is_some() { ":" } else { ": " };
+            println!("{}{separator}{issue}", path.display());
+        }
+        problems += issues.len();
+    }
+    if problems > 0 {
+        return Err(ErrorClass::Validation
+            .message(format!("found {problems} problems in the configs"))
+            .into());
+    }
+    Ok(())
+}
+
+/// Run the matcher tests of the categorizer and every fixture
+fn test(args: &Args, test_args: &TestArgs) -> Result<()> {
+    let categorizer = match &args.config {
+        Some(path) => {
+            args.read_config(path, Config::from_yaml_file, Config::from_yaml_buffer)
+                .with_context(|| {
+                    ErrorClass::Config.message(format!("failed to parse config {}", path.display()))
+                })?
+                .categorizer
+        }
+        None => args.categorizer()?,
+    };
+    let mut outcomes = categorizer.run_tests();
+    for fixture in &test_args.fixtures {
+        for test in MatcherTest::from_csv_file(fixture)
+            .with_context(|| format!("failed to read fixture {}", fixture.display()))?
+        {
+            outcomes.push(categorizer.test(&test));
+        }
+    }
+    for outcome in &outcomes {
+        println!("{outcome}");
+    }
+    let failed = outcomes.iter().filter(|o| !o.passed()).count();
+    println!("{} passed, {failed} failed", outcomes.len() - failed);
+    if failed > 0 {
+        return Err(ErrorClass::Validation
+            .message(format!("{failed} matcher tests failed"))
+            .into());
+    }
+    Ok(())
+}
+
+/// Transaction csv row
+#[derive(Serialize)]
+struct TransactionRow<'a> {
+    amount: &'a BigDecimal,
+    account: &'a str,
+    description: &'a str,
+    time: String,
+}
+
+/// Transaction csv row with its category
+#[derive(Serialize)]
+struct CategorizedTransactionRow<'a> {
+    amount: &'a BigDecimal,
+    account: &'a str,
+    description: &'a str,
+    time: String,
+    category: Option<String>,
+}
+
+/// Format a transaction time for csv, RFC 3339 unless a formatter is given
+fn csv_time(time: &DateTime<Utc>, formatter: Option<&Formatter>) -> String {
+    match formatter {
+        Some(formatter) => formatter.format_time(time),
+        None => time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+    }
+}
+
+/// Write every transaction with the category it is assigned, transactions removed by the
+/// transaction filters have no category
+fn write_categorized_transactions<W: io::Write>(
+    categorizer: &Categorizer,
+    transactions: &[Transaction],
+    time_formatter: Option<&Formatter>,
+    writer: W,
+) -> Result<()> {
+    let mut writer = Writer::from_writer(writer);
+    for transaction in transactions {
+        let category = categorizer
+            .assign_transaction(transaction)
+            .and_then(|assignment| assignment.category_path_joined(":"));
+        writer.serialize(CategorizedTransactionRow {
+            amount: &transaction.amount,
+            account: &transaction.account,
+            description: &transaction.description,
+            time: csv_time(&transaction.time, time_formatter),
+            category,
+        })?;
+    }
+    writer.flush()?;
     Ok(())
 }
 
-fn write_transactions<W: io::Write>(transactions: &[&Transaction], writer: W) -> Result<()> {
+fn write_transactions<W: io::Write>(
+    transactions: &[&Transaction],
+    time_formatter: Option<&Formatter>,
+    writer: W,
+) -> Result<()> {
     let mut writer = Writer::from_writer(writer);
-    for row in transactions {
-        writer.serialize(row)?;
+    for transaction in transactions {
+        writer.serialize(TransactionRow {
+            amount: &transaction.amount,
+            account: &transaction.account,
+            description: &transaction.description,
+            time: csv_time(&transaction.time, time_formatter),
+        })?;
     }
     writer.flush()?;
     Ok(())
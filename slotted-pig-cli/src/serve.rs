@@ -0,0 +1,155 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use slotted_pig_lib::{categorizer::Categorizer, transaction::Transaction};
+use tiny_http::{Header, Method, Response, Server};
+
+/// Path the web UI is built to be served under, the `base_path` in `Dioxus.toml`
+const UI_BASE_PATH: &str = "/slotted-pig";
+
+/// Categorizer and transactions served by the API
+pub struct Data {
+    pub categorizer: Categorizer,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Serialize)]
+struct Recategorized {
+    transactions: usize,
+    uncategorized: usize,
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    error: String,
+}
+
+/// Serve the JSON API and the built web UI until the process is stopped
+///
+/// `load` reads the configs and transaction files, it is called on start and for every
+/// `POST /api/recategorize` so edits on disk are picked up without restarting.
+pub fn run(address: &str, ui_dir: Option<&Path>, load: impl Fn() -> Result<Data>) -> Result<()> {
+    let mut data = load()?;
+    let server =
+        Server::http(address).map_err(|e| anyhow!("failed to listen on {address}: {e}"))?;
+    tracing::info!("serving on http://{address}");
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        let path = url.split('?').next().unwrap_or_default().to_string();
+        tracing::debug!("{} {path}", request.method());
+        let response = match (request.method(), path.as_str()) {
+            (Method::Get, "/api/transactions") => json(&data.transactions),
+            (Method::Get, "/api/categorized") => {
+                let (mut categorized, _uncategorized) =
+                    data.categorizer.categorize(&data.transactions);
+                data.categorizer.round_totals(&mut categorized);
+                json(&categorized)
+            }
+            (Method::Get, "/api/uncategorized") => {
+                let (_categorized, uncategorized) = data.categorizer.categorize(&data.transactions);
+                json(&uncategorized)
+            }
+            (Method::Post, "/api/recategorize") => match load() {
+                Ok(loaded) => {
+                    data = loaded;
+                    let (_categorized, uncategorized) =
+                        data.categorizer.categorize(&data.transactions);
+                    json(&Recategorized {
+                        transactions: data.transactions.len(),
+                        uncategorized: uncategorized.len(),
+                    })
+                }
+                Err(e) => {
+                    tracing::warn!("failed to recategorize: {e:#}");
+                    error(500, format!("{e:#}"))
+                }
+            },
+            (_, path) if path.starts_with("/api/") => error(404, format!("no endpoint {path}")),
+            (Method::Get, "/") => redirect(&format!("{UI_BASE_PATH}/")),
+            (Method::Get, path) => match ui_dir {
+                Some(ui_dir) => static_file(ui_dir, path),
+                None => error(404, String::from("no --ui-dir to serve the web UI from")),
+            },
+            _ => error(405, String::from("method not allowed")),
+        };
+        if let Err(e) = request.respond(response) {
+            tracing::warn!("failed to respond to {path}: {e}");
+        }
+    }
+    Ok(())
+}
+
+type BoxedResponse = Response<Box<dyn Read + Send>>;
+
+fn json<T: Serialize>(value: &T) -> BoxedResponse {
+    match serde_json::to_vec(value) {
+        Ok(body) => with_body(200, body, "application/json"),
+        Err(e) => error(500, e.to_string()),
+    }
+}
+
+fn error(status: u16, error: String) -> BoxedResponse {
+    let body = serde_json::to_vec(&ApiError { error }).unwrap_or_default();
+    with_body(status, body, "application/json")
+}
+
+fn redirect(location: &str) -> BoxedResponse {
+    with_body(302, Vec::new(), "text/plain").with_header(header("Location", location))
+}
+
+/// File of the built web UI, paths without a file (ie client side routes) get `index.html`
+fn static_file(ui_dir: &Path, path: &str) -> BoxedResponse {
+    let Some(relative) = path.strip_prefix(UI_BASE_PATH) else {
+        return error(404, format!("no file {path}"));
+    };
+    let relative = PathBuf::from(relative.trim_start_matches('/'));
+    // Only allow plain names so requests cannot escape the ui directory
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return error(404, format!("no file {path}"));
+    }
+    let mut file = ui_dir.join(&relative);
+    if !file.is_file() {
+        file = ui_dir.join("index.html");
+    }
+    match fs::read(&file) {
+        Ok(body) => with_body(200, body, content_type(&file)),
+        Err(_) => error(404, format!("no file {path}")),
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript",
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+fn with_body(status: u16, body: Vec<u8>, content_type: &str) -> BoxedResponse {
+    let length = body.len();
+    Response::new(
+        status.into(),
+        vec![header("Content-Type", content_type)],
+        Box::new(std::io::Cursor::new(body)) as Box<dyn Read + Send>,
+        Some(length),
+        None,
+    )
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("header is ascii")
+}
@@ -0,0 +1,71 @@
+use std::{env, path::Path, sync::Mutex};
+
+use clap::{error::ErrorKind, CommandFactory};
+
+use crate::{parse_args, Args};
+
+/// Tests reading `SLOTTED_PIG_*` environment variables hold the lock so they do not see each
+/// other's variables
+static ENV: Mutex<()> = Mutex::new(());
+
+/// Command with the combined config set in the defaults file like `defaults::apply`
+fn command_with_default_config() -> clap::Command {
+    Args::command().mut_arg("config", |a| a.default_value("combined.yaml"))
+}
+
+#[test]
+fn test_command_line_paths_override_default_config() {
+    let _env = ENV.lock().unwrap();
+    let args = parse_args(
+        command_with_default_config(),
+        [
+            "slotted-pig",
+            "--categorizer-path",
+            "categorizer.yaml",
+            "validate",
+        ],
+    )
+    .unwrap();
+    assert_eq!(args.config, None);
+    assert_eq!(args.categorizer_path, Path::new("categorizer.yaml"));
+
+    let args = parse_args(command_with_default_config(), ["slotted-pig", "validate"]).unwrap();
+    assert_eq!(args.config.as_deref(), Some(Path::new("combined.yaml")));
+}
+
+#[test]
+fn test_config_precedence_over_environment() {
+    let _env = ENV.lock().unwrap();
+    env::set_var("SLOTTED_PIG_CATEGORIZER_PATH", "env.yaml");
+    let command_line_config = parse_args(
+        Args::command(),
+        ["slotted-pig", "--config", "combined.yaml", "validate"],
+    );
+    let default_config = parse_args(command_with_default_config(), ["slotted-pig", "validate"]);
+    env::remove_var("SLOTTED_PIG_CATEGORIZER_PATH");
+
+    let args = command_line_config.unwrap();
+    assert_eq!(args.config.as_deref(), Some(Path::new("combined.yaml")));
+    let args = default_config.unwrap();
+    assert_eq!(args.config, None);
+    assert_eq!(args.categorizer_path, Path::new("env.yaml"));
+}
+
+#[test]
+fn test_config_conflicts_with_command_line_paths() {
+    let _env = ENV.lock().unwrap();
+    let Err(error) = parse_args(
+        Args::command(),
+        [
+            "slotted-pig",
+            "--config",
+            "combined.yaml",
+            "--categorizer-path",
+            "categorizer.yaml",
+            "validate",
+        ],
+    ) else {
+        panic!("the combined config and a config path on the command line conflict");
+    };
+    assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+}
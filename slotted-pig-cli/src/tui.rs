@@ -0,0 +1,386 @@
+use anyhow::{Context, Result};
+use bigdecimal::{BigDecimal, Zero};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use regex::Regex;
+use slotted_pig_lib::{
+    categorizer::{Categorizer, TransactionMatcher},
+    edit::add_transaction_matcher,
+    transaction::Transaction,
+    util::{normalize_payee, payee_regex},
+};
+
+use crate::Args;
+
+/// Walk through the uncategorized transactions adding a transaction matcher for each to the
+/// categorizer config
+///
+/// Every added matcher is written to the config immediately, so quitting never loses work. An
+/// encrypted categorizer config stays encrypted.
+pub fn run(args: &Args, transactions: Vec<Transaction>) -> Result<()> {
+    let mut app = App::new(args, transactions)?;
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result?;
+    println!(
+        "added {} transaction matchers, {} transactions are uncategorized",
+        app.added,
+        app.uncategorized.len()
+    );
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Focus {
+    Search,
+    Pattern,
+}
+
+struct App<'a> {
+    /// Arguments with the categorizer path and the passphrase to read and write it
+    args: &'a Args,
+    /// Categorizer config as written, edits are made to the text to keep its formatting
+    yaml: String,
+    categorizer: Categorizer,
+    transactions: Vec<Transaction>,
+    /// Indices of the transactions which pass the filters but are uncategorized
+    uncategorized: Vec<usize>,
+    /// Position in `uncategorized` of the transaction being categorized
+    current: usize,
+    /// Colon separated paths of every leaf category in hierarchy order
+    categories: Vec<String>,
+    search: String,
+    pattern: String,
+    focus: Focus,
+    list: ListState,
+    /// Result of the last action
+    message: Option<String>,
+    added: usize,
+}
+
+impl<'a> App<'a> {
+    fn new(args: &'a Args, transactions: Vec<Transaction>) -> Result<Self> {
+        let yaml = args
+            .read_config_yaml(&args.categorizer_path)
+            .with_context(|| {
+                format!(
+                    "failed to read categorizer {}",
+                    args.categorizer_path.display()
+                )
+            })?;
+        let categorizer =
+            Categorizer::from_yaml_buffer(&yaml).context("failed to parse categorizer")?;
+        let mut app = Self {
+            args,
+            yaml,
+            categorizer,
+            transactions,
+            uncategorized: Vec::new(),
+            current: 0,
+            categories: Vec::new(),
+            search: String::new(),
+            pattern: String::new(),
+            focus: Focus::Search,
+            list: ListState::default().with_selected(Some(0)),
+            message: None,
+            added: 0,
+        };
+        app.recategorize();
+        Ok(app)
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.uncategorized.is_empty() {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc => break,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    self.focus = match self.focus {
+                        Focus::Search => Focus::Pattern,
+                        Focus::Pattern => Focus::Search,
+                    }
+                }
+                KeyCode::Up => self.list.select_previous(),
+                KeyCode::Down => self.list.select_next(),
+                KeyCode::PageDown => self.go_to(self.current + 1),
+                KeyCode::PageUp => self.go_to(self.current.saturating_sub(1)),
+                KeyCode::Enter => self.add_matcher()?,
+                KeyCode::Backspace => {
+                    self.input().pop();
+                    self.list.select(Some(0));
+                }
+                KeyCode::Char(c) => {
+                    self.input().push(c);
+                    self.list.select(Some(0));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Text of the focused input
+    fn input(&mut self) -> &mut String {
+        match self.focus {
+            Focus::Search => &mut self.search,
+            Focus::Pattern => &mut self.pattern,
+        }
+    }
+
+    /// Reload the categorizer from the yaml and find the uncategorized transactions
+    fn recategorize(&mut self) {
+        self.uncategorized = self
+            .transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| {
+                self.categorizer
+                    .assign_transaction(t)
+                    .is_some_and(|a| !a.is_categorized())
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.categories = self
+            .categorizer
+            .leaf_category_paths()
+            .into_iter()
+            .map(|path| path.join(":"))
+            .collect();
+        self.go_to(self.current);
+    }
+
+    /// Move to an uncategorized transaction and suggest a pattern from its payee
+    fn go_to(&mut self, position: usize) {
+        self.current = position.min(self.uncategorized.len().saturating_sub(1));
+        if let Some(transaction) = self.transaction() {
+            let mut payee = normalize_payee(&transaction.description);
+            if payee.is_empty() {
+                payee = transaction.description.trim().to_lowercase();
+            }
+            self.pattern = payee_regex(&payee);
+        }
+    }
+
+    fn transaction(&self) -> Option<&Transaction> {
+        self.uncategorized
+            .get(self.current)
+            .map(|i| &self.transactions[*i])
+    }
+
+    /// Leaf categories matching the search, best matches first
+    fn matching_categories(&self) -> Vec<&str> {
+        let mut matches = self
+            .categories
+            .iter()
+            .filter_map(|category| Some((fuzzy_score(&self.search, category)?, category)))
+            .collect::<Vec<_>>();
+        // The stable sort keeps equal scores in hierarchy order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matches.into_iter().map(|(_, c)| c.as_str()).collect()
+    }
+
+    /// Transaction matcher of the pattern limited to the sign of the current transaction
+    fn matcher(&self) -> Result<TransactionMatcher, String> {
+        let transaction = self.transaction().ok_or("no transaction")?;
+        let description = Regex::new(&self.pattern).map_err(|e| e.to_string())?;
+        if !description.is_match(&transaction.description) {
+            return Err(String::from("pattern does not match the transaction"));
+        }
+        let (min, max) = if transaction.amount > BigDecimal::zero() {
+            (Some(BigDecimal::zero()), None)
+        } else {
+            (None, Some(BigDecimal::zero()))
+        };
+        Ok(TransactionMatcher {
+            min,
+            max,
+            account: None,
+            description: vec![description],
+            begin: None,
+            end: None,
+        })
+    }
+
+    fn add_matcher(&mut self) -> Result<()> {
+        let categories = self.matching_categories();
+        let Some(category) = self.list.selected().and_then(|i| categories.get(i)) else {
+            self.message = Some(String::from("no category selected"));
+            return Ok(());
+        };
+        let category = category.to_string();
+        let matcher = match self.matcher() {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                self.message = Some(e);
+                return Ok(());
+            }
+        };
+        let path = category.split(':').collect::<Vec<_>>();
+        let yaml = match add_transaction_matcher(&self.yaml, &path, &matcher) {
+            Ok(yaml) => yaml,
+            Err(e) => {
+                self.message = Some(e.to_string());
+                return Ok(());
+            }
+        };
+        let categorizer_path = &self.args.categorizer_path;
+        self.args
+            .write_config_yaml(categorizer_path, &yaml)
+            .with_context(|| {
+                format!("failed to write categorizer {}", categorizer_path.display())
+            })?;
+        self.categorizer =
+            Categorizer::from_yaml_buffer(&yaml).context("failed to parse categorizer")?;
+        self.yaml = yaml;
+        self.added += 1;
+        let before = self.uncategorized.len();
+        self.recategorize();
+        self.message = Some(format!(
+            "added `{matcher}` to {category}, categorizing {} transactions",
+            before - self.uncategorized.len()
+        ));
+        self.search.clear();
+        self.list.select(Some(0));
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [transaction_area, pattern_area, search_area, list_area, help_area] =
+            Layout::vertical([
+                Constraint::Length(6),
+                Constraint::Length(4),
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(2),
+            ])
+            .areas(frame.area());
+
+        if let Some(transaction) = self.transaction() {
+            let source = transaction
+                .source
+                .as_ref()
+                .map(|s| s.display().to_string())
+                .unwrap_or_default();
+            let lines = vec![
+                Line::from(transaction.description.clone()).bold(),
+                Line::from(format!("Amount:  {}", transaction.amount)),
+                Line::from(format!(
+                    "Time:    {}",
+                    transaction.time.format("%Y-%m-%d %H:%M")
+                )),
+                Line::from(format!("Account: {}  {source}", transaction.account)),
+            ];
+            let title = format!(
+                " Uncategorized transaction {} of {} ",
+                self.current + 1,
+                self.uncategorized.len()
+            );
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::bordered().title(title)),
+                transaction_area,
+            );
+        }
+
+        let preview = match self.matcher() {
+            Ok(matcher) => {
+                let matches = self
+                    .uncategorized
+                    .iter()
+                    .filter(|i| matcher.matches(&self.transactions[**i]))
+                    .count();
+                Line::from(format!("matches {matches} uncategorized transactions"))
+            }
+            Err(e) => Line::from(e).red(),
+        };
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(self.pattern.as_str()), preview]).block(focused_block(
+                " Description pattern ",
+                self.focus == Focus::Pattern,
+            )),
+            pattern_area,
+        );
+        frame.render_widget(
+            Paragraph::new(self.search.as_str()).block(focused_block(
+                " Category search ",
+                self.focus == Focus::Search,
+            )),
+            search_area,
+        );
+        let (input, area) = match self.focus {
+            Focus::Search => (&self.search, search_area),
+            Focus::Pattern => (&self.pattern, pattern_area),
+        };
+        frame.set_cursor_position(cursor(input, area));
+
+        let categories = self
+            .matching_categories()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let list = List::new(categories)
+            .block(Block::bordered().title(" Categories "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let help = "Tab switch input  ↑↓ select category  Enter add matcher  \
+                    PgUp/PgDn previous/next transaction  Esc quit";
+        let message = self.message.as_deref().unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(message).yellow(), Line::from(help).dim()]),
+            help_area,
+        );
+    }
+}
+
+fn focused_block(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::new().cyan())
+    } else {
+        block
+    }
+}
+
+/// Position after the end of the first line of input in a bordered area
+fn cursor(input: &str, area: Rect) -> Position {
+    let x = area.x + 1 + input.chars().count() as u16;
+    Position::new(x.min(area.right().saturating_sub(2)), area.y + 1)
+}
+
+/// Score of the query as a case insensitive subsequence of the candidate, `None` if it is not
+/// one
+///
+/// Consecutive characters and characters at the start of words score higher so `food` prefers
+/// `expenses:food` over `expenses:fast food`.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut next = 0;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = next + candidate[next..].iter().position(|c| *c == q)?;
+        score += 1;
+        if index > 0 && index == next {
+            score += 2;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        next = index + 1;
+    }
+    Some(score)
+}
@@ -0,0 +1,207 @@
+//! gRPC service of the categorization engine, the service definition is
+//! `proto/slotted_pig.proto`
+
+// `tonic::Status` is the error type of every generated rpc so it cannot be boxed
+#![allow(clippy::result_large_err)]
+
+use std::{path::Path, pin::Pin, sync::Arc};
+
+use chrono::DateTime;
+use slotted_pig_lib::{
+    categorizer::{Categorizer, Outcome},
+    import::{parse_ofx, parse_qif, ImportFormat},
+    transaction::{Transaction, TransactionParser},
+};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+pub mod proto {
+    tonic::include_proto!("slotted_pig.v1");
+}
+
+#[cfg(test)]
+mod tests;
+
+use proto::{
+    categorization_server::Categorization, categorize_request, Assignment, CategorizeRequest,
+    ExplainRequest, ExplainResponse, ParseTransactionsRequest,
+};
+
+pub use proto::categorization_server::CategorizationServer;
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+impl From<&Transaction> for proto::Transaction {
+    fn from(transaction: &Transaction) -> Self {
+        Self {
+            amount: transaction.amount.to_string(),
+            account: transaction.account.clone(),
+            description: transaction.description.clone(),
+            time: transaction.time.to_rfc3339(),
+            source: transaction
+                .source
+                .as_ref()
+                .map(|source| source.display().to_string()),
+        }
+    }
+}
+
+impl TryFrom<proto::Transaction> for Transaction {
+    type Error = Status;
+
+    fn try_from(transaction: proto::Transaction) -> Result<Self, Status> {
+        let amount = transaction.amount.parse().map_err(|e| {
+            Status::invalid_argument(format!("invalid amount `{}`: {e}", transaction.amount))
+        })?;
+        let time = DateTime::parse_from_rfc3339(&transaction.time)
+            .map_err(|e| {
+                Status::invalid_argument(format!("invalid time `{}`: {e}", transaction.time))
+            })?
+            .to_utc();
+        Ok(Self {
+            amount,
+            account: transaction.account,
+            description: transaction.description,
+            time,
+            source: transaction.source.map(Into::into),
+        })
+    }
+}
+
+fn categorizer(yaml: &str) -> Result<Categorizer, Status> {
+    Categorizer::from_yaml_buffer(yaml)
+        .map_err(|e| Status::invalid_argument(format!("invalid categorizer: {}", error_chain(&e))))
+}
+
+/// Error and its sources (ie `serde_yaml: missing field ...`), the variants alone only name the
+/// failing step
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        message.push_str(&format!(": {e}"));
+        source = e.source();
+    }
+    message
+}
+
+/// Categorization service, stateless as every request carries its configs
+#[derive(Debug, Default)]
+pub struct CategorizationService;
+
+#[tonic::async_trait]
+impl Categorization for CategorizationService {
+    type ParseTransactionsStream = ResponseStream<proto::Transaction>;
+    type CategorizeStream = ResponseStream<Assignment>;
+
+    async fn parse_transactions(
+        &self,
+        request: Request<ParseTransactionsRequest>,
+    ) -> Result<Response<Self::ParseTransactionsStream>, Status> {
+        let request = request.into_inner();
+        let path = Path::new(&request.file_name);
+        let invalid = |e: &dyn std::error::Error| {
+            Status::invalid_argument(format!(
+                "failed to parse {}: {}",
+                request.file_name,
+                error_chain(e)
+            ))
+        };
+        let transactions = match ImportFormat::detect(path, &request.contents) {
+            ImportFormat::Csv => {
+                let yaml = request.transaction_parser.as_deref().ok_or_else(|| {
+                    Status::invalid_argument("a transaction parser is needed for csv files")
+                })?;
+                TransactionParser::from_yaml_buffer(yaml)
+                    .and_then(|parser| parser.parse_csv_buffer(path, &request.contents))
+                    .map_err(|e| invalid(&e))?
+            }
+            ImportFormat::Ofx => parse_ofx(path, &request.contents).map_err(|e| invalid(&e))?,
+            ImportFormat::Qif => parse_qif(path, &request.contents).map_err(|e| invalid(&e))?,
+        };
+        let transactions = transactions
+            .iter()
+            .map(|transaction| Ok(proto::Transaction::from(transaction)))
+            .collect::<Vec<_>>();
+        Ok(Response::new(Box::pin(tokio_stream::iter(transactions))))
+    }
+
+    async fn categorize(
+        &self,
+        request: Request<Streaming<CategorizeRequest>>,
+    ) -> Result<Response<Self::CategorizeStream>, Status> {
+        let mut stream = request.into_inner();
+        let categorizer = match stream.message().await?.and_then(|m| m.message) {
+            Some(categorize_request::Message::Categorizer(yaml)) => Arc::new(categorizer(&yaml)?),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "the first message must be the categorizer",
+                ))
+            }
+        };
+        // Transactions are assigned one at a time as they arrive so large datasets are never
+        // held in memory
+        let assignments = stream.map(move |message| {
+            let transaction = match message?.message {
+                Some(categorize_request::Message::Transaction(transaction)) => {
+                    Transaction::try_from(transaction)?
+                }
+                _ => {
+                    return Err(Status::invalid_argument(
+                        "every message after the first must be a transaction",
+                    ))
+                }
+            };
+            let assignment = categorizer.assign_transaction(&transaction);
+            Ok(Assignment {
+                transaction: Some(proto::Transaction::from(&transaction)),
+                category: assignment
+                    .as_ref()
+                    .and_then(|assignment| assignment.category_path_joined(":")),
+                matcher: assignment
+                    .as_ref()
+                    .and_then(|assignment| assignment.matcher)
+                    .map(ToString::to_string),
+                filtered: assignment.is_none(),
+            })
+        });
+        Ok(Response::new(Box::pin(assignments)))
+    }
+
+    async fn explain(
+        &self,
+        request: Request<ExplainRequest>,
+    ) -> Result<Response<ExplainResponse>, Status> {
+        let request = request.into_inner();
+        let categorizer = categorizer(&request.categorizer)?;
+        let transaction = Transaction::try_from(
+            request
+                .transaction
+                .ok_or_else(|| Status::invalid_argument("missing transaction"))?,
+        )?;
+        let explanation = categorizer.explain(&transaction);
+        let candidates = explanation
+            .candidates
+            .iter()
+            .map(|candidate| {
+                let (outcome, reasons) = match &candidate.outcome {
+                    Outcome::Assigned => (proto::Outcome::Assigned, Vec::new()),
+                    Outcome::Shadowed => (proto::Outcome::Shadowed, Vec::new()),
+                    Outcome::Rejected(reasons) => (proto::Outcome::Rejected, reasons.clone()),
+                    Outcome::Filtered => (proto::Outcome::Filtered, Vec::new()),
+                };
+                proto::Candidate {
+                    category: candidate.category_path.join(":"),
+                    matcher: candidate.matcher.to_string(),
+                    outcome: outcome.into(),
+                    reasons,
+                }
+            })
+            .collect();
+        Ok(Response::new(ExplainResponse {
+            passes_filters: explanation.passes_filters(),
+            candidates,
+            text: explanation.to_string(),
+        }))
+    }
+}
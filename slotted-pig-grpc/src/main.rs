@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use slotted_pig_grpc::{CategorizationServer, CategorizationService};
+use tonic::transport::Server;
+
+/// gRPC server of the categorization engine
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, env = "SLOTTED_PIG_ADDRESS", default_value = "127.0.0.1:50051")]
+    address: String,
+    /// Log level
+    #[arg(long, env = "SLOTTED_PIG_LOG_LEVEL")]
+    log_level: Option<LogLevel>,
+}
+
+/// Level of the log messages written to stderr
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => Self::TRACE,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Warning => Self::WARN,
+            LogLevel::Error | LogLevel::Critical => Self::ERROR,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::from(args.log_level.unwrap_or_default()))
+        .with_target(false)
+        .init();
+
+    let address = args
+        .address
+        .parse()
+        .with_context(|| format!("invalid address {}", args.address))?;
+    tracing::info!("serving gRPC on {address}");
+    Server::builder()
+        .add_service(CategorizationServer::new(CategorizationService))
+        .serve(address)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use tokio::net::TcpListener;
+use tokio_stream::{wrappers::TcpListenerStream, StreamExt};
+use tonic::{
+    transport::{Channel, Server},
+    Code,
+};
+
+use crate::{
+    proto::{
+        categorization_client::CategorizationClient, categorize_request::Message,
+        CategorizeRequest, ExplainRequest, Outcome, ParseTransactionsRequest, Transaction,
+    },
+    CategorizationServer, CategorizationService,
+};
+
+const CSV: &str = include_str!("../../examples/transactions.csv");
+const TRANSACTION_PARSER: &str = include_str!("../../examples/transaction_parser.yaml");
+const CATEGORIZER: &str = include_str!("../../examples/categorizer.yaml");
+
+async fn client() -> CategorizationClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(CategorizationServer::new(CategorizationService))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    CategorizationClient::connect(format!("http://{address}"))
+        .await
+        .unwrap()
+}
+
+async fn parse(client: &mut CategorizationClient<Channel>) -> Vec<Transaction> {
+    client
+        .parse_transactions(ParseTransactionsRequest {
+            file_name: "transactions.csv".to_string(),
+            contents: CSV.to_string(),
+            transaction_parser: Some(TRANSACTION_PARSER.to_string()),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .collect::<Result<Vec<_>, _>>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_parse_and_categorize() {
+    let mut client = client().await;
+    let transactions = parse(&mut client).await;
+    assert!(!transactions.is_empty());
+    assert_eq!(transactions[0].source.as_deref(), Some("transactions.csv"));
+
+    let requests = std::iter::once(Message::Categorizer(CATEGORIZER.to_string()))
+        .chain(transactions.clone().into_iter().map(Message::Transaction))
+        .map(|message| CategorizeRequest {
+            message: Some(message),
+        })
+        .collect::<Vec<_>>();
+    let assignments = client
+        .categorize(tokio_stream::iter(requests))
+        .await
+        .unwrap()
+        .into_inner()
+        .collect::<Result<Vec<_>, _>>()
+        .await
+        .unwrap();
+    assert_eq!(assignments.len(), transactions.len());
+    assert_eq!(assignments[0].transaction.as_ref(), Some(&transactions[0]));
+    assert_eq!(assignments[0].category.as_deref(), Some("Income"));
+    assert!(assignments[0].matcher.is_some());
+}
+
+#[tokio::test]
+async fn test_explain() {
+    let mut client = client().await;
+    let transaction = parse(&mut client).await.remove(0);
+    let explanation = client
+        .explain(ExplainRequest {
+            categorizer: CATEGORIZER.to_string(),
+            transaction: Some(transaction),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(explanation.passes_filters);
+    let assigned = explanation
+        .candidates
+        .iter()
+        .find(|candidate| candidate.outcome() == Outcome::Assigned)
+        .unwrap();
+    assert_eq!(assigned.category, "Income");
+    assert!(!explanation.text.is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_requests() {
+    let mut client = client().await;
+    let status = client
+        .parse_transactions(ParseTransactionsRequest {
+            file_name: "transactions.csv".to_string(),
+            contents: CSV.to_string(),
+            transaction_parser: None,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    // The categorizer has to come first
+    let requests = [CategorizeRequest {
+        message: Some(Message::Transaction(Transaction::default())),
+    }];
+    let status = client
+        .categorize(tokio_stream::iter(requests))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    let status = client
+        .explain(ExplainRequest {
+            categorizer: "nope: 1".to_string(),
+            transaction: None,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(
+        status.message().contains("unknown field `nope`"),
+        "{status}"
+    );
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{categorizer::TransactionMatcher, transaction::Transaction};
+
+/// Category assigned to a single transaction
+#[derive(Clone, Debug)]
+pub struct Assignment<'a> {
+    /// Assigned transaction
+    pub transaction: &'a Transaction,
+    /// Category names from the top level category down to the matching leaf category, `None` if
+    /// the transaction is uncategorized
+    pub category_path: Option<Vec<&'a str>>,
+    /// Transaction matcher which assigned the category, `None` if the transaction is uncategorized
+    pub matcher: Option<&'a TransactionMatcher>,
+}
+
+impl<'a> Assignment<'a> {
+    /// Check if the transaction was assigned a category
+    pub fn is_categorized(&self) -> bool {
+        self.category_path.is_some()
+    }
+
+    /// Category path joined with a separator (ie `expenses:food`), `None` if uncategorized
+    pub fn category_path_joined(&self, separator: &str) -> Option<String> {
+        self.category_path.as_ref().map(|path| path.join(separator))
+    }
+
+    /// Flatten into a single row with the transaction fields and colon separated category path
+    pub fn row(&self) -> AssignmentRow<'_> {
+        AssignmentRow {
+            time: &self.transaction.time,
+            account: &self.transaction.account,
+            description: &self.transaction.description,
+            amount: &self.transaction.amount,
+            source: self.transaction.source.as_deref(),
+            category: self.category_path_joined(":"),
+            matcher: self.matcher.map(ToString::to_string),
+        }
+    }
+}
+
+/// Transaction with its assigned category flattened into a single row (ie for CSV or JSON Lines)
+#[derive(Clone, Debug, Serialize)]
+pub struct AssignmentRow<'a> {
+    /// Time of the transaction
+    pub time: &'a DateTime<Utc>,
+    /// Account the transaction applied to
+    pub account: &'a str,
+    /// Description of the transaction
+    pub description: &'a str,
+    /// Amount of the transaction
+    pub amount: &'a BigDecimal,
+    /// File the transaction was parsed from
+    pub source: Option<&'a Path>,
+    /// Colon separated category path, `None` if uncategorized
+    pub category: Option<String>,
+    /// Transaction matcher which assigned the category, `None` if uncategorized
+    pub matcher: Option<String>,
+}
@@ -1,11 +1,16 @@
-use std::cmp::Reverse;
+use std::{
+    cmp::Reverse,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
 
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, Zero};
 use derive_more::{From, Into};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use strum::EnumString;
+use strum::{AsRefStr, EnumIter, EnumString};
 
-use crate::transaction::Transaction;
+use crate::{categorizer::Assignment, transaction::Transaction, util::Rounding};
 
 /// Categorized transaction hierarchy
 #[derive(Clone, Debug, Default, Deserialize, Into, From, Eq, PartialEq, Serialize)]
@@ -26,6 +31,185 @@ impl CategorizedList {
             .iter_mut()
             .for_each(|c| c.sort_transactions(sort));
     }
+
+    /// Prune the hierarchy to the subtrees of the `only` category paths less the subtrees of the
+    /// `exclude` category paths
+    ///
+    /// Paths are colon separated (ie `expenses:food`). Every category is selected if `only` is
+    /// empty. Ancestors of selected categories are kept with their totals recomputed from the
+    /// remaining subcategories.
+    pub fn prune(&mut self, only: &[String], exclude: &[String]) {
+        Categorized::prune_categorized(
+            &mut self.categorized,
+            &mut Vec::new(),
+            only,
+            exclude,
+            only.is_empty(),
+        );
+    }
+
+    /// Keep only the transactions the predicate accepts (ie the transactions of one month)
+    ///
+    /// Counts and totals are recomputed and categories left without transactions are removed.
+    pub fn retain_transactions<F: Fn(&Transaction) -> bool>(&mut self, keep: F) {
+        Categorized::retain_categorized(&mut self.categorized, &keep);
+    }
+
+    /// Compare the totals of every category to the totals of a previous categorization
+    ///
+    /// Categories are aligned by path and a category missing from either categorization has a
+    /// total of zero there. Categories are ordered as they appear in `self` followed by categories
+    /// only in `previous`.
+    pub fn diff(&self, previous: &CategorizedList) -> Vec<CategoryDiff> {
+        let previous = previous.flatten();
+        let mut diffs = self
+            .flatten()
+            .into_iter()
+            .map(|(path, current)| CategoryDiff {
+                previous: previous
+                    .iter()
+                    .find(|(p, _)| *p == path)
+                    .map(|(_, c)| c.total.clone())
+                    .unwrap_or_default(),
+                current: current.total.clone(),
+                path,
+            })
+            .collect::<Vec<_>>();
+        for (path, previous) in previous {
+            if !diffs.iter().any(|d| d.path == path) {
+                diffs.push(CategoryDiff {
+                    path,
+                    previous: previous.total.clone(),
+                    current: BigDecimal::default(),
+                });
+            }
+        }
+        diffs
+    }
+
+    /// Every category with its path in depth first order
+    pub(crate) fn flatten(&self) -> Vec<(Vec<String>, &Categorized)> {
+        let mut flattened = Vec::new();
+        for categorized in &self.categorized {
+            categorized.flatten(&mut Vec::new(), &mut flattened);
+        }
+        flattened
+    }
+
+    /// Assign every transaction in the hierarchy to the path of its category
+    ///
+    /// This allows running reports on a categorization read back from a file without its
+    /// categorizer. The matchers are not known so every matcher is `None`.
+    pub fn assignments(&self) -> Vec<Assignment<'_>> {
+        let mut assignments = Vec::new();
+        for categorized in &self.categorized {
+            categorized.assignments(&mut Vec::new(), &mut assignments);
+        }
+        assignments
+    }
+
+    /// Round the totals of every category
+    ///
+    /// Totals should only be rounded after all aggregation is done so sums remain exact.
+    pub fn round_totals(&mut self, rounding: &Rounding) {
+        self.categorized
+            .iter_mut()
+            .for_each(|c| c.round_totals(rounding));
+    }
+}
+
+/// Combine two categorizations (ie of two months) as if their transactions were categorized
+/// together
+///
+/// Categories are aligned by path, categories only in `rhs` follow the categories of `self`.
+impl Add for CategorizedList {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for CategorizedList {
+    fn add_assign(&mut self, rhs: Self) {
+        Categorized::add_categorized(&mut self.categorized, rhs.categorized);
+    }
+}
+
+/// Subtract a categorization (ie this year minus last year) so the counts and totals are the
+/// differences of the counts and totals
+///
+/// Categories are aligned by path, categories only in `rhs` follow the categories of `self` with
+/// their totals negated. The difference carries no transactions and counts stop at zero (ie `a - a`
+/// has zero counts and totals).
+impl Sub for CategorizedList {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for CategorizedList {
+    fn sub_assign(&mut self, rhs: Self) {
+        Categorized::sub_categorized(&mut self.categorized, rhs.categorized);
+    }
+}
+
+/// Negate the totals and the amounts of the transactions
+impl Neg for CategorizedList {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.map_amounts(|amount| -amount);
+        self
+    }
+}
+
+/// Scale the totals, counts and the amounts of the transactions are unchanged
+impl Mul<BigDecimal> for CategorizedList {
+    type Output = Self;
+
+    fn mul(mut self, factor: BigDecimal) -> Self {
+        self.map_totals(|amount| amount * &factor);
+        self
+    }
+}
+
+/// Scale the totals down (ie to average the sum of several months), counts and the amounts of the
+/// transactions are unchanged
+///
+/// # Panics
+///
+/// Panics if the divisor is zero.
+impl Div<BigDecimal> for CategorizedList {
+    type Output = Self;
+
+    fn div(mut self, divisor: BigDecimal) -> Self {
+        self.map_totals(|amount| amount / &divisor);
+        self
+    }
+}
+
+/// Add every categorization, an empty categorization if there are none
+impl Sum for CategorizedList {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl CategorizedList {
+    fn map_amounts(&mut self, map: impl Fn(&BigDecimal) -> BigDecimal) {
+        self.categorized
+            .iter_mut()
+            .for_each(|c| c.map_amounts(&map));
+    }
+
+    fn map_totals(&mut self, map: impl Fn(&BigDecimal) -> BigDecimal) {
+        self.categorized.iter_mut().for_each(|c| c.map_totals(&map));
+    }
 }
 
 /// Categorized transactions
@@ -46,7 +230,300 @@ pub struct Categorized {
     pub children: CategorizedChildren,
 }
 
+/// Combine two categorizations of the same category, keeping the name of `self`
+///
+/// Subcategories are aligned by name. If one side has transactions and the other subcategories
+/// the subcategories are flattened into the transactions.
+impl Add for Categorized {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for Categorized {
+    fn add_assign(&mut self, rhs: Self) {
+        self.count += rhs.count;
+        self.total += rhs.total;
+        self.absolute_total += rhs.absolute_total;
+        match (&mut self.children, rhs.children) {
+            (CategorizedChildren::Transactions(transactions), other) => {
+                transactions.extend(other.into_transactions())
+            }
+            (
+                CategorizedChildren::Subcategories(subcategories),
+                CategorizedChildren::Subcategories(others),
+            ) => Self::add_categorized(subcategories, others),
+            (children, CategorizedChildren::Transactions(others)) => {
+                let mut transactions =
+                    std::mem::replace(children, CategorizedChildren::Transactions(Vec::new()))
+                        .into_transactions();
+                transactions.extend(others);
+                *children = CategorizedChildren::Transactions(transactions);
+            }
+        }
+    }
+}
+
+/// Subtract a categorization of the same category, keeping the name of `self`
+///
+/// Like subtracting a `CategorizedList`, subcategories are aligned by name and the difference
+/// carries no transactions. If one side has transactions and the other subcategories the
+/// subcategories are dropped.
+impl Sub for Categorized {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for Categorized {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.count = self.count.saturating_sub(rhs.count);
+        self.total -= rhs.total;
+        self.absolute_total -= rhs.absolute_total;
+        match (&mut self.children, rhs.children) {
+            (
+                CategorizedChildren::Subcategories(subcategories),
+                CategorizedChildren::Subcategories(others),
+            ) => Self::sub_categorized(subcategories, others),
+            (children, _) => *children = CategorizedChildren::Transactions(Vec::new()),
+        }
+    }
+}
+
+/// Negate the totals and the amounts of the transactions
+impl Neg for Categorized {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.map_amounts(&|amount| -amount);
+        self
+    }
+}
+
+/// Scale the totals, counts and the amounts of the transactions are unchanged
+impl Mul<BigDecimal> for Categorized {
+    type Output = Self;
+
+    fn mul(mut self, factor: BigDecimal) -> Self {
+        self.map_totals(&|amount| amount * &factor);
+        self
+    }
+}
+
+/// Scale the totals down, counts and the amounts of the transactions are unchanged
+///
+/// # Panics
+///
+/// Panics if the divisor is zero.
+impl Div<BigDecimal> for Categorized {
+    type Output = Self;
+
+    fn div(mut self, divisor: BigDecimal) -> Self {
+        self.map_totals(&|amount| amount / &divisor);
+        self
+    }
+}
+
 impl Categorized {
+    fn add_categorized(categorized: &mut Vec<Categorized>, others: Vec<Categorized>) {
+        for other in others {
+            match categorized
+                .iter_mut()
+                .find(|c| c.category == other.category)
+            {
+                Some(c) => *c += other,
+                None => categorized.push(other),
+            }
+        }
+    }
+
+    fn sub_categorized(categorized: &mut Vec<Categorized>, others: Vec<Categorized>) {
+        categorized.iter_mut().for_each(Self::clear_transactions);
+        for other in others {
+            match categorized
+                .iter_mut()
+                .find(|c| c.category == other.category)
+            {
+                Some(c) => *c -= other,
+                None => {
+                    let mut zero = other.zero();
+                    zero -= other;
+                    categorized.push(zero);
+                }
+            }
+        }
+    }
+
+    /// Same category and subcategories with zero counts and totals and no transactions
+    fn zero(&self) -> Self {
+        Self {
+            category: self.category.clone(),
+            count: 0,
+            total: BigDecimal::zero(),
+            absolute_total: BigDecimal::zero(),
+            children: match &self.children {
+                CategorizedChildren::Transactions(_) => {
+                    CategorizedChildren::Transactions(Vec::new())
+                }
+                CategorizedChildren::Subcategories(subcategories) => {
+                    CategorizedChildren::Subcategories(
+                        subcategories.iter().map(Self::zero).collect(),
+                    )
+                }
+            },
+        }
+    }
+
+    fn clear_transactions(&mut self) {
+        match &mut self.children {
+            CategorizedChildren::Transactions(transactions) => transactions.clear(),
+            CategorizedChildren::Subcategories(subcategories) => {
+                subcategories.iter_mut().for_each(Self::clear_transactions)
+            }
+        }
+    }
+
+    /// Apply the map to the totals of this category and every subcategory, leaving the
+    /// transactions as they are
+    fn map_totals(&mut self, map: &impl Fn(&BigDecimal) -> BigDecimal) {
+        self.total = map(&self.total);
+        self.absolute_total = map(&self.absolute_total).abs();
+        if let CategorizedChildren::Subcategories(subcategories) = &mut self.children {
+            subcategories.iter_mut().for_each(|c| c.map_totals(map));
+        }
+    }
+
+    /// Apply the map to the amount of every transaction and the totals
+    ///
+    /// The map must be linear (ie scaling) for the totals to remain the sums of the transactions.
+    fn map_amounts(&mut self, map: &impl Fn(&BigDecimal) -> BigDecimal) {
+        self.total = map(&self.total);
+        self.absolute_total = map(&self.absolute_total).abs();
+        match &mut self.children {
+            CategorizedChildren::Transactions(transactions) => transactions
+                .iter_mut()
+                .for_each(|t| t.amount = map(&t.amount)),
+            CategorizedChildren::Subcategories(subcategories) => {
+                subcategories.iter_mut().for_each(|c| c.map_amounts(map))
+            }
+        }
+    }
+
+    fn flatten<'a>(
+        &'a self,
+        path: &mut Vec<String>,
+        flattened: &mut Vec<(Vec<String>, &'a Categorized)>,
+    ) {
+        path.push(self.category.clone());
+        flattened.push((path.clone(), self));
+        if let CategorizedChildren::Subcategories(subcategories) = &self.children {
+            subcategories
+                .iter()
+                .for_each(|c| c.flatten(path, flattened));
+        }
+        path.pop();
+    }
+
+    fn assignments<'a>(&'a self, path: &mut Vec<&'a str>, assignments: &mut Vec<Assignment<'a>>) {
+        path.push(&self.category);
+        match &self.children {
+            CategorizedChildren::Transactions(transactions) => {
+                assignments.extend(transactions.iter().map(|transaction| Assignment {
+                    transaction,
+                    category_path: Some(path.clone()),
+                    matcher: None,
+                }))
+            }
+            CategorizedChildren::Subcategories(subcategories) => subcategories
+                .iter()
+                .for_each(|c| c.assignments(path, assignments)),
+        }
+        path.pop();
+    }
+
+    /// Prune the categories returning if any category within them was removed
+    fn prune_categorized(
+        categorized: &mut Vec<Categorized>,
+        path: &mut Vec<String>,
+        only: &[String],
+        exclude: &[String],
+        selected: bool,
+    ) -> bool {
+        let len = categorized.len();
+        let mut changed = false;
+        categorized.retain_mut(|c| {
+            let (keep, c_changed) = c.prune(path, only, exclude, selected);
+            changed |= c_changed;
+            keep
+        });
+        changed || categorized.len() != len
+    }
+
+    /// Prune the category returning if it should be kept and if any category within it was
+    /// removed
+    fn prune(
+        &mut self,
+        path: &mut Vec<String>,
+        only: &[String],
+        exclude: &[String],
+        selected: bool,
+    ) -> (bool, bool) {
+        path.push(self.category.clone());
+        let joined = path.join(":");
+        let pruned = if exclude.contains(&joined) {
+            (false, true)
+        } else {
+            let selected = selected || only.contains(&joined);
+            match &mut self.children {
+                CategorizedChildren::Transactions(_) => (selected, false),
+                CategorizedChildren::Subcategories(subcategories) => {
+                    let changed =
+                        Self::prune_categorized(subcategories, path, only, exclude, selected);
+                    if changed {
+                        self.count = subcategories.iter().map(|c| c.count).sum();
+                        self.total = subcategories.iter().map(|c| &c.total).sum();
+                        self.absolute_total = subcategories.iter().map(|c| &c.absolute_total).sum();
+                    }
+                    (selected || !subcategories.is_empty(), changed)
+                }
+            }
+        };
+        path.pop();
+        pruned
+    }
+
+    fn retain_categorized<F: Fn(&Transaction) -> bool>(
+        categorized: &mut Vec<Categorized>,
+        keep: &F,
+    ) {
+        categorized.retain_mut(|c| c.retain_transactions(keep));
+    }
+
+    /// Keep the transactions the predicate accepts returning if any transaction remains
+    fn retain_transactions<F: Fn(&Transaction) -> bool>(&mut self, keep: &F) -> bool {
+        match &mut self.children {
+            CategorizedChildren::Transactions(transactions) => {
+                transactions.retain(|t| keep(t));
+                self.count = transactions.len() as u64;
+                self.total = transactions.iter().map(|t| &t.amount).sum();
+                self.absolute_total = transactions.iter().map(|t| t.amount.abs()).sum();
+            }
+            CategorizedChildren::Subcategories(subcategories) => {
+                Self::retain_categorized(subcategories, keep);
+                self.count = subcategories.iter().map(|c| c.count).sum();
+                self.total = subcategories.iter().map(|c| &c.total).sum();
+                self.absolute_total = subcategories.iter().map(|c| &c.absolute_total).sum();
+            }
+        }
+        self.count > 0
+    }
+
     fn sort_subcategories(&mut self, sort: CategorySort) {
         let CategorizedChildren::Subcategories(categories) = &mut self.children else {
             return;
@@ -76,23 +553,19 @@ impl Categorized {
             .for_each(|c| c.sort_subcategories(sort));
     }
 
+    fn round_totals(&mut self, rounding: &Rounding) {
+        self.total = rounding.round(&self.total);
+        self.absolute_total = rounding.round(&self.absolute_total);
+        if let CategorizedChildren::Subcategories(subcategories) = &mut self.children {
+            subcategories
+                .iter_mut()
+                .for_each(|c| c.round_totals(rounding));
+        }
+    }
+
     fn sort_transactions(&mut self, sort: TransactionSort) {
         match &mut self.children {
-            CategorizedChildren::Transactions(transactions) => match sort {
-                TransactionSort::TimeDescending => transactions.sort_by_key(|t| Reverse(t.time)),
-                TransactionSort::TimeAscending => transactions.sort_by_key(|t| t.time),
-                TransactionSort::AmountDescending => {
-                    transactions.sort_by(|t1, t2| t2.amount.cmp(&t1.amount))
-                }
-                TransactionSort::AmountAscending => {
-                    transactions.sort_by(|t1, t2| t1.amount.cmp(&t2.amount))
-                }
-                TransactionSort::AbsoluteAmountDescending => transactions
-                    .sort_by(|t1: &Transaction, t2| t2.amount.abs().cmp(&t1.amount.abs())),
-                TransactionSort::AbsoluteAmountAscending => {
-                    transactions.sort_by(|t1, t2| t1.amount.abs().cmp(&t2.amount.abs()))
-                }
-            },
+            CategorizedChildren::Transactions(transactions) => sort.sort(transactions),
             CategorizedChildren::Subcategories(subcategories) => {
                 subcategories
                     .iter_mut()
@@ -102,6 +575,33 @@ impl Categorized {
     }
 }
 
+/// Change in a category total between two categorizations
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryDiff {
+    /// Category names from the top level category down to this category
+    pub path: Vec<String>,
+    /// Total of the previous categorization
+    pub previous: BigDecimal,
+    /// Total of the current categorization
+    pub current: BigDecimal,
+}
+
+impl CategoryDiff {
+    /// Difference from the previous to the current total
+    pub fn change(&self) -> BigDecimal {
+        &self.current - &self.previous
+    }
+
+    /// Change as a percent of the absolute previous total, `None` if the previous total is zero
+    pub fn percent_change(&self) -> Option<BigDecimal> {
+        if self.previous.is_zero() {
+            return None;
+        }
+        Some(self.change() * BigDecimal::from(100) / self.previous.abs())
+    }
+}
+
 // Possible categorized children, either a list of transactions or subcategories
 #[derive(Clone, Debug, Deserialize, From, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -112,8 +612,33 @@ pub enum CategorizedChildren {
     Subcategories(Vec<Categorized>),
 }
 
+impl CategorizedChildren {
+    /// Every transaction of the children and their subcategories in depth first order
+    fn into_transactions(self) -> Vec<Transaction> {
+        match self {
+            Self::Transactions(transactions) => transactions,
+            Self::Subcategories(subcategories) => subcategories
+                .into_iter()
+                .flat_map(|c| c.children.into_transactions())
+                .collect(),
+        }
+    }
+}
+
 /// Sort possibilities for scategories
-#[derive(Clone, Copy, Debug, Deserialize, EnumString, Serialize)]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    Eq,
+    JsonSchema,
+    PartialEq,
+    Serialize,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum CategorySort {
@@ -132,7 +657,19 @@ pub enum CategorySort {
 }
 
 /// Sort possibilities for transactions
-#[derive(Clone, Copy, Debug, Deserialize, EnumString, Serialize)]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    Eq,
+    JsonSchema,
+    PartialEq,
+    Serialize,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum TransactionSort {
@@ -149,3 +686,17 @@ pub enum TransactionSort {
     /// Sort by transaction absolute amount ascending
     AbsoluteAmountAscending,
 }
+
+impl TransactionSort {
+    /// Sort the transactions, keeping the order of equal transactions
+    pub fn sort(self, transactions: &mut [Transaction]) {
+        match self {
+            Self::TimeDescending => transactions.sort_by_key(|t| Reverse(t.time)),
+            Self::TimeAscending => transactions.sort_by_key(|t| t.time),
+            Self::AmountDescending => transactions.sort_by(|t1, t2| t2.amount.cmp(&t1.amount)),
+            Self::AmountAscending => transactions.sort_by(|t1, t2| t1.amount.cmp(&t2.amount)),
+            Self::AbsoluteAmountDescending => transactions.sort_by_key(|t| Reverse(t.amount.abs())),
+            Self::AbsoluteAmountAscending => transactions.sort_by_key(|t| t.amount.abs()),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt;
+
+use crate::{categorizer::TransactionMatcher, transaction::Transaction};
+
+/// Why a transaction was or was not assigned each category
+#[derive(Clone, Debug)]
+pub struct Explanation<'a> {
+    /// Explained transaction
+    pub transaction: &'a Transaction,
+    /// Result of every transaction filter, empty if there are no filters
+    pub filters: Vec<FilterResult<'a>>,
+    /// Every leaf category transaction matcher in the order they are tried
+    pub candidates: Vec<Candidate<'a>>,
+}
+
+/// Result of checking a transaction against a transaction filter
+#[derive(Clone, Debug)]
+pub struct FilterResult<'a> {
+    /// Transaction filter
+    pub matcher: &'a TransactionMatcher,
+    /// Reasons the transaction does not match, empty if it is a match
+    pub mismatches: Vec<String>,
+}
+
+/// Transaction matcher of a leaf category that could assign the transaction
+#[derive(Clone, Debug)]
+pub struct Candidate<'a> {
+    /// Category names from the top level category down to the leaf category
+    pub category_path: Vec<&'a str>,
+    /// Transaction matcher of the leaf category
+    pub matcher: &'a TransactionMatcher,
+    /// Result of checking the transaction against the matcher
+    pub outcome: Outcome,
+}
+
+/// Result of checking a transaction against a candidate transaction matcher
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// First matching candidate which assigned the category
+    Assigned,
+    /// Matching candidate tried after the assigned candidate
+    Shadowed,
+    /// Candidate does not match for the reasons
+    Rejected(Vec<String>),
+    /// Candidate was not tried because the transaction was filtered out
+    Filtered,
+}
+
+impl Explanation<'_> {
+    /// Check if the transaction passes the transaction filters
+    pub fn passes_filters(&self) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|f| f.mismatches.is_empty())
+    }
+
+    /// Candidate which assigned the category, `None` if uncategorized or filtered out
+    pub fn assigned(&self) -> Option<&Candidate<'_>> {
+        self.candidates
+            .iter()
+            .find(|c| c.outcome == Outcome::Assigned)
+    }
+}
+
+/// Multi-line summary of the filters and every candidate
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let transaction = self.transaction;
+        writeln!(
+            f,
+            "transaction: {} `{}` {} ({})",
+            transaction.time.to_rfc3339(),
+            transaction.description,
+            transaction.amount,
+            transaction.account
+        )?;
+        if self.filters.is_empty() {
+            writeln!(f, "filters: none")?;
+        } else {
+            let passed = if self.passes_filters() {
+                "passed"
+            } else {
+                "filtered out"
+            };
+            writeln!(f, "filters: {passed}")?;
+            for filter in &self.filters {
+                if filter.mismatches.is_empty() {
+                    writeln!(f, "  {}: matched", filter.matcher)?;
+                } else {
+                    writeln!(f, "  {}: {}", filter.matcher, filter.mismatches.join(", "))?;
+                }
+            }
+        }
+        match self.assigned() {
+            Some(candidate) => writeln!(f, "category: {}", candidate.category_path.join(":"))?,
+            None if self.passes_filters() => writeln!(f, "category: uncategorized")?,
+            None => writeln!(f, "category: none")?,
+        }
+        writeln!(f, "candidates:")?;
+        for candidate in &self.candidates {
+            let outcome = match &candidate.outcome {
+                Outcome::Assigned => String::from("assigned"),
+                Outcome::Shadowed => String::from("matched but an earlier candidate was assigned"),
+                Outcome::Rejected(mismatches) => format!("rejected, {}", mismatches.join(", ")),
+                Outcome::Filtered => String::from("not tried"),
+            };
+            writeln!(
+                f,
+                "  {} [{}]: {outcome}",
+                candidate.category_path.join(":"),
+                candidate.matcher
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt, fs::File, io::Read, path::Path};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, FromInto};
+
+use crate::{categorizer::Error, transaction::Transaction, util::BigDecimalSerde};
+
+/// Example transaction with the category it is expected to be assigned, embedded in a categorizer
+/// so changes to the transaction matchers can be checked
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatcherTest {
+    /// Description of the transaction
+    pub description: String,
+    /// Amount of the transaction, defaults to zero
+    #[serde_as(as = "FromInto<BigDecimalSerde>")]
+    #[serde(default)]
+    pub amount: BigDecimal,
+    /// Account of the transaction, defaults to empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub account: String,
+    /// Time of the transaction, defaults to 1970-01-01
+    #[serde(default)]
+    pub time: DateTime<Utc>,
+    /// Expected colon separated category path (ie `expenses:food`), `None` expects the
+    /// transaction to be uncategorized or filtered out
+    #[serde(default)]
+    pub expected: Option<String>,
+}
+
+/// Row of a fixture csv, extra columns (ie `source`) are ignored
+#[derive(Deserialize)]
+struct FixtureRow {
+    amount: BigDecimal,
+    account: String,
+    description: String,
+    time: DateTime<Utc>,
+    category: Option<String>,
+}
+
+impl MatcherTest {
+    /// Read tests from a csv file with `amount`, `account`, `description`, `time`, and `category`
+    /// columns (ie written by `transactions --category-column`)
+    ///
+    /// An empty category expects the transaction to be uncategorized.
+    pub fn from_csv_file<P: AsRef<Path>>(path: P) -> Result<Vec<Self>, Error> {
+        Self::from_csv_reader(File::open(path)?)
+    }
+
+    /// Read tests from a csv reader
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Vec<Self>, Error> {
+        csv::Reader::from_reader(reader)
+            .deserialize::<FixtureRow>()
+            .map(|row| {
+                let row = row?;
+                Ok(Self {
+                    description: row.description,
+                    amount: row.amount,
+                    account: row.account,
+                    time: row.time,
+                    expected: row.category,
+                })
+            })
+            .collect()
+    }
+
+    /// Transaction to categorize
+    pub fn transaction(&self) -> Transaction {
+        Transaction {
+            amount: self.amount.clone(),
+            account: self.account.clone(),
+            description: self.description.clone(),
+            time: self.time,
+            source: None,
+        }
+    }
+}
+
+/// Result of running a matcher test
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TestOutcome {
+    /// Test which was run
+    pub test: MatcherTest,
+    /// Colon separated category path the transaction was assigned, `None` if it is
+    /// uncategorized or filtered out
+    pub actual: Option<String>,
+}
+
+impl TestOutcome {
+    /// Check if the transaction was assigned the expected category
+    pub fn passed(&self) -> bool {
+        self.test.expected == self.actual
+    }
+}
+
+impl fmt::Display for TestOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let category = |category: &Option<String>| {
+            category
+                .clone()
+                .unwrap_or_else(|| String::from("uncategorized"))
+        };
+        let status = if self.passed() { "pass" } else { "FAIL" };
+        write!(
+            f,
+            "{status} {}: {:?} {}",
+            category(&self.test.expected),
+            self.test.description,
+            self.test.amount
+        )?;
+        if !self.passed() {
+            write!(f, " was assigned {}", category(&self.actual))?;
+        }
+        Ok(())
+    }
+}
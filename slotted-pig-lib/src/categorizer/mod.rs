@@ -1,29 +1,44 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     fs::File,
-    io::{BufReader, Cursor, Read},
+    io::{Cursor, Read},
     path::Path,
 };
 
 use bigdecimal::BigDecimal;
 use displaydoc::Display;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, FromInto};
 use thiserror::Error;
 
-use crate::transaction::Transaction;
+use crate::{
+    diagnostic::{from_yaml_str, YamlDiagnostic},
+    transaction::Transaction,
+    util::{BigDecimalSerde, Rounding},
+    warning::Warning,
+};
 
+pub use assignment::*;
 pub use categorized::*;
+pub use explanation::*;
+pub use matcher_test::*;
 pub use transaction_matcher::*;
 
+mod assignment;
 mod categorized;
+mod explanation;
+mod matcher_test;
 mod transaction_matcher;
 
 #[derive(Error, Debug, Display)]
 pub enum Error {
     /// io
     Io(#[from] std::io::Error),
-    /// serde_yaml
-    SerdeYaml(#[from] serde_yaml::Error),
+    /// invalid yaml
+    Yaml(#[from] YamlDiagnostic),
+    /// csv
+    Csv(#[from] csv::Error),
     /// duplicate categories in transaction matchers: {0:?}
     DuplicateCategoriesInTransactionMatchers(HashSet<String>),
     /// duplicate categories in category hierarchy: {0:?}
@@ -36,19 +51,26 @@ pub enum Error {
 ///
 /// Categories are constructed into a hierarchy. Matchers are use to assign transactions to leaf
 /// categories.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Categorizer {
     /// Filters to apply to transactions before doing any categorization
     pub transaction_filters: Option<Vec<TransactionMatcher>>,
     /// Category hierarchy
     pub categories: Vec<Category>,
+    /// Rounding applied to category totals when they are output
+    #[serde(default)]
+    pub rounding: Option<Rounding>,
+    /// Example transactions with the category they are expected to be assigned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<MatcherTest>,
 }
 
 impl Categorizer {
     /// Create a new categorizer from a yaml file
     pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        Self::from_reader(File::open(path)?)
+        let path = path.as_ref();
+        Self::from_named_reader(path.display().to_string(), File::open(path)?)
     }
 
     /// Create a new categorizer from a yaml buffer
@@ -58,8 +80,30 @@ impl Categorizer {
 
     /// Create a new categorizer from a reader
     pub fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
-        let reader = BufReader::new(reader);
-        Ok(serde_yaml::from_reader::<_, Self>(reader)?)
+        Self::from_named_reader("categorizer", reader)
+    }
+
+    fn from_named_reader<R: Read>(name: impl AsRef<str>, mut reader: R) -> Result<Self, Error> {
+        let mut yaml = String::new();
+        reader.read_to_string(&mut yaml)?;
+        Ok(from_yaml_str(name, yaml)?)
+    }
+
+    /// Round the category totals with the rounding policy, if any
+    ///
+    /// Categorizing keeps totals exact so diffs, arithmetic and reports are not skewed by rounding,
+    /// this is applied right before the categorization is output.
+    pub fn round_totals(&self, categorized: &mut CategorizedList) {
+        if let Some(rounding) = &self.rounding {
+            categorized.round_totals(rounding);
+        }
+    }
+
+    /// Check if a transaction passes the transaction filters
+    pub fn filter(&self, transaction: &Transaction) -> bool {
+        self.transaction_filters
+            .as_ref()
+            .is_none_or(|filters| filters.iter().any(|f| f.matches(transaction)))
     }
 
     /// Categorize transactions returning a new category hierarchy
@@ -67,16 +111,24 @@ impl Categorizer {
         &self,
         transactions: &'a [Transaction],
     ) -> (CategorizedList, Vec<&'a Transaction>) {
+        let all = transactions.len();
         let transactions = transactions
             .iter()
             .filter(|t| {
-                self.transaction_filters
-                    .as_ref()
-                    .map_or(true, |filters| filters.iter().any(|f| f.matches(t)))
+                let passes = self.filter(t);
+                if !passes {
+                    tracing::trace!(
+                        description = %t.description,
+                        amount = %t.amount,
+                        account = %t.account,
+                        "transaction filtered out"
+                    );
+                }
+                passes
             })
             .collect::<Vec<_>>();
         let mut categorized_transactions = HashSet::new();
-        let categorized = self
+        let categorized: CategorizedList = self
             .categories
             .iter()
             .map(|category| category.categorize(&transactions, &mut categorized_transactions))
@@ -87,22 +139,312 @@ impl Categorizer {
             .enumerate()
             .filter(|(i, _)| !categorized_transactions.contains(i))
             .map(|(_, t)| *t)
-            .collect();
+            .collect::<Vec<_>>();
+        tracing::debug!(
+            categorized = categorized_transactions.len(),
+            uncategorized = uncategorized.len(),
+            filtered = all - transactions.len(),
+            "categorized transactions"
+        );
         (categorized, uncategorized)
     }
+
+    /// Problems categorizing the transactions that do not stop categorizing (ie transaction
+    /// filters which filter out every transaction)
+    pub fn warnings(&self, transactions: &[Transaction]) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        if !transactions.is_empty() && !transactions.iter().any(|t| self.filter(t)) {
+            warnings.push(Warning::EveryTransactionFiltered {
+                count: transactions.len(),
+            });
+        }
+        warnings
+    }
+
+    /// Assign a category to each transaction that passes the transaction filters
+    ///
+    /// Assignments are returned in the same order as the transactions. A transaction is assigned to
+    /// the first leaf category, in hierarchy order, with a matching transaction matcher. This is the
+    /// same category `categorize` places the transaction in.
+    pub fn assign<'a>(&'a self, transactions: &'a [Transaction]) -> Vec<Assignment<'a>> {
+        transactions
+            .iter()
+            .filter_map(|transaction| self.assign_transaction(transaction))
+            .collect()
+    }
+
+    /// Assign a category to a single transaction, `None` if it does not pass the transaction
+    /// filters
+    ///
+    /// This allows categorizing transactions one at a time as they are parsed.
+    pub fn assign_transaction<'a>(
+        &'a self,
+        transaction: &'a Transaction,
+    ) -> Option<Assignment<'a>> {
+        if !self.filter(transaction) {
+            return None;
+        }
+        let assigned = self.categories.iter().find_map(|category| {
+            let mut path = Vec::new();
+            category
+                .assign(transaction, &mut path)
+                .map(|matcher| (path, matcher))
+        });
+        let (category_path, matcher) = assigned.unzip();
+        Some(Assignment {
+            transaction,
+            category_path,
+            matcher,
+        })
+    }
+
+    /// Explain why a transaction is assigned its category by checking it against the transaction
+    /// filters and every leaf category transaction matcher
+    pub fn explain<'a>(&'a self, transaction: &'a Transaction) -> Explanation<'a> {
+        let filters = self
+            .transaction_filters
+            .iter()
+            .flatten()
+            .map(|matcher| FilterResult {
+                matcher,
+                mismatches: matcher.mismatches(transaction),
+            })
+            .collect::<Vec<_>>();
+        let passes_filters = self.filter(transaction);
+        let mut assigned = false;
+        let candidates = self
+            .leaf_matchers()
+            .into_iter()
+            .map(|(category_path, matcher)| {
+                let mismatches = matcher.mismatches(transaction);
+                let outcome = if !passes_filters {
+                    Outcome::Filtered
+                } else if !mismatches.is_empty() {
+                    Outcome::Rejected(mismatches)
+                } else if assigned {
+                    Outcome::Shadowed
+                } else {
+                    assigned = true;
+                    Outcome::Assigned
+                };
+                Candidate {
+                    category_path,
+                    matcher,
+                    outcome,
+                }
+            })
+            .collect();
+        Explanation {
+            transaction,
+            filters,
+            candidates,
+        }
+    }
+
+    /// Check which category the test transaction is assigned
+    pub fn test(&self, test: &MatcherTest) -> TestOutcome {
+        let transaction = test.transaction();
+        let actual = self
+            .assign_transaction(&transaction)
+            .and_then(|assignment| assignment.category_path_joined(":"));
+        TestOutcome {
+            test: test.clone(),
+            actual,
+        }
+    }
+
+    /// Run every test embedded in the categorizer
+    pub fn run_tests(&self) -> Vec<TestOutcome> {
+        self.tests.iter().map(|test| self.test(test)).collect()
+    }
+
+    /// Every leaf category transaction matcher with its category path in the order they are tried
+    pub fn leaf_matchers(&self) -> Vec<(Vec<&str>, &TransactionMatcher)> {
+        let mut matchers = Vec::new();
+        for category in &self.categories {
+            category.leaf_matchers(&mut Vec::new(), &mut matchers);
+        }
+        matchers
+    }
+
+    /// Category names from the top level category down to every leaf category in hierarchy order
+    pub fn leaf_category_paths(&self) -> Vec<Vec<&str>> {
+        let mut paths = Vec::new();
+        for category in &self.categories {
+            category.leaf_category_paths(&mut Vec::new(), &mut paths);
+        }
+        paths
+    }
+
+    /// Monthly budgets keyed by colon separated category path (ie `expenses:food`)
+    pub fn budgets(&self) -> BTreeMap<String, &BigDecimal> {
+        let mut budgets = BTreeMap::new();
+        for category in &self.categories {
+            category.budgets(&mut Vec::new(), &mut budgets);
+        }
+        budgets
+    }
+
+    /// Colors and icons keyed by colon separated category path (ie `expenses:food`), only
+    /// categories with a color or icon of their own or an ancestor's color are included
+    pub fn category_styles(&self) -> BTreeMap<String, CategoryStyle> {
+        let mut styles = BTreeMap::new();
+        for category in &self.categories {
+            category.styles(&mut Vec::new(), None, &mut styles);
+        }
+        styles
+    }
+
+    /// Check if the category path or any of its ancestors is tax relevant
+    pub fn is_tax_relevant(&self, path: &[&str]) -> bool {
+        let mut categories = &self.categories;
+        for name in path {
+            let Some(category) = categories.iter().find(|c| c.category == *name) else {
+                return false;
+            };
+            if category.tax_relevant {
+                return true;
+            }
+            match &category.children {
+                CategoryChildren::Subcategories(subcategories) => categories = subcategories,
+                CategoryChildren::TransactionMatchers(_) => return false,
+            }
+        }
+        false
+    }
+}
+
+/// Color and icon of a category
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CategoryStyle {
+    /// Color of the category or its nearest ancestor with a color
+    pub color: Option<String>,
+    /// Icon of the category
+    pub icon: Option<String>,
 }
 
 /// Hierarchy of categories with arbitrary depth
-#[derive(Debug, Deserialize, Serialize)]
+#[serde_as]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Category {
     /// Category name
     pub category: String,
+    /// Maximum amount to spend in the category each month (ie `500` for spending up to 500)
+    #[serde_as(as = "Option<FromInto<BigDecimalSerde>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BigDecimal>,
+    /// Transactions in the category and its subcategories are relevant for taxes (ie deductible)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tax_relevant: bool,
+    /// CSS color of the category in charts and the UI (ie `#e15759` or `teal`), subcategories
+    /// without a color use it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Icon shown before the category name (ie an emoji)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
     /// Category children
     pub children: CategoryChildren,
 }
 
 impl Category {
+    fn budgets<'a>(
+        &'a self,
+        path: &mut Vec<&'a str>,
+        budgets: &mut BTreeMap<String, &'a BigDecimal>,
+    ) {
+        path.push(&self.category);
+        if let Some(budget) = &self.budget {
+            budgets.insert(path.join(":"), budget);
+        }
+        if let CategoryChildren::Subcategories(subcategories) = &self.children {
+            for subcategory in subcategories {
+                subcategory.budgets(path, budgets);
+            }
+        }
+        path.pop();
+    }
+
+    fn styles<'a>(
+        &'a self,
+        path: &mut Vec<&'a str>,
+        inherited_color: Option<&'a str>,
+        styles: &mut BTreeMap<String, CategoryStyle>,
+    ) {
+        path.push(&self.category);
+        let color = self.color.as_deref().or(inherited_color);
+        if color.is_some() || self.icon.is_some() {
+            styles.insert(
+                path.join(":"),
+                CategoryStyle {
+                    color: color.map(String::from),
+                    icon: self.icon.clone(),
+                },
+            );
+        }
+        if let CategoryChildren::Subcategories(subcategories) = &self.children {
+            for subcategory in subcategories {
+                subcategory.styles(path, color, styles);
+            }
+        }
+        path.pop();
+    }
+
+    fn leaf_category_paths<'a>(&'a self, path: &mut Vec<&'a str>, paths: &mut Vec<Vec<&'a str>>) {
+        path.push(&self.category);
+        match &self.children {
+            CategoryChildren::TransactionMatchers(_) => paths.push(path.clone()),
+            CategoryChildren::Subcategories(subcategories) => {
+                for subcategory in subcategories {
+                    subcategory.leaf_category_paths(path, paths);
+                }
+            }
+        }
+        path.pop();
+    }
+
+    /// Collect every transaction matcher of the leaf categories with its category path in the
+    /// order they are tried
+    fn leaf_matchers<'a>(
+        &'a self,
+        path: &mut Vec<&'a str>,
+        matchers: &mut Vec<(Vec<&'a str>, &'a TransactionMatcher)>,
+    ) {
+        path.push(&self.category);
+        match &self.children {
+            CategoryChildren::TransactionMatchers(leaf_matchers) => {
+                matchers.extend(leaf_matchers.iter().map(|m| (path.clone(), m)));
+            }
+            CategoryChildren::Subcategories(subcategories) => {
+                for subcategory in subcategories {
+                    subcategory.leaf_matchers(path, matchers);
+                }
+            }
+        }
+        path.pop();
+    }
+
+    fn assign<'a>(
+        &'a self,
+        transaction: &Transaction,
+        path: &mut Vec<&'a str>,
+    ) -> Option<&'a TransactionMatcher> {
+        path.push(&self.category);
+        let matcher = match &self.children {
+            CategoryChildren::TransactionMatchers(matchers) => {
+                matchers.iter().find(|m| m.matches(transaction))
+            }
+            CategoryChildren::Subcategories(subcategories) => subcategories
+                .iter()
+                .find_map(|subcategory| subcategory.assign(transaction, path)),
+        };
+        if matcher.is_none() {
+            path.pop();
+        }
+        matcher
+    }
+
     fn categorize(
         &self,
         transactions: &[&Transaction],
@@ -156,7 +498,7 @@ impl Category {
 }
 
 // Possible category children, either a list of transaction matchers or subcategories
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CategoryChildren {
     /// Transaction matchers
@@ -1,19 +1,27 @@
-use bigdecimal::BigDecimal;
+use std::fmt;
+
+use bigdecimal::{BigDecimal, Zero};
 use chrono::{DateTime, Utc};
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{formats::PreferOne, serde_as, FromInto, OneOrMany};
 
-use crate::{transaction::Transaction, util::RegexSerde};
+use crate::{
+    transaction::Transaction,
+    util::{normalize_payee, payee_regex, BigDecimalSerde, RegexSerde},
+};
 
 /// Rules to determine if a transaction matches a category
 #[serde_as]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct TransactionMatcher {
     /// Minimum amount of the transaction inclusive
+    #[serde_as(as = "Option<FromInto<BigDecimalSerde>>")]
     pub min: Option<BigDecimal>,
     /// Maximum amount of the transaction inclusive
+    #[serde_as(as = "Option<FromInto<BigDecimalSerde>>")]
     pub max: Option<BigDecimal>,
     /// Match against account name of the transaction
     pub account: Option<String>,
@@ -28,6 +36,38 @@ pub struct TransactionMatcher {
 }
 
 impl TransactionMatcher {
+    /// Suggested matcher of the transactions of the transaction's payee with the same sign
+    ///
+    /// Descriptions without a payee (ie only reference codes) fall back to the exact description.
+    pub fn for_payee(transaction: &Transaction) -> Self {
+        let payee = normalize_payee(&transaction.description);
+        let zero = BigDecimal::zero();
+        Self {
+            min: (transaction.amount >= zero).then(|| zero.clone()),
+            max: (transaction.amount <= zero).then(|| zero.clone()),
+            account: None,
+            description: if payee.is_empty() {
+                vec![exact_regex(&transaction.description)]
+            } else {
+                vec![Regex::new(&payee_regex(&payee)).expect("payee regex is escaped")]
+            },
+            begin: None,
+            end: None,
+        }
+    }
+
+    /// Matcher of only the transaction by its exact amount, account, description, and time
+    pub fn for_transaction(transaction: &Transaction) -> Self {
+        Self {
+            min: Some(transaction.amount.clone()),
+            max: Some(transaction.amount.clone()),
+            account: Some(transaction.account.clone()),
+            description: vec![exact_regex(&transaction.description)],
+            begin: Some(transaction.time),
+            end: Some(transaction.time),
+        }
+    }
+
     /// Check if a transaction is a match
     pub fn matches(&self, transaction: &Transaction) -> bool {
         let min = self
@@ -62,4 +102,100 @@ impl TransactionMatcher {
             .unwrap_or(true);
         min && max && account && description && begin && end
     }
+
+    /// Reasons the transaction does not match, empty if it is a match
+    pub fn mismatches(&self, transaction: &Transaction) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if let Some(min) = self.min.as_ref().filter(|a| *a > &transaction.amount) {
+            mismatches.push(format!(
+                "amount {} is less than min {min}",
+                transaction.amount
+            ));
+        }
+        if let Some(max) = self.max.as_ref().filter(|a| *a < &transaction.amount) {
+            mismatches.push(format!(
+                "amount {} is greater than max {max}",
+                transaction.amount
+            ));
+        }
+        if let Some(account) = self.account.as_ref().filter(|a| *a != &transaction.account) {
+            mismatches.push(format!(
+                "account `{}` is not `{account}`",
+                transaction.account
+            ));
+        }
+        if !self.description.is_empty()
+            && !self
+                .description
+                .iter()
+                .any(|r| r.is_match(&transaction.description))
+        {
+            let description = self
+                .description
+                .iter()
+                .map(Regex::as_str)
+                .collect::<Vec<_>>()
+                .join("|");
+            mismatches.push(format!(
+                "description `{}` does not match `{description}`",
+                transaction.description
+            ));
+        }
+        if let Some(begin) = self.begin.as_ref().filter(|a| *a > &transaction.time) {
+            mismatches.push(format!(
+                "time {} is before begin {}",
+                transaction.time.to_rfc3339(),
+                begin.to_rfc3339()
+            ));
+        }
+        if let Some(end) = self.end.as_ref().filter(|a| *a < &transaction.time) {
+            mismatches.push(format!(
+                "time {} is after end {}",
+                transaction.time.to_rfc3339(),
+                end.to_rfc3339()
+            ));
+        }
+        mismatches
+    }
+}
+
+/// Regex matching only the whole text
+fn exact_regex(text: &str) -> Regex {
+    Regex::new(&format!("^{}$", regex::escape(text))).expect("text is escaped")
+}
+
+/// Compact single line description of the rules (ie `max<=0 description=~Groceries.*|Restaurant`)
+impl fmt::Display for TransactionMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rules = Vec::new();
+        if let Some(min) = &self.min {
+            rules.push(format!("min>={min}"));
+        }
+        if let Some(max) = &self.max {
+            rules.push(format!("max<={max}"));
+        }
+        if let Some(account) = &self.account {
+            rules.push(format!("account=={account}"));
+        }
+        if !self.description.is_empty() {
+            let description = self
+                .description
+                .iter()
+                .map(Regex::as_str)
+                .collect::<Vec<_>>()
+                .join("|");
+            rules.push(format!("description=~{description}"));
+        }
+        if let Some(begin) = &self.begin {
+            rules.push(format!("begin>={}", begin.to_rfc3339()));
+        }
+        if let Some(end) = &self.end {
+            rules.push(format!("end<={}", end.to_rfc3339()));
+        }
+        if rules.is_empty() {
+            write!(f, "*")
+        } else {
+            write!(f, "{}", rules.join(" "))
+        }
+    }
 }
@@ -0,0 +1,232 @@
+use std::path::Path;
+
+use bigdecimal::ToPrimitive;
+use displaydoc::Display;
+use plotters::{
+    coord::{types::RangedCoordf64, Shift},
+    prelude::*,
+    style::{Palette, Palette99},
+};
+use thiserror::Error;
+
+use crate::{categorizer::Categorized, report::Pivot};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// plotters: {0}
+    Plotters(String),
+}
+
+const FONT: &str = "sans-serif";
+
+/// Chart that can be rendered to an SVG or PNG image
+#[derive(Clone, Copy, Debug)]
+pub enum Chart<'a> {
+    /// Donut of the absolute totals of the categories
+    Donut(&'a [Categorized]),
+    /// Bars for each time period stacked with the absolute totals of each category
+    StackedBars(&'a Pivot),
+    /// Line for each category of its total over time
+    Lines(&'a Pivot),
+}
+
+impl Chart<'_> {
+    /// Render the chart as an SVG document
+    pub fn to_svg(&self, size: (u32, u32)) -> Result<String, Error> {
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+            self.draw(&root)?;
+            root.present().map_err(plotters_error)?;
+        }
+        Ok(svg)
+    }
+
+    /// Save the chart to a file, the image format is determined by the `svg` or `png` extension
+    pub fn save(&self, path: &Path, size: (u32, u32)) -> Result<(), Error> {
+        if path.extension().is_some_and(|e| e == "svg") {
+            let root = SVGBackend::new(path, size).into_drawing_area();
+            self.draw(&root)?;
+            root.present().map_err(plotters_error)
+        } else {
+            let root = BitMapBackend::new(path, size).into_drawing_area();
+            self.draw(&root)?;
+            root.present().map_err(plotters_error)
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) -> Result<(), Error> {
+        root.fill(&WHITE).map_err(plotters_error)?;
+        match self {
+            Self::Donut(categories) => draw_donut(root, categories),
+            Self::StackedBars(pivot) => draw_stacked_bars(root, pivot),
+            Self::Lines(pivot) => draw_lines(root, pivot),
+        }
+    }
+}
+
+fn draw_donut<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    categories: &[Categorized],
+) -> Result<(), Error> {
+    let categories = categories
+        .iter()
+        .filter_map(|c| Some((c.category.as_str(), c.absolute_total.to_f64()?)))
+        .filter(|(_, total)| *total > 0.0)
+        .collect::<Vec<_>>();
+    let (width, height) = root.dim_in_pixel();
+    let center = (width as i32 / 2, height as i32 / 2);
+    let radius = f64::from(width.min(height)) * 0.35;
+    let sizes = categories.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+    let labels = categories.iter().map(|(c, _)| *c).collect::<Vec<_>>();
+    let colors = (0..categories.len()).map(color).collect::<Vec<_>>();
+
+    let mut donut = Pie::new(&center, &radius, &sizes, &colors, &labels);
+    donut.start_angle(-90.0);
+    donut.donut_hole(radius * 0.5);
+    donut.label_style((FONT, 16).into_font());
+    donut.percentages((FONT, 12).into_font().color(&BLACK));
+    root.draw(&donut).map_err(plotters_error)
+}
+
+fn draw_stacked_bars<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    pivot: &Pivot,
+) -> Result<(), Error> {
+    // Stack the absolute totals of every category for each time period
+    let mut stacks = vec![0.0; pivot.periods.len()];
+    let bars = pivot
+        .rows
+        .iter()
+        .map(|row| {
+            row.totals
+                .iter()
+                .enumerate()
+                .map(|(period, total)| {
+                    let bottom = stacks[period];
+                    stacks[period] += total.abs().to_f64().unwrap_or_default();
+                    (period as f64, bottom, stacks[period])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let max = stacks.iter().copied().fold(0.0, f64::max);
+    // An empty pivot or one with only zero totals still renders an empty chart
+    let top = if max > 0.0 { max * 1.05 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(root)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(period_range(pivot), 0.0..top)
+        .map_err(plotters_error)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(pivot.periods.len())
+        .x_label_formatter(&|x| period_label(pivot, *x))
+        .draw()
+        .map_err(plotters_error)?;
+
+    for (i, (row, bars)) in pivot.rows.iter().zip(bars).enumerate() {
+        let style = color(i).filled();
+        chart
+            .draw_series(
+                bars.into_iter().map(|(x, bottom, top)| {
+                    Rectangle::new([(x - 0.4, bottom), (x + 0.4, top)], style)
+                }),
+            )
+            .map_err(plotters_error)?
+            .label(&row.category)
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], style));
+    }
+    draw_legend(&mut chart)
+}
+
+fn draw_lines<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    pivot: &Pivot,
+) -> Result<(), Error> {
+    let lines = pivot
+        .rows
+        .iter()
+        .map(|row| {
+            row.totals
+                .iter()
+                .enumerate()
+                .map(|(period, total)| (period as f64, total.to_f64().unwrap_or_default()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let (min, max) = lines
+        .iter()
+        .flatten()
+        .fold((0.0, 0.0), |(min, max), (_, y)| (y.min(min), y.max(max)));
+    // An empty pivot or one with a single total still renders an empty chart
+    let margin = if max > min { (max - min) * 0.05 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(root)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(period_range(pivot), (min - margin)..(max + margin))
+        .map_err(plotters_error)?;
+    chart
+        .configure_mesh()
+        .x_labels(pivot.periods.len())
+        .x_label_formatter(&|x| period_label(pivot, *x))
+        .draw()
+        .map_err(plotters_error)?;
+
+    for (i, (row, line)) in pivot.rows.iter().zip(lines).enumerate() {
+        let style = color(i).stroke_width(2);
+        chart
+            .draw_series(LineSeries::new(line, style))
+            .map_err(plotters_error)?
+            .label(&row.category)
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 10, y)], style));
+    }
+    draw_legend(&mut chart)
+}
+
+fn draw_legend<'a, DB: DrawingBackend + 'a>(
+    chart: &mut ChartContext<'a, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+) -> Result<(), Error> {
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .label_font((FONT, 12))
+        .draw()
+        .map_err(plotters_error)
+}
+
+/// Range of the x axis centering each time period, with room for one when there are none
+fn period_range(pivot: &Pivot) -> std::ops::Range<f64> {
+    -0.5..pivot.periods.len().max(1) as f64 - 0.5
+}
+
+/// Label of the time period at an x coordinate, empty between periods
+fn period_label(pivot: &Pivot, x: f64) -> String {
+    let period = x.round();
+    if (x - period).abs() > f64::EPSILON || period < 0.0 {
+        return String::new();
+    }
+    pivot
+        .periods
+        .get(period as usize)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn color(index: usize) -> RGBColor {
+    let (r, g, b) = Palette99::COLORS[index % Palette99::COLORS.len()];
+    RGBColor(r, g, b)
+}
+
+fn plotters_error<E: std::error::Error + Send + Sync>(error: DrawingAreaErrorKind<E>) -> Error {
+    Error::Plotters(error.to_string())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use test_case::test_case;
+
+use crate::{
+    categorizer::Categorizer,
+    charts::Chart,
+    report::{Bucket, Pivot},
+    transaction::Transaction,
+};
+
+#[test_case("donut"; "donut")]
+#[test_case("stacked_bars"; "stacked bars")]
+#[test_case("lines"; "lines")]
+fn test_chart_to_svg(chart: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let pivot = Pivot::new(&categorizer.assign(&transactions), Bucket::Month, Some(1));
+    let chart = match chart {
+        "donut" => Chart::Donut(&categorized.categorized),
+        "stacked_bars" => Chart::StackedBars(&pivot),
+        _ => Chart::Lines(&pivot),
+    };
+    let svg = chart.to_svg((800, 600))?;
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("expenses"));
+    Ok(())
+}
+
+#[test_case("stacked_bars"; "stacked bars")]
+#[test_case("lines"; "lines")]
+fn test_chart_empty_pivot_to_svg(chart: &str) -> Result<()> {
+    let pivot = Pivot::default();
+    let chart = match chart {
+        "stacked_bars" => Chart::StackedBars(&pivot),
+        _ => Chart::Lines(&pivot),
+    };
+    let svg = chart.to_svg((800, 600))?;
+    assert!(svg.starts_with("<svg"));
+    // The y axis is labeled, a degenerate range draws no labels
+    assert!(svg.contains("<text"));
+    Ok(())
+}
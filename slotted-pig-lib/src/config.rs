@@ -0,0 +1,86 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use displaydoc::Display;
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter, EnumString};
+use thiserror::Error;
+
+use crate::{
+    categorizer::{Categorizer, CategorySort, TransactionSort},
+    diagnostic::{from_yaml_str, YamlDiagnostic},
+    transaction::TransactionParser,
+};
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] std::io::Error),
+    /// invalid yaml
+    Yaml(#[from] YamlDiagnostic),
+}
+
+/// Transaction parser, categorizer, and defaults combined in a single file (ie `slotted-pig.yaml`)
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Config for reading transactions from files
+    pub transaction_parser: TransactionParser,
+    /// Config categorizing transactions
+    pub categorizer: Categorizer,
+    /// File glob pattern of transaction files to parse, relative to the config file
+    #[serde(default)]
+    pub transaction_path_pattern: Option<String>,
+    /// Default sort of categorized subcategories
+    #[serde(default)]
+    pub category_sort: Option<CategorySort>,
+    /// Default sort of categorized transactions
+    #[serde(default)]
+    pub transaction_sort: Option<TransactionSort>,
+}
+
+impl Config {
+    /// Create a new config from a yaml file
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        Self::from_reader(path.display().to_string(), File::open(path)?)
+    }
+
+    /// Create a new config from a yaml buffer
+    pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
+        Self::from_reader("config", Cursor::new(buffer))
+    }
+
+    fn from_reader<R: Read>(name: impl AsRef<str>, mut reader: R) -> Result<Self, Error> {
+        let mut yaml = String::new();
+        reader.read_to_string(&mut yaml)?;
+        Ok(from_yaml_str(name, yaml)?)
+    }
+}
+
+/// Kind of yaml config
+#[derive(AsRefStr, Clone, Copy, Debug, EnumIter, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ConfigKind {
+    /// Transaction parser config (ie `transaction_parser.yaml`)
+    TransactionParser,
+    /// Categorizer config (ie `categorizer.yaml`)
+    Categorizer,
+    /// Combined config (ie `slotted-pig.yaml`)
+    Config,
+}
+
+impl ConfigKind {
+    /// JSON schema of the config, editors use it to autocomplete and validate the yaml
+    pub fn json_schema(self) -> RootSchema {
+        match self {
+            Self::TransactionParser => schema_for!(TransactionParser),
+            Self::Categorizer => schema_for!(Categorizer),
+            Self::Config => schema_for!(Config),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+/// Yaml config which failed to deserialize with the span of the problem in its source, render it
+/// with a miette report handler to show the offending lines
+#[derive(Debug, Diagnostic, Error)]
+#[error("{message}")]
+pub struct YamlDiagnostic {
+    message: String,
+    // Behind a pointer so the error enums holding this stay small
+    #[source_code]
+    source_code: Arc<NamedSource<String>>,
+    #[label("{label}")]
+    span: Option<SourceSpan>,
+    label: String,
+    #[help]
+    help: Option<String>,
+}
+
+impl YamlDiagnostic {
+    /// Diagnostic of a serde_yaml error of the yaml named `name` (ie its path)
+    pub fn new(name: impl AsRef<str>, yaml: String, error: &serde_yaml::Error) -> Self {
+        let mut message = error.to_string();
+        let span = error.location().map(|location| {
+            message = message.replace(
+                &format!(" at line {} column {}", location.line(), location.column()),
+                "",
+            );
+            let token = yaml[location.index()..]
+                .split([':', '\n'])
+                .next()
+                .unwrap_or_default()
+                .trim_end();
+            SourceSpan::from((location.index(), token.len()))
+        });
+        // The label is the first line without the path to the field (ie `categories[0]: `) and
+        // the expected values
+        let label = message
+            .split_once(": ")
+            .filter(|(path, _)| !path.contains(' '))
+            .map_or(message.as_str(), |(_, label)| label)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split(", expected")
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(':')
+            .to_string();
+        Self {
+            help: suggestion(&message).map(|suggestion| format!("did you mean `{suggestion}`?")),
+            source_code: Arc::new(NamedSource::new(name, yaml)),
+            span,
+            label,
+            message,
+        }
+    }
+
+    /// Message of the problem without its location
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Suggested fix (ie `did you mean `description`?`)
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+}
+
+/// Deserialize the yaml named `name`, failures carry the span of the problem
+pub fn from_yaml_str<T: DeserializeOwned>(
+    name: impl AsRef<str>,
+    yaml: String,
+) -> Result<T, YamlDiagnostic> {
+    serde_yaml::from_str(&yaml).map_err(|e| YamlDiagnostic::new(name, yaml, &e))
+}
+
+/// Closest expected name to an unknown field or variant of a serde message (ie `description` for
+/// "unknown field `descripton`, expected one of `min`, `description`")
+pub(crate) fn suggestion(message: &str) -> Option<&str> {
+    let (_, rest) = message
+        .split_once("unknown field `")
+        .or_else(|| message.split_once("unknown variant `"))?;
+    let (unknown, expected) = rest.split_once('`')?;
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|name| (strsim::jaro_winkler(unknown, name), name))
+        .filter(|(similarity, _)| *similarity > 0.8)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, name)| name)
+}
@@ -0,0 +1,208 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::{
+    categorizer::{Categorizer, TransactionMatcher},
+    util::yaml_string,
+};
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// categorizer
+    Categorizer(#[from] crate::categorizer::Error),
+    /// category `{0}` was not found, categories must be written in block style
+    CategoryNotFound(String),
+    /// category `{0}` has subcategories instead of transaction matchers
+    NotLeafCategory(String),
+    /// transaction matchers of category `{0}` are written in flow style
+    FlowStyleMatchers(String),
+}
+
+/// Add a transaction matcher after the existing transaction matchers of a leaf category
+///
+/// The yaml is edited as text so comments and formatting are preserved. The edited yaml is parsed
+/// to make sure it is still a valid categorizer before it is returned.
+pub fn add_transaction_matcher(
+    yaml: &str,
+    category_path: &[&str],
+    matcher: &TransactionMatcher,
+) -> Result<String, Error> {
+    let joined = category_path.join(":");
+    let mut lines = yaml.lines().map(String::from).collect::<Vec<_>>();
+
+    // Track the path of the current category by the columns of the `category` keys
+    let mut path = Vec::<(usize, String)>::new();
+    let (category_line, column) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, line)| {
+            let (column, value) = key_value(line, "category")?;
+            while path.last().is_some_and(|(c, _)| *c >= column) {
+                path.pop();
+            }
+            path.push((column, unquote(value)));
+            path.iter()
+                .map(|(_, name)| name.as_str())
+                .eq(category_path.iter().copied())
+                .then_some((i, column))
+        })
+        .ok_or_else(|| Error::CategoryNotFound(joined.clone()))?;
+
+    // Find the `children` key of the category mapping
+    let (children_line, children) = lines
+        .iter()
+        .enumerate()
+        .skip(category_line + 1)
+        .filter(|(_, line)| !is_blank(line))
+        .take_while(|(_, line)| indent(line) >= column)
+        .find_map(|(i, line)| {
+            key_value(line, "children")
+                .filter(|(c, _)| *c == column)
+                .map(|(_, value)| (i, value.to_string()))
+        })
+        .ok_or_else(|| Error::CategoryNotFound(joined.clone()))?;
+    let Some(children) = children.strip_prefix("!transaction_matchers") else {
+        return Err(Error::NotLeafCategory(joined));
+    };
+    let children = children.split(" #").next().unwrap_or_default().trim();
+
+    let (insert_at, item_column) = match children {
+        "" => {
+            // Items may be at the same indentation as the `children` key
+            let first = lines
+                .iter()
+                .enumerate()
+                .skip(children_line + 1)
+                .find(|(_, line)| !is_blank(line))
+                .map(|(i, line)| (i, indent(line)))
+                .filter(|(i, item_column)| {
+                    *item_column >= column && lines[*i].trim_start().starts_with('-')
+                });
+            let Some((first, item_column)) = first else {
+                return Err(Error::FlowStyleMatchers(joined));
+            };
+            let last = lines
+                .iter()
+                .enumerate()
+                .skip(first)
+                .filter(|(_, line)| !is_blank(line))
+                .take_while(|(_, line)| {
+                    indent(line) > item_column
+                        || indent(line) == item_column && line.trim_start().starts_with('-')
+                })
+                .last()
+                .map_or(first, |(i, _)| i);
+            (last + 1, item_column)
+        }
+        "[]" => {
+            let line = &mut lines[children_line];
+            let end = line.find("[]").expect("flow sequence is on the line");
+            line.replace_range(end..end + 2, "");
+            *line = line.trim_end().to_string();
+            (children_line + 1, column + 2)
+        }
+        _ => return Err(Error::FlowStyleMatchers(joined)),
+    };
+
+    let item = matcher_lines(matcher)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { "- " } else { "  " };
+            format!("{}{prefix}{line}", " ".repeat(item_column))
+        });
+    lines.splice(insert_at..insert_at, item);
+
+    let mut edited = lines.join("\n");
+    if yaml.ends_with('\n') {
+        edited.push('\n');
+    }
+    Categorizer::from_yaml_buffer(&edited)?;
+    Ok(edited)
+}
+
+/// Yaml `key: value` lines of a transaction matcher
+fn matcher_lines(matcher: &TransactionMatcher) -> Vec<String> {
+    let mut lines = Vec::new();
+    match matcher.description.as_slice() {
+        [] => {}
+        [description] => lines.push(format!(
+            "description: {}",
+            yaml_string(description.as_str())
+        )),
+        descriptions => lines.push(format!(
+            "description: [{}]",
+            descriptions
+                .iter()
+                .map(|d| yaml_string(d.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+    if let Some(min) = &matcher.min {
+        lines.push(format!("min: {min}"));
+    }
+    if let Some(max) = &matcher.max {
+        lines.push(format!("max: {max}"));
+    }
+    if let Some(account) = &matcher.account {
+        lines.push(format!("account: {}", yaml_string(account)));
+    }
+    if let Some(begin) = &matcher.begin {
+        lines.push(format!("begin: {}", yaml_string(&begin.to_rfc3339())));
+    }
+    if let Some(end) = &matcher.end {
+        lines.push(format!("end: {}", yaml_string(&end.to_rfc3339())));
+    }
+    if lines.is_empty() {
+        lines.push(String::from("{}"));
+    }
+    lines
+}
+
+/// Column of the key and its value if the line is a block style `key: value` (optionally a
+/// sequence item)
+fn key_value<'a>(line: &'a str, key: &str) -> Option<(usize, &'a str)> {
+    let trimmed = line.trim_start();
+    let trimmed = trimmed
+        .strip_prefix('-')
+        .map(str::trim_start)
+        .unwrap_or(trimmed);
+    let value = trimmed.strip_prefix(key)?.trim_start().strip_prefix(':')?;
+    Some((line.len() - trimmed.len(), value.trim()))
+}
+
+/// Plain or quoted yaml scalar without a trailing comment
+fn unquote(value: &str) -> String {
+    if let Some(rest) = value.strip_prefix('\'') {
+        // A quote is escaped by doubling it
+        let mut unquoted = String::new();
+        let mut chars = rest.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                break;
+            }
+            unquoted.push(c);
+        }
+        unquoted
+    } else if let Some(rest) = value.strip_prefix('"') {
+        rest.split_once('"').map_or(rest, |(s, _)| s).to_string()
+    } else {
+        value
+            .split(" #")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Empty or comment only lines do not affect the structure
+fn is_blank(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
@@ -0,0 +1,155 @@
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read},
+    path::Path,
+};
+
+use displaydoc::Display;
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+use thiserror::Error;
+
+use crate::{
+    report::{Digest, DigestPeriod, StatementFormat},
+    util::Formatter,
+};
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] std::io::Error),
+    /// serde_yaml
+    SerdeYaml(#[from] serde_yaml::Error),
+    /// report
+    Report(#[from] crate::report::Error),
+    /// address
+    Address(#[from] lettre::address::AddressError),
+    /// email
+    Email(#[from] lettre::error::Error),
+    /// smtp
+    Smtp(#[from] lettre::transport::smtp::Error),
+    /// missing environment variable {0} with the smtp password
+    MissingPassword(String),
+}
+
+/// Configuration for emailing digests
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmailConfig {
+    /// Server to send email through
+    pub smtp: SmtpConfig,
+    /// Sender address (ie `Slotted Pig <pig@example.com>`)
+    pub from: String,
+    /// Recipient addresses
+    pub to: Vec<String>,
+    /// Subject prefixed to the digest title
+    #[serde(default = "EmailConfig::default_subject")]
+    pub subject: String,
+    /// Length of time each digest summarizes
+    #[serde(default)]
+    pub period: DigestPeriod,
+}
+
+/// SMTP server connection settings
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    /// Server host name
+    pub host: String,
+    /// Server port, defaults to the standard port of the security
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// User name to authenticate with
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Environment variable holding the password so it is not stored in the config file
+    #[serde(default)]
+    pub password_env: Option<String>,
+    /// Connection encryption
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+/// Encryption of the SMTP connection
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS
+    #[default]
+    StartTls,
+    /// Connect with TLS
+    Tls,
+    /// No encryption, only for local relays
+    None,
+}
+
+impl EmailConfig {
+    /// Create a new email config from a yaml file
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Create a new email config from a yaml buffer
+    pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
+        Self::from_reader(Cursor::new(buffer))
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let reader = BufReader::new(reader);
+        Ok(serde_yaml::from_reader::<_, Self>(reader)?)
+    }
+
+    /// Build an email of the digest with plain text and HTML bodies
+    pub fn message(&self, digest: &Digest, formatter: &Formatter) -> Result<Message, Error> {
+        let mut text = Vec::new();
+        digest.write(StatementFormat::Text, formatter, &mut text)?;
+        let mut html = Vec::new();
+        digest.write(StatementFormat::Html, formatter, &mut html)?;
+
+        let mut builder = Message::builder()
+            .from(self.from.parse::<Mailbox>()?)
+            .subject(format!("{}: {}", self.subject, digest.title(formatter)));
+        for to in &self.to {
+            builder = builder.to(to.parse::<Mailbox>()?);
+        }
+        Ok(builder.multipart(MultiPart::alternative_plain_html(
+            String::from_utf8_lossy(&text).into_owned(),
+            String::from_utf8_lossy(&html).into_owned(),
+        ))?)
+    }
+
+    /// Email the digest to the recipients
+    pub fn send(&self, digest: &Digest, formatter: &Formatter) -> Result<(), Error> {
+        let message = self.message(digest, formatter)?;
+        let smtp = &self.smtp;
+        let mut transport = match smtp.security {
+            SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&smtp.host)?,
+            SmtpSecurity::Tls => SmtpTransport::relay(&smtp.host)?,
+            SmtpSecurity::None => SmtpTransport::builder_dangerous(&smtp.host),
+        };
+        if let Some(port) = smtp.port {
+            transport = transport.port(port);
+        }
+        if let Some(username) = &smtp.username {
+            let password = match &smtp.password_env {
+                Some(variable) => {
+                    std::env::var(variable).map_err(|_| Error::MissingPassword(variable.clone()))?
+                }
+                None => String::new(),
+            };
+            transport = transport.credentials(Credentials::new(username.clone(), password));
+        }
+        transport.build().send(&message)?;
+        Ok(())
+    }
+
+    fn default_subject() -> String {
+        String::from("Slotted Pig")
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use age::{scrypt, secrecy::SecretString};
+use displaydoc::Display;
+use thiserror::Error;
+
+/// Extension of files encrypted at rest, the files are compatible with `age --passphrase`
+pub const ENCRYPTED_EXTENSION: &str = "age";
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] io::Error),
+    /// failed to encrypt
+    Encrypt(#[from] age::EncryptError),
+    /// failed to decrypt, is the passphrase correct?
+    Decrypt(#[from] age::DecryptError),
+}
+
+/// Passphrase unlocking files encrypted at rest, zeroed when dropped
+#[derive(Clone)]
+pub struct Passphrase(SecretString);
+
+impl Passphrase {
+    pub fn new(passphrase: impl Into<String>) -> Self {
+        Self(SecretString::from(passphrase.into()))
+    }
+}
+
+impl std::fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Passphrase(..)")
+    }
+}
+
+/// Encrypt a buffer with the passphrase
+pub fn encrypt(passphrase: &Passphrase, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+    let recipient = scrypt::Recipient::new(passphrase.0.clone());
+    Ok(age::encrypt(&recipient, plaintext)?)
+}
+
+/// Decrypt a buffer (binary or armored) encrypted with the passphrase
+pub fn decrypt(passphrase: &Passphrase, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+    let identity = scrypt::Identity::new(passphrase.0.clone());
+    Ok(age::decrypt(&identity, ciphertext)?)
+}
+
+/// Whether the file is encrypted at rest based on its extension (ie `categorizer.yaml.age`)
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == ENCRYPTED_EXTENSION)
+}
+
+/// Read and decrypt a file encrypted with the passphrase
+pub fn read(path: &Path, passphrase: &Passphrase) -> Result<Vec<u8>, Error> {
+    decrypt(passphrase, &fs::read(path)?)
+}
+
+/// Encrypt and write a file with the passphrase
+///
+/// The file is written next to the path and renamed over it so a failed write never leaves a
+/// truncated file behind.
+pub fn write(path: &Path, passphrase: &Passphrase, plaintext: &[u8]) -> Result<(), Error> {
+    let ciphertext = encrypt(passphrase, plaintext)?;
+    let mut partial = PathBuf::from(path);
+    partial.as_mut_os_string().push(".partial");
+    fs::write(&partial, ciphertext)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufReader, Cursor, Read, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    categorizer::Assignment,
+    export::{map_category_path, Error},
+};
+
+/// Configuration for writing a Beancount ledger
+///
+/// Category paths and source accounts are mapped to Beancount accounts. The most specific mapping
+/// prefix of a category path is used and any remaining path segments are appended as
+/// subaccounts. For example, with the mapping `expenses: Expenses:Living` the category path
+/// `expenses:food` becomes `Expenses:Living:Food`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BeancountExporter {
+    /// Currency of every amount
+    #[serde(default = "BeancountExporter::default_currency")]
+    pub currency: String,
+    /// Mapping of colon separated category path prefixes to Beancount accounts
+    #[serde(default)]
+    pub categories: BTreeMap<String, String>,
+    /// Mapping of source account names to Beancount accounts
+    #[serde(default)]
+    pub accounts: BTreeMap<String, String>,
+    /// Beancount account for categories without a mapping
+    #[serde(default = "BeancountExporter::default_category_account")]
+    pub default_category_account: String,
+    /// Beancount account for source accounts without a mapping
+    #[serde(default = "BeancountExporter::default_account")]
+    pub default_account: String,
+    /// Beancount account for uncategorized transactions
+    #[serde(default = "BeancountExporter::default_uncategorized_account")]
+    pub uncategorized_account: String,
+}
+
+impl BeancountExporter {
+    /// Create a new beancount exporter from a yaml file
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Create a new beancount exporter from a yaml buffer
+    pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
+        Self::from_reader(Cursor::new(buffer))
+    }
+
+    /// Create a new beancount exporter from a reader
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let reader = BufReader::new(reader);
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+
+    /// Write the assigned transactions as a Beancount ledger sorted by time
+    ///
+    /// An `open` directive is written for every account on the date of the earliest transaction.
+    pub fn write<W: Write>(&self, assignments: &[Assignment], mut writer: W) -> Result<(), Error> {
+        let mut assignments = assignments.iter().collect::<Vec<_>>();
+        assignments.sort_by_key(|a| a.transaction.time);
+        let Some(first) = assignments.first() else {
+            return Ok(());
+        };
+
+        let postings = assignments
+            .iter()
+            .map(|assignment| {
+                let category_account = match &assignment.category_path {
+                    Some(path) => self.category_account(path),
+                    None => self.uncategorized_account.clone(),
+                };
+                let source_account = self.source_account(&assignment.transaction.account);
+                (category_account, source_account)
+            })
+            .collect::<Vec<_>>();
+
+        // Open every account before its first use
+        let open_date = first.transaction.time.format("%Y-%m-%d");
+        let accounts = postings
+            .iter()
+            .flat_map(|(category, source)| [category, source])
+            .collect::<BTreeSet<_>>();
+        for account in accounts {
+            writeln!(writer, "{open_date} open {account}")?;
+        }
+        writeln!(writer)?;
+
+        for (assignment, (category_account, source_account)) in assignments.iter().zip(&postings) {
+            let transaction = assignment.transaction;
+            writeln!(
+                writer,
+                "{} * \"{}\"",
+                transaction.time.format("%Y-%m-%d"),
+                escape(&transaction.description)
+            )?;
+            if let Some(source) = &transaction.source {
+                writeln!(
+                    writer,
+                    "  source: \"{}\"",
+                    escape(&source.display().to_string())
+                )?;
+            }
+            if let Some(matcher) = assignment.matcher {
+                writeln!(writer, "  matcher: \"{}\"", escape(&matcher.to_string()))?;
+            }
+            let currency = &self.currency;
+            let amount = &transaction.amount;
+            writeln!(writer, "  {category_account:<40}  {} {currency}", -amount)?;
+            writeln!(writer, "  {source_account:<40}  {amount} {currency}")?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    fn category_account(&self, path: &[&str]) -> String {
+        match map_category_path(&self.categories, path) {
+            Some((account, rest)) => subaccount(account, rest),
+            None => subaccount(&self.default_category_account, path),
+        }
+    }
+
+    fn source_account(&self, account: &str) -> String {
+        match self.accounts.get(account) {
+            Some(mapped) => mapped.clone(),
+            None => subaccount(&self.default_account, &[account]),
+        }
+    }
+
+    fn default_currency() -> String {
+        String::from("USD")
+    }
+
+    fn default_category_account() -> String {
+        String::from("Expenses")
+    }
+
+    fn default_account() -> String {
+        String::from("Assets")
+    }
+
+    fn default_uncategorized_account() -> String {
+        String::from("Expenses:Uncategorized")
+    }
+}
+
+impl Default for BeancountExporter {
+    fn default() -> Self {
+        Self {
+            currency: Self::default_currency(),
+            categories: Default::default(),
+            accounts: Default::default(),
+            default_category_account: Self::default_category_account(),
+            default_account: Self::default_account(),
+            uncategorized_account: Self::default_uncategorized_account(),
+        }
+    }
+}
+
+/// Append segments to an account, converting each into a valid Beancount account component
+///
+/// Components must start with an uppercase letter or digit and only contain letters, digits, and
+/// dashes (ie `credit card` becomes `Credit-card`).
+fn subaccount(account: &str, segments: &[&str]) -> String {
+    let mut account = account.to_string();
+    for segment in segments {
+        let component = segment
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let mut chars = component.chars();
+        let Some(first) = chars.next() else {
+            continue;
+        };
+        account.push(':');
+        account.extend(first.to_uppercase());
+        account.extend(chars);
+    }
+    account
+}
+
+/// Escape a value for use in a Beancount string literal
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Cursor, Read, Write},
+    path::Path,
+};
+
+use bigdecimal::Signed;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Assignment,
+    export::{map_category_path, write_qif_account, Error},
+};
+
+/// File formats GnuCash can import
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GnucashFormat {
+    /// CSV for the GnuCash transaction importer
+    #[default]
+    Csv,
+    /// Quicken Interchange Format
+    Qif,
+}
+
+/// Configuration for writing files GnuCash can import
+///
+/// Category paths and source accounts are mapped to full GnuCash account names. The most specific
+/// mapping prefix of a category path is used and any remaining path segments are appended as
+/// subaccounts.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GnucashExporter {
+    /// Mapping of colon separated category path prefixes to GnuCash accounts
+    #[serde(default)]
+    pub categories: BTreeMap<String, String>,
+    /// Mapping of source account names to GnuCash accounts
+    #[serde(default)]
+    pub accounts: BTreeMap<String, String>,
+    /// GnuCash account for categories without a mapping
+    #[serde(default = "GnucashExporter::default_category_account")]
+    pub default_category_account: String,
+    /// GnuCash account for source accounts without a mapping
+    #[serde(default = "GnucashExporter::default_account")]
+    pub default_account: String,
+    /// GnuCash account for uncategorized transactions
+    #[serde(default = "GnucashExporter::default_uncategorized_account")]
+    pub uncategorized_account: String,
+}
+
+impl GnucashExporter {
+    /// Create a new gnucash exporter from a yaml file
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Create a new gnucash exporter from a yaml buffer
+    pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
+        Self::from_reader(Cursor::new(buffer))
+    }
+
+    /// Create a new gnucash exporter from a reader
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let reader = BufReader::new(reader);
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+
+    /// Write the assigned transactions in the given format sorted by time
+    pub fn write<W: Write>(
+        &self,
+        assignments: &[Assignment],
+        format: GnucashFormat,
+        writer: W,
+    ) -> Result<(), Error> {
+        let mut assignments = assignments.iter().collect::<Vec<_>>();
+        assignments.sort_by_key(|a| a.transaction.time);
+        match format {
+            GnucashFormat::Csv => self.write_csv(&assignments, writer),
+            GnucashFormat::Qif => self.write_qif(&assignments, writer),
+        }
+    }
+
+    /// Write one row per transaction using the columns of the GnuCash CSV transaction importer
+    fn write_csv<W: Write>(&self, assignments: &[&Assignment], writer: W) -> Result<(), Error> {
+        let mut writer = Writer::from_writer(writer);
+        writer.write_record([
+            "Date",
+            "Description",
+            "Account",
+            "Deposit",
+            "Withdrawal",
+            "Transfer Account",
+        ])?;
+        for assignment in assignments {
+            let transaction = assignment.transaction;
+            let (deposit, withdrawal) = if transaction.amount.is_negative() {
+                (String::new(), transaction.amount.abs().to_string())
+            } else {
+                (transaction.amount.to_string(), String::new())
+            };
+            writer.write_record([
+                transaction.time.format("%Y-%m-%d").to_string(),
+                transaction.description.clone(),
+                self.source_account(&transaction.account),
+                deposit,
+                withdrawal,
+                self.category_account(assignment),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write a QIF bank section per source account with categories as transfer accounts
+    fn write_qif<W: Write>(&self, assignments: &[&Assignment], mut writer: W) -> Result<(), Error> {
+        let mut by_account = BTreeMap::<_, Vec<_>>::new();
+        for assignment in assignments {
+            by_account
+                .entry(self.source_account(&assignment.transaction.account))
+                .or_default()
+                .push((
+                    assignment.transaction,
+                    Some(self.category_account(assignment)),
+                ));
+        }
+        for (account, entries) in by_account {
+            write_qif_account(&mut writer, &account, "Bank", &entries)?;
+        }
+        Ok(())
+    }
+
+    fn category_account(&self, assignment: &Assignment) -> String {
+        let Some(path) = &assignment.category_path else {
+            return self.uncategorized_account.clone();
+        };
+        let (account, rest) = map_category_path(&self.categories, path)
+            .unwrap_or((&self.default_category_account, path));
+        std::iter::once(account)
+            .chain(rest.iter().copied())
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    fn source_account(&self, account: &str) -> String {
+        match self.accounts.get(account) {
+            Some(mapped) => mapped.clone(),
+            None => format!("{}:{account}", self.default_account),
+        }
+    }
+
+    fn default_category_account() -> String {
+        String::from("Expenses")
+    }
+
+    fn default_account() -> String {
+        String::from("Assets")
+    }
+
+    fn default_uncategorized_account() -> String {
+        String::from("Imbalance-USD")
+    }
+}
+
+impl Default for GnucashExporter {
+    fn default() -> Self {
+        Self {
+            categories: Default::default(),
+            accounts: Default::default(),
+            default_category_account: Self::default_category_account(),
+            default_account: Self::default_account(),
+            uncategorized_account: Self::default_uncategorized_account(),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Write;
+
+use bigdecimal::Signed;
+
+use crate::{
+    export::Error,
+    report::{Cadence, Recurring},
+    util::Formatter,
+};
+
+/// Configuration for writing expected recurring charges as an iCalendar (`.ics`) file
+#[derive(Clone, Debug)]
+pub struct IcalExporter {
+    /// Number of days before each expected charge to trigger a reminder
+    pub reminder_days: u32,
+    /// Include recurring deposits (ie paychecks) in addition to charges
+    pub include_deposits: bool,
+    /// Formatter for the expected amounts
+    pub formatter: Formatter,
+}
+
+impl IcalExporter {
+    /// Write a repeating all-day event per recurring charge starting at its next expected date
+    ///
+    /// Events are stamped with the date of the most recent transaction so the same input always
+    /// produces the same calendar.
+    pub fn write<W: Write>(&self, recurring: &[Recurring], mut writer: W) -> Result<(), Error> {
+        write!(writer, "BEGIN:VCALENDAR\r\n")?;
+        write!(writer, "VERSION:2.0\r\n")?;
+        write!(writer, "PRODID:-//slotted-pig//recurring charges//EN\r\n")?;
+        write!(writer, "CALSCALE:GREGORIAN\r\n")?;
+        for recurring in recurring {
+            if !self.include_deposits && !recurring.average_amount.is_negative() {
+                continue;
+            }
+            let summary = format!(
+                "{} ({})",
+                recurring.payee,
+                self.formatter.format(&recurring.average_amount)
+            );
+            let description = format!(
+                "Expected {} charge to {}, last {} on {}",
+                cadence_name(recurring.cadence),
+                recurring.account,
+                self.formatter.format(&recurring.last_amount),
+                recurring.last_date
+            );
+            write!(writer, "BEGIN:VEVENT\r\n")?;
+            write!(
+                writer,
+                "UID:{}-{}@slotted-pig\r\n",
+                uid_part(&recurring.payee),
+                uid_part(&recurring.account)
+            )?;
+            write!(
+                writer,
+                "DTSTAMP:{}T000000Z\r\n",
+                recurring.last_date.format("%Y%m%d")
+            )?;
+            write!(
+                writer,
+                "DTSTART;VALUE=DATE:{}\r\n",
+                recurring.next_date.format("%Y%m%d")
+            )?;
+            write!(writer, "RRULE:{}\r\n", rrule(recurring.cadence))?;
+            write!(writer, "SUMMARY:{}\r\n", escape(&summary))?;
+            write!(writer, "DESCRIPTION:{}\r\n", escape(&description))?;
+            write!(writer, "TRANSP:TRANSPARENT\r\n")?;
+            write!(writer, "BEGIN:VALARM\r\n")?;
+            write!(writer, "ACTION:DISPLAY\r\n")?;
+            write!(writer, "DESCRIPTION:{}\r\n", escape(&summary))?;
+            write!(writer, "TRIGGER:-P{}D\r\n", self.reminder_days)?;
+            write!(writer, "END:VALARM\r\n")?;
+            write!(writer, "END:VEVENT\r\n")?;
+        }
+        write!(writer, "END:VCALENDAR\r\n")?;
+        Ok(())
+    }
+}
+
+impl Default for IcalExporter {
+    fn default() -> Self {
+        Self {
+            reminder_days: 3,
+            include_deposits: false,
+            formatter: Formatter::default(),
+        }
+    }
+}
+
+fn rrule(cadence: Cadence) -> &'static str {
+    match cadence {
+        Cadence::Weekly => "FREQ=WEEKLY",
+        Cadence::Biweekly => "FREQ=WEEKLY;INTERVAL=2",
+        Cadence::Monthly => "FREQ=MONTHLY",
+        Cadence::Quarterly => "FREQ=MONTHLY;INTERVAL=3",
+        Cadence::Yearly => "FREQ=YEARLY",
+    }
+}
+
+fn cadence_name(cadence: Cadence) -> &'static str {
+    match cadence {
+        Cadence::Weekly => "weekly",
+        Cadence::Biweekly => "biweekly",
+        Cadence::Monthly => "monthly",
+        Cadence::Quarterly => "quarterly",
+        Cadence::Yearly => "yearly",
+    }
+}
+
+/// Replace everything but ascii alphanumerics so the value can be used in an event uid
+fn uid_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Escape a value for use as iCalendar text
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use crate::{categorizer::Assignment, export::Error};
+
+/// Writes assignments as JSON Lines, one flattened row per line as soon as it is given
+///
+/// Combined with `TransactionParser::parse_csvs_iter` and `Categorizer::assign_transaction` no
+/// more than a single transaction needs to be held in memory.
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Create a new writer
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write an assignment as a single line
+    pub fn write(&mut self, assignment: &Assignment) -> Result<(), Error> {
+        serde_json::to_writer(&mut self.writer, &assignment.row())?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::Write;
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{categorizer::Assignment, export::Error};
+
+/// Configuration for writing a ledger-format journal (compatible with ledger and hledger)
+///
+/// Each transaction is written with two postings. One to the account derived from the category
+/// path and one to the source account of the transaction.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedgerExporter {
+    /// Account prefix for category postings (ie `Expenses` results in `Expenses:Food:Groceries`)
+    #[serde(default)]
+    pub category_prefix: Option<String>,
+    /// Account prefix for source account postings (ie `Assets` results in `Assets:checking`)
+    #[serde(default)]
+    pub account_prefix: Option<String>,
+    /// Account used for the category posting of uncategorized transactions
+    #[serde(default = "LedgerExporter::default_uncategorized_account")]
+    pub uncategorized_account: String,
+    /// Commodity appended to every amount (ie `USD`)
+    #[serde(default)]
+    pub commodity: Option<String>,
+}
+
+impl LedgerExporter {
+    /// Write the assigned transactions as a journal sorted by time
+    pub fn write<W: Write>(&self, assignments: &[Assignment], mut writer: W) -> Result<(), Error> {
+        let mut assignments = assignments.iter().collect::<Vec<_>>();
+        assignments.sort_by_key(|a| a.transaction.time);
+
+        for assignment in assignments {
+            let transaction = assignment.transaction;
+            let category_account = match &assignment.category_path {
+                Some(path) => account_name(self.category_prefix.as_deref(), path),
+                None => self.uncategorized_account.clone(),
+            };
+            let source_account =
+                account_name(self.account_prefix.as_deref(), &[&transaction.account]);
+
+            writeln!(
+                writer,
+                "{} {}",
+                transaction.time.format("%Y-%m-%d"),
+                transaction.description
+            )?;
+            self.write_posting(&mut writer, &category_account, &-&transaction.amount)?;
+            self.write_posting(&mut writer, &source_account, &transaction.amount)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    fn write_posting<W: Write>(
+        &self,
+        writer: &mut W,
+        account: &str,
+        amount: &BigDecimal,
+    ) -> Result<(), Error> {
+        // Ledger requires at least two spaces between the account and the amount
+        match &self.commodity {
+            Some(commodity) => writeln!(writer, "    {account:<40}  {amount} {commodity}")?,
+            None => writeln!(writer, "    {account:<40}  {amount}")?,
+        }
+        Ok(())
+    }
+
+    fn default_uncategorized_account() -> String {
+        String::from("Uncategorized")
+    }
+}
+
+impl Default for LedgerExporter {
+    fn default() -> Self {
+        Self {
+            category_prefix: Default::default(),
+            account_prefix: Default::default(),
+            uncategorized_account: Self::default_uncategorized_account(),
+            commodity: Default::default(),
+        }
+    }
+}
+
+/// Join segments into a ledger account name
+///
+/// Colons separate ledger account segments and runs of whitespace would be parsed as the start of
+/// the amount so both are replaced within each segment.
+fn account_name(prefix: Option<&str>, segments: &[&str]) -> String {
+    prefix
+        .into_iter()
+        .chain(segments.iter().copied())
+        .map(|segment| {
+            segment
+                .replace(':', "-")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
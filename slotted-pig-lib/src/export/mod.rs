@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use displaydoc::Display;
+use thiserror::Error;
+
+pub use beancount::*;
+pub use gnucash::*;
+pub use ical::*;
+pub use json_lines::*;
+pub use ledger::*;
+pub use ofx::*;
+#[cfg(feature = "parquet")]
+pub use parquet::*;
+pub use qif::*;
+pub use sql::*;
+
+mod beancount;
+mod gnucash;
+mod ical;
+mod json_lines;
+mod ledger;
+mod ofx;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod qif;
+mod sql;
+#[cfg(test)]
+mod tests;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] std::io::Error),
+    /// csv
+    Csv(#[from] csv::Error),
+    /// serde_json
+    SerdeJson(#[from] serde_json::Error),
+    /// serde_yaml
+    SerdeYaml(#[from] serde_yaml::Error),
+    /// sqlite
+    #[cfg(feature = "sqlite")]
+    Sqlite(#[from] rusqlite::Error),
+    /// parquet
+    #[cfg(feature = "parquet")]
+    Parquet(#[from] ::parquet::errors::ParquetError),
+    /// amount {0} does not fit in a parquet decimal
+    #[cfg(feature = "parquet")]
+    AmountOutOfRange(bigdecimal::BigDecimal),
+}
+
+/// Find the longest colon separated category path prefix with a mapping
+///
+/// Returns the mapped value and the remaining unmapped segments of the path.
+fn map_category_path<'a, 'b>(
+    mapping: &'a BTreeMap<String, String>,
+    path: &'b [&'b str],
+) -> Option<(&'a str, &'b [&'b str])> {
+    (1..=path.len()).rev().find_map(|len| {
+        mapping
+            .get(&path[..len].join(":"))
+            .map(|mapped| (mapped.as_str(), &path[len..]))
+    })
+}
@@ -0,0 +1,144 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+};
+
+use bigdecimal::Signed;
+use sha2::{Digest, Sha256};
+
+use crate::{export::Error, transaction::Transaction};
+
+/// Configuration for writing transactions as an OFX 2 bank statement
+#[derive(Clone, Debug)]
+pub struct OfxExporter {
+    /// Currency of every amount (ie `USD`)
+    pub currency: String,
+}
+
+impl OfxExporter {
+    /// Write a statement per account with the transactions sorted by time
+    ///
+    /// Accounts are identified by their name and each transaction is given an id that is unique
+    /// within its account. Ids are derived from the transaction so exports of overlapping
+    /// transactions agree on the ids of the transactions they share and importers skip them.
+    pub fn write<W: Write>(
+        &self,
+        transactions: &[Transaction],
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let mut by_account = BTreeMap::<_, Vec<_>>::new();
+        for transaction in transactions {
+            by_account
+                .entry(transaction.account.as_str())
+                .or_default()
+                .push(transaction);
+        }
+
+        writeln!(
+            writer,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+        )?;
+        writeln!(
+            writer,
+            r#"<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>"#
+        )?;
+        writeln!(writer, "<OFX>")?;
+        writeln!(writer, "<BANKMSGSRSV1>")?;
+        for (account, mut transactions) in by_account {
+            transactions.sort_by_key(|t| t.time);
+            let (Some(first), Some(last)) = (transactions.first(), transactions.last()) else {
+                continue;
+            };
+            writeln!(writer, "<STMTTRNRS>")?;
+            writeln!(writer, "<TRNUID>0</TRNUID>")?;
+            writeln!(
+                writer,
+                "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>"
+            )?;
+            writeln!(writer, "<STMTRS>")?;
+            writeln!(writer, "<CURDEF>{}</CURDEF>", escape(&self.currency))?;
+            writeln!(
+                writer,
+                "<BANKACCTFROM><BANKID>0</BANKID><ACCTID>{}</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM>",
+                escape(account)
+            )?;
+            writeln!(writer, "<BANKTRANLIST>")?;
+            writeln!(
+                writer,
+                "<DTSTART>{}</DTSTART>",
+                first.time.format("%Y%m%d%H%M%S")
+            )?;
+            writeln!(
+                writer,
+                "<DTEND>{}</DTEND>",
+                last.time.format("%Y%m%d%H%M%S")
+            )?;
+            // Identical transactions (ie two equal purchases on the same day) are numbered by
+            // occurrence to keep their ids unique
+            let mut occurrences = HashMap::<String, usize>::new();
+            for transaction in transactions {
+                let hash = fitid_hash(transaction);
+                let occurrence = occurrences.entry(hash.clone()).or_default();
+                let fitid = format!("{hash}-{occurrence}");
+                *occurrence += 1;
+                let transaction_type = if transaction.amount.is_negative() {
+                    "DEBIT"
+                } else {
+                    "CREDIT"
+                };
+                // OFX limits names to 32 characters, the full description is kept in the memo
+                let name = transaction.description.chars().take(32).collect::<String>();
+                writeln!(writer, "<STMTTRN>")?;
+                writeln!(writer, "<TRNTYPE>{transaction_type}</TRNTYPE>")?;
+                writeln!(
+                    writer,
+                    "<DTPOSTED>{}</DTPOSTED>",
+                    transaction.time.format("%Y%m%d%H%M%S")
+                )?;
+                writeln!(writer, "<TRNAMT>{}</TRNAMT>", transaction.amount)?;
+                writeln!(writer, "<FITID>{fitid}</FITID>")?;
+                writeln!(writer, "<NAME>{}</NAME>", escape(&name))?;
+                writeln!(writer, "<MEMO>{}</MEMO>", escape(&transaction.description))?;
+                writeln!(writer, "</STMTTRN>")?;
+            }
+            writeln!(writer, "</BANKTRANLIST>")?;
+            writeln!(writer, "</STMTRS>")?;
+            writeln!(writer, "</STMTTRNRS>")?;
+        }
+        writeln!(writer, "</BANKMSGSRSV1>")?;
+        writeln!(writer, "</OFX>")?;
+        Ok(())
+    }
+}
+
+impl Default for OfxExporter {
+    fn default() -> Self {
+        Self {
+            currency: String::from("USD"),
+        }
+    }
+}
+
+/// First 16 hex characters of the hash of the fields identifying a transaction
+fn fitid_hash(transaction: &Transaction) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(transaction.account.as_bytes());
+    hasher.update([0]);
+    hasher.update(transaction.time.to_rfc3339().as_bytes());
+    hasher.update([0]);
+    hasher.update(transaction.amount.normalized().to_string().as_bytes());
+    hasher.update([0]);
+    hasher.update(transaction.description.as_bytes());
+    hasher.finalize()[..8]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Escape a value for use as XML element content
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,167 @@
+use std::{io::Write, sync::Arc};
+
+use ::parquet::{
+    basic::Compression,
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{
+        properties::WriterProperties,
+        writer::{SerializedFileWriter, SerializedRowGroupWriter},
+    },
+    schema::parser::parse_message_type,
+};
+use bigdecimal::{rounding::RoundingMode, ToPrimitive};
+
+use crate::{categorizer::Assignment, export::Error, report::Bucket};
+
+/// Configuration for writing the flattened assignment table as a Parquet file
+///
+/// Each row has the transaction fields, the period of the transaction, the colon separated
+/// category path, and the matcher which assigned it. This allows analyzing many years of data in
+/// tools like DuckDB or Polars.
+#[derive(Clone, Debug)]
+pub struct ParquetExporter {
+    /// Bucket used to label the period of each transaction
+    pub bucket: Bucket,
+    /// Number of decimal places amounts are stored with
+    pub amount_scale: u8,
+}
+
+impl ParquetExporter {
+    /// Write a single row group with a row per assignment
+    pub fn write<W: Write + Send>(
+        &self,
+        assignments: &[Assignment],
+        writer: W,
+    ) -> Result<(), Error> {
+        let schema = Arc::new(parse_message_type(&format!(
+            "message assignment {{
+                REQUIRED INT64 time (TIMESTAMP(MILLIS,true));
+                REQUIRED BINARY period (STRING);
+                REQUIRED BINARY account (STRING);
+                REQUIRED BINARY description (STRING);
+                REQUIRED INT64 amount (DECIMAL(18,{}));
+                OPTIONAL BINARY source (STRING);
+                OPTIONAL BINARY category (STRING);
+                OPTIONAL BINARY matcher (STRING);
+            }}",
+            self.amount_scale
+        ))?);
+        let properties = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+        );
+        let mut writer = SerializedFileWriter::new(writer, schema, properties)?;
+        let mut row_group = writer.next_row_group()?;
+
+        let times = assignments
+            .iter()
+            .map(|a| a.transaction.time.timestamp_millis())
+            .collect::<Vec<_>>();
+        let amounts = assignments
+            .iter()
+            .map(|a| {
+                let amount = a
+                    .transaction
+                    .amount
+                    .with_scale_round(self.amount_scale.into(), RoundingMode::HalfEven);
+                amount
+                    .as_bigint_and_exponent()
+                    .0
+                    .to_i64()
+                    .ok_or_else(|| Error::AmountOutOfRange(a.transaction.amount.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let periods = assignments
+            .iter()
+            .map(|a| {
+                let date = a.transaction.time.date_naive();
+                Some(self.bucket.label(self.bucket.start(date)))
+            })
+            .collect::<Vec<_>>();
+        let accounts = assignments
+            .iter()
+            .map(|a| Some(a.transaction.account.clone()))
+            .collect::<Vec<_>>();
+        let descriptions = assignments
+            .iter()
+            .map(|a| Some(a.transaction.description.clone()))
+            .collect::<Vec<_>>();
+        let sources = assignments
+            .iter()
+            .map(|a| {
+                a.transaction
+                    .source
+                    .as_ref()
+                    .map(|s| s.display().to_string())
+            })
+            .collect::<Vec<_>>();
+        let categories = assignments
+            .iter()
+            .map(|a| a.category_path_joined(":"))
+            .collect::<Vec<_>>();
+        let matchers = assignments
+            .iter()
+            .map(|a| a.matcher.map(ToString::to_string))
+            .collect::<Vec<_>>();
+
+        // Columns are written in schema order
+        write_int64(&mut row_group, &times)?;
+        for strings in [&periods, &accounts, &descriptions] {
+            write_strings(&mut row_group, strings)?;
+        }
+        write_int64(&mut row_group, &amounts)?;
+        for strings in [&sources, &categories, &matchers] {
+            write_strings(&mut row_group, strings)?;
+        }
+
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+impl Default for ParquetExporter {
+    fn default() -> Self {
+        Self {
+            bucket: Bucket::default(),
+            amount_scale: 2,
+        }
+    }
+}
+
+/// Write the next required integer column
+fn write_int64<W: Write + Send>(
+    row_group: &mut SerializedRowGroupWriter<'_, W>,
+    values: &[i64],
+) -> Result<(), Error> {
+    let mut column = row_group.next_column()?.expect("column in schema");
+    column
+        .typed::<Int64Type>()
+        .write_batch(values, None, None)?;
+    column.close()?;
+    Ok(())
+}
+
+/// Write the next string column, definition levels are always written and ignored for required
+/// columns
+fn write_strings<W: Write + Send>(
+    row_group: &mut SerializedRowGroupWriter<'_, W>,
+    strings: &[Option<String>],
+) -> Result<(), Error> {
+    let values = strings
+        .iter()
+        .flatten()
+        .map(|s| ByteArray::from(s.as_str()))
+        .collect::<Vec<_>>();
+    let definition_levels = strings
+        .iter()
+        .map(|s| i16::from(s.is_some()))
+        .collect::<Vec<_>>();
+    let mut column = row_group.next_column()?.expect("column in schema");
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&values, Some(&definition_levels), None)?;
+    column.close()?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::{collections::BTreeMap, io::Write};
+
+use bigdecimal::BigDecimal;
+
+use crate::{export::Error, transaction::Transaction};
+
+/// Configuration for writing transactions in the Quicken Interchange Format
+#[derive(Clone, Debug)]
+pub struct QifExporter {
+    /// QIF account type of every account (ie `Bank` or `CCard`)
+    pub account_type: String,
+}
+
+impl QifExporter {
+    /// Write a QIF section per account with the transactions sorted by time
+    pub fn write<W: Write>(
+        &self,
+        transactions: &[Transaction],
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let mut by_account = BTreeMap::<_, Vec<_>>::new();
+        for transaction in transactions {
+            by_account
+                .entry(transaction.account.as_str())
+                .or_default()
+                .push((transaction, None));
+        }
+        for (account, mut entries) in by_account {
+            entries.sort_by_key(|(t, _)| t.time);
+            write_qif_account(&mut writer, account, &self.account_type, &entries)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for QifExporter {
+    fn default() -> Self {
+        Self {
+            account_type: String::from("Bank"),
+        }
+    }
+}
+
+/// Write an account header followed by its transactions with optional categories
+pub(crate) fn write_qif_account<W: Write>(
+    writer: &mut W,
+    account: &str,
+    account_type: &str,
+    entries: &[(&Transaction, Option<String>)],
+) -> Result<(), Error> {
+    writeln!(writer, "!Account")?;
+    writeln!(writer, "N{account}")?;
+    writeln!(writer, "T{account_type}")?;
+    writeln!(writer, "^")?;
+    writeln!(writer, "!Type:{account_type}")?;
+    for (transaction, category) in entries {
+        writeln!(writer, "D{}", transaction.time.format("%m/%d/%Y"))?;
+        writeln!(writer, "T{}", qif_amount(&transaction.amount))?;
+        writeln!(writer, "P{}", transaction.description)?;
+        if let Some(category) = category {
+            writeln!(writer, "L{category}")?;
+        }
+        writeln!(writer, "^")?;
+    }
+    Ok(())
+}
+
+/// Format an amount with two decimal places as expected by QIF
+fn qif_amount(amount: &BigDecimal) -> String {
+    amount
+        .with_scale_round(2, bigdecimal::RoundingMode::HalfEven)
+        .to_string()
+}
@@ -0,0 +1,159 @@
+use std::{collections::HashMap, io::Write};
+
+use crate::{
+    categorizer::{Assignment, Categorizer, Category, CategoryChildren},
+    export::Error,
+};
+
+/// Schema of the tables written by [`SqlExporter`]
+const SCHEMA: &str = "CREATE TABLE categories (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER REFERENCES categories(id),
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE
+);
+CREATE TABLE transactions (
+    id INTEGER PRIMARY KEY,
+    time TEXT NOT NULL,
+    account TEXT NOT NULL,
+    description TEXT NOT NULL,
+    amount NUMERIC NOT NULL,
+    source TEXT
+);
+CREATE TABLE assignments (
+    transaction_id INTEGER PRIMARY KEY REFERENCES transactions(id),
+    category_id INTEGER REFERENCES categories(id),
+    matcher TEXT
+);
+";
+
+/// Configuration for writing categorized transactions as a SQL script
+///
+/// The script creates a `categories` table with the configured hierarchy, a `transactions` table,
+/// and an `assignments` table linking every transaction to its category (`NULL` when
+/// uncategorized). Category paths are colon separated (ie `expenses:food`).
+#[derive(Clone, Debug)]
+pub struct SqlExporter {
+    /// Drop existing tables before creating them so the script can be rerun against a database
+    pub drop_existing: bool,
+}
+
+impl SqlExporter {
+    /// Write the schema and `INSERT` statements in a single transaction
+    pub fn write<W: Write>(
+        &self,
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        mut writer: W,
+    ) -> Result<(), Error> {
+        writeln!(writer, "BEGIN TRANSACTION;")?;
+        if self.drop_existing {
+            writeln!(writer, "DROP TABLE IF EXISTS assignments;")?;
+            writeln!(writer, "DROP TABLE IF EXISTS transactions;")?;
+            writeln!(writer, "DROP TABLE IF EXISTS categories;")?;
+        }
+        write!(writer, "{SCHEMA}")?;
+
+        let mut category_ids = HashMap::new();
+        for category in &categorizer.categories {
+            write_category(
+                category,
+                None,
+                &mut Vec::new(),
+                &mut category_ids,
+                &mut writer,
+            )?;
+        }
+
+        for (index, assignment) in assignments.iter().enumerate() {
+            let id = index + 1;
+            let transaction = assignment.transaction;
+            writeln!(
+                writer,
+                "INSERT INTO transactions VALUES ({id}, {}, {}, {}, {}, {});",
+                quote(&transaction.time.to_rfc3339()),
+                quote(&transaction.account),
+                quote(&transaction.description),
+                transaction.amount,
+                transaction
+                    .source
+                    .as_ref()
+                    .map(|source| quote(&source.to_string_lossy()))
+                    .unwrap_or_else(|| String::from("NULL"))
+            )?;
+            let category_id = assignment
+                .category_path_joined(":")
+                .and_then(|path| category_ids.get(&path))
+                .map(ToString::to_string)
+                .unwrap_or_else(|| String::from("NULL"));
+            let matcher = assignment
+                .matcher
+                .map(|matcher| quote(&matcher.to_string()))
+                .unwrap_or_else(|| String::from("NULL"));
+            writeln!(
+                writer,
+                "INSERT INTO assignments VALUES ({id}, {category_id}, {matcher});"
+            )?;
+        }
+        writeln!(writer, "COMMIT;")?;
+        Ok(())
+    }
+
+    /// Write the categorized transactions into a SQLite database file, creating it if needed
+    #[cfg(feature = "sqlite")]
+    pub fn write_sqlite<P: AsRef<std::path::Path>>(
+        &self,
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        path: P,
+    ) -> Result<(), Error> {
+        let mut script = Vec::new();
+        self.write(categorizer, assignments, &mut script)?;
+        let script = String::from_utf8_lossy(&script);
+        rusqlite::Connection::open(path)?.execute_batch(&script)?;
+        Ok(())
+    }
+}
+
+impl Default for SqlExporter {
+    fn default() -> Self {
+        Self {
+            drop_existing: true,
+        }
+    }
+}
+
+/// Write the category and its subcategories recording the id of each category path
+fn write_category<'a, W: Write>(
+    category: &'a Category,
+    parent_id: Option<usize>,
+    path: &mut Vec<&'a str>,
+    category_ids: &mut HashMap<String, usize>,
+    writer: &mut W,
+) -> Result<(), Error> {
+    path.push(&category.category);
+    let id = category_ids.len() + 1;
+    let joined = path.join(":");
+    writeln!(
+        writer,
+        "INSERT INTO categories VALUES ({id}, {}, {}, {});",
+        parent_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| String::from("NULL")),
+        quote(&category.category),
+        quote(&joined)
+    )?;
+    category_ids.insert(joined, id);
+    if let CategoryChildren::Subcategories(subcategories) = &category.children {
+        for subcategory in subcategories {
+            write_category(subcategory, Some(id), path, category_ids, writer)?;
+        }
+    }
+    path.pop();
+    Ok(())
+}
+
+/// Quote a value as a SQL string literal
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
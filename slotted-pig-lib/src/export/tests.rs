@@ -0,0 +1,213 @@
+use anyhow::Result;
+use insta::assert_snapshot;
+use test_case::test_case;
+
+use crate::{
+    categorizer::Categorizer,
+    export::{
+        BeancountExporter, GnucashExporter, GnucashFormat, IcalExporter, JsonLinesWriter,
+        LedgerExporter, OfxExporter, QifExporter, SqlExporter,
+    },
+    report::RecurringDetector,
+    transaction::{Transaction, TransactionParser},
+};
+
+#[test]
+fn test_ledger() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let exporter = LedgerExporter {
+        category_prefix: Some(String::from("Categories")),
+        account_prefix: Some(String::from("Assets")),
+        commodity: Some(String::from("USD")),
+        ..Default::default()
+    };
+    let mut journal = Vec::new();
+    exporter.write(&assignments, &mut journal)?;
+    assert_snapshot!(String::from_utf8(journal)?);
+    Ok(())
+}
+
+#[test]
+fn test_beancount() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let transactions =
+        transaction_parser.parse_csv("tests/transactions_uncategorized.csv".as_ref())?;
+    let assignments = categorizer.assign(&transactions);
+    let exporter = BeancountExporter::from_yaml_buffer(
+        r#"
+currency: EUR
+categories:
+  income: Income:Salary
+accounts:
+  credit card: Liabilities:CreditCard
+"#,
+    )?;
+    let mut ledger = Vec::new();
+    exporter.write(&assignments, &mut ledger)?;
+    assert_snapshot!(String::from_utf8(ledger)?);
+    Ok(())
+}
+
+#[test_case(GnucashFormat::Csv, "gnucash_csv"; "csv")]
+#[test_case(GnucashFormat::Qif, "gnucash_qif"; "qif")]
+fn test_gnucash(format: GnucashFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let exporter = GnucashExporter::from_yaml_buffer(
+        r#"
+categories:
+  income: Income
+accounts:
+  credit card: Liabilities:Credit Card
+"#,
+    )?;
+    let mut output = Vec::new();
+    exporter.write(&assignments, format, &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_ofx() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let mut output = Vec::new();
+    OfxExporter::default().write(&transactions, &mut output)?;
+    assert_snapshot!(String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_ofx_fitids_stable() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let fitids = |transactions: &[Transaction]| -> Result<Vec<String>> {
+        let mut output = Vec::new();
+        OfxExporter::default().write(transactions, &mut output)?;
+        Ok(String::from_utf8(output)?
+            .lines()
+            .filter_map(|l| l.strip_prefix("<FITID>")?.strip_suffix("</FITID>"))
+            .map(String::from)
+            .collect())
+    };
+    // Exports of different transactions, the first with an earlier transaction in the account of
+    // the shared transactions which shifts their position
+    let mut earlier = transactions[1].clone();
+    earlier.time = earlier.time - chrono::Days::new(30);
+    let first = fitids(&[&transactions[..3], std::slice::from_ref(&earlier)].concat())?;
+    let second = fitids(&transactions[1..])?;
+    let shared = first
+        .iter()
+        .filter(|fitid| second.contains(fitid))
+        .collect::<Vec<_>>();
+    assert_eq!(shared.len(), 2);
+
+    // True duplicates are numbered by occurrence
+    let duplicates = fitids(&[transactions[0].clone(), transactions[0].clone()])?;
+    assert_eq!(duplicates.len(), 2);
+    assert_ne!(duplicates[0], duplicates[1]);
+    assert!(duplicates[0].ends_with("-0") && duplicates[1].ends_with("-1"));
+    Ok(())
+}
+
+#[test]
+fn test_qif() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let mut output = Vec::new();
+    QifExporter::default().write(&transactions, &mut output)?;
+    assert_snapshot!(String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_ical() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_recurring.csv")?;
+    let recurring = RecurringDetector::default().detect(&transactions);
+    let mut output = Vec::new();
+    IcalExporter::default().write(&recurring, &mut output)?;
+    assert_snapshot!(String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_sql() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let mut output = Vec::new();
+    SqlExporter::default().write(&categorizer, &assignments, &mut output)?;
+    assert_snapshot!(String::from_utf8(output)?);
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_sqlite() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let path = std::env::temp_dir().join("slotted_pig_test_sqlite.db");
+    // Write twice to check existing tables are replaced
+    SqlExporter::default().write_sqlite(&categorizer, &assignments, &path)?;
+    SqlExporter::default().write_sqlite(&categorizer, &assignments, &path)?;
+    let connection = rusqlite::Connection::open(&path)?;
+    let uncategorized: i64 = connection.query_row(
+        "SELECT COUNT(*) FROM assignments WHERE category_id IS NULL",
+        [],
+        |row| row.get(0),
+    )?;
+    let store_total: f64 = connection.query_row(
+        "SELECT SUM(t.amount) FROM transactions t
+        JOIN assignments a ON a.transaction_id = t.id
+        JOIN categories c ON c.id = a.category_id
+        WHERE c.path = 'expenses:store'",
+        [],
+        |row| row.get(0),
+    )?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(uncategorized, 1);
+    assert_eq!(store_total, -30.0);
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet() -> Result<()> {
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+    use crate::export::ParquetExporter;
+
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let path = std::env::temp_dir().join("slotted_pig_test.parquet");
+    ParquetExporter::default().write(&assignments, std::fs::File::create(&path)?)?;
+    let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+    let rows = reader
+        .get_row_iter(None)?
+        .map(|row| Ok(row?.to_string()))
+        .collect::<Result<Vec<_>>>()?;
+    std::fs::remove_file(&path)?;
+    assert_snapshot!(rows.join("\n"));
+    Ok(())
+}
+
+#[test]
+fn test_json_lines() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let paths = ["tests/transactions_uncategorized.csv".as_ref()];
+    let mut output = Vec::new();
+    let mut writer = JsonLinesWriter::new(&mut output);
+    for transaction in transaction_parser.parse_csvs_iter(paths.into_iter()) {
+        let transaction = transaction?;
+        if let Some(assignment) = categorizer.assign_transaction(&transaction) {
+            writer.write(&assignment)?;
+        }
+    }
+    writer.flush()?;
+    assert_snapshot!(String::from_utf8(output)?);
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use displaydoc::Display;
+use thiserror::Error;
+use tokio::task::JoinSet;
+
+use crate::{
+    import::{self, parse_ofx, parse_qif, ImportFormat},
+    transaction::{self, Transaction, TransactionParser},
+};
+
+/// Extensions of the files `read_transaction_dir` parses
+pub const TRANSACTION_FILE_EXTENSIONS: [&str; 4] = ["csv", "ofx", "qfx", "qif"];
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] io::Error),
+    /// transaction
+    Transaction(#[from] transaction::Error),
+    /// import {0}: {1}
+    Import(String, #[source] import::Error),
+    /// a transaction parser is needed for csv file {0}
+    MissingTransactionParser(String),
+    /// parsing task failed
+    Join(#[from] tokio::task::JoinError),
+    /// http
+    #[cfg(feature = "fetch")]
+    Http(#[from] reqwest::Error),
+}
+
+impl TransactionParser {
+    /// Parse transactions from CSV files without blocking the runtime
+    ///
+    /// Files are read and parsed concurrently, parsing on the blocking thread pool, and the
+    /// transactions are returned in the order of the paths like `parse_csvs`.
+    pub async fn parse_csvs_async(
+        &self,
+        paths: impl IntoIterator<Item = PathBuf>,
+    ) -> Result<Vec<Transaction>, transaction::Error> {
+        let transaction_parser = Arc::new(self.clone());
+        let mut tasks = JoinSet::new();
+        for (index, path) in paths.into_iter().enumerate() {
+            let transaction_parser = Arc::clone(&transaction_parser);
+            tasks.spawn(async move {
+                let parse_failed =
+                    |e: transaction::Error| transaction::Error::ParseFailed(path.clone(), e.into());
+                let contents = tokio::fs::read(&path)
+                    .await
+                    .map_err(|e| parse_failed(e.into()))?;
+                let transactions = tokio::task::spawn_blocking({
+                    let path = path.clone();
+                    move || transaction_parser.parse_csv_buffer(&path, contents)
+                })
+                .await
+                .map_err(|e| parse_failed(io::Error::other(e).into()))??;
+                Ok::<_, transaction::Error>((index, transactions))
+            });
+        }
+        let mut batches = Vec::new();
+        while let Some(batch) = tasks.join_next().await {
+            batches.push(batch.map_err(|e| transaction::Error::Io(io::Error::other(e)))??);
+        }
+        batches.sort_by_key(|(index, _)| *index);
+        Ok(batches
+            .into_iter()
+            .flat_map(|(_, transactions)| transactions)
+            .collect())
+    }
+}
+
+/// Parse a transaction file of any import format, csv files with the transaction parser
+///
+/// The name selects the format and csv parsing config and becomes the source of the transactions.
+pub fn parse_transaction_file(
+    transaction_parser: Option<&TransactionParser>,
+    name: &str,
+    contents: &str,
+) -> Result<Vec<Transaction>, Error> {
+    let path = Path::new(name);
+    match ImportFormat::detect(path, contents) {
+        ImportFormat::Csv => Ok(transaction_parser
+            .ok_or_else(|| Error::MissingTransactionParser(name.to_string()))?
+            .parse_csv_buffer(path, contents)?),
+        ImportFormat::Ofx => parse_ofx(path, contents).map_err(|e| Error::Import(name.into(), e)),
+        ImportFormat::Qif => parse_qif(path, contents).map_err(|e| Error::Import(name.into(), e)),
+    }
+}
+
+/// Read and parse every transaction file of a directory without blocking the runtime, merging
+/// transactions in more than one file (ie overlapping downloads)
+///
+/// Files are parsed concurrently and merged in file name order, the file names become the sources
+/// of the transactions so they do not expose the directory. Files without a transaction file
+/// extension are skipped and a missing directory has no transactions.
+pub async fn read_transaction_dir(
+    transaction_parser: Option<Arc<TransactionParser>>,
+    dir: &Path,
+) -> Result<Vec<Transaction>, Error> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let extension = Path::new(&name)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        if extension.is_some_and(|e| TRANSACTION_FILE_EXTENSIONS.contains(&e.as_str())) {
+            names.push(name);
+        }
+    }
+    names.sort();
+
+    let mut tasks = JoinSet::new();
+    for (index, name) in names.into_iter().enumerate() {
+        let transaction_parser = transaction_parser.clone();
+        let path = dir.join(&name);
+        tasks.spawn(async move {
+            let contents = tokio::fs::read_to_string(&path).await?;
+            let transactions = tokio::task::spawn_blocking(move || {
+                parse_transaction_file(transaction_parser.as_deref(), &name, &contents)
+            })
+            .await??;
+            Ok::<_, Error>((index, transactions))
+        });
+    }
+    let mut batches = Vec::new();
+    while let Some(batch) = tasks.join_next().await {
+        batches.push(batch??);
+    }
+    batches.sort_by_key(|(index, _)| *index);
+    Ok(Transaction::merge(
+        batches
+            .iter()
+            .map(|(_, transactions)| transactions.as_slice()),
+    ))
+}
+
+/// Download and parse a remote transaction file (ie a statement export link of a bank)
+///
+/// The last segment of the url path selects the format and csv parsing config and becomes the
+/// source of the transactions.
+#[cfg(feature = "fetch")]
+pub async fn fetch_transaction_file(
+    transaction_parser: Option<&TransactionParser>,
+    url: &str,
+) -> Result<Vec<Transaction>, Error> {
+    let response = reqwest::get(url).await?.error_for_status()?;
+    let name = response
+        .url()
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default()
+        .to_string();
+    let contents = response.text().await?;
+    parse_transaction_file(transaction_parser, &name, &contents)
+}
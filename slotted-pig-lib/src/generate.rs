@@ -0,0 +1,357 @@
+use std::io::Write;
+
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::transaction::Transaction;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// csv
+    Csv(#[from] csv::Error),
+    /// io
+    Io(#[from] std::io::Error),
+}
+
+/// Categorizer config matching every payee of the generated transactions
+pub const CATEGORIZER_YAML: &str = r#"categories:
+  - category: income
+    children: !subcategories
+      - category: salary
+        children: !transaction_matchers
+          - min: 0
+            description: '(?i)\bpayroll\b'
+      - category: interest
+        children: !transaction_matchers
+          - min: 0
+            description: '(?i)\binterest\b'
+  - category: transfers
+    children: !transaction_matchers
+      - description:
+          - '(?i)\btransfer\b'
+          - '(?i)\bautopay\b'
+  - category: expenses
+    children: !subcategories
+      - category: housing
+        budget: 1800
+        children: !transaction_matchers
+          - max: 0
+            description: '(?i)\brent\b'
+      - category: utilities
+        budget: 220
+        children: !transaction_matchers
+          - max: 0
+            description:
+              - '(?i)\bpower\b'
+              - '(?i)\binternet\b'
+      - category: groceries
+        budget: 600
+        children: !transaction_matchers
+          - max: 0
+            description:
+              - '(?i)\bwhole foods\b'
+              - '(?i)\btrader joe'
+      - category: dining
+        budget: 250
+        children: !transaction_matchers
+          - max: 0
+            description:
+              - '(?i)\bchipotle\b'
+              - '(?i)\bstarbucks\b'
+              - '(?i)\bthai basil\b'
+      - category: transportation
+        budget: 200
+        children: !transaction_matchers
+          - max: 0
+            description: '(?i)\bshell oil\b'
+      - category: shopping
+        budget: 300
+        children: !transaction_matchers
+          - max: 0
+            description: '(?i)\bamzn mktp\b'
+      - category: subscriptions
+        budget: 30
+        children: !transaction_matchers
+          - max: 0
+            description:
+              - '(?i)\bnetflix\b'
+              - '(?i)\bspotify\b'
+"#;
+
+/// Transaction parser config reading the written transaction csv files
+pub const TRANSACTION_PARSER_YAML: &str = r#"csv:
+  - filename_regex: .*\.csv
+    has_header: true
+    amount_column: !header amount
+    account_column: !header account
+    description_column: !header description
+    time_column: !header time
+"#;
+
+/// How often a generated payee is paid
+#[derive(Clone, Copy, Debug)]
+enum Schedule {
+    /// On a day of every month
+    Monthly(u32),
+    /// Every 14 days from the start
+    Biweekly,
+    /// On random days, about this many times a month
+    Random(u32),
+}
+
+/// Kind of account a generated payee is paid from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AccountKind {
+    Checking,
+    Card,
+    Savings,
+}
+
+struct Payee {
+    /// Descriptions to pick from, `{n}` is replaced with a random number
+    descriptions: &'static [&'static str],
+    schedule: Schedule,
+    account: AccountKind,
+    /// Range of the amount in cents
+    cents: (i64, i64),
+}
+
+const PAYEES: &[Payee] = &[
+    Payee {
+        descriptions: &["ACME CORP PAYROLL {n}"],
+        schedule: Schedule::Biweekly,
+        account: AccountKind::Checking,
+        cents: (250_000, 250_000),
+    },
+    Payee {
+        descriptions: &["PARKSIDE PROPERTY MGMT RENT"],
+        schedule: Schedule::Monthly(1),
+        account: AccountKind::Checking,
+        cents: (-180_000, -180_000),
+    },
+    Payee {
+        descriptions: &["CITY POWER & LIGHT"],
+        schedule: Schedule::Monthly(12),
+        account: AccountKind::Checking,
+        cents: (-14_000, -6_000),
+    },
+    Payee {
+        descriptions: &["FASTNET INTERNET"],
+        schedule: Schedule::Monthly(18),
+        account: AccountKind::Checking,
+        cents: (-6_500, -6_500),
+    },
+    Payee {
+        descriptions: &["ONLINE TRANSFER TO SAVINGS"],
+        schedule: Schedule::Monthly(15),
+        account: AccountKind::Checking,
+        cents: (-50_000, -50_000),
+    },
+    Payee {
+        descriptions: &["CARD AUTOPAY PAYMENT"],
+        schedule: Schedule::Monthly(25),
+        account: AccountKind::Checking,
+        cents: (-120_000, -80_000),
+    },
+    Payee {
+        descriptions: &["NETFLIX.COM {n}"],
+        schedule: Schedule::Monthly(5),
+        account: AccountKind::Card,
+        cents: (-1_599, -1_599),
+    },
+    Payee {
+        descriptions: &["SPOTIFY USA {n}"],
+        schedule: Schedule::Monthly(22),
+        account: AccountKind::Card,
+        cents: (-1_099, -1_099),
+    },
+    Payee {
+        descriptions: &["WHOLE FOODS MKT #{n}", "TRADER JOE'S #{n}"],
+        schedule: Schedule::Random(6),
+        account: AccountKind::Card,
+        cents: (-18_000, -2_500),
+    },
+    Payee {
+        descriptions: &["CHIPOTLE {n}", "STARBUCKS STORE #{n}", "THAI BASIL"],
+        schedule: Schedule::Random(8),
+        account: AccountKind::Card,
+        cents: (-6_000, -400),
+    },
+    Payee {
+        descriptions: &["SHELL OIL {n}"],
+        schedule: Schedule::Random(3),
+        account: AccountKind::Card,
+        cents: (-7_000, -3_000),
+    },
+    Payee {
+        descriptions: &["AMZN MKTP US*{n}"],
+        schedule: Schedule::Random(4),
+        account: AccountKind::Card,
+        cents: (-15_000, -1_000),
+    },
+    Payee {
+        descriptions: &["ONLINE TRANSFER FROM CHECKING"],
+        schedule: Schedule::Monthly(15),
+        account: AccountKind::Savings,
+        cents: (50_000, 50_000),
+    },
+    Payee {
+        descriptions: &["INTEREST PAYMENT"],
+        schedule: Schedule::Monthly(28),
+        account: AccountKind::Savings,
+        cents: (100, 1_500),
+    },
+];
+
+/// Generate realistic synthetic transactions for demos, benchmarks, and trying the UI without
+/// real financial data
+///
+/// The first account is `checking` which is paid salary and pays rent, utilities, and the card
+/// bills. Every account after it is a credit card (`credit card`, `credit card 2`, ...) sharing
+/// the everyday spending, except the third which is `savings`. The same seed always generates the
+/// same transactions, all of which `CATEGORIZER_YAML` categorizes.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    /// Number of months of transactions
+    pub months: u32,
+    /// Number of accounts
+    pub accounts: usize,
+    /// First day of the transactions
+    pub start: NaiveDate,
+    /// Seed of the random amounts, days, and descriptions
+    pub seed: u64,
+}
+
+impl Generator {
+    /// Names of the generated accounts
+    pub fn account_names(&self) -> Vec<String> {
+        (0..self.accounts.max(1))
+            .map(|i| match i {
+                0 => String::from("checking"),
+                1 => String::from("credit card"),
+                2 => String::from("savings"),
+                i => format!("credit card {}", i - 1),
+            })
+            .collect()
+    }
+
+    /// Generate the transactions sorted by time
+    pub fn transactions(&self) -> Vec<Transaction> {
+        let accounts = self.account_names();
+        let cards = accounts
+            .iter()
+            .filter(|a| a.starts_with("credit card"))
+            .collect::<Vec<_>>();
+        let has_savings = accounts.iter().any(|a| a == "savings");
+        let end = self.start + Months::new(self.months);
+        let mut random = Random::new(self.seed);
+        let mut transactions = Vec::new();
+        for payee in PAYEES {
+            let mut dates = Vec::new();
+            let mut month = self
+                .start
+                .with_day(1)
+                .expect("first of month is a valid date");
+            while month < end {
+                match payee.schedule {
+                    Schedule::Monthly(day) => dates.push(month.with_day(day).unwrap_or(month)),
+                    Schedule::Biweekly => {}
+                    Schedule::Random(count) => {
+                        let days = (month + Months::new(1) - month).num_days() as u64;
+                        let count = random.between(count as i64 / 2, count as i64 * 3 / 2);
+                        for _ in 0..count {
+                            dates.push(month + Days::new(random.below(days)));
+                        }
+                    }
+                }
+                month = month + Months::new(1);
+            }
+            if let Schedule::Biweekly = payee.schedule {
+                let mut date = self.start;
+                while date < end {
+                    dates.push(date);
+                    date = date + Days::new(14);
+                }
+            }
+            dates.sort();
+            for date in dates.into_iter().filter(|d| *d >= self.start && *d < end) {
+                let account = match payee.account {
+                    AccountKind::Checking => &accounts[0],
+                    // Card spending falls back to checking without a card
+                    AccountKind::Card if cards.is_empty() => &accounts[0],
+                    AccountKind::Card => cards[random.below(cards.len() as u64) as usize],
+                    AccountKind::Savings if has_savings => &accounts[2],
+                    AccountKind::Savings => continue,
+                };
+                // Transfers and card payments only happen between generated accounts
+                let description =
+                    payee.descriptions[random.below(payee.descriptions.len() as u64) as usize];
+                if (description.contains("SAVINGS") && !has_savings)
+                    || (description.contains("AUTOPAY") && cards.is_empty())
+                {
+                    continue;
+                }
+                let description =
+                    description.replace("{n}", &format!("{:04}", random.below(10_000)));
+                let cents = random.between(payee.cents.0, payee.cents.1);
+                let time = NaiveTime::from_hms_opt(
+                    random.below(14) as u32 + 8,
+                    random.below(60) as u32,
+                    0,
+                )
+                .expect("valid time");
+                transactions.push(Transaction {
+                    amount: BigDecimal::new(cents.into(), 2),
+                    account: account.clone(),
+                    description,
+                    time: Utc.from_utc_datetime(&date.and_time(time)),
+                    source: None,
+                });
+            }
+        }
+        transactions.sort_by_key(|t| t.time);
+        transactions
+    }
+}
+
+/// Write transactions as a csv file readable with `TRANSACTION_PARSER_YAML`
+pub fn write_csv<'a, W: Write>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    writer: W,
+) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for transaction in transactions {
+        writer.serialize(transaction)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Small deterministic random number generator (splitmix64)
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number less than `n`, which must not be zero
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    /// Random number between `low` and `high` inclusive
+    fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low) as u64 + 1) as i64
+    }
+}
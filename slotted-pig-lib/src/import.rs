@@ -0,0 +1,247 @@
+use std::{path::Path, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::transaction::Transaction;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// bigdecimal
+    BigDecimal(#[from] bigdecimal::ParseBigDecimalError),
+    /// invalid date `{0}`
+    InvalidDate(String),
+    /// transaction {0} is missing its {1}
+    MissingField(usize, &'static str),
+}
+
+/// Formats of bank downloads transactions can be read from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImportFormat {
+    /// CSV parsed with a transaction parser config
+    Csv,
+    /// Open Financial Exchange, both the SGML (1.x) and XML (2.x) versions
+    Ofx,
+    /// Quicken Interchange Format
+    Qif,
+}
+
+impl ImportFormat {
+    /// Format from the file extension (`.ofx` and `.qfx` are OFX), sniffing the contents of
+    /// files with other extensions and falling back to CSV
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("ofx" | "qfx") => Self::Ofx,
+            Some("qif") => Self::Qif,
+            Some("csv") => Self::Csv,
+            _ => {
+                let start = contents.trim_start();
+                if start.starts_with("OFXHEADER") || start.contains("<OFX>") {
+                    Self::Ofx
+                } else if start.starts_with("!Type:") || start.starts_with("!Account") {
+                    Self::Qif
+                } else {
+                    Self::Csv
+                }
+            }
+        }
+    }
+}
+
+/// Parse the transactions of every statement of an OFX file
+///
+/// The account is the `ACCTID` of the statement, or the file stem if it has none. The
+/// description is the `NAME`, or the `MEMO` if it has no name or the memo is the untruncated
+/// name.
+pub fn parse_ofx(path: &Path, buffer: &str) -> Result<Vec<Transaction>, Error> {
+    let mut transactions = Vec::new();
+    let mut account = file_stem(path);
+    let mut current = None::<OfxTransaction>;
+    for (tag, value) in ofx_elements(buffer) {
+        match tag {
+            "ACCTID" => account = unescape(value),
+            "STMTTRN" => current = Some(OfxTransaction::default()),
+            "/STMTTRN" => {
+                if let Some(transaction) = current.take() {
+                    transactions.push(transaction.finish(transactions.len(), &account, path)?);
+                }
+            }
+            _ => {
+                let Some(transaction) = &mut current else {
+                    continue;
+                };
+                match tag {
+                    "DTPOSTED" => transaction.time = Some(ofx_time(value)?),
+                    "TRNAMT" => transaction.amount = Some(BigDecimal::from_str(value)?),
+                    "NAME" => transaction.name = unescape(value),
+                    "MEMO" => transaction.memo = unescape(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(transactions)
+}
+
+/// Parse the transactions of every account of a QIF file
+///
+/// The account is the name of the last `!Account` block, or the file stem if there is none. The
+/// description is the payee, or the memo if there is no payee.
+pub fn parse_qif(path: &Path, buffer: &str) -> Result<Vec<Transaction>, Error> {
+    let mut transactions = Vec::new();
+    let mut account = file_stem(path);
+    let mut in_account = false;
+    let mut current = QifTransaction::default();
+    for line in buffer.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with("!Account") {
+            in_account = true;
+            continue;
+        }
+        if line.starts_with('!') {
+            in_account = false;
+            continue;
+        }
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        if in_account {
+            match code {
+                "N" => account = value.to_string(),
+                "^" => in_account = false,
+                _ => {}
+            }
+            continue;
+        }
+        match code {
+            "D" => current.date = Some(qif_date(value)?),
+            "T" | "U" => current.amount = Some(BigDecimal::from_str(&value.replace(',', ""))?),
+            "P" => current.payee = value.to_string(),
+            "M" => current.memo = value.to_string(),
+            "^" => {
+                let transaction = std::mem::take(&mut current);
+                let index = transactions.len();
+                let description = if transaction.payee.is_empty() {
+                    transaction.memo
+                } else {
+                    transaction.payee
+                };
+                transactions.push(Transaction {
+                    amount: transaction
+                        .amount
+                        .ok_or(Error::MissingField(index, "amount"))?,
+                    account: account.clone(),
+                    description,
+                    time: transaction
+                        .date
+                        .ok_or(Error::MissingField(index, "date"))?
+                        .and_hms_opt(0, 0, 0)
+                        .expect("midnight is valid")
+                        .and_utc(),
+                    source: Some(path.to_path_buf()),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(transactions)
+}
+
+#[derive(Default)]
+struct OfxTransaction {
+    time: Option<DateTime<Utc>>,
+    amount: Option<BigDecimal>,
+    name: String,
+    memo: String,
+}
+
+impl OfxTransaction {
+    fn finish(self, index: usize, account: &str, path: &Path) -> Result<Transaction, Error> {
+        let description = if self.name.is_empty() || self.memo.starts_with(&self.name) {
+            self.memo
+        } else {
+            self.name
+        };
+        Ok(Transaction {
+            amount: self.amount.ok_or(Error::MissingField(index, "amount"))?,
+            account: account.to_string(),
+            description,
+            time: self.time.ok_or(Error::MissingField(index, "time"))?,
+            source: Some(path.to_path_buf()),
+        })
+    }
+}
+
+#[derive(Default)]
+struct QifTransaction {
+    date: Option<NaiveDate>,
+    amount: Option<BigDecimal>,
+    payee: String,
+    memo: String,
+}
+
+/// Tags and the text following them, SGML files only close aggregates (ie `STMTTRN`) so closing
+/// tags are returned like any other tag
+fn ofx_elements(buffer: &str) -> impl Iterator<Item = (&str, &str)> {
+    buffer.split('<').skip(1).filter_map(|element| {
+        let (tag, value) = element.split_once('>')?;
+        Some((tag.trim(), value.trim()))
+    })
+}
+
+/// Parse an OFX time (ie `20240224201059.000[-5:EST]`), the offset defaults to UTC
+fn ofx_time(value: &str) -> Result<DateTime<Utc>, Error> {
+    let invalid = || Error::InvalidDate(value.to_string());
+    let digits = value
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    let time = match digits.len() {
+        8 => NaiveDate::parse_from_str(&digits, "%Y%m%d")
+            .map_err(|_| invalid())?
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is valid"),
+        14.. => {
+            NaiveDateTime::parse_from_str(&digits[..14], "%Y%m%d%H%M%S").map_err(|_| invalid())?
+        }
+        _ => return Err(invalid()),
+    };
+    let offset_hours = match value.split_once('[') {
+        Some((_, offset)) => offset
+            .split([':', ']'])
+            .next()
+            .and_then(|hours| f64::from_str(hours).ok())
+            .ok_or_else(invalid)?,
+        None => 0.0,
+    };
+    let offset = Duration::try_seconds((offset_hours * 3600.0) as i64).ok_or_else(invalid)?;
+    Ok((time - offset).and_utc())
+}
+
+/// Parse a QIF date (ie `02/24/2024`, `2/24/24`, or `2/24'24`), two digit years are in the 2000s
+fn qif_date(value: &str) -> Result<NaiveDate, Error> {
+    let invalid = || Error::InvalidDate(value.to_string());
+    let parts = value
+        .split(['/', '-', '\'', '.'])
+        .map(|part| u32::from_str(part.trim()).map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [month, day, year] = parts[..] else {
+        return Err(invalid());
+    };
+    let year = if year < 100 { year + 2000 } else { year };
+    NaiveDate::from_ymd_opt(year as i32, month, day).ok_or_else(invalid)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Unescape XML entities of element content
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -1,5 +1,28 @@
 pub mod categorizer;
+#[cfg(feature = "charts")]
+pub mod charts;
+pub mod config;
+pub mod diagnostic;
+pub mod edit;
+#[cfg(feature = "email")]
+pub mod email;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod export;
+#[cfg(feature = "async")]
+pub mod fetch;
+pub mod generate;
+pub mod import;
+pub mod overrides;
+pub mod query;
+pub mod redact;
+pub mod report;
+pub mod source;
+#[cfg(feature = "sqlite")]
+pub mod store;
 #[cfg(test)]
 mod tests;
 pub mod transaction;
 pub mod util;
+pub mod validate;
+pub mod warning;
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    transaction::{Transaction, TransactionKey},
+    warning::Warning,
+};
+
+/// Edits of individual transactions applied to the parsed transactions before categorizing (ie
+/// fixing a garbled description or the amount of a cash withdrawal)
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionOverrides {
+    pub overrides: Vec<TransactionOverride>,
+}
+
+/// Fields replacing the fields of one transaction, fields that are `None` are kept
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionOverride {
+    /// Transaction as parsed, found by its time, account, description, and amount
+    pub transaction: Transaction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<BigDecimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TransactionOverride {
+    /// Override the fields of the parsed transaction that differ in the edited transaction
+    pub fn new(original: &Transaction, edited: &Transaction) -> Self {
+        let changed = |original, edited: &String| (original != edited).then(|| edited.clone());
+        Self {
+            transaction: Transaction {
+                source: None,
+                ..original.clone()
+            },
+            amount: (original.amount != edited.amount).then(|| edited.amount.clone()),
+            account: changed(&original.account, &edited.account),
+            description: changed(&original.description, &edited.description),
+        }
+    }
+
+    /// Check if the override does not change any field
+    pub fn is_empty(&self) -> bool {
+        self.amount.is_none() && self.account.is_none() && self.description.is_none()
+    }
+
+    /// Transaction with the overridden fields
+    pub fn apply(&self, transaction: &Transaction) -> Transaction {
+        Transaction {
+            amount: self.amount.clone().unwrap_or(transaction.amount.clone()),
+            account: self.account.clone().unwrap_or(transaction.account.clone()),
+            description: self
+                .description
+                .clone()
+                .unwrap_or(transaction.description.clone()),
+            ..transaction.clone()
+        }
+    }
+}
+
+impl TransactionOverrides {
+    /// Override the parsed transaction with the edited transaction, replacing an earlier override
+    /// of it
+    ///
+    /// An edit restoring every field removes the override.
+    pub fn set(&mut self, original: &Transaction, edited: &Transaction) {
+        let key = TransactionKey::new(original);
+        self.overrides
+            .retain(|o| TransactionKey::new(&o.transaction) != key);
+        let transaction_override = TransactionOverride::new(original, edited);
+        if !transaction_override.is_empty() {
+            self.overrides.push(transaction_override);
+        }
+    }
+
+    /// Parsed transaction of a transaction the overrides were applied to, the transaction itself if
+    /// it was not overridden
+    pub fn original(&self, transaction: &Transaction) -> Transaction {
+        let key = TransactionKey::new(transaction);
+        self.overrides
+            .iter()
+            .find(|o| TransactionKey::new(&o.apply(&o.transaction)) == key)
+            .map(|o| Transaction {
+                source: transaction.source.clone(),
+                ..o.transaction.clone()
+            })
+            .unwrap_or_else(|| transaction.clone())
+    }
+
+    /// Apply the overrides to the parsed transactions, transactions without an override are
+    /// unchanged
+    pub fn apply(&self, transactions: &[Transaction]) -> Vec<Transaction> {
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|o| (TransactionKey::new(&o.transaction), o))
+            .collect::<HashMap<_, _>>();
+        let transactions = transactions
+            .iter()
+            .map(
+                |transaction| match overrides.get(&TransactionKey::new(transaction)) {
+                    Some(transaction_override) => transaction_override.apply(transaction),
+                    None => transaction.clone(),
+                },
+            )
+            .collect();
+        tracing::debug!(overrides = overrides.len(), "applied transaction overrides");
+        transactions
+    }
+
+    /// Overrides of transactions which are not among the parsed transactions (ie the file with the
+    /// transaction was removed), they are never applied
+    pub fn warnings(&self, transactions: &[Transaction]) -> Vec<Warning> {
+        let keys = transactions
+            .iter()
+            .map(TransactionKey::new)
+            .collect::<HashSet<_>>();
+        self.overrides
+            .iter()
+            .filter(|o| !keys.contains(&TransactionKey::new(&o.transaction)))
+            .map(|o| Warning::UnmatchedOverride {
+                description: o.transaction.description.clone(),
+                time: o.transaction.time,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,386 @@
+use std::{iter::Peekable, str::CharIndices, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use displaydoc::Display;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::transaction::Transaction;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// regex
+    Regex(#[from] regex::Error),
+    /// unexpected `{0}` at position {1}
+    UnexpectedCharacter(char, usize),
+    /// unterminated string starting at position {0}
+    UnterminatedString(usize),
+    /// invalid number or date `{0}` at position {1}
+    InvalidLiteral(String, usize),
+    /// expected {0} at position {1}
+    Expected(&'static str, usize),
+    /// unknown field `{0}`, expected amount, account, description, time, or category
+    UnknownField(String),
+    /// `{0}` cannot be compared with `{1}` to {2}
+    InvalidComparison(&'static str, &'static str, &'static str),
+}
+
+/// Filter expression over transactions for ad hoc questions
+///
+/// Comparisons of a field to a value are combined with `&&`, `||`, `!`, and parentheses (ie
+/// `amount < -100 && (description =~ "AMZN" || account == "credit card")`). `&&` binds tighter than
+/// `||`.
+///
+/// | Field | Values | Operators |
+/// | ----- | ------ | --------- |
+/// | `amount` | numbers (ie `-12.50`) | `==` `!=` `<` `<=` `>` `>=` |
+/// | `account`, `description`, `category` | double quoted strings | `==` `!=` `=~` `!~` |
+/// | `time` | dates (ie `2024-01-31`) | `==` `!=` `<` `<=` `>` `>=` |
+///
+/// `=~` and `!~` match a regex anywhere in the string. `category` is the colon separated path of
+/// the assigned category (ie `expenses:food`), empty if the transaction is uncategorized. `time` is
+/// compared by the date of the transaction.
+#[derive(Clone, Debug)]
+pub struct Query {
+    expression: Expression,
+}
+
+#[derive(Clone, Debug)]
+enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Amount(Ordering, BigDecimal),
+    Time(Ordering, NaiveDate),
+    Text(Field, TextMatch),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Ordering {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Ordering {
+    fn compare<T: PartialOrd>(self, left: &T, right: &T) -> bool {
+        match self {
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Field {
+    Account,
+    Description,
+    Category,
+}
+
+#[derive(Clone, Debug)]
+enum TextMatch {
+    Equal(String),
+    NotEqual(String),
+    Matches(Regex),
+    NotMatches(Regex),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Literal(String),
+    String(String),
+    Operator(&'static str),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Identifier(_) => "a field",
+            Self::Literal(_) => "a number or date",
+            Self::String(_) => "a string",
+            Self::Operator(_) => "an operator",
+            Self::And | Self::Or | Self::Not | Self::Open | Self::Close => "a symbol",
+        }
+    }
+}
+
+const OPERATORS: [&str; 8] = ["==", "!=", "<=", ">=", "=~", "!~", "<", ">"];
+
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let mut chars = query.char_indices().peekable();
+    let mut tokens = Vec::new();
+    while let Some(&(position, c)) = chars.peek() {
+        let rest = &query[position..];
+        let token = if c.is_whitespace() {
+            chars.next();
+            continue;
+        } else if let Some(operator) = OPERATORS.iter().find(|o| rest.starts_with(**o)) {
+            advance(&mut chars, operator.len());
+            Token::Operator(operator)
+        } else if rest.starts_with("&&") {
+            advance(&mut chars, 2);
+            Token::And
+        } else if rest.starts_with("||") {
+            advance(&mut chars, 2);
+            Token::Or
+        } else if c == '!' {
+            chars.next();
+            Token::Not
+        } else if c == '(' {
+            chars.next();
+            Token::Open
+        } else if c == ')' {
+            chars.next();
+            Token::Close
+        } else if c == '"' {
+            chars.next();
+            Token::String(string(&mut chars, position)?)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            Token::Identifier(take_while(&mut chars, |c| {
+                c.is_ascii_alphanumeric() || c == '_'
+            }))
+        } else if c.is_ascii_digit() || c == '-' || c == '.' {
+            chars.next();
+            let mut literal = c.to_string();
+            literal.push_str(&take_while(&mut chars, |c| {
+                c.is_ascii_digit() || c == '-' || c == '.'
+            }));
+            Token::Literal(literal)
+        } else {
+            return Err(Error::UnexpectedCharacter(c, position));
+        };
+        tokens.push((token, position));
+    }
+    Ok(tokens)
+}
+
+fn advance(chars: &mut Peekable<CharIndices>, bytes: usize) {
+    // Operators are ascii so each byte is a character
+    for _ in 0..bytes {
+        chars.next();
+    }
+}
+
+fn take_while(chars: &mut Peekable<CharIndices>, predicate: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(&(_, c)) = chars.peek().filter(|(_, c)| predicate(*c)) {
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+/// Rest of a string after its opening quote, `\"` and `\\` are escapes
+fn string(chars: &mut Peekable<CharIndices>, start: usize) -> Result<String, Error> {
+    let mut string = String::new();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Ok(string),
+            '\\' => match chars.next() {
+                Some((_, escaped)) => string.push(escaped),
+                None => break,
+            },
+            c => string.push(c),
+        }
+    }
+    Err(Error::UnterminatedString(start))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |(_, position)| *position)
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<Token, Error> {
+        let position = self.position();
+        let token = self
+            .tokens
+            .get(self.index)
+            .map(|(token, _)| token.clone())
+            .ok_or(Error::Expected(expected, position))?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.index += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.index += 1;
+            expression = Expression::And(Box::new(expression), Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, Error> {
+        let position = self.position();
+        match self.next("a comparison")? {
+            Token::Not => Ok(Expression::Not(Box::new(self.unary()?))),
+            Token::Open => {
+                let expression = self.or()?;
+                let position = self.position();
+                match self.next("`)`")? {
+                    Token::Close => Ok(expression),
+                    _ => Err(Error::Expected("`)`", position)),
+                }
+            }
+            Token::Identifier(field) => self.comparison(field),
+            _ => Err(Error::Expected("a comparison", position)),
+        }
+    }
+
+    fn comparison(&mut self, field: String) -> Result<Expression, Error> {
+        let field = match field.as_str() {
+            "amount" => "amount",
+            "time" => "time",
+            "account" => "account",
+            "description" => "description",
+            "category" => "category",
+            _ => return Err(Error::UnknownField(field)),
+        };
+        let position = self.position();
+        let Token::Operator(operator) = self.next("an operator")? else {
+            return Err(Error::Expected("an operator", position));
+        };
+        let position = self.position();
+        let value = self.next("a value")?;
+        let invalid = || Error::InvalidComparison(field, operator, value.describe());
+        let ordering = match operator {
+            "==" => Some(Ordering::Equal),
+            "!=" => Some(Ordering::NotEqual),
+            "<" => Some(Ordering::Less),
+            "<=" => Some(Ordering::LessOrEqual),
+            ">" => Some(Ordering::Greater),
+            ">=" => Some(Ordering::GreaterOrEqual),
+            _ => None,
+        };
+        let literal = match &value {
+            Token::Literal(literal) => Some(literal.as_str()),
+            _ => None,
+        };
+        match field {
+            "amount" => {
+                let (Some(ordering), Some(literal)) = (ordering, literal) else {
+                    return Err(invalid());
+                };
+                let amount = BigDecimal::from_str(literal)
+                    .map_err(|_| Error::InvalidLiteral(literal.to_string(), position))?;
+                Ok(Expression::Amount(ordering, amount))
+            }
+            "time" => {
+                let (Some(ordering), Some(literal)) = (ordering, literal) else {
+                    return Err(invalid());
+                };
+                let date = NaiveDate::parse_from_str(literal, "%Y-%m-%d")
+                    .map_err(|_| Error::InvalidLiteral(literal.to_string(), position))?;
+                Ok(Expression::Time(ordering, date))
+            }
+            // account, description, or category
+            _ => {
+                let Token::String(string) = &value else {
+                    return Err(invalid());
+                };
+                let text_match = match operator {
+                    "==" => TextMatch::Equal(string.clone()),
+                    "!=" => TextMatch::NotEqual(string.clone()),
+                    "=~" => TextMatch::Matches(Regex::new(string)?),
+                    "!~" => TextMatch::NotMatches(Regex::new(string)?),
+                    _ => return Err(invalid()),
+                };
+                let field = match field {
+                    "account" => Field::Account,
+                    "description" => Field::Description,
+                    _ => Field::Category,
+                };
+                Ok(Expression::Text(field, text_match))
+            }
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query expression
+    pub fn parse(query: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            index: 0,
+            end: query.len(),
+        };
+        let expression = parser.or()?;
+        if parser.peek().is_some() {
+            return Err(Error::Expected("`&&`, `||`, or the end", parser.position()));
+        }
+        Ok(Self { expression })
+    }
+
+    /// Check if the transaction matches, `category` is the colon separated path of its assigned
+    /// category or `None` if it is uncategorized
+    pub fn matches(&self, transaction: &Transaction, category: Option<&str>) -> bool {
+        self.expression
+            .matches(transaction, category.unwrap_or_default())
+    }
+}
+
+impl Expression {
+    fn matches(&self, transaction: &Transaction, category: &str) -> bool {
+        match self {
+            Self::And(left, right) => {
+                left.matches(transaction, category) && right.matches(transaction, category)
+            }
+            Self::Or(left, right) => {
+                left.matches(transaction, category) || right.matches(transaction, category)
+            }
+            Self::Not(expression) => !expression.matches(transaction, category),
+            Self::Amount(ordering, amount) => ordering.compare(&transaction.amount, amount),
+            Self::Time(ordering, date) => ordering.compare(&transaction.time.date_naive(), date),
+            Self::Text(field, text_match) => {
+                let text = match field {
+                    Field::Account => transaction.account.as_str(),
+                    Field::Description => transaction.description.as_str(),
+                    Field::Category => category,
+                };
+                match text_match {
+                    TextMatch::Equal(string) => text == string,
+                    TextMatch::NotEqual(string) => text != string,
+                    TextMatch::Matches(regex) => regex.is_match(text),
+                    TextMatch::NotMatches(regex) => !regex.is_match(text),
+                }
+            }
+        }
+    }
+}
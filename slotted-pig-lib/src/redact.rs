@@ -0,0 +1,237 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    str::FromStr,
+};
+
+use bigdecimal::{BigDecimal, RoundingMode};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    categorizer::{Categorizer, Category, CategoryChildren, TransactionMatcher},
+    report::StartingBalances,
+    transaction::{CsvColumn, Transaction},
+};
+
+/// Scrub personal details from transactions so reports can be shared
+///
+/// Descriptions and accounts are replaced with pseudonyms derived from a hash of their value
+/// (ie `payee-1a2b3c4d` and `account-5e6f7a8b`). The same value always produces the same pseudonym
+/// so the structure of the data is preserved. Category names are kept.
+#[derive(Clone, Debug, Default)]
+pub struct Redactor {
+    /// Secret mixed into the hashes so pseudonyms can not be reversed by hashing guessed values
+    pub salt: String,
+    /// Factor to multiply amounts by
+    pub amount_scale: Option<BigDecimal>,
+    /// Size of the buckets amounts are rounded to after scaling (ie `10` rounds to the nearest 10)
+    pub amount_bucket: Option<BigDecimal>,
+}
+
+impl Redactor {
+    /// Redact transactions returning a categorizer which assigns the redacted transactions the
+    /// same categories the original categorizer assigned the original transactions
+    ///
+    /// Transactions removed by the transaction filters are dropped. The categorizer matches the
+    /// redacted descriptions so its matchers do not leak the original rules. Descriptions are
+    /// hashed along with their assigned category, so the same description assigned different
+    /// categories (ie by amount) gets different pseudonyms.
+    pub fn redact(
+        &self,
+        categorizer: &Categorizer,
+        transactions: &[Transaction],
+    ) -> (Categorizer, Vec<Transaction>) {
+        let mut descriptions = HashMap::<Vec<&str>, BTreeSet<String>>::new();
+        let mut redacted = Vec::new();
+        for transaction in transactions {
+            let Some(assignment) = categorizer.assign_transaction(transaction) else {
+                continue;
+            };
+            let category_path = assignment.category_path.unwrap_or_default();
+            let description = self.redact_description(&transaction.description, &category_path);
+            if !category_path.is_empty() {
+                descriptions
+                    .entry(category_path)
+                    .or_default()
+                    .insert(description.clone());
+            }
+            redacted.push(Transaction {
+                amount: self.redact_amount(&transaction.amount),
+                account: self.redact_account(&transaction.account),
+                description,
+                time: transaction.time,
+                source: None,
+            });
+        }
+        let categorizer = Categorizer {
+            transaction_filters: None,
+            categories: categorizer
+                .categories
+                .iter()
+                .map(|category| self.redact_category(category, &mut Vec::new(), &descriptions))
+                .collect(),
+            rounding: categorizer.rounding,
+            tests: Vec::new(),
+        };
+        (categorizer, redacted)
+    }
+
+    /// Pseudonym of an account
+    pub fn redact_account(&self, account: &str) -> String {
+        format!("account-{}", self.hash(&[account]))
+    }
+
+    /// Scale and bucket an amount
+    pub fn redact_amount(&self, amount: &BigDecimal) -> BigDecimal {
+        let mut amount = match &self.amount_scale {
+            Some(scale) => amount * scale,
+            None => amount.clone(),
+        };
+        if let Some(bucket) = &self.amount_bucket {
+            if bucket != &BigDecimal::default() {
+                amount = (amount / bucket).round(0) * bucket;
+            }
+        }
+        amount
+    }
+
+    /// Redact the accounts and amounts of starting balances to match redacted transactions
+    pub fn redact_starting_balances(
+        &self,
+        starting_balances: &StartingBalances,
+    ) -> StartingBalances {
+        StartingBalances {
+            accounts: starting_balances
+                .accounts
+                .iter()
+                .map(|(account, balance)| {
+                    (self.redact_account(account), self.redact_amount(balance))
+                })
+                .collect(),
+        }
+    }
+
+    /// Scramble a value of a transaction csv column so the file can be shared (ie in a bug
+    /// report) and still parses the same way
+    ///
+    /// Amounts are multiplied by a factor between 0.8 and 1.2 derived from their hash keeping
+    /// their decimal places, then scaled and bucketed like [`Redactor::redact_amount`]. Times are
+    /// kept. Every word of other values is replaced by a word of the same shape (ie `Coffee 42`
+    /// might become `Kqzbwe 17`) and the same word is always replaced the same way.
+    pub fn anonymize(&self, column: CsvColumn, value: &str) -> String {
+        match column {
+            CsvColumn::Time => value.to_string(),
+            CsvColumn::Amount => match BigDecimal::from_str(value.trim()) {
+                Ok(amount) => {
+                    let (_, scale) = amount.as_bigint_and_exponent();
+                    let percent = 80
+                        + u32::from_str_radix(&self.hash(&["amount", value]), 16)
+                            .expect("hash is hex")
+                            % 41;
+                    let perturbed = (amount * BigDecimal::from(percent) / BigDecimal::from(100))
+                        .with_scale_round(scale.max(0), RoundingMode::HalfEven);
+                    self.redact_amount(&perturbed).to_string()
+                }
+                Err(_) => self.scramble(value),
+            },
+            CsvColumn::Account | CsvColumn::Description | CsvColumn::Other => self.scramble(value),
+        }
+    }
+
+    /// Replace every letter and digit with one of the same kind chosen by the hash of its word
+    fn scramble(&self, value: &str) -> String {
+        let mut scrambled = String::with_capacity(value.len());
+        for word in value.split_inclusive(|c: char| !c.is_alphanumeric()) {
+            let mut hasher = Sha256::new();
+            hasher.update(self.salt.as_bytes());
+            hasher.update([0]);
+            hasher.update(
+                word.trim_end_matches(|c: char| !c.is_alphanumeric())
+                    .as_bytes(),
+            );
+            let digest = hasher.finalize();
+            for (i, c) in word.chars().enumerate() {
+                let byte = digest[i % digest.len()].wrapping_add((i / digest.len()) as u8);
+                scrambled.push(if c.is_ascii_digit() {
+                    char::from(b'0' + byte % 10)
+                } else if c.is_ascii_uppercase() {
+                    char::from(b'A' + byte % 26)
+                } else if c.is_alphabetic() {
+                    char::from(b'a' + byte % 26)
+                } else {
+                    c
+                });
+            }
+        }
+        scrambled
+    }
+
+    fn redact_description(&self, description: &str, category_path: &[&str]) -> String {
+        let mut values = vec![description];
+        values.extend(category_path);
+        format!("payee-{}", self.hash(&values))
+    }
+
+    fn redact_category<'a>(
+        &self,
+        category: &'a Category,
+        path: &mut Vec<&'a str>,
+        descriptions: &HashMap<Vec<&str>, BTreeSet<String>>,
+    ) -> Category {
+        path.push(&category.category);
+        let children = match &category.children {
+            CategoryChildren::TransactionMatchers(_) => CategoryChildren::TransactionMatchers(
+                descriptions
+                    .get(path)
+                    .map(|descriptions| TransactionMatcher {
+                        min: None,
+                        max: None,
+                        account: None,
+                        description: descriptions
+                            .iter()
+                            .map(|d| {
+                                Regex::new(&format!("^{}$", regex::escape(d)))
+                                    .expect("escaped regex is valid")
+                            })
+                            .collect(),
+                        begin: None,
+                        end: None,
+                    })
+                    .into_iter()
+                    .collect(),
+            ),
+            CategoryChildren::Subcategories(subcategories) => CategoryChildren::Subcategories(
+                subcategories
+                    .iter()
+                    .map(|subcategory| self.redact_category(subcategory, path, descriptions))
+                    .collect(),
+            ),
+        };
+        path.pop();
+        Category {
+            category: category.category.clone(),
+            budget: category
+                .budget
+                .as_ref()
+                .map(|budget| self.redact_amount(budget)),
+            tax_relevant: category.tax_relevant,
+            color: category.color.clone(),
+            icon: category.icon.clone(),
+            children,
+        }
+    }
+
+    /// First 8 hex characters of the salted hash of the values
+    fn hash(&self, values: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        for value in values {
+            hasher.update([0]);
+            hasher.update(value.as_bytes());
+        }
+        hasher.finalize()[..4]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
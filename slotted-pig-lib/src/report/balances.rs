@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, io::Write};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    report::{
+        table::{write_markdown, write_text, Table},
+        DateRange, Error, StartingBalances,
+    },
+    transaction::Transaction,
+    util::Formatter,
+};
+
+/// Output formats for account balances
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BalancesFormat {
+    /// Plain text table
+    #[default]
+    Text,
+    /// Markdown table
+    Markdown,
+    /// JSON accounts
+    Json,
+}
+
+/// Net total of every account over a period, for checking imports against the balances shown by
+/// the bank
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Balances {
+    /// Label of the period (ie `2024-03`), `all transactions` if there is no period
+    pub label: String,
+    /// Totals of each account sorted by account name
+    pub accounts: Vec<AccountTotals>,
+}
+
+/// Totals of a single account over the period of `Balances`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountTotals {
+    /// Account name
+    pub account: String,
+    /// Number of transactions in the period
+    pub count: u64,
+    /// Sum of the positive amounts
+    pub inflow: BigDecimal,
+    /// Sum of the negative amounts
+    pub outflow: BigDecimal,
+    /// Inflow plus outflow
+    pub net: BigDecimal,
+    /// Balance before the period, `None` if the account has no starting balance
+    pub opening: Option<BigDecimal>,
+    /// Balance at the end of the period, `None` if the account has no starting balance
+    pub closing: Option<BigDecimal>,
+}
+
+impl Balances {
+    /// Total the transactions of every account within the period, or all transactions if there is
+    /// no period
+    ///
+    /// Accounts with a starting balance also get their opening and closing balances, which
+    /// accumulate every transaction before the end of the period onto the starting balance.
+    /// Accounts without transactions in the period are still listed so a missing import stands
+    /// out.
+    pub fn new(
+        transactions: &[Transaction],
+        starting_balances: &StartingBalances,
+        period: Option<&DateRange>,
+    ) -> Self {
+        let mut accounts = BTreeMap::<&str, AccountTotals>::new();
+        let account_names = starting_balances
+            .accounts
+            .keys()
+            .chain(transactions.iter().map(|t| &t.account));
+        for account in account_names {
+            accounts.entry(account).or_insert_with(|| AccountTotals {
+                account: account.clone(),
+                count: 0,
+                inflow: BigDecimal::zero(),
+                outflow: BigDecimal::zero(),
+                net: BigDecimal::zero(),
+                opening: starting_balances.accounts.get(account).cloned(),
+                closing: None,
+            });
+        }
+        for transaction in transactions {
+            let date = transaction.time.date_naive();
+            let totals = accounts
+                .get_mut(transaction.account.as_str())
+                .expect("every account was inserted");
+            if period.is_some_and(|p| date < p.start) {
+                if let Some(opening) = &mut totals.opening {
+                    *opening += &transaction.amount;
+                }
+            } else if period.is_none_or(|p| p.contains(date)) {
+                totals.count += 1;
+                if transaction.amount > BigDecimal::zero() {
+                    totals.inflow += &transaction.amount;
+                } else {
+                    totals.outflow += &transaction.amount;
+                }
+            }
+        }
+        let accounts = accounts
+            .into_values()
+            .map(|mut totals| {
+                totals.net = &totals.inflow + &totals.outflow;
+                totals.closing = totals.opening.as_ref().map(|opening| opening + &totals.net);
+                totals
+            })
+            .collect();
+        Self {
+            label: period.map_or_else(|| String::from("all transactions"), |p| p.label.clone()),
+            accounts,
+        }
+    }
+
+    /// Write the balances in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: BalancesFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let title = format!("Account balances of {}", self.label);
+        let optional = |amount: &Option<BigDecimal>| {
+            amount
+                .as_ref()
+                .map(|amount| formatter.format(amount))
+                .unwrap_or_default()
+        };
+        let table = Table {
+            title: String::from("Accounts"),
+            headers: [
+                "Account",
+                "Transactions",
+                "Opening",
+                "Inflow",
+                "Outflow",
+                "Net",
+                "Closing",
+            ]
+            .map(String::from)
+            .to_vec(),
+            numeric: vec![false, true, true, true, true, true, true],
+            rows: self
+                .accounts
+                .iter()
+                .map(|account| {
+                    vec![
+                        account.account.clone(),
+                        account.count.to_string(),
+                        optional(&account.opening),
+                        formatter.format(&account.inflow),
+                        formatter.format(&account.outflow),
+                        formatter.format(&account.net),
+                        optional(&account.closing),
+                    ]
+                })
+                .collect(),
+        };
+        match format {
+            BalancesFormat::Text => write_text(&title, &[table], writer),
+            BalancesFormat::Markdown => write_markdown(&title, &[table], writer),
+            BalancesFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
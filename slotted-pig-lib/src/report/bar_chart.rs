@@ -0,0 +1,122 @@
+use std::io::Write;
+
+use bigdecimal::{Signed, ToPrimitive};
+
+use crate::{
+    categorizer::{Categorized, CategorizedChildren, CategorizedList},
+    report::Error,
+    util::Formatter,
+};
+
+/// Unicode blocks for drawing fractions of a character cell in eighths
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const FULL_BLOCK: char = '█';
+
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Horizontal bar chart of category absolute totals for display in a terminal
+///
+/// Each category is a row with its name indented by depth, a bar scaled to the largest absolute
+/// total shown, and its formatted total.
+#[derive(Clone, Debug)]
+pub struct BarChart {
+    /// Maximum depth of categories to show, top level categories have a depth of 1
+    pub depth: Option<usize>,
+    /// Width of the longest bar in characters
+    pub width: usize,
+    /// Color bars with ANSI escape codes, green for positive totals and red for negative totals
+    pub color: bool,
+    /// Formatting of the totals
+    pub formatter: Formatter,
+}
+
+impl BarChart {
+    /// Write the chart for the categorized transactions
+    pub fn write<W: Write>(
+        &self,
+        categorized: &CategorizedList,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let mut rows = Vec::new();
+        for category in &categorized.categorized {
+            self.rows(category, 1, &mut rows);
+        }
+
+        let label_width = rows
+            .iter()
+            .map(|(depth, c)| 2 * (depth - 1) + c.category.chars().count())
+            .max()
+            .unwrap_or_default();
+        let amount_width = rows
+            .iter()
+            .map(|(_, c)| self.formatter.format(&c.total).chars().count())
+            .max()
+            .unwrap_or_default();
+        let max = rows
+            .iter()
+            .filter_map(|(_, c)| c.absolute_total.to_f64())
+            .fold(0.0, f64::max);
+
+        for (depth, category) in rows {
+            let label = format!("{}{}", "  ".repeat(depth - 1), category.category);
+            let fraction = match category.absolute_total.to_f64() {
+                Some(absolute_total) if max > 0.0 => absolute_total / max,
+                _ => 0.0,
+            };
+            let bar = bar(fraction, self.width);
+            let (color, reset) = match (self.color, category.total.is_negative()) {
+                (false, _) => ("", ""),
+                (true, true) => (ANSI_RED, ANSI_RESET),
+                (true, false) => (ANSI_GREEN, ANSI_RESET),
+            };
+            writeln!(
+                writer,
+                "{label:<label_width$}  {color}{bar:<width$}{reset}  {:>amount_width$}",
+                self.formatter.format(&category.total),
+                width = self.width,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn rows<'a>(
+        &self,
+        category: &'a Categorized,
+        depth: usize,
+        rows: &mut Vec<(usize, &'a Categorized)>,
+    ) {
+        if self.depth.is_some_and(|max_depth| depth > max_depth) {
+            return;
+        }
+        rows.push((depth, category));
+        if let CategorizedChildren::Subcategories(subcategories) = &category.children {
+            for subcategory in subcategories {
+                self.rows(subcategory, depth + 1, rows);
+            }
+        }
+    }
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        Self {
+            depth: None,
+            width: 40,
+            color: false,
+            formatter: Formatter::default(),
+        }
+    }
+}
+
+/// Draw a bar filling the fraction of the width with eighth block precision
+fn bar(fraction: f64, width: usize) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = FULL_BLOCK.to_string().repeat(eighths / 8);
+    let remainder = eighths % 8;
+    if remainder > 0 {
+        bar.push(PARTIAL_BLOCKS[remainder]);
+    }
+    bar
+}
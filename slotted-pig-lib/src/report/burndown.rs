@@ -0,0 +1,220 @@
+use std::io::Write;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Assignment, Categorizer},
+    report::{
+        table::{write_markdown, write_text, Table},
+        Bucket, BudgetStatus, Error,
+    },
+    util::Formatter,
+};
+
+/// Output formats for a budget burndown
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BurndownFormat {
+    /// Plain text table with a burn indicator per budget
+    #[default]
+    Text,
+    /// Markdown table with a burn indicator per budget
+    Markdown,
+    /// JSON rows
+    Json,
+}
+
+/// How spending in a budgeted category compares to its budget
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BurnStatus {
+    /// Spent no more than the prorated budget
+    OnTrack,
+    /// Spent more than the prorated budget but not the full budget
+    AheadOfPace,
+    /// Spent more than the full budget
+    OverBudget,
+}
+
+/// Month to date spending of every budgeted category compared to its prorated budget
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Burndown {
+    /// Label of the month (ie `2024-02`)
+    pub month: String,
+    /// Last day included in the month to date spending
+    pub as_of: NaiveDate,
+    /// Number of days of the month up to and including `as_of`
+    pub days_elapsed: u32,
+    /// Number of days in the month
+    pub days_in_month: u32,
+    /// Burndown of each budget
+    pub rows: Vec<BurndownRow>,
+}
+
+/// Month to date spending of a single budgeted category
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BurndownRow {
+    /// Colon separated category path
+    pub category: String,
+    /// Maximum amount to spend in the month
+    pub budget: BigDecimal,
+    /// Amount spent so far
+    pub spent: BigDecimal,
+    /// Share of the budget for the elapsed days
+    pub prorated_budget: BigDecimal,
+    /// Amount spent by the end of the month if spending continues at the daily rate so far
+    pub projected: BigDecimal,
+    /// Spending compared to the budget
+    pub status: BurnStatus,
+}
+
+impl Burndown {
+    /// Create a burndown of the month containing `as_of` including transactions up to `as_of`
+    pub fn new(categorizer: &Categorizer, assignments: &[Assignment], as_of: NaiveDate) -> Self {
+        let start = Bucket::Month.start(as_of);
+        let days_in_month = (Bucket::Month.next(start) - start).num_days() as u32;
+        let days_elapsed = as_of.day();
+        let month_to_date = assignments
+            .iter()
+            .filter(|a| {
+                let date = a.transaction.time.date_naive();
+                start <= date && date <= as_of
+            })
+            .collect::<Vec<_>>();
+
+        let rows = BudgetStatus::all(categorizer, &month_to_date)
+            .into_iter()
+            .map(|status| {
+                let prorated_budget = (&status.budget * BigDecimal::from(days_elapsed)
+                    / BigDecimal::from(days_in_month))
+                .round(2);
+                let projected = (&status.spent * BigDecimal::from(days_in_month)
+                    / BigDecimal::from(days_elapsed))
+                .round(2);
+                let burn_status = if status.is_over() {
+                    BurnStatus::OverBudget
+                } else if status.spent > prorated_budget {
+                    BurnStatus::AheadOfPace
+                } else {
+                    BurnStatus::OnTrack
+                };
+                BurndownRow {
+                    category: status.category,
+                    budget: status.budget,
+                    spent: status.spent,
+                    prorated_budget,
+                    projected,
+                    status: burn_status,
+                }
+            })
+            .collect();
+
+        Self {
+            month: Bucket::Month.label(start),
+            as_of,
+            days_elapsed,
+            days_in_month,
+            rows,
+        }
+    }
+
+    /// Write the burndown in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: BurndownFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let title = format!(
+            "Budget burndown for {} as of {} (day {} of {})",
+            self.month,
+            formatter.format_date(self.as_of),
+            self.days_elapsed,
+            self.days_in_month
+        );
+        let table = Table {
+            title: String::from("Budgets"),
+            headers: [
+                "Category",
+                "Budget",
+                "Prorated",
+                "Spent",
+                "Projected",
+                "Burn",
+                "Status",
+            ]
+            .map(String::from)
+            .to_vec(),
+            numeric: vec![false, true, true, true, true, false, false],
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    let status = match row.status {
+                        BurnStatus::OnTrack => "on track",
+                        BurnStatus::AheadOfPace => "ahead of pace",
+                        BurnStatus::OverBudget => "over budget",
+                    };
+                    vec![
+                        row.category.clone(),
+                        formatter.format(&row.budget),
+                        formatter.format(&row.prorated_budget),
+                        formatter.format(&row.spent),
+                        formatter.format(&row.projected),
+                        row.indicator(INDICATOR_WIDTH),
+                        status.to_string(),
+                    ]
+                })
+                .collect(),
+        };
+        match format {
+            BurndownFormat::Text => write_text(&title, &[table], writer),
+            BurndownFormat::Markdown => write_markdown(&title, &[table], writer),
+            BurndownFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Number of characters in a burn indicator
+const INDICATOR_WIDTH: usize = 20;
+
+impl BurndownRow {
+    /// Bar of the spent share of the budget with a `|` marking the prorated budget and a `!` at the
+    /// end when over budget (ie `[#####|.....]`)
+    pub fn indicator(&self, width: usize) -> String {
+        let position = |amount: &BigDecimal| {
+            if self.budget <= BigDecimal::default() {
+                return width;
+            }
+            let position = (amount * BigDecimal::from(width as u64) / &self.budget)
+                .round(0)
+                .to_i64()
+                .unwrap_or_default();
+            position.clamp(0, width as i64) as usize
+        };
+        let spent = position(&self.spent);
+        let prorated = position(&self.prorated_budget);
+        let mut bar = (0..width)
+            .map(|i| if i < spent { '#' } else { '.' })
+            .collect::<Vec<_>>();
+        if prorated < width {
+            bar[prorated] = '|';
+        }
+        let over = if self.status == BurnStatus::OverBudget {
+            "!"
+        } else {
+            ""
+        };
+        format!("[{}]{over}", bar.into_iter().collect::<String>())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{cmp::Reverse, io::Write};
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::CategorizedList,
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    util::Formatter,
+};
+
+/// Output formats for a change report
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChangesFormat {
+    /// Plain text table with aligned columns
+    #[default]
+    Text,
+    /// Markdown table
+    Markdown,
+    /// JSON labels and rows
+    Json,
+}
+
+/// Period-over-period change of every category total sorted by the biggest absolute change
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Changes {
+    /// Label of the previous period (ie `2024-02`)
+    pub previous_label: String,
+    /// Label of the current period
+    pub current_label: String,
+    /// Change of each category
+    pub rows: Vec<ChangesRow>,
+}
+
+/// Change of a single category
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChangesRow {
+    /// Colon separated category path
+    pub category: String,
+    /// Total of the previous period
+    pub previous: BigDecimal,
+    /// Total of the current period
+    pub current: BigDecimal,
+    /// Difference from the previous to the current total
+    pub change: BigDecimal,
+    /// Change as a percent of the absolute previous total rounded to one decimal place, `None` if
+    /// the previous total is zero
+    pub percent_change: Option<BigDecimal>,
+}
+
+impl Changes {
+    /// Compare two categorizations of the same categorizer
+    ///
+    /// Categories deeper than `depth` are left out, top level categories have a depth of 1.
+    pub fn new(
+        previous: &CategorizedList,
+        current: &CategorizedList,
+        previous_label: impl Into<String>,
+        current_label: impl Into<String>,
+        depth: Option<usize>,
+    ) -> Self {
+        let mut rows = current
+            .diff(previous)
+            .into_iter()
+            .filter(|diff| depth.is_none_or(|depth| diff.path.len() <= depth))
+            .map(|diff| ChangesRow {
+                category: diff.path.join(":"),
+                change: diff.change(),
+                percent_change: diff.percent_change().map(|percent| percent.round(1)),
+                previous: diff.previous,
+                current: diff.current,
+            })
+            .collect::<Vec<_>>();
+        // Stable sort keeps the hierarchy order for equal changes
+        rows.sort_by_key(|row| Reverse(row.change.abs()));
+        Self {
+            previous_label: previous_label.into(),
+            current_label: current_label.into(),
+            rows,
+        }
+    }
+
+    /// Write the changes in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: ChangesFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let title = format!(
+            "Changes from {} to {}",
+            self.previous_label, self.current_label
+        );
+        let table = Table {
+            title: String::from("Categories"),
+            headers: vec![
+                String::from("Category"),
+                self.previous_label.clone(),
+                self.current_label.clone(),
+                String::from("Change"),
+                String::from("Percent"),
+            ],
+            numeric: vec![false, true, true, true, true],
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    vec![
+                        row.category.clone(),
+                        formatter.format(&row.previous),
+                        formatter.format(&row.current),
+                        formatter.format(&row.change),
+                        row.percent_change
+                            .as_ref()
+                            .map(|percent| format!("{percent}%"))
+                            .unwrap_or_else(|| String::from("new")),
+                    ]
+                })
+                .collect(),
+        };
+        match format {
+            ChangesFormat::Text => write_text(&title, &[table], writer),
+            ChangesFormat::Markdown => write_markdown(&title, &[table], writer),
+            ChangesFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
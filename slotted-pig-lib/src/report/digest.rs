@@ -0,0 +1,315 @@
+use std::{collections::HashMap, io::Write};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Assignment, Categorizer},
+    report::{
+        table::{write_html, write_markdown, write_text, Table},
+        BurnStatus, Burndown, BurndownRow, Error, StatementFormat, StatementTransaction,
+    },
+    util::Formatter,
+};
+
+/// Length of time a digest summarizes
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DigestPeriod {
+    /// Seven days
+    #[default]
+    Weekly,
+    /// One month
+    Monthly,
+}
+
+impl DigestPeriod {
+    /// First day of the period ending on `end`
+    pub fn start(&self, end: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Weekly => end - Days::new(6),
+            Self::Monthly => end - Months::new(1) + Days::new(1),
+        }
+    }
+}
+
+/// Summary of recent activity meant to be sent periodically (ie by email)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Digest {
+    /// Length of the summarized time
+    pub period: DigestPeriod,
+    /// First day of the summarized time
+    pub start: NaiveDate,
+    /// Last day of the summarized time
+    pub end: NaiveDate,
+    /// Transactions of the period, newest first
+    pub transactions: Vec<StatementTransaction>,
+    /// Totals of every top level category with transactions in the period
+    pub categories: Vec<DigestCategory>,
+    /// Uncategorized transactions total of the period
+    pub uncategorized: BigDecimal,
+    /// Budgets which are ahead of pace or over budget for the month to date
+    pub budget_alerts: Vec<BurndownRow>,
+    /// Transactions much larger than is typical for their category
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Total of a top level category over a digest period
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestCategory {
+    /// Category name
+    pub category: String,
+    /// Number of transactions
+    pub count: u64,
+    /// Sum of the transactions
+    pub total: BigDecimal,
+}
+
+/// Transaction much larger than the earlier transactions of its category
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Anomaly {
+    /// Unusual transaction
+    pub transaction: StatementTransaction,
+    /// Average absolute amount of the earlier transactions in the category
+    pub typical: BigDecimal,
+}
+
+impl Digest {
+    /// Create a digest of the period ending on `end`
+    ///
+    /// A transaction is an anomaly if its absolute amount is more than `anomaly_factor` times the
+    /// average absolute amount of at least three earlier transactions in the same category.
+    pub fn new(
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        period: DigestPeriod,
+        end: NaiveDate,
+        anomaly_factor: &BigDecimal,
+    ) -> Self {
+        let start = period.start(end);
+        let in_period = assignments
+            .iter()
+            .filter(|a| {
+                let date = a.transaction.time.date_naive();
+                start <= date && date <= end
+            })
+            .collect::<Vec<_>>();
+
+        let mut transactions = in_period
+            .iter()
+            .map(|a| statement_transaction(a))
+            .collect::<Vec<_>>();
+        transactions.sort_by_key(|t| std::cmp::Reverse(t.date));
+
+        let mut totals = HashMap::<&str, (u64, BigDecimal)>::new();
+        let mut uncategorized = BigDecimal::zero();
+        for assignment in &in_period {
+            match &assignment.category_path {
+                Some(path) => {
+                    let (count, total) = totals.entry(path[0]).or_default();
+                    *count += 1;
+                    *total += &assignment.transaction.amount;
+                }
+                None => uncategorized += &assignment.transaction.amount,
+            }
+        }
+        // Keep the configured category order
+        let categories = categorizer
+            .categories
+            .iter()
+            .filter_map(|category| {
+                totals
+                    .remove(category.category.as_str())
+                    .map(|(count, total)| DigestCategory {
+                        category: category.category.clone(),
+                        count,
+                        total,
+                    })
+            })
+            .collect();
+
+        let budget_alerts = Burndown::new(categorizer, assignments, end)
+            .rows
+            .into_iter()
+            .filter(|row| row.status != BurnStatus::OnTrack)
+            .collect();
+
+        let mut history = HashMap::<Vec<&str>, (u64, BigDecimal)>::new();
+        for assignment in assignments {
+            if let Some(path) = &assignment.category_path {
+                if assignment.transaction.time.date_naive() < start {
+                    let (count, total) = history.entry(path.clone()).or_default();
+                    *count += 1;
+                    *total += assignment.transaction.amount.abs();
+                }
+            }
+        }
+        let mut anomalies = in_period
+            .iter()
+            .filter_map(|assignment| {
+                let (count, total) = history.get(assignment.category_path.as_ref()?)?;
+                if *count < 3 {
+                    return None;
+                }
+                let typical = (total / BigDecimal::from(*count)).round(2);
+                (assignment.transaction.amount.abs() > &typical * anomaly_factor).then(|| Anomaly {
+                    transaction: statement_transaction(assignment),
+                    typical,
+                })
+            })
+            .collect::<Vec<_>>();
+        anomalies.sort_by_key(|a| std::cmp::Reverse(a.transaction.date));
+
+        Self {
+            period,
+            start,
+            end,
+            transactions,
+            categories,
+            uncategorized,
+            budget_alerts,
+            anomalies,
+        }
+    }
+
+    /// Title naming the period (ie `Weekly digest for 2024-02-18 to 2024-02-24`)
+    pub fn title(&self, formatter: &Formatter) -> String {
+        let period = match self.period {
+            DigestPeriod::Weekly => "Weekly",
+            DigestPeriod::Monthly => "Monthly",
+        };
+        format!(
+            "{period} digest for {} to {}",
+            formatter.format_date(self.start),
+            formatter.format_date(self.end)
+        )
+    }
+
+    /// Write the digest in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: StatementFormat,
+        formatter: &Formatter,
+        writer: W,
+    ) -> Result<(), Error> {
+        let title = self.title(formatter);
+        let tables = self.tables(formatter);
+        match format {
+            StatementFormat::Text => write_text(&title, &tables, writer),
+            StatementFormat::Markdown => write_markdown(&title, &tables, writer),
+            StatementFormat::Html => write_html(&title, &tables, writer),
+        }
+    }
+
+    fn tables(&self, formatter: &Formatter) -> Vec<Table> {
+        let transaction_headers = ["Date", "Description", "Account", "Category", "Amount"]
+            .map(String::from)
+            .to_vec();
+        let transaction_row = |t: &StatementTransaction| {
+            vec![
+                formatter.format_date(t.date),
+                t.description.clone(),
+                t.account.clone(),
+                t.category.clone().unwrap_or_default(),
+                formatter.format(&t.amount),
+            ]
+        };
+
+        let mut categories = self
+            .categories
+            .iter()
+            .map(|c| {
+                vec![
+                    c.category.clone(),
+                    c.count.to_string(),
+                    formatter.format(&c.total),
+                ]
+            })
+            .collect::<Vec<_>>();
+        if !self.uncategorized.is_zero() {
+            categories.push(vec![
+                String::from("Uncategorized"),
+                String::new(),
+                formatter.format(&self.uncategorized),
+            ]);
+        }
+
+        let mut tables = Vec::new();
+        if !self.budget_alerts.is_empty() {
+            tables.push(Table {
+                title: String::from("Budget alerts"),
+                headers: ["Category", "Budget", "Spent", "Projected", "Status"]
+                    .map(String::from)
+                    .to_vec(),
+                numeric: vec![false, true, true, true, false],
+                rows: self
+                    .budget_alerts
+                    .iter()
+                    .map(|row| {
+                        let status = match row.status {
+                            BurnStatus::OnTrack => "on track",
+                            BurnStatus::AheadOfPace => "ahead of pace",
+                            BurnStatus::OverBudget => "over budget",
+                        };
+                        vec![
+                            row.category.clone(),
+                            formatter.format(&row.budget),
+                            formatter.format(&row.spent),
+                            formatter.format(&row.projected),
+                            status.to_string(),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        if !self.anomalies.is_empty() {
+            let mut headers = transaction_headers.clone();
+            headers.push(String::from("Typical"));
+            tables.push(Table {
+                title: String::from("Unusual transactions"),
+                headers,
+                numeric: vec![false, false, false, false, true, true],
+                rows: self
+                    .anomalies
+                    .iter()
+                    .map(|a| {
+                        let mut row = transaction_row(&a.transaction);
+                        row.push(formatter.format(&a.typical));
+                        row
+                    })
+                    .collect(),
+            });
+        }
+        tables.push(Table {
+            title: String::from("Category totals"),
+            headers: ["Category", "Transactions", "Total"]
+                .map(String::from)
+                .to_vec(),
+            numeric: vec![false, true, true],
+            rows: categories,
+        });
+        tables.push(Table {
+            title: String::from("Transactions"),
+            headers: transaction_headers,
+            numeric: vec![false, false, false, false, true],
+            rows: self.transactions.iter().map(transaction_row).collect(),
+        });
+        tables
+    }
+}
+
+fn statement_transaction(assignment: &Assignment) -> StatementTransaction {
+    StatementTransaction {
+        date: assignment.transaction.time.date_naive(),
+        description: assignment.transaction.description.clone(),
+        account: assignment.transaction.account.clone(),
+        amount: assignment.transaction.amount.clone(),
+        category: assignment.category_path_joined(":"),
+    }
+}
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, io::Write};
+
+use crate::{
+    categorizer::{
+        Categorized, CategorizedChildren, CategorizedList, Categorizer, Category, CategoryChildren,
+    },
+    report::Error,
+    util::Formatter,
+};
+
+/// Write the category hierarchy of the categorizer as a Graphviz DOT digraph
+///
+/// Every category is a node labeled with its name, budget, and number of transaction matchers. When
+/// `categorized` is given the nodes are also labeled with the total and transaction count of the
+/// category in that run.
+pub fn dot<W: Write>(
+    categorizer: &Categorizer,
+    categorized: Option<&CategorizedList>,
+    formatter: &Formatter,
+    mut writer: W,
+) -> Result<(), Error> {
+    let mut totals = HashMap::new();
+    if let Some(categorized) = categorized {
+        for categorized in &categorized.categorized {
+            collect_totals(categorized, &mut Vec::new(), &mut totals);
+        }
+    }
+
+    writeln!(writer, "digraph categories {{")?;
+    writeln!(writer, "  rankdir=LR;")?;
+    writeln!(writer, "  node [shape=box];")?;
+    for category in &categorizer.categories {
+        write_category(
+            category,
+            None,
+            &mut Vec::new(),
+            &totals,
+            formatter,
+            &mut writer,
+        )?;
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// Record every categorized keyed by its colon separated path
+fn collect_totals<'a>(
+    categorized: &'a Categorized,
+    path: &mut Vec<&'a str>,
+    totals: &mut HashMap<String, &'a Categorized>,
+) {
+    path.push(&categorized.category);
+    totals.insert(path.join(":"), categorized);
+    if let CategorizedChildren::Subcategories(subcategories) = &categorized.children {
+        for subcategory in subcategories {
+            collect_totals(subcategory, path, totals);
+        }
+    }
+    path.pop();
+}
+
+fn write_category<'a, W: Write>(
+    category: &'a Category,
+    parent: Option<&str>,
+    path: &mut Vec<&'a str>,
+    totals: &HashMap<String, &Categorized>,
+    formatter: &Formatter,
+    writer: &mut W,
+) -> Result<(), Error> {
+    path.push(&category.category);
+    let id = path.join(":");
+
+    let mut label = vec![category.category.clone()];
+    if let Some(categorized) = totals.get(&id) {
+        let plural = if categorized.count == 1 { "" } else { "s" };
+        label.push(format!(
+            "{} ({} transaction{plural})",
+            formatter.format(&categorized.total),
+            categorized.count
+        ));
+    }
+    if let Some(budget) = &category.budget {
+        label.push(format!("budget {}", formatter.format(budget)));
+    }
+    if let CategoryChildren::TransactionMatchers(matchers) = &category.children {
+        let plural = if matchers.len() == 1 { "" } else { "s" };
+        label.push(format!("{} matcher{plural}", matchers.len()));
+    }
+    let label = label.iter().map(|l| escape(l)).collect::<Vec<_>>();
+    writeln!(
+        writer,
+        "  \"{}\" [label=\"{}\"];",
+        escape(&id),
+        label.join("\\n")
+    )?;
+    if let Some(parent) = parent {
+        writeln!(writer, "  \"{}\" -> \"{}\";", escape(parent), escape(&id))?;
+    }
+
+    if let CategoryChildren::Subcategories(subcategories) = &category.children {
+        for subcategory in subcategories {
+            write_category(subcategory, Some(&id), path, totals, formatter, writer)?;
+        }
+    }
+    path.pop();
+    Ok(())
+}
+
+/// Escape a value for use in a DOT quoted string
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
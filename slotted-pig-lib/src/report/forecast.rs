@@ -0,0 +1,269 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::Write,
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Assignment,
+    report::{
+        table::{write_markdown, write_text, Table},
+        Bucket, Error, Recurring,
+    },
+    util::{normalize_payee, Formatter},
+};
+
+/// Output formats for a cashflow forecast
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ForecastFormat {
+    /// Plain text tables
+    #[default]
+    Text,
+    /// Markdown tables
+    Markdown,
+    /// JSON months, category averages, and recurring transactions
+    Json,
+}
+
+/// Expected cashflow of the coming months from recurring transactions plus the average monthly
+/// total of every category over the recent history
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Forecast {
+    /// Last day of the history the forecast is based on
+    pub as_of: NaiveDate,
+    /// Labels of the whole months the averages are taken over
+    pub history: Vec<String>,
+    /// Expected cashflow of each month
+    pub months: Vec<ForecastMonth>,
+    /// Average monthly total of each category, excluding recurring transactions
+    pub averages: Vec<CategoryAverage>,
+    /// Recurring transactions which have not stopped, projected on their cadence
+    pub recurring: Vec<Recurring>,
+}
+
+/// Expected cashflow of a single month
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForecastMonth {
+    /// Label of the month (ie `2024-03`)
+    pub month: String,
+    /// Sum of the expected positive amounts
+    pub income: BigDecimal,
+    /// Sum of the expected negative amounts
+    pub expenses: BigDecimal,
+    /// Income plus expenses
+    pub net: BigDecimal,
+}
+
+/// Average monthly total of a category
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryAverage {
+    /// Colon separated category path, `None` for uncategorized transactions
+    pub category: Option<String>,
+    /// Total over the history divided by the number of history months
+    pub monthly_average: BigDecimal,
+}
+
+impl Forecast {
+    /// Forecast the `months` months after the month containing `as_of`
+    ///
+    /// Averages are taken over the `history` whole months up to `as_of`, a partial month containing
+    /// `as_of` is left out. Transactions of recurring payees are left out of the averages and the
+    /// recurring transactions are projected instead, starting from their next date after `as_of`.
+    /// Recurring transactions which missed two occurrences by `as_of` are assumed to have stopped
+    /// and are not projected.
+    pub fn new(
+        assignments: &[Assignment],
+        recurring: Vec<Recurring>,
+        as_of: NaiveDate,
+        months: u32,
+        history: u32,
+    ) -> Self {
+        let history = history.max(1);
+        let forecast_start = Bucket::Month.next(Bucket::Month.start(as_of));
+        let history_end = if as_of + Days::new(1) == forecast_start {
+            forecast_start
+        } else {
+            Bucket::Month.start(as_of)
+        };
+        let history_start = history_end - Months::new(history);
+
+        let recurring_payees = recurring
+            .iter()
+            .map(|r| r.payee.as_str())
+            .collect::<HashSet<_>>();
+        let mut totals = BTreeMap::<Option<String>, BigDecimal>::new();
+        for assignment in assignments {
+            let date = assignment.transaction.time.date_naive();
+            if date < history_start
+                || date >= history_end
+                || recurring_payees
+                    .contains(normalize_payee(&assignment.transaction.description).as_str())
+            {
+                continue;
+            }
+            *totals
+                .entry(assignment.category_path_joined(":"))
+                .or_default() += &assignment.transaction.amount;
+        }
+        let averages = totals
+            .into_iter()
+            .map(|(category, total)| CategoryAverage {
+                category,
+                monthly_average: (total / BigDecimal::from(history)).round(2),
+            })
+            .collect::<Vec<_>>();
+
+        let recurring = recurring
+            .into_iter()
+            .filter(|r| r.cadence.next(r.next_date) >= as_of)
+            .collect::<Vec<_>>();
+        let mut start = forecast_start;
+        let months = (0..months)
+            .map(|_| {
+                let end = Bucket::Month.next(start);
+                let mut amounts = averages
+                    .iter()
+                    .map(|average| average.monthly_average.clone())
+                    .collect::<Vec<_>>();
+                for recurring in &recurring {
+                    let mut date = recurring.next_date;
+                    while date < end {
+                        if date >= start && date > as_of {
+                            amounts.push(recurring.average_amount.clone());
+                        }
+                        date = recurring.cadence.next(date);
+                    }
+                }
+                let income = amounts
+                    .iter()
+                    .filter(|a| *a > &BigDecimal::zero())
+                    .sum::<BigDecimal>();
+                let expenses = amounts
+                    .iter()
+                    .filter(|a| *a < &BigDecimal::zero())
+                    .sum::<BigDecimal>();
+                let month = ForecastMonth {
+                    month: Bucket::Month.label(start),
+                    net: &income + &expenses,
+                    income,
+                    expenses,
+                };
+                start = end;
+                month
+            })
+            .collect();
+
+        let mut history_labels = Vec::new();
+        let mut month = history_start;
+        while month < history_end {
+            history_labels.push(Bucket::Month.label(month));
+            month = Bucket::Month.next(month);
+        }
+
+        Self {
+            as_of,
+            history: history_labels,
+            months,
+            averages: averages
+                .into_iter()
+                .filter(|average| !average.monthly_average.is_zero())
+                .collect(),
+            recurring,
+        }
+    }
+
+    /// Write the forecast in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: ForecastFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let title = format!("Forecast as of {}", self.as_of);
+        let months = Table {
+            title: String::from("Months"),
+            headers: vec![
+                String::from("Month"),
+                String::from("Income"),
+                String::from("Expenses"),
+                String::from("Net"),
+            ],
+            numeric: vec![false, true, true, true],
+            rows: self
+                .months
+                .iter()
+                .map(|month| {
+                    vec![
+                        month.month.clone(),
+                        formatter.format(&month.income),
+                        formatter.format(&month.expenses),
+                        formatter.format(&month.net),
+                    ]
+                })
+                .collect(),
+        };
+        let history = match (self.history.first(), self.history.last()) {
+            (Some(first), Some(last)) => format!("{first} to {last}"),
+            _ => String::new(),
+        };
+        let averages = Table {
+            title: format!("Monthly averages from {history}"),
+            headers: vec![String::from("Category"), String::from("Average")],
+            numeric: vec![false, true],
+            rows: self
+                .averages
+                .iter()
+                .map(|average| {
+                    vec![
+                        average
+                            .category
+                            .clone()
+                            .unwrap_or_else(|| String::from("uncategorized")),
+                        formatter.format(&average.monthly_average),
+                    ]
+                })
+                .collect(),
+        };
+        let recurring = Table {
+            title: String::from("Recurring"),
+            headers: vec![
+                String::from("Payee"),
+                String::from("Cadence"),
+                String::from("Amount"),
+                String::from("Next"),
+            ],
+            numeric: vec![false, false, true, false],
+            rows: self
+                .recurring
+                .iter()
+                .map(|recurring| {
+                    vec![
+                        recurring.payee.clone(),
+                        format!("{:?}", recurring.cadence).to_lowercase(),
+                        formatter.format(&recurring.average_amount),
+                        formatter.format_date(recurring.next_date),
+                    ]
+                })
+                .collect(),
+        };
+        let tables = [months, averages, recurring];
+        match format {
+            ForecastFormat::Text => write_text(&title, &tables, writer),
+            ForecastFormat::Markdown => write_markdown(&title, &tables, writer),
+            ForecastFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::io::Write;
+
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+
+use crate::{
+    categorizer::{Categorized, CategorizedChildren, Categorizer},
+    report::{
+        table::{escape, write_html_table, Table},
+        Error, Period, Statement,
+    },
+    transaction::Transaction,
+    util::Formatter,
+};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.6em; }
+.numeric { text-align: right; }
+.bar { background: #eee; width: 20em; height: 1em; }
+.bar div { background: #4a90d9; height: 100%; }
+.bar div.over { background: #d9534f; }
+.tree summary { cursor: pointer; }
+.tree ul { list-style: none; padding-left: 1.5em; }
+.tree .total { float: right; margin-left: 2em; }
+figure { margin: 1em 0; }";
+
+/// Chart image embedded in an HTML report
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HtmlChart {
+    /// Caption shown below the chart
+    pub title: String,
+    /// Complete SVG document of the chart
+    pub svg: String,
+}
+
+/// Self-contained HTML document of a period with the statement, spending by category bars,
+/// budget progress, and the category tree down to the transactions
+///
+/// Everything is inlined, so the single file can be archived and opened without the
+/// transactions or configs.
+#[derive(Clone, Debug)]
+pub struct HtmlReport {
+    /// Statement of the period compared to the prior period
+    pub statement: Statement,
+    /// Categorized transactions of the period
+    pub categorized: Vec<Categorized>,
+    /// Transactions of the period no category matched
+    pub uncategorized: Vec<Transaction>,
+    /// Charts embedded after the spending bars
+    pub charts: Vec<HtmlChart>,
+}
+
+impl HtmlReport {
+    /// Create a report of the current range of the period
+    ///
+    /// Top level categories named in `income_categories` are income and all other transactions
+    /// are expenses. At most `biggest` transactions are listed in the statement.
+    pub fn new(
+        categorizer: &Categorizer,
+        transactions: &[Transaction],
+        period: &Period,
+        income_categories: &[&str],
+        biggest: usize,
+    ) -> Self {
+        let assignments = categorizer.assign(transactions);
+        let statement = Statement::for_period(
+            categorizer,
+            &assignments,
+            period,
+            income_categories,
+            biggest,
+        );
+        let transactions = transactions
+            .iter()
+            .filter(|t| period.current.contains(t.time.date_naive()))
+            .cloned()
+            .collect::<Vec<_>>();
+        let (categorized, uncategorized) = categorizer.categorize(&transactions);
+        Self {
+            statement,
+            categorized: categorized.categorized,
+            uncategorized: uncategorized.into_iter().cloned().collect(),
+            charts: Vec::new(),
+        }
+    }
+
+    /// Write the report as an HTML document with amounts formatted by the formatter
+    pub fn write<W: Write>(&self, formatter: &Formatter, mut writer: W) -> Result<(), Error> {
+        let title = format!("Report for {}", self.statement.period);
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, r#"<meta charset="utf-8">"#)?;
+        writeln!(writer, "<title>{}</title>", escape(&title))?;
+        writeln!(writer, "<style>\n{STYLE}\n</style>")?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<h1>{}</h1>", escape(&title))?;
+
+        for table in self.statement.comparison_tables(formatter) {
+            writeln!(writer, "<h2>{}</h2>", escape(&table.title))?;
+            write_html_table(&table, &mut writer)?;
+        }
+
+        self.write_spending(formatter, &mut writer)?;
+        for chart in &self.charts {
+            writeln!(writer, "<figure>")?;
+            writeln!(writer, "{}", chart.svg.trim())?;
+            writeln!(writer, "<figcaption>{}</figcaption>", escape(&chart.title))?;
+            writeln!(writer, "</figure>")?;
+        }
+        self.write_budgets(formatter, &mut writer)?;
+
+        writeln!(writer, "<h2>Categories</h2>")?;
+        writeln!(writer, r#"<ul class="tree">"#)?;
+        for categorized in &self.categorized {
+            write_category(categorized, formatter, &mut writer)?;
+        }
+        writeln!(writer, "</ul>")?;
+        if !self.uncategorized.is_empty() {
+            writeln!(writer, "<h2>Uncategorized</h2>")?;
+            write_html_table(
+                &transactions_table(&self.uncategorized, formatter),
+                &mut writer,
+            )?;
+        }
+
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")?;
+        Ok(())
+    }
+
+    /// Bars of the absolute total of every top level expense category
+    fn write_spending<W: Write>(&self, formatter: &Formatter, mut writer: W) -> Result<(), Error> {
+        let categories = &self.statement.categories;
+        if categories.is_empty() {
+            return Ok(());
+        }
+        let max = categories
+            .iter()
+            .map(|c| c.comparison.current.abs())
+            .max()
+            .unwrap_or_default();
+        writeln!(writer, "<h2>Spending by category</h2>")?;
+        writeln!(writer, "<table>")?;
+        for category in categories {
+            let total = &category.comparison.current;
+            writeln!(
+                writer,
+                r#"<tr><td>{}</td><td class="numeric">{}</td><td>{}</td></tr>"#,
+                escape(&category.category),
+                escape(&formatter.format(total)),
+                bar(&total.abs(), &max, false)
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+        Ok(())
+    }
+
+    /// Budgets table with a progress bar of the amount spent
+    fn write_budgets<W: Write>(&self, formatter: &Formatter, mut writer: W) -> Result<(), Error> {
+        let Some(table) = self.statement.budget_table(formatter) else {
+            return Ok(());
+        };
+        writeln!(writer, "<h2>{}</h2>", escape(&table.title))?;
+        writeln!(writer, "<table>")?;
+        let headers = table
+            .headers
+            .iter()
+            .zip(&table.numeric)
+            .map(|(header, numeric)| {
+                let class = if *numeric { r#" class="numeric""# } else { "" };
+                format!("<th{class}>{}</th>", escape(header))
+            })
+            .collect::<String>();
+        writeln!(writer, "<tr>{headers}<th>Progress</th></tr>")?;
+        for (row, budget) in table.rows.iter().zip(&self.statement.budgets) {
+            let cells = row
+                .iter()
+                .zip(&table.numeric)
+                .map(|(cell, numeric)| {
+                    let class = if *numeric { r#" class="numeric""# } else { "" };
+                    format!("<td{class}>{}</td>", escape(cell))
+                })
+                .collect::<String>();
+            writeln!(
+                writer,
+                "<tr>{cells}<td>{}</td></tr>",
+                bar(&budget.spent, &budget.budget, budget.is_over())
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+        Ok(())
+    }
+}
+
+/// CSS bar filled by the fraction of `amount` in `max`
+fn bar(amount: &BigDecimal, max: &BigDecimal, over: bool) -> String {
+    let percent = if max.is_zero() {
+        if amount > max {
+            100.0
+        } else {
+            0.0
+        }
+    } else {
+        (amount / max * BigDecimal::from(100))
+            .to_f64()
+            .unwrap_or_default()
+            .clamp(0.0, 100.0)
+    };
+    let class = if over { r#" class="over""# } else { "" };
+    format!(r#"<div class="bar"><div{class} style="width: {percent:.1}%"></div></div>"#)
+}
+
+fn write_category<W: Write>(
+    categorized: &Categorized,
+    formatter: &Formatter,
+    writer: &mut W,
+) -> Result<(), Error> {
+    writeln!(writer, "<li><details>")?;
+    writeln!(
+        writer,
+        r#"<summary>{} ({}) <span class="total">{}</span></summary>"#,
+        escape(&categorized.category),
+        categorized.count,
+        escape(&formatter.format(&categorized.total))
+    )?;
+    match &categorized.children {
+        CategorizedChildren::Subcategories(subcategories) => {
+            writeln!(writer, "<ul>")?;
+            for subcategory in subcategories {
+                write_category(subcategory, formatter, writer)?;
+            }
+            writeln!(writer, "</ul>")?;
+        }
+        CategorizedChildren::Transactions(transactions) => {
+            write_html_table(&transactions_table(transactions, formatter), &mut *writer)?;
+        }
+    }
+    writeln!(writer, "</details></li>")?;
+    Ok(())
+}
+
+fn transactions_table(transactions: &[Transaction], formatter: &Formatter) -> Table {
+    Table {
+        title: String::from("Transactions"),
+        headers: ["Date", "Description", "Account", "Amount"]
+            .map(String::from)
+            .to_vec(),
+        numeric: vec![false, false, false, true],
+        rows: transactions
+            .iter()
+            .map(|t| {
+                vec![
+                    formatter.format_date(t.time.date_naive()),
+                    t.description.clone(),
+                    t.account.clone(),
+                    formatter.format(&t.amount),
+                ]
+            })
+            .collect(),
+    }
+}
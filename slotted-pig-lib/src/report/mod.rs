@@ -0,0 +1,63 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+pub use balances::*;
+pub use bar_chart::*;
+pub use burndown::*;
+pub use changes::*;
+pub use digest::*;
+pub use dot::*;
+pub use forecast::*;
+pub use html_report::*;
+pub use net_worth::*;
+pub use period::*;
+pub use pivot::*;
+pub use recurring::*;
+pub use rules::*;
+pub use sankey::*;
+pub use statement::*;
+pub use stats::*;
+pub use tax_years::*;
+pub use top::*;
+pub use totals::*;
+pub use transaction_table::*;
+pub use uncategorized::*;
+
+mod balances;
+mod bar_chart;
+mod burndown;
+mod changes;
+mod digest;
+mod dot;
+mod forecast;
+mod html_report;
+mod net_worth;
+mod period;
+mod pivot;
+mod recurring;
+mod rules;
+mod sankey;
+mod statement;
+mod stats;
+mod table;
+mod tax_years;
+#[cfg(test)]
+mod tests;
+mod top;
+mod totals;
+mod transaction_table;
+mod uncategorized;
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] std::io::Error),
+    /// csv
+    Csv(#[from] csv::Error),
+    /// serde_json
+    SerdeJson(#[from] serde_json::Error),
+    /// serde_yaml
+    SerdeYaml(#[from] serde_yaml::Error),
+    /// invalid period `{0}`, expected a month (ie `2024-03`), quarter (ie `2024-Q1`), year, `this-month`, `last-month`, `this-quarter`, `last-quarter`, `this-year`, `last-year`, `mtd`, `qtd`, `ytd`, or `last-N-days`
+    InvalidPeriod(String),
+}
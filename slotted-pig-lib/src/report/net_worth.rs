@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Cursor, Read, Write},
+    path::Path,
+};
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, FromInto};
+use strum::EnumString;
+
+use crate::{
+    report::{Bucket, Error, Pivot, PivotRow},
+    transaction::Transaction,
+    util::BigDecimalSerde,
+};
+
+/// Balance of each account before its first parsed transaction
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StartingBalances {
+    /// Starting balance keyed by account name, accounts without one start at zero
+    #[serde_as(as = "BTreeMap<_, FromInto<BigDecimalSerde>>")]
+    #[serde(default)]
+    pub accounts: BTreeMap<String, BigDecimal>,
+}
+
+impl StartingBalances {
+    /// Create new starting balances from a yaml file
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Create new starting balances from a yaml buffer
+    pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
+        Self::from_reader(Cursor::new(buffer))
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let reader = BufReader::new(reader);
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+}
+
+/// Output formats for net worth
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NetWorthFormat {
+    /// CSV with a row per time period and a column per account followed by the total
+    #[default]
+    Csv,
+    /// JSON periods, accounts, and totals
+    Json,
+}
+
+/// Balance of every account and their total at the end of each time period
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetWorth {
+    /// Labels of the time periods
+    pub periods: Vec<String>,
+    /// Balances of each account sorted by account name
+    pub accounts: Vec<AccountBalances>,
+    /// Sum of the account balances for every time period
+    pub totals: Vec<BigDecimal>,
+}
+
+/// Balances of an account at the end of each time period of `NetWorth`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountBalances {
+    /// Account name
+    pub account: String,
+    /// Balance for each time period
+    pub balances: Vec<BigDecimal>,
+}
+
+impl NetWorth {
+    /// Accumulate transactions onto the starting balances
+    ///
+    /// Time periods are consecutive from the earliest to the latest transaction. Accounts with a
+    /// starting balance but no transactions hold that balance in every period.
+    pub fn new(
+        transactions: &[Transaction],
+        starting_balances: &StartingBalances,
+        bucket: Bucket,
+    ) -> Self {
+        let mut changes = BTreeMap::<&str, BTreeMap<NaiveDate, BigDecimal>>::new();
+        for account in starting_balances.accounts.keys() {
+            changes.entry(account).or_default();
+        }
+        for transaction in transactions {
+            let start = bucket.start(transaction.time.date_naive());
+            *changes
+                .entry(&transaction.account)
+                .or_default()
+                .entry(start)
+                .or_default() += &transaction.amount;
+        }
+
+        let first = transactions
+            .iter()
+            .map(|t| bucket.start(t.time.date_naive()))
+            .min();
+        let last = transactions
+            .iter()
+            .map(|t| bucket.start(t.time.date_naive()))
+            .max();
+        let mut starts = Vec::new();
+        if let (Some(first), Some(last)) = (first, last) {
+            let mut start = first;
+            while start <= last {
+                starts.push(start);
+                start = bucket.next(start);
+            }
+        }
+
+        let accounts = changes
+            .into_iter()
+            .map(|(account, changes)| {
+                let mut balance = starting_balances
+                    .accounts
+                    .get(account)
+                    .cloned()
+                    .unwrap_or_default();
+                let balances = starts
+                    .iter()
+                    .map(|start| {
+                        if let Some(change) = changes.get(start) {
+                            balance += change;
+                        }
+                        balance.clone()
+                    })
+                    .collect();
+                AccountBalances {
+                    account: account.to_string(),
+                    balances,
+                }
+            })
+            .collect::<Vec<_>>();
+        let totals = (0..starts.len())
+            .map(|i| accounts.iter().map(|a| &a.balances[i]).sum())
+            .collect();
+
+        Self {
+            periods: starts.iter().map(|&start| bucket.label(start)).collect(),
+            accounts,
+            totals,
+        }
+    }
+
+    /// Write the net worth in the given format
+    pub fn write<W: Write>(&self, format: NetWorthFormat, writer: W) -> Result<(), Error> {
+        match format {
+            NetWorthFormat::Csv => self.write_csv(writer),
+            NetWorthFormat::Json => Ok(serde_json::to_writer_pretty(writer, self)?),
+        }
+    }
+
+    /// Write the net worth as CSV with a `period` column followed by a column per account and a
+    /// `total` column
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(
+            std::iter::once("period")
+                .chain(self.accounts.iter().map(|a| a.account.as_str()))
+                .chain(std::iter::once("total")),
+        )?;
+        for (i, period) in self.periods.iter().enumerate() {
+            writer.write_record(
+                std::iter::once(period.clone())
+                    .chain(self.accounts.iter().map(|a| a.balances[i].to_string()))
+                    .chain(std::iter::once(self.totals[i].to_string())),
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Convert to a pivot with a row per account and a `total` row (ie for charting)
+    pub fn to_pivot(&self) -> Pivot {
+        let rows = self
+            .accounts
+            .iter()
+            .map(|a| PivotRow {
+                category: a.account.clone(),
+                totals: a.balances.clone(),
+            })
+            .chain(std::iter::once(PivotRow {
+                category: String::from("total"),
+                totals: self.totals.clone(),
+            }))
+            .collect();
+        Pivot {
+            periods: self.periods.clone(),
+            rows,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::report::{Bucket, Error};
+
+/// Days a report covers and the days it is compared against
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Period {
+    /// Days the report covers
+    pub current: DateRange,
+    /// Equivalent days before the current range (ie the prior month or the same days last year)
+    pub previous: DateRange,
+}
+
+/// Inclusive range of days
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DateRange {
+    /// Label of the range (ie `2024-03` or `2024 through 2024-03-15`)
+    pub label: String,
+    /// First day
+    pub start: NaiveDate,
+    /// Last day
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// Check if the date is within the range
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+impl Period {
+    /// Whole bucket containing the date compared to the bucket before it
+    ///
+    /// # Panics
+    ///
+    /// Panics if either bucket is outside of the dates chrono supports, see
+    /// `Period::checked_bucket`.
+    pub fn bucket(bucket: Bucket, date: NaiveDate) -> Self {
+        Self::checked_bucket(bucket, date).expect("bucket is within the supported dates")
+    }
+
+    /// Whole bucket containing the date compared to the bucket before it, `None` if either bucket
+    /// is outside of the dates chrono supports
+    pub fn checked_bucket(bucket: Bucket, date: NaiveDate) -> Option<Self> {
+        let start = bucket.start(date);
+        let previous_start = bucket.start(start.pred_opt()?);
+        let range = |start: NaiveDate| {
+            Some(DateRange {
+                label: bucket.label(start),
+                start,
+                end: start
+                    .checked_add_months(Months::new(bucket.months()))?
+                    .pred_opt()?,
+            })
+        };
+        Some(Self {
+            current: range(start)?,
+            previous: range(previous_start)?,
+        })
+    }
+
+    /// Start of the bucket containing the date through the date, compared to the same days of the
+    /// bucket before it
+    ///
+    /// The previous range is cut short at the end of its bucket (ie March 31st month to date is
+    /// compared to all of February). `None` if the previous range is outside of the dates chrono
+    /// supports.
+    pub fn to_date(bucket: Bucket, date: NaiveDate) -> Option<Self> {
+        let start = bucket.start(date);
+        let before_start = start.pred_opt()?;
+        let previous_start = bucket.start(before_start);
+        let previous_end = date
+            .checked_sub_months(Months::new(bucket.months()))?
+            .min(before_start);
+        let range = |start: NaiveDate, end: NaiveDate| DateRange {
+            label: format!("{} through {end}", bucket.label(start)),
+            start,
+            end,
+        };
+        Some(Self {
+            current: range(start, date),
+            previous: range(previous_start, previous_end),
+        })
+    }
+
+    /// Number of days ending on the date compared to the same number of days before them, `None`
+    /// if the days are outside of the dates chrono supports
+    pub fn days(days: u64, end: NaiveDate) -> Option<Self> {
+        let days = Days::new(days.max(1) - 1);
+        let start = end.checked_sub_days(days)?;
+        let previous_end = start.pred_opt()?;
+        let range = |start: NaiveDate, end: NaiveDate| DateRange {
+            label: format!("{start} to {end}"),
+            start,
+            end,
+        };
+        Some(Self {
+            current: range(start, end),
+            previous: range(previous_end.checked_sub_days(days)?, previous_end),
+        })
+    }
+
+    /// Parse a period relative to today
+    ///
+    /// Periods are a month (ie `2024-03`), quarter (ie `2024-Q1`), year (ie `2024`),
+    /// `this-month`, `last-month`, `this-quarter`, `last-quarter`, `this-year`, `last-year`,
+    /// `mtd`, `qtd`, `ytd`, or `last-N-days`.
+    pub fn parse(period: &str, today: NaiveDate) -> Result<Self, Error> {
+        let invalid = || Error::InvalidPeriod(period.to_string());
+        let last = |bucket: Bucket| {
+            let previous = bucket.start(today).pred_opt()?;
+            Self::checked_bucket(bucket, previous)
+        };
+        // Dates outside of what chrono supports (ie `last-999999999-days`) are invalid periods
+        let parsed = match period.to_lowercase().as_str() {
+            "this-month" => Self::checked_bucket(Bucket::Month, today),
+            "last-month" => last(Bucket::Month),
+            "this-quarter" => Self::checked_bucket(Bucket::Quarter, today),
+            "last-quarter" => last(Bucket::Quarter),
+            "this-year" => Self::checked_bucket(Bucket::Year, today),
+            "last-year" => last(Bucket::Year),
+            "mtd" => Self::to_date(Bucket::Month, today),
+            "qtd" => Self::to_date(Bucket::Quarter, today),
+            "ytd" => Self::to_date(Bucket::Year, today),
+            lower => {
+                if let Some(days) = lower
+                    .strip_prefix("last-")
+                    .and_then(|rest| rest.strip_suffix("-days"))
+                {
+                    let days = days.parse::<u64>().map_err(|_| invalid())?;
+                    if days == 0 {
+                        return Err(invalid());
+                    }
+                    Self::days(days, today)
+                } else if let Some((year, quarter)) = lower.split_once("-q") {
+                    let year = year.parse::<i32>().map_err(|_| invalid())?;
+                    let quarter = quarter.parse::<u32>().map_err(|_| invalid())?;
+                    if !(1..=4).contains(&quarter) {
+                        return Err(invalid());
+                    }
+                    let date =
+                        NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1).ok_or_else(invalid)?;
+                    Self::checked_bucket(Bucket::Quarter, date)
+                } else if let Ok(date) =
+                    NaiveDate::parse_from_str(&format!("{lower}-01"), "%Y-%m-%d")
+                {
+                    Self::checked_bucket(Bucket::Month, date)
+                } else {
+                    let year = lower.parse::<i32>().map_err(|_| invalid())?;
+                    let date = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid)?;
+                    Self::checked_bucket(Bucket::Year, date)
+                }
+            }
+        };
+        parsed.ok_or_else(invalid)
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::BTreeMap, io::Write};
+
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Assignment,
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    util::Formatter,
+};
+
+/// Length of the time periods transactions are grouped into
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Bucket {
+    /// Calendar month
+    #[default]
+    Month,
+    /// Calendar quarter
+    Quarter,
+    /// Calendar year
+    Year,
+}
+
+impl Bucket {
+    /// First day of the period containing the date
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        let month = match self {
+            Self::Month => date.month(),
+            Self::Quarter => (date.month0() / 3) * 3 + 1,
+            Self::Year => 1,
+        };
+        NaiveDate::from_ymd_opt(date.year(), month, 1).expect("first of month is a valid date")
+    }
+
+    /// Number of months in the period
+    pub fn months(&self) -> u32 {
+        match self {
+            Self::Month => 1,
+            Self::Quarter => 3,
+            Self::Year => 12,
+        }
+    }
+
+    /// First day of the period following the period starting on `start`
+    pub fn next(&self, start: NaiveDate) -> NaiveDate {
+        let month0 = start.month0() + self.months();
+        NaiveDate::from_ymd_opt(start.year() + (month0 / 12) as i32, month0 % 12 + 1, 1)
+            .expect("first of month is a valid date")
+    }
+
+    /// Label for the period starting on `start` (ie `2024-03`, `2024-Q1`, or `2024`)
+    pub fn label(&self, start: NaiveDate) -> String {
+        match self {
+            Self::Month => start.format("%Y-%m").to_string(),
+            Self::Quarter => format!("{}-Q{}", start.year(), start.month0() / 3 + 1),
+            Self::Year => start.year().to_string(),
+        }
+    }
+}
+
+/// Output formats for a pivot
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PivotFormat {
+    /// CSV with a row per category and a column per time period
+    #[default]
+    Csv,
+    /// JSON periods and rows
+    Json,
+    /// Plain text table with a total row
+    Text,
+    /// Markdown table with a total row
+    Markdown,
+}
+
+/// Category totals for each time period
+///
+/// Rows are category paths and columns are consecutive time periods from the earliest to the latest
+/// categorized transaction. Uncategorized transactions are not included.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pivot {
+    /// Labels of the time periods
+    pub periods: Vec<String>,
+    /// Totals of each category for every time period
+    pub rows: Vec<PivotRow>,
+}
+
+/// Totals of a category for every time period of a `Pivot`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PivotRow {
+    /// Colon separated category path
+    pub category: String,
+    /// Total for each time period
+    pub totals: Vec<BigDecimal>,
+}
+
+impl Pivot {
+    /// Group the assigned transactions by time period and category
+    ///
+    /// Category paths deeper than `depth` are truncated so their totals roll up into the ancestor
+    /// at `depth`. Rows are sorted by category path.
+    pub fn new(assignments: &[Assignment], bucket: Bucket, depth: Option<usize>) -> Self {
+        let mut totals = BTreeMap::<String, BTreeMap<NaiveDate, BigDecimal>>::new();
+        for assignment in assignments {
+            let Some(path) = &assignment.category_path else {
+                continue;
+            };
+            let path = match depth {
+                Some(depth) => &path[..depth.min(path.len())],
+                None => &path[..],
+            };
+            let start = bucket.start(assignment.transaction.time.date_naive());
+            *totals
+                .entry(path.join(":"))
+                .or_default()
+                .entry(start)
+                .or_default() += &assignment.transaction.amount;
+        }
+
+        let first = totals.values().filter_map(|t| t.keys().next()).min();
+        let last = totals.values().filter_map(|t| t.keys().next_back()).max();
+        let mut starts = Vec::new();
+        if let (Some(&first), Some(&last)) = (first, last) {
+            let mut start = first;
+            while start <= last {
+                starts.push(start);
+                start = bucket.next(start);
+            }
+        }
+
+        let periods = starts.iter().map(|&start| bucket.label(start)).collect();
+        let rows = totals
+            .into_iter()
+            .map(|(category, mut totals)| PivotRow {
+                category,
+                totals: starts
+                    .iter()
+                    .map(|start| totals.remove(start).unwrap_or_default())
+                    .collect(),
+            })
+            .collect();
+        Self { periods, rows }
+    }
+
+    /// Keep only the rows within the top level categories
+    pub fn retain_top_level(&mut self, categories: &[String]) {
+        self.rows.retain(|row| {
+            let top_level = row.category.split(':').next().unwrap_or_default();
+            categories.iter().any(|category| category == top_level)
+        });
+    }
+
+    /// Write the pivot in the given format, the formatter only applies to table formats
+    pub fn write<W: Write>(
+        &self,
+        format: PivotFormat,
+        formatter: &Formatter,
+        writer: W,
+    ) -> Result<(), Error> {
+        match format {
+            PivotFormat::Csv => self.write_csv(writer),
+            PivotFormat::Json => self.write_json(writer),
+            PivotFormat::Text => write_text(&self.title(), &[self.table(formatter)], writer),
+            PivotFormat::Markdown => {
+                write_markdown(&self.title(), &[self.table(formatter)], writer)
+            }
+        }
+    }
+
+    fn title(&self) -> String {
+        match (self.periods.first(), self.periods.last()) {
+            (Some(first), Some(last)) => format!("Category totals from {first} to {last}"),
+            _ => String::from("Category totals"),
+        }
+    }
+
+    fn table(&self, formatter: &Formatter) -> Table {
+        let mut totals = vec![BigDecimal::default(); self.periods.len()];
+        let mut rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                for (total, amount) in totals.iter_mut().zip(&row.totals) {
+                    *total += amount;
+                }
+                std::iter::once(row.category.clone())
+                    .chain(row.totals.iter().map(|amount| formatter.format(amount)))
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        rows.push(
+            std::iter::once(String::from("Total"))
+                .chain(totals.iter().map(|amount| formatter.format(amount)))
+                .collect(),
+        );
+        Table {
+            title: String::from("Totals"),
+            headers: std::iter::once(String::from("Category"))
+                .chain(self.periods.iter().cloned())
+                .collect(),
+            numeric: std::iter::once(false)
+                .chain(self.periods.iter().map(|_| true))
+                .collect(),
+            rows,
+        }
+    }
+
+    /// Write the pivot as CSV with a `category` column followed by a column per time period
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(
+            std::iter::once("category").chain(self.periods.iter().map(String::as_str)),
+        )?;
+        for row in &self.rows {
+            writer.write_record(
+                std::iter::once(row.category.clone())
+                    .chain(row.totals.iter().map(ToString::to_string)),
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the pivot as JSON
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<(), Error> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+}
@@ -0,0 +1,319 @@
+use std::{cmp::Reverse, collections::BTreeMap, io::Write};
+
+use bigdecimal::BigDecimal;
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    transaction::Transaction,
+    util::{normalize_payee, Formatter},
+};
+
+/// How often a recurring transaction occurs
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumString, Eq, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Cadence {
+    /// Every week
+    Weekly,
+    /// Every two weeks
+    Biweekly,
+    /// Every month
+    Monthly,
+    /// Every three months
+    Quarterly,
+    /// Every year
+    Yearly,
+}
+
+impl Cadence {
+    /// Number of occurrences in a year
+    pub fn per_year(&self) -> u32 {
+        match self {
+            Self::Weekly => 52,
+            Self::Biweekly => 26,
+            Self::Monthly => 12,
+            Self::Quarterly => 4,
+            Self::Yearly => 1,
+        }
+    }
+
+    /// Date of the occurrence following `date`
+    pub fn next(&self, date: NaiveDate) -> NaiveDate {
+        let next = match self {
+            Self::Weekly => date.checked_add_days(Days::new(7)),
+            Self::Biweekly => date.checked_add_days(Days::new(14)),
+            Self::Monthly => date.checked_add_months(Months::new(1)),
+            Self::Quarterly => date.checked_add_months(Months::new(3)),
+            Self::Yearly => date.checked_add_months(Months::new(12)),
+        };
+        next.unwrap_or(NaiveDate::MAX)
+    }
+
+    /// Cadence with an interval range containing the number of days
+    fn from_days(days: i64) -> Option<Self> {
+        match days {
+            6..=8 => Some(Self::Weekly),
+            13..=15 => Some(Self::Biweekly),
+            26..=35 => Some(Self::Monthly),
+            85..=96 => Some(Self::Quarterly),
+            355..=375 => Some(Self::Yearly),
+            _ => None,
+        }
+    }
+}
+
+/// Transactions detected to recur on a regular cadence
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Recurring {
+    /// Normalized payee of the transactions
+    pub payee: String,
+    /// Account of the most recent transaction
+    pub account: String,
+    /// How often the transactions occur
+    pub cadence: Cadence,
+    /// Number of occurrences
+    pub count: u64,
+    /// Amount of the most recent transaction
+    pub last_amount: BigDecimal,
+    /// Average amount of all occurrences
+    pub average_amount: BigDecimal,
+    /// Average amount over a year
+    pub annualized_amount: BigDecimal,
+    /// Date of the most recent transaction
+    pub last_date: NaiveDate,
+    /// Expected date of the next transaction
+    pub next_date: NaiveDate,
+}
+
+/// Detect transactions that recur on a regular cadence (ie subscriptions and bills)
+///
+/// Transactions are grouped by normalized payee. A group is recurring when the median number of
+/// days between transactions matches a cadence and nearly all intervals match the same cadence.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecurringDetector {
+    /// Minimum number of transactions for a payee to be considered recurring
+    #[serde(default = "RecurringDetector::default_min_occurrences")]
+    pub min_occurrences: usize,
+    /// Fraction of the intervals between transactions that must match the cadence
+    #[serde(default = "RecurringDetector::default_min_matching_intervals")]
+    pub min_matching_intervals: f64,
+}
+
+impl RecurringDetector {
+    /// Detect the recurring transactions sorted by payee
+    pub fn detect<'a>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) -> Vec<Recurring> {
+        let mut by_payee = BTreeMap::<_, Vec<_>>::new();
+        for transaction in transactions {
+            let payee = normalize_payee(&transaction.description);
+            if !payee.is_empty() {
+                by_payee.entry(payee).or_default().push(transaction);
+            }
+        }
+        by_payee
+            .into_iter()
+            .filter_map(|(payee, transactions)| self.detect_payee(payee, transactions))
+            .collect()
+    }
+
+    fn detect_payee(
+        &self,
+        payee: String,
+        mut transactions: Vec<&Transaction>,
+    ) -> Option<Recurring> {
+        if transactions.len() < self.min_occurrences.max(2) {
+            return None;
+        }
+        transactions.sort_by_key(|t| t.time);
+
+        let mut intervals = transactions
+            .windows(2)
+            .map(|w| (w[1].time.date_naive() - w[0].time.date_naive()).num_days())
+            .collect::<Vec<_>>();
+        intervals.sort_unstable();
+        let cadence = Cadence::from_days(intervals[intervals.len() / 2])?;
+        let matching = intervals
+            .iter()
+            .filter(|days| Cadence::from_days(**days) == Some(cadence))
+            .count();
+        if (matching as f64) < self.min_matching_intervals * intervals.len() as f64 {
+            return None;
+        }
+
+        let last = transactions.last()?;
+        let count = transactions.len() as u64;
+        let total = transactions.iter().map(|t| &t.amount).sum::<BigDecimal>();
+        let average_amount = (total / BigDecimal::from(count)).round(2);
+        let annualized_amount = &average_amount * BigDecimal::from(cadence.per_year());
+        let last_date = last.time.date_naive();
+        Some(Recurring {
+            payee,
+            account: last.account.clone(),
+            cadence,
+            count,
+            last_amount: last.amount.clone(),
+            average_amount,
+            annualized_amount,
+            last_date,
+            next_date: cadence.next(last_date),
+        })
+    }
+
+    fn default_min_occurrences() -> usize {
+        3
+    }
+
+    fn default_min_matching_intervals() -> f64 {
+        0.75
+    }
+}
+
+impl Default for RecurringDetector {
+    fn default() -> Self {
+        Self {
+            min_occurrences: Self::default_min_occurrences(),
+            min_matching_intervals: Self::default_min_matching_intervals(),
+        }
+    }
+}
+
+/// Output formats for a list of recurring transactions
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RecurringFormat {
+    /// Plain text table with the total yearly cost
+    #[default]
+    Text,
+    /// Markdown table with the total yearly cost
+    Markdown,
+    /// CSV with a row per recurring transaction
+    Csv,
+    /// JSON recurring transactions
+    Json,
+}
+
+/// Sort possibilities for recurring transactions
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RecurringSort {
+    /// Sort by payee ascending
+    #[default]
+    Payee,
+    /// Sort by absolute annualized amount descending
+    YearlyCost,
+    /// Sort by expected date of the next transaction ascending
+    NextDate,
+}
+
+/// Detected recurring transactions (ie subscriptions and bills) in a sorted order
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecurringList {
+    /// Recurring transactions
+    pub recurring: Vec<Recurring>,
+}
+
+impl RecurringList {
+    /// Sort the recurring transactions, equal keys keep their detected order
+    pub fn new(mut recurring: Vec<Recurring>, sort: RecurringSort) -> Self {
+        match sort {
+            RecurringSort::Payee => recurring.sort_by(|r1, r2| r1.payee.cmp(&r2.payee)),
+            RecurringSort::YearlyCost => {
+                recurring.sort_by_key(|r| Reverse(r.annualized_amount.abs()))
+            }
+            RecurringSort::NextDate => recurring.sort_by_key(|r| r.next_date),
+        }
+        Self { recurring }
+    }
+
+    /// Write the recurring transactions in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: RecurringFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let table = || {
+            let total = self
+                .recurring
+                .iter()
+                .map(|r| &r.annualized_amount)
+                .sum::<BigDecimal>();
+            let mut rows = self
+                .recurring
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.payee.clone(),
+                        r.account.clone(),
+                        format!("{:?}", r.cadence).to_lowercase(),
+                        r.count.to_string(),
+                        formatter.format(&r.last_amount),
+                        formatter.format(&r.annualized_amount),
+                        formatter.format_date(r.last_date),
+                        formatter.format_date(r.next_date),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            rows.push(vec![
+                String::from("Total"),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                formatter.format(&total),
+                String::new(),
+                String::new(),
+            ]);
+            Table {
+                title: String::from("Payees"),
+                headers: [
+                    "Payee",
+                    "Account",
+                    "Cadence",
+                    "Count",
+                    "Last amount",
+                    "Yearly cost",
+                    "Last date",
+                    "Next date",
+                ]
+                .map(String::from)
+                .to_vec(),
+                numeric: vec![false, false, false, true, true, true, false, false],
+                rows,
+            }
+        };
+        let title = "Recurring transactions";
+        match format {
+            RecurringFormat::Text => write_text(title, &[table()], writer),
+            RecurringFormat::Markdown => write_markdown(title, &[table()], writer),
+            RecurringFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                for recurring in &self.recurring {
+                    writer.serialize(recurring)?;
+                }
+                writer.flush()?;
+                Ok(())
+            }
+            RecurringFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
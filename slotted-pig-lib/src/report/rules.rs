@@ -0,0 +1,190 @@
+use std::io::Write;
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Categorizer,
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    transaction::Transaction,
+    validate::Issue,
+};
+
+/// Output formats for a rules listing
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RulesFormat {
+    /// Plain text tables
+    #[default]
+    Text,
+    /// Markdown tables
+    Markdown,
+    /// JSON rules and problems
+    Json,
+}
+
+/// Every leaf category transaction matcher with how the transactions exercise it and problems
+/// found with it
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rules {
+    /// Number of transactions passing the transaction filters
+    pub transactions: u64,
+    /// Each transaction matcher in the order they are tried
+    pub rules: Vec<Rule>,
+    /// Problems found by validating the categorizer config
+    pub problems: Vec<String>,
+}
+
+/// A single leaf category transaction matcher
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Colon separated category path
+    pub category: String,
+    /// Transaction matcher rules
+    pub matcher: String,
+    /// Number of transactions assigned by the matcher
+    pub hits: u64,
+    /// Number of transactions the matcher matches, including those assigned by an earlier matcher
+    pub matches: u64,
+    /// Lint findings (ie never matched, shadowed by an earlier matcher, or overly broad)
+    pub findings: Vec<String>,
+}
+
+impl Rules {
+    /// List the rules of the categorizer exercised by the transactions
+    ///
+    /// A matcher matching more than `broad_percent` of the transactions is reported as overly
+    /// broad. `problems` are the issues of validating the categorizer config, if it was validated.
+    pub fn new(
+        categorizer: &Categorizer,
+        transactions: &[Transaction],
+        broad_percent: &BigDecimal,
+        problems: &[Issue],
+    ) -> Self {
+        let matchers = categorizer.leaf_matchers();
+        let mut hits = vec![0; matchers.len()];
+        let mut matches = vec![0; matchers.len()];
+        // Index of the earliest matcher which assigned transactions a shadowed matcher matches
+        let mut shadowed_by = vec![None; matchers.len()];
+        let mut filtered = 0;
+        for transaction in transactions.iter().filter(|t| categorizer.filter(t)) {
+            filtered += 1;
+            let mut assigned = None;
+            for (i, (_, matcher)) in matchers.iter().enumerate() {
+                if !matcher.matches(transaction) {
+                    continue;
+                }
+                matches[i] += 1;
+                match assigned {
+                    None => {
+                        hits[i] += 1;
+                        assigned = Some(i);
+                    }
+                    Some(assigned) => {
+                        shadowed_by[i] =
+                            Some(shadowed_by[i].map_or(assigned, |s: usize| s.min(assigned)));
+                    }
+                }
+            }
+        }
+
+        let category = |i: usize| matchers[i].0.join(":");
+        let rules = matchers
+            .iter()
+            .enumerate()
+            .map(|(i, (_, matcher))| {
+                let mut findings = Vec::new();
+                if matches[i] == 0 {
+                    findings.push(String::from("never matched a transaction"));
+                } else if hits[i] == 0 {
+                    if let Some(earlier) = shadowed_by[i] {
+                        findings.push(format!(
+                            "shadowed by the earlier `{}` matcher {}",
+                            category(earlier),
+                            matchers[earlier].1
+                        ));
+                    }
+                }
+                let percent =
+                    BigDecimal::from(matches[i] * 100) / BigDecimal::from(filtered.max(1));
+                if filtered > 0 && &percent > broad_percent {
+                    findings.push(format!(
+                        "matches {}% of transactions so it may be too broad",
+                        percent.round(1)
+                    ));
+                }
+                Rule {
+                    category: category(i),
+                    matcher: matcher.to_string(),
+                    hits: hits[i],
+                    matches: matches[i],
+                    findings,
+                }
+            })
+            .collect();
+
+        Self {
+            transactions: filtered,
+            rules,
+            problems: problems.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Check if any rule has findings or the config has problems
+    pub fn has_findings(&self) -> bool {
+        !self.problems.is_empty() || self.rules.iter().any(|rule| !rule.findings.is_empty())
+    }
+
+    /// Write the rules in the format
+    pub fn write<W: Write>(&self, format: RulesFormat, mut writer: W) -> Result<(), Error> {
+        let title = format!("Rules for {} transactions", self.transactions);
+        let mut tables = vec![Table {
+            title: String::from("Matchers"),
+            headers: ["Category", "Matcher", "Hits", "Matches", "Findings"]
+                .map(String::from)
+                .to_vec(),
+            numeric: vec![false, false, true, true, false],
+            rows: self
+                .rules
+                .iter()
+                .map(|rule| {
+                    vec![
+                        rule.category.clone(),
+                        rule.matcher.clone(),
+                        rule.hits.to_string(),
+                        rule.matches.to_string(),
+                        rule.findings.join("; "),
+                    ]
+                })
+                .collect(),
+        }];
+        if !self.problems.is_empty() {
+            tables.push(Table {
+                title: String::from("Config problems"),
+                headers: vec![String::from("Problem")],
+                numeric: vec![false],
+                rows: self
+                    .problems
+                    .iter()
+                    .map(|problem| vec![problem.clone()])
+                    .collect(),
+            });
+        }
+        match format {
+            RulesFormat::Text => write_text(&title, &tables, writer),
+            RulesFormat::Markdown => write_markdown(&title, &tables, writer),
+            RulesFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::Write;
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Categorized, CategorizedChildren, CategorizedList},
+    report::Error,
+};
+
+/// Output formats for Sankey diagram data
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SankeyFormat {
+    /// JSON nodes and links
+    #[default]
+    Json,
+    /// SankeyMATIC text input
+    Sankeymatic,
+}
+
+/// Flow of money for rendering a Sankey diagram
+///
+/// Income categories flow into a central budget node which flows out to every other top level
+/// category and then down through subcategories to the leaf categories. Any difference between
+/// income and expenses flows to a savings node or from a deficit node.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sankey {
+    /// Nodes of the diagram
+    pub nodes: Vec<SankeyNode>,
+    /// Links between nodes
+    pub links: Vec<SankeyLink>,
+}
+
+/// Node in a Sankey diagram
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SankeyNode {
+    /// Unique name of the node (ie the colon separated category path)
+    pub name: String,
+    /// Display label of the node (ie the category name)
+    pub label: String,
+}
+
+/// Link between two nodes in a Sankey diagram
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SankeyLink {
+    /// Index of the source node
+    pub source: usize,
+    /// Index of the target node
+    pub target: usize,
+    /// Absolute amount flowing from the source to the target
+    pub value: BigDecimal,
+}
+
+impl Sankey {
+    /// Name of the central node income flows into
+    pub const BUDGET: &'static str = "Budget";
+    /// Name of the node unspent income flows into
+    pub const SAVINGS: &'static str = "Savings";
+    /// Name of the node that covers expenses exceeding income
+    pub const DEFICIT: &'static str = "Deficit";
+
+    /// Create the flow of money from the categorized transactions
+    ///
+    /// `income_categories` are the names of the top level categories treated as income. Categories
+    /// with a total of zero are left out of the diagram.
+    pub fn new(categorized: &CategorizedList, income_categories: &[&str]) -> Self {
+        let mut sankey = Self::default();
+        let budget = sankey.add_node(Self::BUDGET.into(), Self::BUDGET.into());
+
+        let mut income = BigDecimal::zero();
+        let mut expenses = BigDecimal::zero();
+        for category in &categorized.categorized {
+            let value = category.total.abs();
+            if income_categories.contains(&category.category.as_str()) {
+                sankey.add_category(category, &[], budget, true);
+                income += value;
+            } else {
+                sankey.add_category(category, &[], budget, false);
+                expenses += value;
+            }
+        }
+
+        let difference = &income - &expenses;
+        if difference.is_positive() {
+            let savings = sankey.add_node(Self::SAVINGS.into(), Self::SAVINGS.into());
+            sankey.add_link(budget, savings, difference);
+        } else if difference.is_negative() {
+            let deficit = sankey.add_node(Self::DEFICIT.into(), Self::DEFICIT.into());
+            sankey.add_link(deficit, budget, difference.abs());
+        }
+        sankey
+    }
+
+    /// Write the flows in the given format
+    pub fn write<W: Write>(&self, format: SankeyFormat, writer: W) -> Result<(), Error> {
+        match format {
+            SankeyFormat::Json => self.write_json(writer),
+            SankeyFormat::Sankeymatic => self.write_sankeymatic(writer),
+        }
+    }
+
+    /// Write the flows as JSON nodes and links (ie for d3-sankey or plotly)
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<(), Error> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+
+    /// Write the flows in the SankeyMATIC text format (ie `Budget [100] Food`)
+    pub fn write_sankeymatic<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        for link in &self.links {
+            writeln!(
+                writer,
+                "{} [{}] {}",
+                self.nodes[link.source].name, link.value, self.nodes[link.target].name
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Add a category and its subcategories linked to the parent node
+    ///
+    /// Income categories flow from subcategories into their parent while all other categories
+    /// flow from the parent into subcategories.
+    fn add_category(
+        &mut self,
+        category: &Categorized,
+        parent_path: &[&str],
+        parent: usize,
+        income: bool,
+    ) {
+        let value = category.total.abs();
+        if value.is_zero() {
+            return;
+        }
+        let mut path = parent_path.to_vec();
+        path.push(&category.category);
+        let node = self.add_node(path.join(":"), category.category.clone());
+        if income {
+            self.add_link(node, parent, value);
+        } else {
+            self.add_link(parent, node, value);
+        }
+
+        if let CategorizedChildren::Subcategories(subcategories) = &category.children {
+            for subcategory in subcategories {
+                self.add_category(subcategory, &path, node, income);
+            }
+        }
+    }
+
+    fn add_node(&mut self, name: String, label: String) -> usize {
+        self.nodes.push(SankeyNode { name, label });
+        self.nodes.len() - 1
+    }
+
+    fn add_link(&mut self, source: usize, target: usize, value: BigDecimal) {
+        self.links.push(SankeyLink {
+            source,
+            target,
+            value,
+        });
+    }
+}
@@ -0,0 +1,386 @@
+use std::{collections::BTreeMap, io::Write};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Assignment, Categorizer},
+    report::{
+        table::{write_html, write_markdown, write_text, Table},
+        Bucket, DateRange, Error, Period,
+    },
+    util::Formatter,
+};
+
+/// Output formats for a statement
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatementFormat {
+    /// Plain text with aligned columns
+    #[default]
+    Text,
+    /// Markdown tables
+    Markdown,
+    /// HTML document with tables
+    Html,
+}
+
+/// Summary of a period compared to the prior period
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Statement {
+    /// Label of the period (ie `2024-02`)
+    pub period: String,
+    /// Label of the prior period
+    pub previous_period: String,
+    /// Total of the income categories
+    pub income: Comparison,
+    /// Total of every other category including uncategorized transactions
+    pub expenses: Comparison,
+    /// Sum of income and expenses
+    pub net: Comparison,
+    /// Totals of every top level category that is not income
+    pub categories: Vec<CategoryComparison>,
+    /// Uncategorized transactions total
+    pub uncategorized: Comparison,
+    /// Largest transactions by absolute amount
+    pub biggest_transactions: Vec<StatementTransaction>,
+    /// Spending compared to the budget of every category with a budget
+    pub budgets: Vec<BudgetStatus>,
+}
+
+/// Total of the period and prior period
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Comparison {
+    /// Total of the period
+    pub current: BigDecimal,
+    /// Total of the prior period
+    pub previous: BigDecimal,
+}
+
+impl Comparison {
+    /// Difference from the prior period to the period
+    pub fn change(&self) -> BigDecimal {
+        &self.current - &self.previous
+    }
+}
+
+/// Top level category total of the period and prior period
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryComparison {
+    /// Category name
+    pub category: String,
+    /// Category totals
+    #[serde(flatten)]
+    pub comparison: Comparison,
+}
+
+/// Transaction listed in a statement
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatementTransaction {
+    /// Date of the transaction
+    pub date: NaiveDate,
+    /// Transaction description
+    pub description: String,
+    /// Transaction account
+    pub account: String,
+    /// Transaction amount
+    pub amount: BigDecimal,
+    /// Colon separated category path, `None` if uncategorized
+    pub category: Option<String>,
+}
+
+/// Amount spent in a category compared to its budget
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetStatus {
+    /// Colon separated category path
+    pub category: String,
+    /// Maximum amount to spend
+    pub budget: BigDecimal,
+    /// Amount spent (ie the negated category total)
+    pub spent: BigDecimal,
+}
+
+impl BudgetStatus {
+    /// Amount spent of every budget of the categorizer in the assignments
+    pub(crate) fn all(categorizer: &Categorizer, assignments: &[&Assignment]) -> Vec<Self> {
+        categorizer
+            .budgets()
+            .into_iter()
+            .map(|(category, budget)| {
+                let path = category.split(':').collect::<Vec<_>>();
+                let spent = -assignments
+                    .iter()
+                    .filter(|a| {
+                        a.category_path
+                            .as_ref()
+                            .is_some_and(|p| p.starts_with(&path))
+                    })
+                    .map(|a| &a.transaction.amount)
+                    .sum::<BigDecimal>();
+                Self {
+                    category,
+                    budget: budget.clone(),
+                    spent,
+                }
+            })
+            .collect()
+    }
+
+    /// Amount left to spend, negative when over budget
+    pub fn remaining(&self) -> BigDecimal {
+        &self.budget - &self.spent
+    }
+
+    /// Check if more than the budget has been spent
+    pub fn is_over(&self) -> bool {
+        self.spent > self.budget
+    }
+}
+
+impl Statement {
+    /// Create a statement for the month containing `date`
+    ///
+    /// Top level categories named in `income_categories` are income and all other transactions
+    /// are expenses. At most `biggest` transactions are listed.
+    pub fn new(
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        date: NaiveDate,
+        income_categories: &[&str],
+        biggest: usize,
+    ) -> Self {
+        Self::for_period(
+            categorizer,
+            assignments,
+            &Period::bucket(Bucket::Month, date),
+            income_categories,
+            biggest,
+        )
+    }
+
+    /// Create a statement for the current range of the period compared to its previous range
+    pub fn for_period(
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        period: &Period,
+        income_categories: &[&str],
+        biggest: usize,
+    ) -> Self {
+        let in_range = |range: &DateRange| {
+            let range = range.clone();
+            assignments
+                .iter()
+                .filter(move |a| range.contains(a.transaction.time.date_naive()))
+        };
+
+        let mut income = Comparison::default();
+        let mut expenses = Comparison::default();
+        let mut uncategorized = Comparison::default();
+        let mut categories = BTreeMap::<&str, Comparison>::new();
+        for (range, is_current) in [(&period.current, true), (&period.previous, false)] {
+            for assignment in in_range(range) {
+                let amount = &assignment.transaction.amount;
+                let top_level = assignment.category_path.as_ref().map(|path| path[0]);
+                let mut totals = vec![];
+                match top_level {
+                    Some(category) if income_categories.contains(&category) => {
+                        totals.push(&mut income)
+                    }
+                    Some(category) => {
+                        totals.push(&mut expenses);
+                        totals.push(categories.entry(category).or_default());
+                    }
+                    None => {
+                        totals.push(&mut expenses);
+                        totals.push(&mut uncategorized);
+                    }
+                }
+                for comparison in totals {
+                    let total = if is_current {
+                        &mut comparison.current
+                    } else {
+                        &mut comparison.previous
+                    };
+                    *total += amount;
+                }
+            }
+        }
+        let net = Comparison {
+            current: &income.current + &expenses.current,
+            previous: &income.previous + &expenses.previous,
+        };
+        // Keep the configured category order
+        let categories = categorizer
+            .categories
+            .iter()
+            .filter_map(|category| {
+                categories
+                    .remove(category.category.as_str())
+                    .map(|comparison| CategoryComparison {
+                        category: category.category.clone(),
+                        comparison,
+                    })
+            })
+            .collect();
+
+        let mut biggest_transactions = in_range(&period.current).collect::<Vec<_>>();
+        biggest_transactions.sort_by_key(|a| std::cmp::Reverse(a.transaction.amount.abs()));
+        let biggest_transactions = biggest_transactions
+            .into_iter()
+            .take(biggest)
+            .map(|assignment| StatementTransaction {
+                date: assignment.transaction.time.date_naive(),
+                description: assignment.transaction.description.clone(),
+                account: assignment.transaction.account.clone(),
+                amount: assignment.transaction.amount.clone(),
+                category: assignment.category_path_joined(":"),
+            })
+            .collect();
+
+        let budgets =
+            BudgetStatus::all(categorizer, &in_range(&period.current).collect::<Vec<_>>());
+
+        Self {
+            period: period.current.label.clone(),
+            previous_period: period.previous.label.clone(),
+            income,
+            expenses,
+            net,
+            categories,
+            uncategorized,
+            biggest_transactions,
+            budgets,
+        }
+    }
+
+    /// Write the statement in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: StatementFormat,
+        formatter: &Formatter,
+        writer: W,
+    ) -> Result<(), Error> {
+        let title = format!("Statement for {}", self.period);
+        let tables = self.tables(formatter);
+        match format {
+            StatementFormat::Text => write_text(&title, &tables, writer),
+            StatementFormat::Markdown => write_markdown(&title, &tables, writer),
+            StatementFormat::Html => write_html(&title, &tables, writer),
+        }
+    }
+
+    fn tables(&self, formatter: &Formatter) -> Vec<Table> {
+        let mut tables = self.comparison_tables(formatter);
+        tables.extend(self.budget_table(formatter));
+        tables
+    }
+
+    /// Summary, expenses by category, and biggest transactions tables
+    pub(crate) fn comparison_tables(&self, formatter: &Formatter) -> Vec<Table> {
+        let comparison_headers = vec![
+            String::new(),
+            self.period.clone(),
+            self.previous_period.clone(),
+            String::from("Change"),
+        ];
+        let comparison_row = |label: &str, comparison: &Comparison| {
+            vec![
+                label.to_string(),
+                formatter.format(&comparison.current),
+                formatter.format(&comparison.previous),
+                formatter.format(&comparison.change()),
+            ]
+        };
+
+        let summary = vec![
+            comparison_row("Income", &self.income),
+            comparison_row("Expenses", &self.expenses),
+            comparison_row("Net", &self.net),
+        ];
+        let mut categories = self
+            .categories
+            .iter()
+            .map(|c| comparison_row(&c.category, &c.comparison))
+            .collect::<Vec<_>>();
+        if self.uncategorized != Comparison::default() {
+            categories.push(comparison_row("Uncategorized", &self.uncategorized));
+        }
+
+        vec![
+            Table {
+                title: String::from("Summary"),
+                headers: comparison_headers.clone(),
+                numeric: vec![false, true, true, true],
+                rows: summary,
+            },
+            Table {
+                title: String::from("Expenses by category"),
+                headers: comparison_headers,
+                numeric: vec![false, true, true, true],
+                rows: categories,
+            },
+            Table {
+                title: String::from("Biggest transactions"),
+                headers: ["Date", "Description", "Account", "Category", "Amount"]
+                    .map(String::from)
+                    .to_vec(),
+                numeric: vec![false, false, false, false, true],
+                rows: self
+                    .biggest_transactions
+                    .iter()
+                    .map(|t| {
+                        vec![
+                            formatter.format_date(t.date),
+                            t.description.clone(),
+                            t.account.clone(),
+                            t.category.clone().unwrap_or_default(),
+                            formatter.format(&t.amount),
+                        ]
+                    })
+                    .collect(),
+            },
+        ]
+    }
+
+    /// Budgets table, `None` if no category has a budget
+    pub(crate) fn budget_table(&self, formatter: &Formatter) -> Option<Table> {
+        if self.budgets.is_empty() {
+            return None;
+        }
+        Some(Table {
+            title: String::from("Budgets"),
+            headers: ["Category", "Budget", "Spent", "Remaining", "Status"]
+                .map(String::from)
+                .to_vec(),
+            numeric: vec![false, true, true, true, false],
+            rows: self
+                .budgets
+                .iter()
+                .map(|b| {
+                    let status = if b.is_over() {
+                        "over"
+                    } else if b.remaining().is_zero() {
+                        "at budget"
+                    } else {
+                        "under"
+                    };
+                    vec![
+                        b.category.clone(),
+                        formatter.format(&b.budget),
+                        formatter.format(&b.spent),
+                        formatter.format(&b.remaining()),
+                        status.to_string(),
+                    ]
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Categorizer, TransactionMatcher},
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    transaction::Transaction,
+    util::Formatter,
+};
+
+/// Output formats for stats
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatsFormat {
+    /// Plain text tables
+    #[default]
+    Text,
+    /// Markdown tables
+    Markdown,
+    /// JSON stats
+    Json,
+}
+
+/// Overall statistics of a set of transactions and how well the categorizer covers them
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stats {
+    /// Date of the earliest transaction, `None` if there are no transactions
+    pub first: Option<NaiveDate>,
+    /// Date of the latest transaction, `None` if there are no transactions
+    pub last: Option<NaiveDate>,
+    /// Number of transactions
+    pub transactions: u64,
+    /// Number of transactions of each account sorted by account
+    pub accounts: Vec<StatsCount>,
+    /// Number of transactions parsed from each file sorted by file, transactions without a source
+    /// file are not included
+    pub sources: Vec<StatsCount>,
+    /// Sum of the positive amounts
+    pub inflow: BigDecimal,
+    /// Sum of the negative amounts
+    pub outflow: BigDecimal,
+    /// Number of transactions removed by the transaction filters
+    pub filtered: u64,
+    /// Number of transactions which passed the filters but were not assigned a category
+    pub uncategorized: u64,
+    /// Percentage of the transactions which passed the filters that are uncategorized
+    pub uncategorized_percent: BigDecimal,
+    /// Number of transactions assigned by each leaf category transaction matcher in the order
+    /// they are tried
+    pub matchers: Vec<MatcherHits>,
+}
+
+/// Number of transactions with a value
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsCount {
+    /// Account name or source file path
+    pub name: String,
+    /// Number of transactions
+    pub count: u64,
+}
+
+/// Number of transactions a transaction matcher assigned
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatcherHits {
+    /// Colon separated category path of the leaf category
+    pub category: String,
+    /// Transaction matcher rules
+    pub matcher: String,
+    /// Number of transactions assigned, matches shadowed by an earlier matcher are not counted
+    pub hits: u64,
+}
+
+impl Stats {
+    /// Calculate the statistics of the transactions categorized by the categorizer
+    pub fn new(categorizer: &Categorizer, transactions: &[Transaction]) -> Self {
+        let first = transactions.iter().map(|t| t.time.date_naive()).min();
+        let last = transactions.iter().map(|t| t.time.date_naive()).max();
+
+        let mut accounts = BTreeMap::<&str, u64>::new();
+        let mut sources = BTreeMap::<String, u64>::new();
+        let mut inflow = BigDecimal::zero();
+        let mut outflow = BigDecimal::zero();
+        for transaction in transactions {
+            *accounts.entry(&transaction.account).or_default() += 1;
+            if let Some(source) = &transaction.source {
+                *sources.entry(source.display().to_string()).or_default() += 1;
+            }
+            if transaction.amount > BigDecimal::zero() {
+                inflow += &transaction.amount;
+            } else {
+                outflow += &transaction.amount;
+            }
+        }
+
+        let assignments = categorizer.assign(transactions);
+        let uncategorized = assignments.iter().filter(|a| !a.is_categorized()).count() as u64;
+        let uncategorized_percent = if assignments.is_empty() {
+            BigDecimal::zero()
+        } else {
+            (BigDecimal::from(uncategorized * 100) / BigDecimal::from(assignments.len() as u64))
+                .round(2)
+        };
+
+        // Matchers are identified by address since equal rules can appear in several categories
+        let mut hits = HashMap::<*const TransactionMatcher, u64>::new();
+        for matcher in assignments.iter().filter_map(|a| a.matcher) {
+            *hits.entry(matcher).or_default() += 1;
+        }
+        let matchers = categorizer
+            .leaf_matchers()
+            .into_iter()
+            .map(|(path, matcher)| MatcherHits {
+                category: path.join(":"),
+                matcher: matcher.to_string(),
+                hits: hits
+                    .get(&(matcher as *const TransactionMatcher))
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            first,
+            last,
+            transactions: transactions.len() as u64,
+            accounts: counts(accounts),
+            sources: counts(sources),
+            inflow,
+            outflow,
+            filtered: (transactions.len() - assignments.len()) as u64,
+            uncategorized,
+            uncategorized_percent,
+            matchers,
+        }
+    }
+
+    /// Write the stats in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: StatsFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let tables = self.tables(formatter);
+        match format {
+            StatsFormat::Text => write_text("Stats", &tables, writer),
+            StatsFormat::Markdown => write_markdown("Stats", &tables, writer),
+            StatsFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn tables(&self, formatter: &Formatter) -> Vec<Table> {
+        let date = |date: Option<NaiveDate>| date.map(|d| formatter.format_date(d));
+        let overview = [
+            ("First transaction", date(self.first).unwrap_or_default()),
+            ("Last transaction", date(self.last).unwrap_or_default()),
+            ("Transactions", self.transactions.to_string()),
+            ("Inflow", formatter.format(&self.inflow)),
+            ("Outflow", formatter.format(&self.outflow)),
+            ("Net", formatter.format(&(&self.inflow + &self.outflow))),
+            ("Filtered out", self.filtered.to_string()),
+            ("Uncategorized", self.uncategorized.to_string()),
+            ("Uncategorized %", self.uncategorized_percent.to_string()),
+        ];
+        let count_rows = |counts: &[StatsCount]| {
+            counts
+                .iter()
+                .map(|c| vec![c.name.clone(), c.count.to_string()])
+                .collect::<Vec<_>>()
+        };
+
+        let mut tables = vec![
+            Table {
+                title: String::from("Overview"),
+                headers: ["Statistic", "Value"].map(String::from).to_vec(),
+                numeric: vec![false, true],
+                rows: overview
+                    .into_iter()
+                    .map(|(name, value)| vec![String::from(name), value])
+                    .collect(),
+            },
+            Table {
+                title: String::from("Accounts"),
+                headers: ["Account", "Transactions"].map(String::from).to_vec(),
+                numeric: vec![false, true],
+                rows: count_rows(&self.accounts),
+            },
+        ];
+        if !self.sources.is_empty() {
+            tables.push(Table {
+                title: String::from("Source files"),
+                headers: ["File", "Transactions"].map(String::from).to_vec(),
+                numeric: vec![false, true],
+                rows: count_rows(&self.sources),
+            });
+        }
+        tables.push(Table {
+            title: String::from("Matcher hits"),
+            headers: ["Category", "Matcher", "Hits"].map(String::from).to_vec(),
+            numeric: vec![false, false, true],
+            rows: self
+                .matchers
+                .iter()
+                .map(|m| vec![m.category.clone(), m.matcher.clone(), m.hits.to_string()])
+                .collect(),
+        });
+        tables
+    }
+}
+
+fn counts<K: Into<String>>(counts: BTreeMap<K, u64>) -> Vec<StatsCount> {
+    counts
+        .into_iter()
+        .map(|(name, count)| StatsCount {
+            name: name.into(),
+            count,
+        })
+        .collect()
+}
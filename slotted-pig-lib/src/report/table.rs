@@ -0,0 +1,131 @@
+use std::io::Write;
+
+use crate::report::Error;
+
+/// Titled table of a rendered report
+pub(crate) struct Table {
+    pub(crate) title: String,
+    pub(crate) headers: Vec<String>,
+    /// Columns which are right aligned
+    pub(crate) numeric: Vec<bool>,
+    pub(crate) rows: Vec<Vec<String>>,
+}
+
+pub(crate) fn write_text<W: Write>(
+    title: &str,
+    tables: &[Table],
+    mut writer: W,
+) -> Result<(), Error> {
+    writeln!(writer, "{title}")?;
+    for table in tables {
+        writeln!(writer)?;
+        writeln!(writer, "{}", table.title)?;
+        let widths = (0..table.headers.len())
+            .map(|column| {
+                std::iter::once(&table.headers)
+                    .chain(&table.rows)
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        for row in std::iter::once(&table.headers).chain(&table.rows) {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .zip(&table.numeric)
+                .map(|((cell, width), numeric)| {
+                    if *numeric {
+                        format!("{cell:>width$}")
+                    } else {
+                        format!("{cell:<width$}")
+                    }
+                })
+                .collect::<Vec<_>>();
+            writeln!(writer, "  {}", cells.join("  ").trim_end())?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_markdown<W: Write>(
+    title: &str,
+    tables: &[Table],
+    mut writer: W,
+) -> Result<(), Error> {
+    writeln!(writer, "# {title}")?;
+    for table in tables {
+        writeln!(writer)?;
+        writeln!(writer, "## {}", table.title)?;
+        writeln!(writer)?;
+        writeln!(writer, "| {} |", table.headers.join(" | "))?;
+        let alignments = table
+            .numeric
+            .iter()
+            .map(|numeric| if *numeric { "---:" } else { "---" })
+            .collect::<Vec<_>>();
+        writeln!(writer, "| {} |", alignments.join(" | "))?;
+        for row in &table.rows {
+            let cells = row
+                .iter()
+                .map(|cell| cell.replace('|', "\\|"))
+                .collect::<Vec<_>>();
+            writeln!(writer, "| {} |", cells.join(" | "))?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_html<W: Write>(
+    title: &str,
+    tables: &[Table],
+    mut writer: W,
+) -> Result<(), Error> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, r#"<meta charset="utf-8">"#)?;
+    writeln!(writer, "<title>{}</title>", escape(title))?;
+    writeln!(
+        writer,
+        "<style>table {{ border-collapse: collapse; }} th, td {{ padding: 0.2em 0.6em; }} .numeric {{ text-align: right; }}</style>"
+    )?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>{}</h1>", escape(title))?;
+    for table in tables {
+        writeln!(writer, "<h2>{}</h2>", escape(&table.title))?;
+        write_html_table(table, &mut writer)?;
+    }
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+    Ok(())
+}
+
+/// Write the rows of a table as an HTML table element without its title
+pub(crate) fn write_html_table<W: Write>(table: &Table, mut writer: W) -> Result<(), Error> {
+    writeln!(writer, "<table>")?;
+    let cells = |row: &[String], tag: &str| {
+        row.iter()
+            .zip(&table.numeric)
+            .map(|(cell, numeric)| {
+                let class = if *numeric { r#" class="numeric""# } else { "" };
+                format!("<{tag}{class}>{}</{tag}>", escape(cell))
+            })
+            .collect::<String>()
+    };
+    writeln!(writer, "<tr>{}</tr>", cells(&table.headers, "th"))?;
+    for row in &table.rows {
+        writeln!(writer, "<tr>{}</tr>", cells(row, "td"))?;
+    }
+    writeln!(writer, "</table>")?;
+    Ok(())
+}
+
+/// Escape a value for use as HTML content
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
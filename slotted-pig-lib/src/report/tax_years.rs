@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, io::Write};
+
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::{Assignment, Categorizer},
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    util::Formatter,
+};
+
+/// Year starting on the first day of a configurable month (ie April for a UK tax year)
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FiscalYear {
+    /// Month the year starts in from 1 through 12, 1 for calendar years
+    pub start_month: u32,
+}
+
+impl FiscalYear {
+    /// First day of the fiscal year containing the date
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        let year = if date.month() >= self.start_month {
+            date.year()
+        } else {
+            date.year() - 1
+        };
+        NaiveDate::from_ymd_opt(year, self.start_month, 1).expect("first of month is a valid date")
+    }
+
+    /// Last day of the fiscal year starting on `start`
+    pub fn end(&self, start: NaiveDate) -> NaiveDate {
+        start + Months::new(12) - Days::new(1)
+    }
+
+    /// Label for the fiscal year starting on `start` (ie `2024` for calendar years or `FY2023-24`)
+    pub fn label(&self, start: NaiveDate) -> String {
+        if self.start_month == 1 {
+            start.year().to_string()
+        } else {
+            format!("FY{}-{:02}", start.year(), (start.year() + 1) % 100)
+        }
+    }
+}
+
+impl Default for FiscalYear {
+    fn default() -> Self {
+        Self { start_month: 1 }
+    }
+}
+
+/// Output formats for tax years
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TaxYearsFormat {
+    /// Plain text table per year
+    #[default]
+    Text,
+    /// Markdown table per year
+    Markdown,
+    /// JSON years
+    Json,
+}
+
+/// Category totals grouped by fiscal year
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxYears {
+    /// Every fiscal year with categorized transactions in chronological order
+    pub years: Vec<TaxYear>,
+}
+
+/// Category totals of a single fiscal year
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxYear {
+    /// Label of the year (ie `FY2023-24`)
+    pub year: String,
+    /// First day of the year
+    pub start: NaiveDate,
+    /// Last day of the year
+    pub end: NaiveDate,
+    /// Total of each category sorted by category path
+    pub categories: Vec<TaxYearCategory>,
+    /// Sum of the tax relevant category totals
+    pub tax_relevant_total: BigDecimal,
+}
+
+/// Total of a category in a fiscal year
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxYearCategory {
+    /// Colon separated category path
+    pub category: String,
+    /// Total amount
+    pub total: BigDecimal,
+    /// The category or one of its ancestors is tax relevant
+    pub tax_relevant: bool,
+}
+
+impl TaxYears {
+    /// Group the assigned transactions by fiscal year and category
+    ///
+    /// Category paths deeper than `depth` are truncated so their totals roll up into the ancestor
+    /// at `depth`, a truncated path is tax relevant only if the ancestor is. Uncategorized
+    /// transactions are not included.
+    pub fn new(
+        categorizer: &Categorizer,
+        assignments: &[Assignment],
+        fiscal_year: FiscalYear,
+        depth: Option<usize>,
+    ) -> Self {
+        let mut totals = BTreeMap::<NaiveDate, BTreeMap<Vec<&str>, BigDecimal>>::new();
+        for assignment in assignments {
+            let Some(path) = &assignment.category_path else {
+                continue;
+            };
+            let path = match depth {
+                Some(depth) => &path[..depth.min(path.len())],
+                None => &path[..],
+            };
+            let start = fiscal_year.start(assignment.transaction.time.date_naive());
+            *totals
+                .entry(start)
+                .or_default()
+                .entry(path.to_vec())
+                .or_default() += &assignment.transaction.amount;
+        }
+
+        let years = totals
+            .into_iter()
+            .map(|(start, totals)| {
+                let categories = totals
+                    .into_iter()
+                    .map(|(path, total)| TaxYearCategory {
+                        category: path.join(":"),
+                        total,
+                        tax_relevant: categorizer.is_tax_relevant(&path),
+                    })
+                    .collect::<Vec<_>>();
+                let tax_relevant_total = categories
+                    .iter()
+                    .filter(|c| c.tax_relevant)
+                    .map(|c| &c.total)
+                    .sum();
+                TaxYear {
+                    year: fiscal_year.label(start),
+                    start,
+                    end: fiscal_year.end(start),
+                    categories,
+                    tax_relevant_total,
+                }
+            })
+            .collect();
+        Self { years }
+    }
+
+    /// Keep only the tax relevant categories (ie for a deductible summary)
+    pub fn tax_relevant_only(mut self) -> Self {
+        for year in &mut self.years {
+            year.categories.retain(|c| c.tax_relevant);
+        }
+        self
+    }
+
+    /// Write the years in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: TaxYearsFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let tables = self
+            .years
+            .iter()
+            .map(|year| {
+                let mut rows = year
+                    .categories
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            c.category.clone(),
+                            formatter.format(&c.total),
+                            String::from(if c.tax_relevant { "yes" } else { "" }),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                rows.push(vec![
+                    String::from("Tax relevant total"),
+                    formatter.format(&year.tax_relevant_total),
+                    String::new(),
+                ]);
+                Table {
+                    title: format!(
+                        "{} ({} to {})",
+                        year.year,
+                        formatter.format_date(year.start),
+                        formatter.format_date(year.end)
+                    ),
+                    headers: ["Category", "Total", "Tax relevant"]
+                        .map(String::from)
+                        .to_vec(),
+                    numeric: vec![false, true, false],
+                    rows,
+                }
+            })
+            .collect::<Vec<_>>();
+        match format {
+            TaxYearsFormat::Text => write_text("Tax years", &tables, writer),
+            TaxYearsFormat::Markdown => write_markdown("Tax years", &tables, writer),
+            TaxYearsFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
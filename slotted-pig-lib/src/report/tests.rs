@@ -0,0 +1,502 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use insta::{assert_snapshot, assert_yaml_snapshot};
+
+use test_case::test_case;
+
+use crate::{
+    categorizer::Categorizer,
+    report::{
+        dot, Balances, BalancesFormat, BarChart, Bucket, Burndown, BurndownFormat, Changes,
+        ChangesFormat, Digest, DigestPeriod, FiscalYear, Forecast, ForecastFormat, HtmlChart,
+        HtmlReport, NetWorth, NetWorthFormat, Period, Pivot, PivotFormat, RecurringDetector,
+        RecurringFormat, RecurringList, RecurringSort, Rules, RulesFormat, Sankey,
+        StartingBalances, Statement, StatementFormat, Stats, StatsFormat, TaxYears, TaxYearsFormat,
+        Top, TopFormat, Totals, TotalsFormat, TransactionTable, TransactionTableFormat,
+        UncategorizedFormat, UncategorizedPayees,
+    },
+    transaction::Transaction,
+    util::Formatter,
+    validate::validate_categorizer,
+};
+
+#[test]
+fn test_sankey() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let sankey = Sankey::new(&categorized, &["income"]);
+    assert_yaml_snapshot!("sankey", sankey);
+    let mut sankeymatic = Vec::new();
+    sankey.write_sankeymatic(&mut sankeymatic)?;
+    assert_snapshot!("sankeymatic", String::from_utf8(sankeymatic)?);
+    Ok(())
+}
+
+#[test_case(None, "bar_chart"; "all")]
+#[test_case(Some(1), "bar_chart_depth"; "depth")]
+fn test_bar_chart(depth: Option<usize>, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let chart = BarChart {
+        depth,
+        width: 20,
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    chart.write(&categorized, &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(Bucket::Month, None, "pivot_month"; "month")]
+#[test_case(Bucket::Quarter, Some(1), "pivot_quarter_depth"; "quarter depth")]
+fn test_pivot(bucket: Bucket, depth: Option<usize>, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    assert_yaml_snapshot!(name, Pivot::new(&assignments, bucket, depth));
+    Ok(())
+}
+
+#[test]
+fn test_pivot_csv() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let mut output = Vec::new();
+    Pivot::new(&assignments, Bucket::Month, None).write(
+        PivotFormat::Csv,
+        &Formatter::default(),
+        &mut output,
+    )?;
+    assert_snapshot!("pivot_csv", String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(Bucket::Month, &[], "pivot_text"; "month")]
+#[test_case(Bucket::Quarter, &["income"], "pivot_text_income_quarter"; "income quarter")]
+fn test_pivot_text(bucket: Bucket, categories: &[&str], name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let mut pivot = Pivot::new(&categorizer.assign(&transactions), bucket, None);
+    if !categories.is_empty() {
+        pivot.retain_top_level(
+            &categories
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut output = Vec::new();
+    pivot.write(PivotFormat::Text, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_recurring() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_recurring.csv")?;
+    let recurring = RecurringDetector::default().detect(&transactions);
+    assert_yaml_snapshot!("recurring", recurring);
+    Ok(())
+}
+
+#[test_case(RecurringSort::YearlyCost, RecurringFormat::Text, "recurring_text"; "text yearly cost")]
+#[test_case(RecurringSort::NextDate, RecurringFormat::Csv, "recurring_csv"; "csv next date")]
+fn test_recurring_list(sort: RecurringSort, format: RecurringFormat, name: &str) -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_recurring.csv")?;
+    let recurring = RecurringDetector::default().detect(&transactions);
+    let mut output = Vec::new();
+    RecurringList::new(recurring, sort).write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(NaiveDate::from_ymd_opt(2024, 4, 30), ForecastFormat::Text, "forecast_text"; "text")]
+#[test_case(NaiveDate::from_ymd_opt(2024, 4, 10), ForecastFormat::Json, "forecast_json"; "json partial month")]
+fn test_forecast(as_of: Option<NaiveDate>, format: ForecastFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_recurring.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let recurring = RecurringDetector::default().detect(&transactions);
+    let forecast = Forecast::new(&assignments, recurring, as_of.expect("valid date"), 3, 3);
+    let mut output = Vec::new();
+    forecast.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(None, None, TopFormat::Text, "top_text"; "text")]
+#[test_case(Some("paycheck"), Some("2024-01"), TopFormat::Json, "top_json"; "json category period")]
+fn test_top(
+    category: Option<&str>,
+    period: Option<&str>,
+    format: TopFormat,
+    name: &str,
+) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_recurring.csv")?;
+    let period = period
+        .map(|period| Period::parse(period, NaiveDate::default()))
+        .transpose()?;
+    let top = Top::new(
+        &categorizer.assign(&transactions),
+        3,
+        category,
+        period.as_ref().map(|period| &period.current),
+    );
+    let mut output = Vec::new();
+    top.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(RulesFormat::Text, "rules_text"; "text")]
+#[test_case(RulesFormat::Json, "rules_json"; "json")]
+fn test_rules(format: RulesFormat, name: &str) -> Result<()> {
+    let yaml = std::fs::read_to_string("tests/categorizer_rules.yaml")?;
+    let categorizer = Categorizer::from_yaml_buffer(&yaml)?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let problems = validate_categorizer(&yaml);
+    let rules = Rules::new(
+        &categorizer,
+        &transactions,
+        &BigDecimal::from(50),
+        &problems,
+    );
+    assert!(rules.has_findings());
+    let mut output = Vec::new();
+    rules.write(format, &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(NaiveDate::from_ymd_opt(2024, 1, 15), StatementFormat::Text, "statement_text"; "text")]
+#[test_case(NaiveDate::from_ymd_opt(2024, 2, 1), StatementFormat::Markdown, "statement_markdown"; "markdown")]
+#[test_case(NaiveDate::from_ymd_opt(2024, 1, 31), StatementFormat::Html, "statement_html"; "html")]
+fn test_statement(date: Option<NaiveDate>, format: StatementFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let statement = Statement::new(
+        &categorizer,
+        &assignments,
+        date.expect("valid date"),
+        &["income"],
+        3,
+    );
+    let mut output = Vec::new();
+    statement.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_html_report() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let today = NaiveDate::from_ymd_opt(2024, 1, 31).expect("valid date");
+    let mut report = HtmlReport::new(
+        &categorizer,
+        &transactions,
+        &Period::parse("this-month", today)?,
+        &["income"],
+        3,
+    );
+    report.charts.push(HtmlChart {
+        title: String::from("Chart"),
+        svg: String::from("<svg></svg>"),
+    });
+    let mut output = Vec::new();
+    report.write(&Formatter::default(), &mut output)?;
+    assert_snapshot!("html_report", String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case("2024-03", "2024-03-01", "2024-03-31", "2024-02-01", "2024-02-29"; "month")]
+#[test_case("2024-Q1", "2024-01-01", "2024-03-31", "2023-10-01", "2023-12-31"; "quarter")]
+#[test_case("2023", "2023-01-01", "2023-12-31", "2022-01-01", "2022-12-31"; "year")]
+#[test_case("last-month", "2024-02-01", "2024-02-29", "2024-01-01", "2024-01-31"; "last month")]
+#[test_case("this-quarter", "2024-01-01", "2024-03-31", "2023-10-01", "2023-12-31"; "this quarter")]
+#[test_case("last-year", "2023-01-01", "2023-12-31", "2022-01-01", "2022-12-31"; "last year")]
+#[test_case("mtd", "2024-03-01", "2024-03-31", "2024-02-01", "2024-02-29"; "month to date")]
+#[test_case("ytd", "2024-01-01", "2024-03-31", "2023-01-01", "2023-03-31"; "year to date")]
+#[test_case("last-7-days", "2024-03-25", "2024-03-31", "2024-03-18", "2024-03-24"; "days")]
+fn test_period(
+    period: &str,
+    start: &str,
+    end: &str,
+    previous_start: &str,
+    previous_end: &str,
+) -> Result<()> {
+    let today = NaiveDate::from_ymd_opt(2024, 3, 31).expect("valid date");
+    let period = Period::parse(period, today)?;
+    let dates = [
+        period.current.start,
+        period.current.end,
+        period.previous.start,
+        period.previous.end,
+    ];
+    assert_eq!(
+        dates.map(|d| d.to_string()),
+        [start, end, previous_start, previous_end]
+    );
+    Ok(())
+}
+
+#[test_case("2024-13"; "month")]
+#[test_case("2024-Q5"; "quarter")]
+#[test_case("last-0-days"; "zero days")]
+#[test_case("yesterday"; "unknown")]
+#[test_case("last-999999999-days"; "days out of range")]
+#[test_case("last-18446744073709551615-days"; "days beyond i64")]
+#[test_case("-262143"; "year before the supported dates")]
+#[test_case("262143"; "year after the supported dates")]
+#[test_case("262143-12"; "month after the supported dates")]
+#[test_case("-262144-Q1"; "quarter before the supported dates")]
+fn test_period_invalid(period: &str) {
+    let today = NaiveDate::from_ymd_opt(2024, 3, 31).expect("valid date");
+    assert!(Period::parse(period, today).is_err());
+}
+
+#[test]
+fn test_statement_period() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let today = NaiveDate::from_ymd_opt(2024, 2, 15).expect("valid date");
+    let statement = Statement::for_period(
+        &categorizer,
+        &assignments,
+        &Period::parse("ytd", today)?,
+        &["income"],
+        3,
+    );
+    let mut output = Vec::new();
+    statement.write(
+        StatementFormat::Markdown,
+        &Formatter::default(),
+        &mut output,
+    )?;
+    assert_snapshot!("statement_period", String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(false, "dot"; "hierarchy")]
+#[test_case(true, "dot_totals"; "totals")]
+fn test_dot(with_totals: bool, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let categorized = with_totals.then_some(&categorized);
+    let mut output = Vec::new();
+    dot(
+        &categorizer,
+        categorized,
+        &Formatter::default(),
+        &mut output,
+    )?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(ChangesFormat::Text, None, "changes_text"; "text")]
+#[test_case(ChangesFormat::Markdown, Some(1), "changes_markdown_depth"; "markdown depth")]
+#[test_case(ChangesFormat::Json, None, "changes_json"; "json")]
+fn test_changes(format: ChangesFormat, depth: Option<usize>, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let (january, february): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .filter(|t| t.time.date_naive() >= NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date"))
+        .partition(|t| {
+            t.time.date_naive() < NaiveDate::from_ymd_opt(2024, 2, 1).expect("valid date")
+        });
+    let (previous, _uncategorized) = categorizer.categorize(&january);
+    let (current, _uncategorized) = categorizer.categorize(&february);
+    let changes = Changes::new(&previous, &current, "2024-01", "2024-02", depth);
+    let mut output = Vec::new();
+    changes.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(TotalsFormat::Csv, None, "totals_csv"; "csv")]
+#[test_case(TotalsFormat::Markdown, Some(1), "totals_markdown_depth"; "markdown depth")]
+#[test_case(TotalsFormat::Html, None, "totals_html"; "html")]
+fn test_totals(format: TotalsFormat, depth: Option<usize>, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let mut output = Vec::new();
+    Totals::new(&categorized, depth).write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(TransactionTableFormat::Markdown, true, "transaction_table_markdown"; "markdown categorized")]
+#[test_case(TransactionTableFormat::Html, false, "transaction_table_html"; "html")]
+#[test_case(TransactionTableFormat::Text, true, "transaction_table_text"; "text categorized")]
+fn test_transaction_table(
+    format: TransactionTableFormat,
+    categorized: bool,
+    name: &str,
+) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let table = TransactionTable::new(&transactions, categorized.then_some(&categorizer));
+    let mut output = Vec::new();
+    table.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(NaiveDate::from_ymd_opt(2024, 1, 10), BurndownFormat::Text, "burndown_text"; "text")]
+#[test_case(NaiveDate::from_ymd_opt(2024, 1, 31), BurndownFormat::Json, "burndown_json"; "json")]
+fn test_burndown(as_of: Option<NaiveDate>, format: BurndownFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let burndown = Burndown::new(&categorizer, &assignments, as_of.expect("valid date"));
+    let mut output = Vec::new();
+    burndown.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(NetWorthFormat::Csv, "net_worth_csv"; "csv")]
+#[test_case(NetWorthFormat::Json, "net_worth_json"; "json")]
+fn test_net_worth(format: NetWorthFormat, name: &str) -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let starting_balances = StartingBalances::from_yaml_buffer(
+        r#"
+accounts:
+  checking: 100
+  savings: 1000.50
+"#,
+    )?;
+    let net_worth = NetWorth::new(&transactions, &starting_balances, Bucket::Month);
+    let mut output = Vec::new();
+    net_worth.write(format, &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(None, BalancesFormat::Text, "balances_text"; "text")]
+#[test_case(Some("2024-01"), BalancesFormat::Json, "balances_json"; "json period")]
+fn test_balances(period: Option<&str>, format: BalancesFormat, name: &str) -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let starting_balances = StartingBalances::from_yaml_buffer(
+        r#"
+accounts:
+  checking: 100
+  savings: 1000.50
+"#,
+    )?;
+    let period = period
+        .map(|period| Period::parse(period, NaiveDate::default()))
+        .transpose()?;
+    let balances = Balances::new(
+        &transactions,
+        &starting_balances,
+        period.as_ref().map(|period| &period.current),
+    );
+    let mut output = Vec::new();
+    balances.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(1, false, TaxYearsFormat::Text, "tax_years_calendar"; "calendar")]
+#[test_case(12, false, TaxYearsFormat::Text, "tax_years_fiscal"; "fiscal")]
+#[test_case(4, true, TaxYearsFormat::Json, "tax_years_deductible"; "deductible")]
+fn test_tax_years(
+    start_month: u32,
+    tax_relevant_only: bool,
+    format: TaxYearsFormat,
+    name: &str,
+) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let mut tax_years = TaxYears::new(&categorizer, &assignments, FiscalYear { start_month }, None);
+    if tax_relevant_only {
+        tax_years = tax_years.tax_relevant_only();
+    }
+    let mut output = Vec::new();
+    tax_years.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(DigestPeriod::Weekly, StatementFormat::Text, "digest_weekly_text"; "weekly text")]
+#[test_case(DigestPeriod::Monthly, StatementFormat::Markdown, "digest_monthly_markdown"; "monthly markdown")]
+fn test_digest(period: DigestPeriod, format: StatementFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_digest.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let end = NaiveDate::from_ymd_opt(2024, 2, 24).expect("valid date");
+    let digest = Digest::new(
+        &categorizer,
+        &assignments,
+        period,
+        end,
+        &BigDecimal::from(3),
+    );
+    let mut output = Vec::new();
+    digest.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(StatsFormat::Text, "stats_text"; "text")]
+#[test_case(StatsFormat::Json, "stats_json"; "json")]
+fn test_stats(format: StatsFormat, name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let mut transactions = Transaction::from_csv_file("tests/transactions_months.csv")?;
+    for (i, transaction) in transactions.iter_mut().enumerate() {
+        let source = if i % 2 == 0 { "even.csv" } else { "odd.csv" };
+        transaction.source = Some(source.into());
+    }
+    let stats = Stats::new(&categorizer, &transactions);
+    let mut output = Vec::new();
+    stats.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test_case(UncategorizedFormat::Text, "uncategorized_text"; "text")]
+#[test_case(UncategorizedFormat::Matchers, "uncategorized_matchers"; "matchers")]
+fn test_uncategorized(format: UncategorizedFormat, name: &str) -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_payees.csv")?;
+    let payees = UncategorizedPayees::new(&transactions);
+    let mut output = Vec::new();
+    payees.write(format, &Formatter::default(), &mut output)?;
+    assert_snapshot!(name, String::from_utf8(output)?);
+    Ok(())
+}
+
+#[test]
+fn test_uncategorized_matchers_categorize() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_payees.csv")?;
+    let payees = UncategorizedPayees::new(&transactions);
+    let mut output = b"categories:\n".to_vec();
+    payees.write(
+        UncategorizedFormat::Matchers,
+        &Formatter::default(),
+        &mut output,
+    )?;
+    let categorizer = Categorizer::from_yaml_buffer(output)?;
+    for assignment in categorizer.assign(&transactions) {
+        let payee = payees
+            .payees
+            .iter()
+            .find(|p| p.descriptions.contains(&assignment.transaction.description))
+            .expect("transaction has a payee");
+        assert_eq!(assignment.category_path, Some(vec![payee.payee.as_str()]));
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use std::{cmp::Reverse, collections::BTreeMap, io::Write};
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Assignment,
+    report::{
+        table::{write_markdown, write_text, Table},
+        DateRange, Error,
+    },
+    util::{normalize_payee, Formatter},
+};
+
+/// Output formats for the top transactions and merchants
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TopFormat {
+    /// Plain text tables
+    #[default]
+    Text,
+    /// Markdown tables
+    Markdown,
+    /// JSON transactions and merchants
+    Json,
+}
+
+/// Largest transactions and the merchants with the largest totals, a quick view of where the
+/// money went
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Top {
+    /// Label of the category and period the transactions are limited to (ie `dining in 2024`)
+    pub label: String,
+    /// Largest transactions by absolute amount
+    pub transactions: Vec<TopTransaction>,
+    /// Merchants with the largest absolute totals
+    pub merchants: Vec<TopMerchant>,
+}
+
+/// One of the largest transactions
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TopTransaction {
+    /// Day of the transaction
+    pub date: NaiveDate,
+    /// Description of the transaction
+    pub description: String,
+    /// Colon separated category path, `None` if uncategorized
+    pub category: Option<String>,
+    /// Amount of the transaction
+    pub amount: BigDecimal,
+}
+
+/// Total of the transactions of one normalized payee
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TopMerchant {
+    /// Normalized payee (ie `amzn mktp us`)
+    pub payee: String,
+    /// Number of transactions
+    pub count: u64,
+    /// Sum of the amounts
+    pub total: BigDecimal,
+}
+
+impl Top {
+    /// Find the `n` largest transactions and merchants
+    ///
+    /// Transactions are limited to the period and to the category, which is either a colon
+    /// separated path (ie `expenses:dining`) including its subcategories or the name of any
+    /// category in the path (ie `dining`).
+    pub fn new(
+        assignments: &[Assignment],
+        n: usize,
+        category: Option<&str>,
+        period: Option<&DateRange>,
+    ) -> Self {
+        let in_category = |assignment: &Assignment| {
+            let Some(category) = category else {
+                return true;
+            };
+            let Some(path) = &assignment.category_path else {
+                return false;
+            };
+            let joined = path.join(":");
+            path.contains(&category)
+                || joined == category
+                || joined.starts_with(&format!("{category}:"))
+        };
+        let selected = assignments
+            .iter()
+            .filter(|a| period.is_none_or(|p| p.contains(a.transaction.time.date_naive())))
+            .filter(|a| in_category(a))
+            .collect::<Vec<_>>();
+
+        let mut transactions = selected
+            .iter()
+            .map(|a| TopTransaction {
+                date: a.transaction.time.date_naive(),
+                description: a.transaction.description.clone(),
+                category: a.category_path_joined(":"),
+                amount: a.transaction.amount.clone(),
+            })
+            .collect::<Vec<_>>();
+        // Stable sort keeps the transaction order for equal amounts
+        transactions.sort_by_key(|t| Reverse(t.amount.abs()));
+        transactions.truncate(n);
+
+        let mut by_payee = BTreeMap::<String, (u64, BigDecimal)>::new();
+        for assignment in &selected {
+            let payee = normalize_payee(&assignment.transaction.description);
+            let (count, total) = by_payee.entry(payee).or_default();
+            *count += 1;
+            *total += &assignment.transaction.amount;
+        }
+        let mut merchants = by_payee
+            .into_iter()
+            .map(|(payee, (count, total))| TopMerchant {
+                payee,
+                count,
+                total,
+            })
+            .collect::<Vec<_>>();
+        merchants.sort_by_key(|m| Reverse(m.total.abs()));
+        merchants.truncate(n);
+
+        let label = match (category, period) {
+            (Some(category), Some(period)) => format!("{category} in {}", period.label),
+            (Some(category), None) => category.to_string(),
+            (None, Some(period)) => period.label.clone(),
+            (None, None) => String::from("all transactions"),
+        };
+        Self {
+            label,
+            transactions,
+            merchants,
+        }
+    }
+
+    /// Write the top transactions and merchants in the format with amounts formatted by the
+    /// formatter
+    pub fn write<W: Write>(
+        &self,
+        format: TopFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let title = format!("Top of {}", self.label);
+        let tables = [
+            Table {
+                title: String::from("Largest transactions"),
+                headers: ["Date", "Description", "Category", "Amount"]
+                    .map(String::from)
+                    .to_vec(),
+                numeric: vec![false, false, false, true],
+                rows: self
+                    .transactions
+                    .iter()
+                    .map(|t| {
+                        vec![
+                            formatter.format_date(t.date),
+                            t.description.clone(),
+                            t.category.clone().unwrap_or_default(),
+                            formatter.format(&t.amount),
+                        ]
+                    })
+                    .collect(),
+            },
+            Table {
+                title: String::from("Top merchants"),
+                headers: ["Merchant", "Transactions", "Total"]
+                    .map(String::from)
+                    .to_vec(),
+                numeric: vec![false, true, true],
+                rows: self
+                    .merchants
+                    .iter()
+                    .map(|m| {
+                        vec![
+                            m.payee.clone(),
+                            m.count.to_string(),
+                            formatter.format(&m.total),
+                        ]
+                    })
+                    .collect(),
+            },
+        ];
+        match format {
+            TopFormat::Text => write_text(&title, &tables, writer),
+            TopFormat::Markdown => write_markdown(&title, &tables, writer),
+            TopFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+        }
+    }
+}
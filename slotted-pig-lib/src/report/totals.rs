@@ -0,0 +1,111 @@
+use std::io::Write;
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::CategorizedList,
+    report::{
+        table::{write_html, write_markdown, Table},
+        Error,
+    },
+    util::Formatter,
+};
+
+/// Output formats for category totals
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TotalsFormat {
+    /// Comma separated values with unformatted amounts
+    #[default]
+    Csv,
+    /// Markdown table
+    Markdown,
+    /// HTML document with a table
+    Html,
+}
+
+/// Flat list of every category total in hierarchy order
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Totals {
+    /// Total of each category
+    pub rows: Vec<TotalsRow>,
+}
+
+/// Total of a single category
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TotalsRow {
+    /// Colon separated category path
+    pub category: String,
+    /// Count of transactions in the category
+    pub count: u64,
+    /// Total amount of the category
+    pub total: BigDecimal,
+}
+
+impl Totals {
+    /// Flatten the categorization
+    ///
+    /// Categories deeper than `depth` are left out, top level categories have a depth of 1.
+    pub fn new(categorized: &CategorizedList, depth: Option<usize>) -> Self {
+        let rows = categorized
+            .flatten()
+            .into_iter()
+            .filter(|(path, _)| depth.is_none_or(|depth| path.len() <= depth))
+            .map(|(path, categorized)| TotalsRow {
+                category: path.join(":"),
+                count: categorized.count,
+                total: categorized.total.clone(),
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Write the totals in the format with amounts formatted by the formatter
+    ///
+    /// Csv amounts are not formatted so they can be read by other tools.
+    pub fn write<W: Write>(
+        &self,
+        format: TotalsFormat,
+        formatter: &Formatter,
+        writer: W,
+    ) -> Result<(), Error> {
+        let title = "Category totals";
+        let table = || Table {
+            title: String::from("Categories"),
+            headers: vec![
+                String::from("Category"),
+                String::from("Count"),
+                String::from("Total"),
+            ],
+            numeric: vec![false, true, true],
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    vec![
+                        row.category.clone(),
+                        row.count.to_string(),
+                        formatter.format(&row.total),
+                    ]
+                })
+                .collect(),
+        };
+        match format {
+            TotalsFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                for row in &self.rows {
+                    writer.serialize(row)?;
+                }
+                writer.flush()?;
+                Ok(())
+            }
+            TotalsFormat::Markdown => write_markdown(title, &[table()], writer),
+            TotalsFormat::Html => write_html(title, &[table()], writer),
+        }
+    }
+}
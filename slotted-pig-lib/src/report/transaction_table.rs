@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    categorizer::Categorizer,
+    report::{
+        table::{write_html, write_markdown, write_text, Table},
+        Error,
+    },
+    transaction::Transaction,
+    util::Formatter,
+};
+
+/// Output formats for a transaction table
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TransactionTableFormat {
+    /// Markdown table
+    #[default]
+    Markdown,
+    /// Plain text table with aligned columns
+    Text,
+    /// HTML document with a table
+    Html,
+}
+
+/// Transactions with their categories for reading in documents
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionTable {
+    /// Whether the table has a category column
+    pub categorized: bool,
+    /// Each transaction
+    pub rows: Vec<TransactionTableRow>,
+}
+
+/// A single transaction
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionTableRow {
+    /// Time of the transaction
+    pub time: DateTime<Utc>,
+    /// Account of the transaction
+    pub account: String,
+    /// Description of the transaction
+    pub description: String,
+    /// Amount of the transaction
+    pub amount: BigDecimal,
+    /// Colon separated category path, `None` if uncategorized or without a categorizer
+    pub category: Option<String>,
+}
+
+impl TransactionTable {
+    /// Table of the transactions, with a category column if there is a categorizer
+    pub fn new(transactions: &[Transaction], categorizer: Option<&Categorizer>) -> Self {
+        let rows = transactions
+            .iter()
+            .map(|transaction| TransactionTableRow {
+                time: transaction.time,
+                account: transaction.account.clone(),
+                description: transaction.description.clone(),
+                amount: transaction.amount.clone(),
+                category: categorizer
+                    .and_then(|categorizer| categorizer.assign_transaction(transaction))
+                    .and_then(|assignment| assignment.category_path_joined(":")),
+            })
+            .collect();
+        Self {
+            categorized: categorizer.is_some(),
+            rows,
+        }
+    }
+
+    /// Write the table in the format with amounts and times formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: TransactionTableFormat,
+        formatter: &Formatter,
+        writer: W,
+    ) -> Result<(), Error> {
+        let mut headers = vec![
+            String::from("Date"),
+            String::from("Account"),
+            String::from("Description"),
+            String::from("Amount"),
+        ];
+        let mut numeric = vec![false, false, false, true];
+        if self.categorized {
+            headers.push(String::from("Category"));
+            numeric.push(false);
+        }
+        let table = Table {
+            title: String::from("Transactions"),
+            headers,
+            numeric,
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    let mut cells = vec![
+                        formatter.format_time(&row.time),
+                        row.account.clone(),
+                        row.description.clone(),
+                        formatter.format(&row.amount),
+                    ];
+                    if self.categorized {
+                        cells.push(row.category.clone().unwrap_or_default());
+                    }
+                    cells
+                })
+                .collect(),
+        };
+        let title = "Transactions";
+        match format {
+            TransactionTableFormat::Markdown => write_markdown(title, &[table], writer),
+            TransactionTableFormat::Text => write_text(title, &[table], writer),
+            TransactionTableFormat::Html => write_html(title, &[table], writer),
+        }
+    }
+}
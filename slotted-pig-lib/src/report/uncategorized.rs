@@ -0,0 +1,180 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use strum::EnumString;
+
+use crate::{
+    report::{
+        table::{write_markdown, write_text, Table},
+        Error,
+    },
+    transaction::Transaction,
+    util::{normalize_payee, payee_regex, yaml_string, Formatter},
+};
+
+/// Output formats for uncategorized payees
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum UncategorizedFormat {
+    /// Plain text table
+    #[default]
+    Text,
+    /// Markdown table
+    Markdown,
+    /// JSON payees
+    Json,
+    /// Categorizer yaml categories with a suggested transaction matcher for each payee
+    Matchers,
+}
+
+/// Uncategorized transactions grouped by normalized payee
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UncategorizedPayees {
+    /// Every payee, most transactions first
+    pub payees: Vec<UncategorizedPayee>,
+}
+
+/// Uncategorized transactions with the same normalized payee
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UncategorizedPayee {
+    /// Normalized payee (ie `amzn mktp us`)
+    pub payee: String,
+    /// Number of transactions
+    pub count: u64,
+    /// Sum of the transactions
+    pub total: BigDecimal,
+    /// Smallest transaction amount
+    pub min_amount: BigDecimal,
+    /// Largest transaction amount
+    pub max_amount: BigDecimal,
+    /// Date of the earliest transaction
+    pub first_date: NaiveDate,
+    /// Date of the latest transaction
+    pub last_date: NaiveDate,
+    /// Distinct descriptions of the transactions sorted alphabetically
+    pub descriptions: Vec<String>,
+}
+
+impl UncategorizedPayees {
+    /// Group the uncategorized transactions by normalized payee
+    ///
+    /// Payees are sorted by number of transactions, then absolute total, both descending. A
+    /// description which normalizes to nothing (ie `store1`) is grouped by its lowercase
+    /// description instead.
+    pub fn new<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Self {
+        let mut by_payee = BTreeMap::<String, Vec<&Transaction>>::new();
+        for transaction in transactions {
+            let mut payee = normalize_payee(&transaction.description);
+            if payee.is_empty() {
+                payee = transaction.description.trim().to_lowercase();
+            }
+            by_payee.entry(payee).or_default().push(transaction);
+        }
+
+        let mut payees = by_payee
+            .into_iter()
+            .map(|(payee, transactions)| {
+                let dates = transactions.iter().map(|t| t.time.date_naive());
+                let amounts = transactions.iter().map(|t| &t.amount);
+                UncategorizedPayee {
+                    payee,
+                    count: transactions.len() as u64,
+                    total: amounts.clone().sum(),
+                    min_amount: amounts
+                        .clone()
+                        .min()
+                        .expect("group has a transaction")
+                        .clone(),
+                    max_amount: amounts.max().expect("group has a transaction").clone(),
+                    first_date: dates.clone().min().expect("group has a transaction"),
+                    last_date: dates.max().expect("group has a transaction"),
+                    descriptions: transactions
+                        .iter()
+                        .map(|t| t.description.clone())
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        // The stable sort keeps payees with equal counts and totals in alphabetical order
+        payees.sort_by_key(|p| std::cmp::Reverse((p.count, p.total.abs())));
+        Self { payees }
+    }
+
+    /// Write the payees in the format with amounts formatted by the formatter
+    pub fn write<W: Write>(
+        &self,
+        format: UncategorizedFormat,
+        formatter: &Formatter,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let table = Table {
+            title: String::from("Payees"),
+            headers: ["Payee", "Transactions", "Total", "First", "Last"]
+                .map(String::from)
+                .to_vec(),
+            numeric: vec![false, true, true, false, false],
+            rows: self
+                .payees
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.payee.clone(),
+                        p.count.to_string(),
+                        formatter.format(&p.total),
+                        formatter.format_date(p.first_date),
+                        formatter.format_date(p.last_date),
+                    ]
+                })
+                .collect(),
+        };
+        match format {
+            UncategorizedFormat::Text => write_text("Uncategorized", &[table], writer),
+            UncategorizedFormat::Markdown => write_markdown("Uncategorized", &[table], writer),
+            UncategorizedFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writeln!(writer)?;
+                Ok(())
+            }
+            UncategorizedFormat::Matchers => self.write_matchers(formatter, writer),
+        }
+    }
+
+    /// Write a yaml category per payee which can be pasted into a categorizer config
+    ///
+    /// Each category has a transaction matcher with a case insensitive regex requiring the payee
+    /// words in order. Payees with only negative or only positive transactions are also limited
+    /// to that sign.
+    fn write_matchers<W: Write>(&self, formatter: &Formatter, mut writer: W) -> Result<(), Error> {
+        for payee in &self.payees {
+            writeln!(
+                writer,
+                "# count: {}, total: {}",
+                payee.count,
+                formatter.format(&payee.total)
+            )?;
+            writeln!(writer, "- category: {}", yaml_string(&payee.payee))?;
+            writeln!(writer, "  children: !transaction_matchers")?;
+            writeln!(
+                writer,
+                "    - description: {}",
+                yaml_string(&payee_regex(&payee.payee))
+            )?;
+            if payee.max_amount <= BigDecimal::zero() {
+                writeln!(writer, "      max: 0")?;
+            } else if payee.min_amount >= BigDecimal::zero() {
+                writeln!(writer, "      min: 0")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    fs, io, iter,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::{
+    import::{self, parse_ofx, parse_qif, ImportFormat},
+    transaction::{self, Transaction, TransactionParser},
+};
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// failed to read {0}
+    Read(PathBuf, #[source] io::Error),
+    /// transaction
+    Transaction(#[from] transaction::Error),
+    /// import {0}: {1}
+    Import(PathBuf, #[source] import::Error),
+    /// failed to run `{0}`
+    Command(String, #[source] io::Error),
+    /// `{0}` failed: {1}
+    CommandFailed(String, String),
+    /// source {0}
+    Source(String, #[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Anything transactions can be read from (ie transaction files, the store, or a program
+/// downloading from a bank api) so every source plugs into the same pipeline
+pub trait TransactionSource {
+    /// Name of the source for errors and logs (ie the command)
+    fn name(&self) -> String;
+
+    /// Read every transaction of the source
+    fn fetch(&self) -> Result<Vec<Transaction>, Error>;
+}
+
+/// Registered sources read as one list of transactions
+#[derive(Default)]
+pub struct TransactionSources<'a> {
+    sources: Vec<Box<dyn TransactionSource + 'a>>,
+}
+
+impl<'a> TransactionSources<'a> {
+    /// Add a source read after the already registered sources
+    pub fn register(&mut self, source: impl TransactionSource + 'a) -> &mut Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Names of the registered sources in registration order
+    pub fn names(&self) -> Vec<String> {
+        self.sources.iter().map(|source| source.name()).collect()
+    }
+}
+
+impl TransactionSource for TransactionSources<'_> {
+    fn name(&self) -> String {
+        self.names().join(", ")
+    }
+
+    /// Read the sources in registration order, merging transactions in more than one source (ie a
+    /// download which is also in the store)
+    fn fetch(&self) -> Result<Vec<Transaction>, Error> {
+        let mut batches = Vec::new();
+        for source in &self.sources {
+            let transactions = source.fetch()?;
+            tracing::debug!(
+                source = %source.name(),
+                transactions = transactions.len(),
+                "fetched transactions"
+            );
+            batches.push(transactions);
+        }
+        Ok(Transaction::merge(batches.iter().map(Vec::as_slice)))
+    }
+}
+
+/// Transaction files, csv files parsed with the transaction parser and OFX and QIF files detected
+/// by `ImportFormat::detect`
+pub struct TransactionFiles<'a> {
+    transaction_parser: &'a TransactionParser,
+    paths: Vec<PathBuf>,
+}
+
+impl TransactionParser {
+    /// Source of the transaction files parsed with this transaction parser
+    pub fn files(&self, paths: impl IntoIterator<Item = PathBuf>) -> TransactionFiles<'_> {
+        TransactionFiles {
+            transaction_parser: self,
+            paths: paths.into_iter().collect(),
+        }
+    }
+
+    /// Sources with the transaction files parsed with this transaction parser registered, more
+    /// sources can be registered before fetching
+    pub fn sources(&self, paths: impl IntoIterator<Item = PathBuf>) -> TransactionSources<'_> {
+        let mut sources = TransactionSources::default();
+        sources.register(self.files(paths));
+        sources
+    }
+}
+
+impl TransactionSource for TransactionFiles<'_> {
+    fn name(&self) -> String {
+        format!("{} transaction files", self.paths.len())
+    }
+
+    /// Parse the files in order like `TransactionParser::parse_csvs`, without merging them
+    fn fetch(&self) -> Result<Vec<Transaction>, Error> {
+        let mut transactions = Vec::new();
+        for path in &self.paths {
+            let contents = fs::read(path).map_err(|e| Error::Read(path.clone(), e))?;
+            transactions.extend(self.parse(path, contents)?);
+        }
+        Ok(transactions)
+    }
+}
+
+impl TransactionFiles<'_> {
+    /// Lazily parse the files in order like `fetch`
+    ///
+    /// Files with a `.csv` extension are streamed a row at a time like
+    /// `TransactionParser::parse_csvs_iter`, other files are read whole when the iterator reaches
+    /// them so their format can be detected.
+    pub fn iter(&self) -> impl Iterator<Item = Result<Transaction, Error>> + '_ {
+        self.paths.iter().flat_map(move |path| {
+            let is_csv = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+            let transactions: Box<dyn Iterator<Item = _>> = if is_csv {
+                Box::new(
+                    self.transaction_parser
+                        .parse_csvs_iter(iter::once(path.as_path()))
+                        .map(|transaction| Ok(transaction?)),
+                )
+            } else {
+                match fs::read(path)
+                    .map_err(|e| Error::Read(path.clone(), e))
+                    .and_then(|contents| self.parse(path, contents))
+                {
+                    Ok(transactions) => Box::new(transactions.into_iter().map(Ok)),
+                    Err(e) => Box::new(iter::once(Err(e))),
+                }
+            };
+            transactions
+        })
+    }
+
+    fn parse(&self, path: &Path, contents: Vec<u8>) -> Result<Vec<Transaction>, Error> {
+        let text = String::from_utf8_lossy(&contents);
+        let import_failed = |e| Error::Import(path.into(), e);
+        match ImportFormat::detect(path, &text) {
+            ImportFormat::Csv => Ok(self.transaction_parser.parse_csv_buffer(path, contents)?),
+            ImportFormat::Ofx => parse_ofx(path, &text).map_err(import_failed),
+            ImportFormat::Qif => parse_qif(path, &text).map_err(import_failed),
+        }
+    }
+}
+
+/// Transactions a shell command writes to stdout as csv in the format of
+/// `Transaction::from_csv_buffer` (ie a script downloading from a bank api)
+#[derive(Clone, Debug)]
+pub struct CommandSource {
+    command: String,
+}
+
+impl CommandSource {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+impl TransactionSource for CommandSource {
+    fn name(&self) -> String {
+        self.command.clone()
+    }
+
+    fn fetch(&self) -> Result<Vec<Transaction>, Error> {
+        let output = if cfg!(windows) {
+            Command::new("cmd").arg("/C").arg(&self.command).output()
+        } else {
+            Command::new("sh").arg("-c").arg(&self.command).output()
+        }
+        .map_err(|e| Error::Command(self.command.clone(), e))?;
+        if !output.status.success() {
+            // The error the command printed, or its exit status if it printed nothing
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let reason = if stderr.is_empty() {
+                output.status.to_string()
+            } else {
+                stderr
+            };
+            return Err(Error::CommandFailed(self.command.clone(), reason));
+        }
+        Ok(Transaction::from_csv_buffer(output.stdout)?)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl TransactionSource for crate::store::Store {
+    fn name(&self) -> String {
+        String::from("store")
+    }
+
+    fn fetch(&self) -> Result<Vec<Transaction>, Error> {
+        self.transactions()
+            .map_err(|e| Error::Source(self.name(), Box::new(e)))
+    }
+}
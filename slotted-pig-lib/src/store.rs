@@ -0,0 +1,230 @@
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, SecondsFormat, Utc};
+use displaydoc::Display;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(feature = "encryption")]
+use crate::encryption::{self, Passphrase};
+use crate::transaction::{Transaction, TransactionKey};
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// bigdecimal
+    BigDecimal(#[from] bigdecimal::ParseBigDecimalError),
+    /// chrono
+    Chrono(#[from] chrono::ParseError),
+    /// sqlite
+    Sqlite(#[from] rusqlite::Error),
+    /// encryption
+    #[cfg(feature = "encryption")]
+    Encryption(#[from] encryption::Error),
+    /// json
+    #[cfg(feature = "encryption")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Schema of the store, created when a store is opened
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS transactions (
+    id INTEGER PRIMARY KEY,
+    time TEXT NOT NULL,
+    account TEXT NOT NULL,
+    description TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    source TEXT
+);
+CREATE INDEX IF NOT EXISTS transactions_time ON transactions(time);
+";
+
+/// Transactions persisted in a SQLite database so transaction files can be imported as they are
+/// downloaded instead of kept forever
+///
+/// An encrypted store (see `Store::open_encrypted`) is not a SQLite file, it is the transactions
+/// serialized as JSON and encrypted with the passphrase.
+pub struct Store {
+    connection: Connection,
+    /// File the transactions are encrypted to after every import of an encrypted store
+    #[cfg(feature = "encryption")]
+    encrypted: Option<(std::path::PathBuf, Passphrase)>,
+}
+
+/// Number of transactions added to and skipped by a store import
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Imported {
+    /// New transactions added to the store
+    pub added: u64,
+    /// Transactions already in the store
+    pub skipped: u64,
+}
+
+impl Store {
+    /// Open the store, creating the database file and schema if needed
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a store which only lives in memory
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Open a store encrypted at rest with the passphrase, creating it on the first import
+    ///
+    /// The file is a JSON array of the transactions encrypted with the passphrase. It is decrypted
+    /// into an in-memory SQLite database so the financial history never touches the disk
+    /// unencrypted, and the whole file is rewritten after every import.
+    #[cfg(feature = "encryption")]
+    pub fn open_encrypted<P: AsRef<Path>>(path: P, passphrase: Passphrase) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut store = Self::open_in_memory()?;
+        if path.exists() {
+            let transactions: Vec<EncryptedTransaction> =
+                serde_json::from_slice(&encryption::read(path, &passphrase)?)?;
+            let transactions = transactions
+                .into_iter()
+                .map(Transaction::from)
+                .collect::<Vec<_>>();
+            store.import(&transactions)?;
+        }
+        store.encrypted = Some((path.to_path_buf(), passphrase));
+        Ok(store)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, Error> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection,
+            #[cfg(feature = "encryption")]
+            encrypted: None,
+        })
+    }
+
+    /// Add the transactions which are not already in the store
+    ///
+    /// Transactions are the same if their time, account, description, and amount are equal.
+    /// Identical transactions are counted so importing a file with two identical purchases adds
+    /// both, but importing it again (or a file overlapping it) adds neither.
+    pub fn import<'a>(
+        &mut self,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) -> Result<Imported, Error> {
+        let mut stored = HashMap::<TransactionKey, u64>::new();
+        for transaction in self.transactions()? {
+            *stored.entry(TransactionKey::new(&transaction)).or_default() += 1;
+        }
+
+        let mut imported = Imported::default();
+        let sql_transaction = self.connection.transaction()?;
+        {
+            let mut insert = sql_transaction.prepare(
+                "INSERT INTO transactions (time, account, description, amount, source)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for transaction in transactions {
+                let remaining = stored.entry(TransactionKey::new(transaction)).or_default();
+                if *remaining > 0 {
+                    *remaining -= 1;
+                    imported.skipped += 1;
+                    continue;
+                }
+                insert.execute(params![
+                    format_time(&transaction.time),
+                    transaction.account,
+                    transaction.description,
+                    transaction.amount.to_string(),
+                    transaction
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string_lossy()),
+                ])?;
+                imported.added += 1;
+            }
+        }
+        sql_transaction.commit()?;
+        #[cfg(feature = "encryption")]
+        if let Some((path, passphrase)) = &self.encrypted {
+            let transactions = self
+                .transactions()?
+                .into_iter()
+                .map(EncryptedTransaction::from)
+                .collect::<Vec<_>>();
+            encryption::write(path, passphrase, &serde_json::to_vec(&transactions)?)?;
+        }
+        Ok(imported)
+    }
+
+    /// Every transaction in the store in the order it was imported
+    pub fn transactions(&self) -> Result<Vec<Transaction>, Error> {
+        let mut select = self.connection.prepare(
+            "SELECT time, account, description, amount, source FROM transactions ORDER BY id",
+        )?;
+        let rows = select.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        let mut transactions = Vec::new();
+        for row in rows {
+            let (time, account, description, amount, source) = row?;
+            transactions.push(Transaction {
+                amount: BigDecimal::from_str(&amount)?,
+                account,
+                description,
+                time: DateTime::parse_from_rfc3339(&time)?.with_timezone(&Utc),
+                source: source.map(Into::into),
+            });
+        }
+        Ok(transactions)
+    }
+}
+
+/// Transaction of an encrypted store file, unlike a serialized `Transaction` the source is kept
+#[cfg(feature = "encryption")]
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct EncryptedTransaction {
+    amount: BigDecimal,
+    account: String,
+    description: String,
+    time: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "encryption")]
+impl From<Transaction> for EncryptedTransaction {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            amount: transaction.amount,
+            account: transaction.account,
+            description: transaction.description,
+            time: transaction.time,
+            source: transaction.source,
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl From<EncryptedTransaction> for Transaction {
+    fn from(transaction: EncryptedTransaction) -> Self {
+        Self {
+            amount: transaction.amount,
+            account: transaction.account,
+            description: transaction.description,
+            time: transaction.time,
+            source: transaction.source,
+        }
+    }
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
@@ -1,15 +1,1077 @@
 use anyhow::Result;
-use insta::assert_yaml_snapshot;
+use insta::{assert_snapshot, assert_yaml_snapshot};
 use test_case::test_case;
 
-use crate::{categorizer::Categorizer, transaction::Transaction};
+use std::{path::Path, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+
+use crate::{
+    categorizer::{
+        CategorizedList, Categorizer, Error, MatcherTest, TransactionMatcher, TransactionSort,
+    },
+    config::{Config, ConfigKind},
+    edit::add_transaction_matcher,
+    export::{OfxExporter, QifExporter},
+    generate::{write_csv, Generator, CATEGORIZER_YAML, TRANSACTION_PARSER_YAML},
+    import::{parse_ofx, parse_qif, ImportFormat},
+    overrides::TransactionOverrides,
+    query::Query,
+    redact::Redactor,
+    transaction::{Transaction, TransactionParser},
+    validate::{validate_categorizer, validate_config, validate_transaction_parser},
+    warning::Warning,
+};
 
 #[test_case("tests/categorizer_empty.yaml", "tests/transactions_empty.csv", "empty"; "empty")]
 #[test_case("tests/categorizer_simple.yaml", "tests/transactions_simple.csv", "simple"; "simple")]
+#[test_case("tests/categorizer_rounding.yaml", "tests/transactions_rounding.csv", "rounding"; "rounding")]
 fn test_categorizer(categorizer: &str, transactions: &str, name: &str) -> Result<()> {
     let categorizer = Categorizer::from_yaml_file(categorizer)?;
     let transactions = Transaction::from_csv_file(transactions)?;
-    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let (mut categorized, _uncategorized) = categorizer.categorize(&transactions);
+    categorizer.round_totals(&mut categorized);
     assert_yaml_snapshot!(name, categorized);
     Ok(())
 }
+
+#[test]
+fn test_categorize_keeps_exact_totals() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_rounding.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_rounding.csv")?;
+    let (mut categorized, _uncategorized) = categorizer.categorize(&transactions);
+    assert_eq!(categorized.categorized[0].total, "-30.1250".parse()?);
+    let diff = categorized.diff(&CategorizedList::default());
+    assert_eq!(diff[0].current, "-30.1250".parse()?);
+    categorizer.round_totals(&mut categorized);
+    assert_eq!(categorized.categorized[0].total, "-30.13".parse()?);
+    Ok(())
+}
+
+#[test_case(&["income"], &[], "prune_only"; "only")]
+#[test_case(&[], &["expenses:store"], "prune_exclude"; "exclude")]
+#[test_case(&["expenses:store", "income"], &["income:paycheck"], "prune_only_exclude"; "only and exclude")]
+fn test_prune(only: &[&str], exclude: &[&str], name: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (mut categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let strings = |paths: &[&str]| paths.iter().map(ToString::to_string).collect::<Vec<_>>();
+    categorized.prune(&strings(only), &strings(exclude));
+    assert_yaml_snapshot!(name, categorized);
+    Ok(())
+}
+
+#[test]
+fn test_retain_transactions() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (mut categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let store2 = BigDecimal::from(-20);
+    categorized.retain_transactions(|t| t.amount == store2);
+    assert_yaml_snapshot!("retain_transactions", categorized);
+    Ok(())
+}
+
+#[test_case("tests/categorizer_empty.yaml", "tests/transactions_empty.csv"; "empty")]
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_simple.csv"; "simple")]
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_uncategorized.csv"; "uncategorized")]
+fn test_assign_matches_categorize(categorizer: &str, transactions: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file(categorizer)?;
+    let transactions = Transaction::from_csv_file(transactions)?;
+    let (categorized, uncategorized) = categorizer.categorize(&transactions);
+    let assignments = categorizer.assign(&transactions);
+    let assigned_uncategorized = assignments
+        .iter()
+        .filter(|a| !a.is_categorized())
+        .map(|a| a.transaction)
+        .collect::<Vec<_>>();
+    let assigned_count = assignments.iter().filter(|a| a.is_categorized()).count() as u64;
+    assert_eq!(assigned_uncategorized, uncategorized);
+    assert_eq!(
+        assigned_count,
+        categorized.categorized.iter().map(|c| c.count).sum::<u64>()
+    );
+    Ok(())
+}
+
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_simple.csv"; "simple")]
+#[test_case("tests/categorizer_budgets.yaml", "tests/transactions_months.csv"; "budgets")]
+fn test_categorized_assignments(categorizer: &str, transactions: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file(categorizer)?;
+    let transactions = Transaction::from_csv_file(transactions)?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let mut expected = categorizer
+        .assign(&transactions)
+        .into_iter()
+        .filter(|a| a.is_categorized())
+        .map(|a| (a.transaction.clone(), a.category_path_joined(":")))
+        .collect::<Vec<_>>();
+    let mut assigned = categorized
+        .assignments()
+        .into_iter()
+        .map(|a| (a.transaction.clone(), a.category_path_joined(":")))
+        .collect::<Vec<_>>();
+    expected.sort_by_key(|(t, _)| t.time);
+    assigned.sort_by_key(|(t, _)| t.time);
+    assert_eq!(assigned, expected);
+    Ok(())
+}
+
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_simple.csv"; "simple")]
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_uncategorized.csv"; "uncategorized")]
+#[test_case("tests/categorizer_budgets.yaml", "tests/transactions_months.csv"; "budgets")]
+fn test_redact_preserves_categories(categorizer: &str, transactions: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file(categorizer)?;
+    let transactions = Transaction::from_csv_file(transactions)?;
+    let redactor = Redactor {
+        salt: String::from("salt"),
+        ..Default::default()
+    };
+    let (redacted_categorizer, redacted_transactions) =
+        redactor.redact(&categorizer, &transactions);
+    let (categorized, uncategorized) = categorizer.categorize(&transactions);
+    let (redacted_categorized, redacted_uncategorized) =
+        redacted_categorizer.categorize(&redacted_transactions);
+    assert_eq!(
+        serde_yaml::to_string(&categorized.diff(&redacted_categorized))?,
+        serde_yaml::to_string(&categorized.diff(&categorized))?
+    );
+    assert_eq!(uncategorized.len(), redacted_uncategorized.len());
+    for transaction in &redacted_transactions {
+        assert!(transaction.description.starts_with("payee-"));
+        assert!(transaction.account.starts_with("account-"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_redact() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let redactor = Redactor {
+        salt: String::from("salt"),
+        amount_scale: Some(BigDecimal::from(3)),
+        amount_bucket: Some(BigDecimal::from(5)),
+    };
+    let (redacted_categorizer, redacted_transactions) =
+        redactor.redact(&categorizer, &transactions);
+    assert_yaml_snapshot!("redact_categorizer", redacted_categorizer);
+    assert_yaml_snapshot!("redact_transactions", redacted_transactions);
+    Ok(())
+}
+
+#[cfg(feature = "email")]
+#[test]
+fn test_email_message() -> Result<()> {
+    use crate::{
+        email::EmailConfig,
+        report::{Digest, DigestPeriod},
+        util::Formatter,
+    };
+
+    let config = EmailConfig::from_yaml_buffer(
+        r#"
+smtp:
+  host: smtp.example.com
+  username: pig
+  password_env: SLOTTED_PIG_SMTP_PASSWORD
+from: Slotted Pig <pig@example.com>
+to:
+  - me@example.com
+"#,
+    )?;
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_budgets.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_digest.csv")?;
+    let assignments = categorizer.assign(&transactions);
+    let digest = Digest::new(
+        &categorizer,
+        &assignments,
+        DigestPeriod::Weekly,
+        NaiveDate::from_ymd_opt(2024, 2, 24).expect("valid date"),
+        &BigDecimal::from(3),
+    );
+    let message = config.message(&digest, &Formatter::default())?;
+    let message = String::from_utf8(message.formatted())?;
+    assert!(message.contains("Subject: Slotted Pig: Weekly digest for 2024-02-18 to 2024-02-24"));
+    assert!(message.contains("To: me@example.com"));
+    assert!(message.contains("Content-Type: text/html"));
+    assert!(message.contains("Unusual transactions"));
+    Ok(())
+}
+
+#[test_case("amount < 0", &["store1", "store2"]; "amount")]
+#[test_case("amount <= -10 && amount > -20", &["store1"]; "and")]
+#[test_case("description =~ \"1$\" || account == \"checking\"", &["store1", "paycheck"]; "or")]
+#[test_case("!(time >= 2024-02-23)", &["paycheck"]; "not time")]
+#[test_case("category =~ \"^expenses\" && description != \"store2\"", &["store1"]; "category")]
+#[test_case("category == \"\"", &[]; "uncategorized")]
+fn test_query(query: &str, expected: &[&str]) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let query = Query::parse(query)?;
+    let matched = categorizer
+        .assign(&transactions)
+        .iter()
+        .filter(|a| query.matches(a.transaction, a.category_path_joined(":").as_deref()))
+        .map(|a| a.transaction.description.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(matched, expected);
+    Ok(())
+}
+
+#[test_case(TransactionSort::TimeAscending, &["paycheck", "store2", "store1"]; "time ascending")]
+#[test_case(TransactionSort::AmountDescending, &["paycheck", "store1", "store2"]; "amount descending")]
+#[test_case(TransactionSort::AbsoluteAmountDescending, &["store2", "store1", "paycheck"]; "absolute amount descending")]
+fn test_transaction_sort(sort: TransactionSort, expected: &[&str]) -> Result<()> {
+    let mut transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    sort.sort(&mut transactions);
+    let sorted = transactions
+        .iter()
+        .map(|t| t.description.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(sorted, expected);
+    Ok(())
+}
+
+#[test_case("amount <", "expected a value at position 8"; "missing value")]
+#[test_case("amount =~ \"x\"", "`amount` cannot be compared with `=~` to a string"; "regex amount")]
+#[test_case("payee == \"x\"", "unknown field `payee`, expected amount, account, description, time, or category"; "unknown field")]
+#[test_case("time > 2024-13-01", "invalid number or date `2024-13-01` at position 7"; "invalid date")]
+#[test_case("(amount > 0", "expected `)` at position 11"; "unclosed")]
+#[test_case("amount > 0 amount < 5", "expected `&&`, `||`, or the end at position 11"; "trailing")]
+#[test_case("description == \"x", "unterminated string starting at position 15"; "unterminated")]
+fn test_query_invalid(query: &str, expected: &str) {
+    let error = Query::parse(query).expect_err("invalid query");
+    assert_eq!(error.to_string(), expected);
+}
+
+#[test]
+fn test_matcher_tests() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_tests.yaml")?;
+    let mut outcomes = categorizer.run_tests();
+    for test in MatcherTest::from_csv_file("tests/transactions_tests.csv")? {
+        outcomes.push(categorizer.test(&test));
+    }
+    let lines = outcomes
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!("matcher_tests", lines);
+    assert_eq!(outcomes.iter().filter(|o| !o.passed()).count(), 1);
+    Ok(())
+}
+
+#[test_case("tests/categorizer_simple.yaml", "validate_categorizer_valid"; "valid")]
+#[test_case("tests/categorizer_invalid_regex.yaml", "validate_categorizer_invalid_regex"; "invalid regex")]
+#[test_case("tests/categorizer_unknown_field.yaml", "validate_categorizer_unknown_field"; "unknown field")]
+#[test_case("tests/categorizer_problems.yaml", "validate_categorizer_problems"; "problems")]
+fn test_validate_categorizer(path: &str, name: &str) -> Result<()> {
+    let issues = validate_categorizer(&std::fs::read_to_string(path)?);
+    let issues = issues.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_yaml_snapshot!(name, issues);
+    Ok(())
+}
+
+#[test_case("tests/transaction_parser.yaml", "validate_transaction_parser_valid"; "valid")]
+#[test_case("tests/transaction_parser_problems.yaml", "validate_transaction_parser_problems"; "problems")]
+fn test_validate_transaction_parser(path: &str, name: &str) -> Result<()> {
+    let issues = validate_transaction_parser(&std::fs::read_to_string(path)?);
+    let issues = issues.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_yaml_snapshot!(name, issues);
+    Ok(())
+}
+
+#[test_case("tests/config.yaml", "validate_config_valid"; "valid")]
+#[test_case("tests/config_problems.yaml", "validate_config_problems"; "problems")]
+fn test_validate_config(path: &str, name: &str) -> Result<()> {
+    let issues = validate_config(&std::fs::read_to_string(path)?);
+    let issues = issues.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_yaml_snapshot!(name, issues);
+    Ok(())
+}
+
+#[test]
+fn test_config_matches_separate_files() -> Result<()> {
+    let config = Config::from_yaml_file("tests/config.yaml")?;
+    let separate = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let pattern = config
+        .transaction_path_pattern
+        .as_deref()
+        .unwrap_or_default();
+    let transactions = config
+        .transaction_parser
+        .parse_csv(&std::path::Path::new("tests").join(pattern))?;
+    assert_eq!(
+        config.categorizer.categorize(&transactions),
+        separate.categorize(&transactions)
+    );
+    Ok(())
+}
+
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_simple.csv"; "simple")]
+#[test_case("tests/categorizer_simple.yaml", "tests/transactions_uncategorized.csv"; "uncategorized")]
+#[test_case("tests/categorizer_rounding.yaml", "tests/transactions_rounding.csv"; "rounding")]
+fn test_explain_matches_assign(categorizer: &str, transactions: &str) -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file(categorizer)?;
+    let transactions = Transaction::from_csv_file(transactions)?;
+    for transaction in &transactions {
+        let explanation = categorizer.explain(transaction);
+        let assignment = categorizer.assign_transaction(transaction);
+        assert_eq!(explanation.passes_filters(), assignment.is_some());
+        assert_eq!(
+            explanation.assigned().map(|c| c.category_path.clone()),
+            assignment.and_then(|a| a.category_path)
+        );
+        for candidate in &explanation.candidates {
+            assert_eq!(
+                candidate.matcher.matches(transaction),
+                candidate.matcher.mismatches(transaction).is_empty()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_explain() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_buffer(
+        r#"
+transaction_filters:
+  - account: checking
+  - account: credit card
+categories:
+  - category: expenses
+    children: !subcategories
+      - category: coffee
+        children: !transaction_matchers
+          - description: (?i)starbucks
+            min: -3
+      - category: food
+        children: !transaction_matchers
+          - description: (?i)starbucks|grocer
+            max: 0
+      - category: other
+        children: !transaction_matchers
+          - max: 0
+  - category: income
+    children: !transaction_matchers
+      - min: 0
+        end: 2024-01-01T00:00:00Z
+"#,
+    )?;
+    let transaction = Transaction {
+        amount: "-4.50".parse()?,
+        account: String::from("credit card"),
+        description: String::from("STARBUCKS 123"),
+        time: "2024-02-24T20:10:59Z".parse()?,
+        source: None,
+    };
+    assert_snapshot!(categorizer.explain(&transaction).to_string());
+    Ok(())
+}
+
+#[test_case(&["expenses", "coffee & tea"], "edit_indented"; "indented")]
+#[test_case(&["expenses", "groceries"], "edit_same_indentation"; "same indentation")]
+#[test_case(&["expenses", "new"], "edit_empty"; "empty")]
+fn test_add_transaction_matcher(category_path: &[&str], name: &str) -> Result<()> {
+    let yaml = std::fs::read_to_string("tests/categorizer_edit.yaml")?;
+    let matcher = serde_yaml::from_str::<TransactionMatcher>(
+        r#"
+description: '(?i)\bblue\b.*\bbottle\b'
+max: -0.5
+account: checking
+"#,
+    )?;
+    let edited = add_transaction_matcher(&yaml, category_path, &matcher)?;
+    assert_snapshot!(name, edited);
+
+    let transactions = Transaction::from_csv_buffer(
+        "amount,account,description,time\n-4.50,checking,BLUE BOTTLE 12,2024-02-24T08:00:00Z\n",
+    )?;
+    let categorizer = Categorizer::from_yaml_buffer(&edited)?;
+    let assignment = categorizer.assign(&transactions).remove(0);
+    assert_eq!(assignment.category_path.as_deref(), Some(category_path));
+    Ok(())
+}
+
+#[test_case(&["expenses", "missing"], "category `expenses:missing` was not found, categories must be written in block style"; "missing")]
+#[test_case(&["expenses"], "category `expenses` has subcategories instead of transaction matchers"; "not leaf")]
+fn test_add_transaction_matcher_error(category_path: &[&str], expected_error: &str) -> Result<()> {
+    let yaml = std::fs::read_to_string("tests/categorizer_edit.yaml")?;
+    let matcher = serde_yaml::from_str::<TransactionMatcher>("min: 0")?;
+    let error = add_transaction_matcher(&yaml, category_path, &matcher)
+        .expect_err("category cannot be edited");
+    assert_eq!(error.to_string(), expected_error);
+    Ok(())
+}
+
+#[test_case("-4.50", "BLUE BOTTLE 12", "max<=0 description=~(?i)\\bblue\\b.*\\bbottle\\b"; "payee")]
+#[test_case("100", "DEPOSIT", "min>=0 description=~(?i)\\bdeposit\\b"; "income")]
+#[test_case("-20", "#1234", "max<=0 description=~^\\#1234$"; "no payee")]
+fn test_transaction_matcher_for_payee(
+    amount: &str,
+    description: &str,
+    expected: &str,
+) -> Result<()> {
+    let transactions = Transaction::from_csv_buffer(format!(
+        "amount,account,description,time\n{amount},checking,{description},2024-02-24T08:00:00Z\n"
+    ))?;
+    let matcher = TransactionMatcher::for_payee(&transactions[0]);
+    assert_eq!(matcher.to_string(), expected);
+    assert!(matcher.matches(&transactions[0]));
+    Ok(())
+}
+
+#[test]
+fn test_transaction_matcher_for_transaction() -> Result<()> {
+    let transactions = Transaction::from_csv_buffer(
+        "amount,account,description,time\n\
+         -4.50,checking,BLUE BOTTLE 12,2024-02-24T08:00:00Z\n\
+         -4.50,checking,BLUE BOTTLE 12,2024-02-25T08:00:00Z\n\
+         -4.50,savings,BLUE BOTTLE 12,2024-02-24T08:00:00Z\n",
+    )?;
+    let matcher = TransactionMatcher::for_transaction(&transactions[0]);
+    assert!(matcher.matches(&transactions[0]));
+    assert!(!matcher.matches(&transactions[1]));
+    assert!(!matcher.matches(&transactions[2]));
+    Ok(())
+}
+
+#[test]
+fn test_merge_transactions() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let mut first = transactions[..2].to_vec();
+    first.push(transactions[0].clone());
+    // Overlaps the first batch with one of the identical purchases and a new transaction
+    let second = vec![
+        transactions[0].clone(),
+        transactions[1].clone(),
+        transactions[2].clone(),
+    ];
+    let merged = Transaction::merge([first.as_slice(), second.as_slice()]);
+    assert_eq!(
+        merged,
+        vec![
+            transactions[0].clone(),
+            transactions[1].clone(),
+            transactions[0].clone(),
+            transactions[2].clone(),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_category_styles() -> Result<()> {
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_styles.yaml")?;
+    assert_yaml_snapshot!(categorizer.category_styles());
+    Ok(())
+}
+
+#[test]
+fn test_transaction_overrides() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let mut overrides = TransactionOverrides::default();
+    let edited = Transaction {
+        description: String::from("Fixed description"),
+        amount: BigDecimal::from(-25),
+        ..transactions[1].clone()
+    };
+    overrides.set(&transactions[1], &edited);
+    assert_yaml_snapshot!(overrides);
+
+    let applied = overrides.apply(&transactions);
+    assert_eq!(applied[0], transactions[0]);
+    assert_eq!(applied[1], edited);
+    assert_eq!(overrides.original(&applied[1]), transactions[1]);
+    assert_eq!(overrides.original(&applied[0]), transactions[0]);
+
+    // Editing again replaces the override and restoring every field removes it
+    overrides.set(&transactions[1], &transactions[1]);
+    assert!(overrides.overrides.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_warnings() -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    assert_eq!(categorizer.warnings(&transactions), []);
+    let filtering = Categorizer::from_yaml_buffer(
+        "transaction_filters:\n  - account: savings\ncategories: []\n",
+    )?;
+    assert_eq!(
+        filtering.warnings(&transactions),
+        [Warning::EveryTransactionFiltered {
+            count: transactions.len()
+        }]
+    );
+
+    let mut overrides = TransactionOverrides::default();
+    let edited = Transaction {
+        description: String::from("Fixed description"),
+        ..transactions[1].clone()
+    };
+    overrides.set(&transactions[1], &edited);
+    assert_eq!(overrides.warnings(&transactions), []);
+    assert_eq!(
+        overrides.warnings(&transactions[..1]),
+        [Warning::UnmatchedOverride {
+            description: transactions[1].description.clone(),
+            time: transactions[1].time,
+        }]
+    );
+    Ok(())
+}
+
+#[test_case(ImportFormat::Ofx; "ofx")]
+#[test_case(ImportFormat::Qif; "qif")]
+fn test_import_round_trip(format: ImportFormat) -> Result<()> {
+    let transactions = Transaction::from_csv_file("tests/transactions_uncategorized.csv")?;
+    let mut output = Vec::new();
+    let imported = match format {
+        ImportFormat::Ofx => {
+            OfxExporter::default().write(&transactions, &mut output)?;
+            parse_ofx(Path::new("export.ofx"), &String::from_utf8(output)?)?
+        }
+        ImportFormat::Qif => {
+            QifExporter::default().write(&transactions, &mut output)?;
+            parse_qif(Path::new("export.qif"), &String::from_utf8(output)?)?
+        }
+        ImportFormat::Csv => unreachable!(),
+    };
+    assert_eq!(imported.len(), transactions.len());
+    for transaction in &transactions {
+        assert!(imported.iter().any(|t| t.amount == transaction.amount
+            && t.account == transaction.account
+            && t.description == transaction.description
+            && t.time.date_naive() == transaction.time.date_naive()));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_ofx_sgml() -> Result<()> {
+    let ofx = "OFXHEADER:100
+DATA:OFXSGML
+
+<OFX>
+<CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+<CCACCTFROM><ACCTID>1234 &amp; co
+</CCACCTFROM>
+<BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240224150000.000[-5:EST]
+<TRNAMT>-4.50
+<NAME>BLUE BOTTLE
+<MEMO>coffee
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240225
+<TRNAMT>100
+<MEMO>REFUND
+</STMTTRN>
+</BANKTRANLIST>
+</CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+</OFX>
+";
+    let transactions = parse_ofx(Path::new("card.qfx"), ofx)?;
+    assert_eq!(
+        transactions,
+        Transaction::from_csv_buffer(
+            "amount,account,description,time
+-4.50,1234 & co,BLUE BOTTLE,2024-02-24T20:00:00Z
+100,1234 & co,REFUND,2024-02-25T00:00:00Z
+"
+        )?
+        .into_iter()
+        .map(|t| Transaction {
+            source: Some("card.qfx".into()),
+            ..t
+        })
+        .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_qif() -> Result<()> {
+    let qif = "!Type:CCard
+D2/24'24
+T-1,004.50
+PBLUE BOTTLE
+^
+D02/25/2024
+U100.00
+MREFUND
+^
+";
+    let transactions = parse_qif(Path::new("card.qif"), qif)?;
+    let expected = Transaction::from_csv_buffer(
+        "amount,account,description,time
+-1004.50,card,BLUE BOTTLE,2024-02-24T00:00:00Z
+100.00,card,REFUND,2024-02-25T00:00:00Z
+",
+    )?;
+    assert_eq!(transactions.len(), expected.len());
+    for (transaction, expected) in transactions.iter().zip(&expected) {
+        assert_eq!(transaction.amount, expected.amount);
+        assert_eq!(transaction.account, expected.account);
+        assert_eq!(transaction.description, expected.description);
+        assert_eq!(transaction.time, expected.time);
+    }
+    Ok(())
+}
+
+#[test_case("statement.OFX", "", ImportFormat::Ofx; "ofx extension")]
+#[test_case("statement.qfx", "", ImportFormat::Ofx; "qfx extension")]
+#[test_case("statement.qif", "", ImportFormat::Qif; "qif extension")]
+#[test_case("statement.csv", "<OFX>", ImportFormat::Csv; "csv extension")]
+#[test_case("statement.txt", "OFXHEADER:100", ImportFormat::Ofx; "sniff ofx")]
+#[test_case("statement", "!Type:Bank", ImportFormat::Qif; "sniff qif")]
+#[test_case("statement.txt", "amount,account", ImportFormat::Csv; "sniff csv")]
+fn test_import_format_detect(path: &str, contents: &str, expected: ImportFormat) {
+    assert_eq!(ImportFormat::detect(Path::new(path), contents), expected);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_store_import() -> Result<()> {
+    use crate::store::{Imported, Store};
+
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let mut store = Store::open_in_memory()?;
+    assert_eq!(
+        store.import(&transactions)?,
+        Imported {
+            added: 3,
+            skipped: 0
+        }
+    );
+    // An overlapping import with an identical second purchase only adds the new purchase
+    let overlapping = [&transactions[0], &transactions[0], &transactions[1]];
+    assert_eq!(
+        store.import(overlapping)?,
+        Imported {
+            added: 1,
+            skipped: 2
+        }
+    );
+    let mut expected = transactions.clone();
+    expected.push(transactions[0].clone());
+    assert_eq!(store.transactions()?, expected);
+    Ok(())
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_encryption() -> Result<()> {
+    use crate::encryption::{self, Passphrase};
+
+    let passphrase = Passphrase::new("correct horse battery staple");
+    let ciphertext = encryption::encrypt(&passphrase, b"amount,account")?;
+    assert!(!ciphertext
+        .windows(b"account".len())
+        .any(|window| window == b"account"));
+    assert_eq!(
+        encryption::decrypt(&passphrase, &ciphertext)?,
+        b"amount,account"
+    );
+    assert!(matches!(
+        encryption::decrypt(&Passphrase::new("wrong"), &ciphertext),
+        Err(encryption::Error::Decrypt(_))
+    ));
+    assert!(encryption::is_encrypted(Path::new("categorizer.yaml.age")));
+    assert!(!encryption::is_encrypted(Path::new("categorizer.yaml")));
+    Ok(())
+}
+
+#[cfg(all(feature = "sqlite", feature = "encryption"))]
+#[test]
+fn test_store_encrypted() -> Result<()> {
+    use crate::{
+        encryption::Passphrase,
+        store::{Error, Store},
+    };
+
+    let path = std::env::temp_dir().join(format!("slotted-pig-{}.age", std::process::id()));
+    let passphrase = Passphrase::new("correct horse battery staple");
+    let mut transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    transactions[0].source = Some("statement.csv".into());
+    Store::open_encrypted(&path, passphrase.clone())?.import(&transactions)?;
+    let reopened = Store::open_encrypted(&path, passphrase)?.transactions();
+    let wrong = Store::open_encrypted(&path, Passphrase::new("wrong"));
+    std::fs::remove_file(&path)?;
+    let reopened = reopened?;
+    assert_eq!(reopened, transactions);
+    // The source is kept although it is not part of a serialized transaction
+    assert_eq!(reopened[0].source, transactions[0].source);
+    assert!(matches!(wrong, Err(Error::Encryption(_))));
+    Ok(())
+}
+
+#[test_case("tests/transactions_simple.csv"; "simple")]
+#[test_case("tests/transactions_payees.csv"; "payees")]
+fn test_anonymize_csv(path: &str) -> Result<()> {
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let redactor = Redactor {
+        salt: String::from("salt"),
+        ..Default::default()
+    };
+    let mut anonymized = Vec::new();
+    transaction_parser.map_csv(path.as_ref(), &mut anonymized, |column, value| {
+        redactor.anonymize(column, value)
+    })?;
+    let original = Transaction::from_csv_file(path)?;
+    let anonymized = Transaction::from_csv_buffer(anonymized)?;
+    assert_eq!(original.len(), anonymized.len());
+    for (original, anonymized) in original.iter().zip(&anonymized) {
+        assert_eq!(original.time, anonymized.time);
+        assert_ne!(original.description, anonymized.description);
+        assert_eq!(original.description.len(), anonymized.description.len());
+        assert_eq!(
+            original.amount.sign(),
+            anonymized.amount.sign(),
+            "{} became {}",
+            original.amount,
+            anonymized.amount
+        );
+        let ratio = &anonymized.amount / &original.amount;
+        assert!(ratio >= BigDecimal::from_str("0.79")? && ratio <= BigDecimal::from_str("1.21")?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_csv_buffer() -> Result<()> {
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let path = Path::new("tests/transactions_simple.csv");
+    let buffer = std::fs::read(path)?;
+    assert_eq!(
+        transaction_parser.parse_csv_buffer(path, buffer)?,
+        transaction_parser.parse_csv(path)?
+    );
+    assert!(transaction_parser
+        .parse_csv_buffer(Path::new("transactions.txt"), "")
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_csv_rows() -> Result<()> {
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let path = Path::new("tests/transactions_simple.csv");
+    let buffer = std::fs::read(path)?;
+    let rows = transaction_parser.parse_csv_rows(path, &buffer)?;
+    assert_eq!(
+        rows.iter()
+            .map(|r| r.transaction.clone())
+            .collect::<Vec<_>>(),
+        transaction_parser.parse_csv(path)?
+    );
+    assert_eq!(
+        rows[1].headers,
+        ["amount", "account", "description", "time"]
+    );
+    assert_eq!(
+        rows[1].values,
+        ["-20", "credit card", "store2", "2024-02-23T20:10:59Z"]
+    );
+    Ok(())
+}
+
+#[test_case(1; "one account")]
+#[test_case(4; "four accounts")]
+fn test_generate(accounts: usize) -> Result<()> {
+    let generator = Generator {
+        months: 3,
+        accounts,
+        start: NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date"),
+        seed: 7,
+    };
+    let transactions = generator.transactions();
+    assert_eq!(transactions, generator.transactions());
+    assert!(transactions
+        .iter()
+        .all(|t| generator.account_names().contains(&t.account)));
+    let categorizer = Categorizer::from_yaml_buffer(CATEGORIZER_YAML)?;
+    let (_categorized, uncategorized) = categorizer.categorize(&transactions);
+    assert!(uncategorized.is_empty(), "{uncategorized:?}");
+
+    TransactionParser::from_yaml_buffer(TRANSACTION_PARSER_YAML)?;
+    let mut csv = Vec::new();
+    write_csv(&transactions, &mut csv)?;
+    assert_eq!(Transaction::from_csv_buffer(&csv)?, transactions);
+    assert_yaml_snapshot!(format!("generate_{accounts}"), &transactions[..5]);
+    Ok(())
+}
+
+#[test]
+fn test_json_schema() {
+    assert_yaml_snapshot!(
+        "transaction_parser_schema",
+        ConfigKind::TransactionParser.json_schema()
+    );
+    let categorizer = ConfigKind::Categorizer.json_schema();
+    for definition in [
+        "Category",
+        "CategoryChildren",
+        "TransactionMatcher",
+        "Regex",
+        "Decimal",
+    ] {
+        assert!(
+            categorizer.definitions.contains_key(definition),
+            "{definition}"
+        );
+    }
+    let config = ConfigKind::Config.json_schema();
+    assert!(config.definitions.contains_key("TransactionParserCsv"));
+    assert!(config.definitions.contains_key("TransactionMatcher"));
+}
+
+#[test_case("tests/categorizer_unknown_field.yaml", "yaml_diagnostic_unknown_field"; "unknown field")]
+#[test_case("tests/categorizer_invalid_regex.yaml", "yaml_diagnostic_invalid_regex"; "invalid regex")]
+fn test_yaml_diagnostic(path: &str, name: &str) {
+    let error = Categorizer::from_yaml_file(path).unwrap_err();
+    let Error::Yaml(diagnostic) = error else {
+        panic!("expected a yaml diagnostic, got {error:?}");
+    };
+    let mut rendered = String::new();
+    miette::NarratableReportHandler::new()
+        .render_report(&mut rendered, &diagnostic)
+        .expect("rendering to a string does not fail");
+    assert_snapshot!(name, rendered);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_parse_csvs_async() -> Result<()> {
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let paths = [
+        "tests/transactions_simple.csv",
+        "tests/transactions_months.csv",
+        "tests/transactions_uncategorized.csv",
+    ]
+    .map(std::path::PathBuf::from);
+    assert_eq!(
+        transaction_parser
+            .parse_csvs_async(paths.iter().cloned())
+            .await?,
+        transaction_parser.parse_csvs(paths.iter().map(|p| p.as_path()))?
+    );
+    assert!(transaction_parser
+        .parse_csvs_async([std::path::PathBuf::from("tests/missing.csv")])
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_read_transaction_dir() -> Result<()> {
+    use std::sync::Arc;
+
+    use crate::fetch::{parse_transaction_file, read_transaction_dir};
+
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir("tests")? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".csv") {
+            names.push(name);
+        }
+    }
+    names.sort();
+    let batches = names
+        .iter()
+        .map(|name| {
+            let contents = std::fs::read_to_string(Path::new("tests").join(name))?;
+            Ok(parse_transaction_file(
+                Some(&transaction_parser),
+                name,
+                &contents,
+            )?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let transactions =
+        read_transaction_dir(Some(Arc::new(transaction_parser)), Path::new("tests")).await?;
+    assert_eq!(
+        transactions,
+        Transaction::merge(batches.iter().map(Vec::as_slice))
+    );
+    assert_eq!(
+        transactions[0].source.as_deref(),
+        Some(Path::new(names[0].as_str()))
+    );
+    assert!(read_transaction_dir(None, Path::new("tests/missing"))
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_transaction_sources() -> Result<()> {
+    use crate::source::{self, CommandSource, TransactionSource};
+
+    /// User provided source with the first transaction of the files and a new one
+    struct Pending(Vec<Transaction>);
+
+    impl TransactionSource for Pending {
+        fn name(&self) -> String {
+            String::from("pending")
+        }
+
+        fn fetch(&self) -> Result<Vec<Transaction>, source::Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    let transaction_parser = TransactionParser::from_yaml_file("tests/transaction_parser.yaml")?;
+    let paths = [
+        "tests/transactions_simple.csv",
+        "tests/transactions_months.csv",
+    ]
+    .map(std::path::PathBuf::from);
+    let parsed = transaction_parser.parse_csvs(paths.iter().map(|p| p.as_path()))?;
+    assert_eq!(transaction_parser.files(paths.clone()).fetch()?, parsed);
+
+    let ofx = std::env::temp_dir().join(format!("slotted-pig-{}.ofx", std::process::id()));
+    let mut output = Vec::new();
+    OfxExporter::default().write(&parsed, &mut output)?;
+    std::fs::write(&ofx, output)?;
+    let files = transaction_parser.files(paths.iter().cloned().chain([ofx.clone()]));
+    let streamed = files.iter().collect::<Result<Vec<_>, _>>();
+    let fetched = files.fetch();
+    std::fs::remove_file(&ofx)?;
+    assert_eq!(streamed?, fetched?);
+
+    let mut pending = parsed[0].clone();
+    pending.description = String::from("pending");
+    let mut sources = transaction_parser.sources(paths);
+    sources.register(Pending(vec![parsed[0].clone(), pending.clone()]));
+    assert_eq!(sources.names(), ["2 transaction files", "pending"]);
+    let mut expected = parsed.clone();
+    expected.push(pending);
+    assert_eq!(sources.fetch()?, expected);
+
+    if cfg!(unix) {
+        assert_eq!(
+            CommandSource::new("cat tests/transactions_simple.csv").fetch()?,
+            Transaction::from_csv_file("tests/transactions_simple.csv")?
+        );
+        assert_eq!(
+            CommandSource::new("echo failed >&2; exit 1")
+                .fetch()
+                .map_err(|e| e.to_string())
+                .unwrap_err(),
+            "`echo failed >&2; exit 1` failed: failed"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_categorized_arithmetic() -> Result<()> {
+    use crate::categorizer::{Categorized, CategorizedChildren, CategorizedList};
+
+    let categorizer = Categorizer::from_yaml_file("tests/categorizer_simple.yaml")?;
+    let transactions = Transaction::from_csv_file("tests/transactions_simple.csv")?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    let totals = |categorized: &CategorizedList| {
+        categorized
+            .diff(&CategorizedList::default())
+            .into_iter()
+            .map(|diff| (diff.path.join(":"), diff.current))
+            .collect::<Vec<_>>()
+    };
+    let zero = BigDecimal::from(0);
+
+    // Categorizations of disjoint transactions add up to the categorization of all of them
+    let mut expenses = categorized.clone();
+    expenses.retain_transactions(|t| t.amount < zero);
+    let mut income = categorized.clone();
+    income.retain_transactions(|t| t.amount > zero);
+    assert_eq!(expenses.clone() + income, categorized);
+
+    // Subtracting subtracts the counts and totals and carries no transactions
+    let difference = categorized.clone() - categorized.clone();
+    assert!(difference
+        .flatten()
+        .iter()
+        .all(|(_, c)| c.count == 0 && c.total == zero && c.absolute_total == zero));
+    assert!(difference.assignments().is_empty());
+    let difference = expenses.clone() - categorized.clone();
+    assert_eq!(
+        totals(&difference),
+        [
+            (String::from("expenses"), zero.clone()),
+            (String::from("expenses:store"), zero.clone()),
+            (String::from("income"), BigDecimal::from(-5)),
+            (String::from("income:paycheck"), BigDecimal::from(-5)),
+        ]
+    );
+    let difference = categorized.clone() - expenses.clone();
+    assert_eq!(difference.categorized[0].count, 0);
+    assert_eq!(difference.categorized[1].count, 1);
+    assert_eq!(
+        difference.categorized[1].absolute_total,
+        BigDecimal::from(5)
+    );
+    assert!(difference.assignments().is_empty());
+
+    let average = [
+        categorized.clone(),
+        categorized.clone() * BigDecimal::from(2),
+    ]
+    .into_iter()
+    .sum::<CategorizedList>()
+        / BigDecimal::from(2);
+    assert_eq!(
+        totals(&average),
+        totals(&(categorized.clone() * BigDecimal::from_str("1.5")?))
+    );
+    // Scaling leaves the transactions as they are
+    let amounts = |categorized: &CategorizedList| {
+        categorized
+            .assignments()
+            .into_iter()
+            .map(|a| a.transaction.amount.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        amounts(&(categorized.clone() / BigDecimal::from(2))),
+        amounts(&categorized)
+    );
+    assert_eq!(-(-categorized.clone()), categorized);
+
+    // Subcategories added to transactions are flattened into the transactions
+    let pending = Transaction {
+        description: String::from("pending"),
+        ..transactions[0].clone()
+    };
+    let flat = Categorized {
+        category: String::from("expenses"),
+        count: 1,
+        total: pending.amount.clone(),
+        absolute_total: pending.amount.abs(),
+        children: CategorizedChildren::Transactions(vec![pending.clone()]),
+    };
+    let added = categorized.categorized[0].clone() + flat;
+    assert_eq!(added.count, 3);
+    assert_eq!(added.total, BigDecimal::from(-40));
+    assert_eq!(
+        added.children,
+        CategorizedChildren::Transactions(vec![
+            transactions[0].clone(),
+            transactions[1].clone(),
+            pending
+        ])
+    );
+    Ok(())
+}
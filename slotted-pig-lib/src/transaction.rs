@@ -1,22 +1,27 @@
 use std::{
+    collections::HashMap,
     fs::File,
-    io::{BufReader, Cursor, Read},
+    io::{Cursor, Read, Write},
     path::{Path, PathBuf},
     str::FromStr,
 };
 
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
-use csv::{ReaderBuilder, StringRecord};
+use csv::{ReaderBuilder, StringRecord, StringRecordsIntoIter, WriterBuilder};
 use dateparser;
 use derive_more::From;
 use displaydoc::Display;
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, FromInto};
 use thiserror::Error;
 
-use crate::util::RegexSerde;
+use crate::{
+    diagnostic::{from_yaml_str, YamlDiagnostic},
+    util::RegexSerde,
+};
 
 #[derive(Error, Debug, Display)]
 pub enum Error {
@@ -40,15 +45,15 @@ pub enum Error {
     MissingTime(String),
     /// no matching csv parser config: {0}
     NoMatchingCsvConfig(PathBuf),
-    /// serde_yaml
-    SerdeYaml(#[from] serde_yaml::Error),
+    /// invalid yaml
+    Yaml(#[from] YamlDiagnostic),
     // TODO: this is kinda a hack and should be its own error type
     /// failed to parse: {0}
     ParseFailed(PathBuf, #[source] Box<Self>),
 }
 
 /// Transaction
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Transaction {
     /// Amount of the transaction
@@ -59,9 +64,77 @@ pub struct Transaction {
     pub description: String,
     /// Time of the transaction
     pub time: DateTime<Utc>,
+    /// File the transaction was parsed from, only kept in memory (ie for export metadata) so it
+    /// does not change serialized transactions or their equality
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+impl PartialEq for Transaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.amount == other.amount
+            && self.account == other.account
+            && self.description == other.description
+            && self.time == other.time
+    }
+}
+
+impl Eq for Transaction {}
+
+/// Fields identifying a transaction, the source is ignored so the same transaction downloaded in
+/// two files is only counted once
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub(crate) struct TransactionKey {
+    time: DateTime<Utc>,
+    account: String,
+    description: String,
+    amount: BigDecimal,
+}
+
+impl TransactionKey {
+    pub(crate) fn new(transaction: &Transaction) -> Self {
+        Self {
+            time: transaction.time,
+            account: transaction.account.clone(),
+            description: transaction.description.clone(),
+            amount: transaction.amount.normalized(),
+        }
+    }
 }
 
 impl Transaction {
+    /// Merge batches of transactions (ie files) dropping the transactions an earlier batch
+    /// already had
+    ///
+    /// Transactions are the same if their time, account, description, and amount are equal.
+    /// Identical transactions are counted so a batch with two identical purchases keeps both, but
+    /// a batch overlapping it adds neither again.
+    pub fn merge<'a>(batches: impl IntoIterator<Item = &'a [Transaction]>) -> Vec<Transaction> {
+        let mut merged = Vec::new();
+        let mut merged_counts = HashMap::<TransactionKey, usize>::new();
+        for batch in batches {
+            let mut batch_counts = HashMap::<TransactionKey, usize>::new();
+            for transaction in batch {
+                let key = TransactionKey::new(transaction);
+                let merged_count = merged_counts.entry(key.clone()).or_default();
+                let batch_count = batch_counts.entry(key).or_default();
+                *batch_count += 1;
+                if *batch_count > *merged_count {
+                    *merged_count += 1;
+                    merged.push(transaction.clone());
+                } else {
+                    tracing::trace!(
+                        description = %transaction.description,
+                        time = %transaction.time,
+                        "transaction already in an earlier batch"
+                    );
+                }
+            }
+        }
+        tracing::debug!(transactions = merged.len(), "merged transaction batches");
+        merged
+    }
+
     /// Create a new list of transactions from a csv file
     pub fn from_csv_file<P: AsRef<Path>>(path: P) -> Result<Vec<Self>, Error> {
         Self::from_reader(File::open(path)?)
@@ -74,12 +147,24 @@ impl Transaction {
 
     /// Create a new list of transactions from a reader
     fn from_reader<R: Read>(reader: R) -> Result<Vec<Self>, Error> {
-        TransactionParserCsv::default().parse_csv(reader, false)
+        TransactionParserCsv::default().parse_csv(reader, false, None)
+    }
+}
+
+fn log_parsed(path: &Path, transactions: &[Transaction]) {
+    if transactions.is_empty() {
+        tracing::warn!(path = %path.display(), "no transactions parsed");
+    } else {
+        tracing::debug!(
+            path = %path.display(),
+            transactions = transactions.len(),
+            "parsed transactions"
+        );
     }
 }
 
 /// Configuration for parsing transactions
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct TransactionParser {
     /// CSV parsing config
@@ -90,62 +175,177 @@ pub struct TransactionParser {
 impl TransactionParser {
     /// Create a new transaction parser from a yaml file
     pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        Self::from_reader(File::open(path)?)
+        let path = path.as_ref();
+        Self::from_reader(path.display().to_string(), File::open(path)?)
     }
 
     /// Create a new transaction parser from a yaml buffer
     pub fn from_yaml_buffer<B: AsRef<[u8]>>(buffer: B) -> Result<Self, Error> {
-        Self::from_reader(Cursor::new(buffer))
+        Self::from_reader("transaction parser", Cursor::new(buffer))
     }
 
-    /// Create a new transaction parser from a reader
-    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
-        let reader = BufReader::new(reader);
-        Ok(serde_yaml::from_reader(reader)?)
+    /// Create a new transaction parser from a reader of the yaml named `name`
+    fn from_reader<R: Read>(name: impl AsRef<str>, mut reader: R) -> Result<Self, Error> {
+        let mut yaml = String::new();
+        reader.read_to_string(&mut yaml)?;
+        Ok(from_yaml_str(name, yaml)?)
     }
 
     /// Parse transactions from CSV files
+    ///
+    /// Transactions in more than one file are kept, use `Transaction::merge` on the transactions of
+    /// each file to drop them.
     pub fn parse_csvs<'a>(
         &self,
         paths: impl Iterator<Item = &'a Path>,
     ) -> Result<Vec<Transaction>, Error> {
         let mut transactions = Vec::new();
         for path in paths {
-            let new_transactions = self.parse_csv(path)?;
-            // TODO: check for duplicate transactions
-            transactions.extend(new_transactions);
+            transactions.extend(self.parse_csv(path)?);
         }
         Ok(transactions)
     }
 
+    /// Lazily parse transactions from CSV files
+    ///
+    /// Files are opened as the iterator reaches them and only the current row is held in memory,
+    /// unlike `parse_csvs` which collects every transaction.
+    pub fn parse_csvs_iter<'a>(
+        &'a self,
+        paths: impl Iterator<Item = &'a Path> + 'a,
+    ) -> impl Iterator<Item = Result<Transaction, Error>> + 'a {
+        paths.flat_map(move |path| {
+            let parse_failed = move |e: Error| Error::ParseFailed(path.into(), e.into());
+            let transactions: Box<dyn Iterator<Item = _>> = match self.parse_csv_impl(path) {
+                Ok(transactions) => Box::new(transactions.map(move |t| t.map_err(parse_failed))),
+                Err(e) => Box::new(std::iter::once(Err(parse_failed(e)))),
+            };
+            transactions
+        })
+    }
+
     /// Parse transactions from a CSV files
     pub fn parse_csv(&self, path: &Path) -> Result<Vec<Transaction>, Error> {
         self.parse_csv_impl(path)
+            .and_then(Iterator::collect)
+            .map_err(|e| Error::ParseFailed(path.into(), e.into()))
+            .inspect(|transactions: &Vec<Transaction>| log_parsed(path, transactions))
+    }
+
+    /// Parse transactions from the contents of a csv file, such as a file uploaded to a browser
+    ///
+    /// The path selects the csv parsing config like `parse_csv` and becomes the source of the
+    /// transactions, but the file is never read.
+    pub fn parse_csv_buffer<B: AsRef<[u8]>>(
+        &self,
+        path: &Path,
+        buffer: B,
+    ) -> Result<Vec<Transaction>, Error> {
+        self.csv_config(path)
+            .and_then(|csv_config| {
+                csv_config.parse_csv(Cursor::new(buffer), csv_config.invert_amounts, Some(path))
+            })
+            .map_err(|e| Error::ParseFailed(path.into(), e.into()))
+            .inspect(|transactions: &Vec<Transaction>| log_parsed(path, transactions))
+    }
+
+    /// Parse transactions from the contents of a csv file keeping the row each was parsed from,
+    /// such as for showing the original data of a transaction
+    pub fn parse_csv_rows<B: AsRef<[u8]>>(
+        &self,
+        path: &Path,
+        buffer: B,
+    ) -> Result<Vec<CsvRow>, Error> {
+        let transactions = self.parse_csv_buffer(path, buffer.as_ref())?;
+        let has_header = self.csv_config(path)?.has_header;
+        let mut reader = ReaderBuilder::new()
+            .has_headers(has_header)
+            .from_reader(Cursor::new(buffer));
+        let headers = if has_header {
+            reader.headers()?.iter().map(String::from).collect()
+        } else {
+            Vec::new()
+        };
+        reader
+            .records()
+            .zip(transactions)
+            .map(|(record, transaction)| {
+                Ok(CsvRow {
+                    headers: headers.clone(),
+                    values: record?.iter().map(String::from).collect(),
+                    transaction,
+                })
+            })
+            .collect::<Result<_, Error>>()
             .map_err(|e| Error::ParseFailed(path.into(), e.into()))
     }
 
-    fn parse_csv_impl(&self, path: &Path) -> Result<Vec<Transaction>, Error> {
+    /// Copy a csv file replacing every value with the result of `map`, which is given the column
+    /// the value is in
+    ///
+    /// Headers, rows, and the order of columns are kept so the copy is parsed like the original.
+    pub fn map_csv<W: Write>(
+        &self,
+        path: &Path,
+        writer: W,
+        map: impl FnMut(CsvColumn, &str) -> String,
+    ) -> Result<(), Error> {
+        let file = File::open(path)?;
+        self.csv_config(path)?
+            .map_csv(file, writer, map)
+            .map_err(|e| Error::ParseFailed(path.into(), e.into()))
+    }
+
+    fn parse_csv_impl<'a>(&'a self, path: &'a Path) -> Result<CsvTransactions<'a, File>, Error> {
+        let csv_config = self.csv_config(path)?;
+
+        // Parse the file
+        let file = File::open(path)?;
+        csv_config.parse_csv_iter(file, csv_config.invert_amounts, Some(path))
+    }
+
+    /// Find the csv parsing config that matches the filename
+    fn csv_config(&self, path: &Path) -> Result<&TransactionParserCsv, Error> {
         let filename = path
             .file_name()
             .and_then(|f| f.to_str())
             .ok_or_else(|| Error::InvalidPathToFile(path.into()))?;
-
-        // Find the csv parsing config that matches this filename
-        let csv_config = self
-            .csv
+        self.csv
             .iter()
             .find(|csv| csv.filename_regex.is_match(filename))
-            .ok_or_else(|| Error::NoMatchingCsvConfig(path.into()))?;
-
-        // Parse the file
-        let file = File::open(path)?;
-        csv_config.parse_csv(file, csv_config.invert_amounts)
+            .ok_or_else(|| Error::NoMatchingCsvConfig(path.into()))
     }
 }
 
+/// Row of a csv file with the transaction parsed from it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CsvRow {
+    /// Header of every column, empty if the file has no header
+    pub headers: Vec<String>,
+    /// Value of every column
+    pub values: Vec<String>,
+    /// Transaction parsed from the row
+    pub transaction: Transaction,
+}
+
+/// Transaction field a csv column is parsed as
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CsvColumn {
+    /// Amount column
+    Amount,
+    /// Account column
+    Account,
+    /// Description column
+    Description,
+    /// Time column
+    Time,
+    /// Column which is not parsed
+    Other,
+}
+
 /// Configuration for parsing transactions from csv files
 #[serde_as]
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct TransactionParserCsv {
     /// Regex to check if a file should be parsed with this config
@@ -177,73 +377,97 @@ impl TransactionParserCsv {
         &self,
         reader: R,
         invert_amounts: bool,
+        source: Option<&Path>,
     ) -> Result<Vec<Transaction>, Error> {
-        let mut transactions = Vec::new();
+        self.parse_csv_iter(reader, invert_amounts, source)?
+            .collect()
+    }
+
+    fn parse_csv_iter<'a, R: Read>(
+        &'a self,
+        reader: R,
+        invert_amounts: bool,
+        source: Option<&'a Path>,
+    ) -> Result<CsvTransactions<'a, R>, Error> {
         let mut reader = ReaderBuilder::new()
             .has_headers(self.has_header)
             .from_reader(reader);
 
-        // Read the headers and if the file is empty return an empty list
+        // Read the headers and if the file is empty there are no transactions
         let headers = reader.headers()?;
-        if headers.is_empty() {
-            return Ok(transactions);
+        let columns = if headers.is_empty() {
+            None
+        } else {
+            // Find indexes of headers
+            Some(CsvColumns {
+                amount: self
+                    .amount_column
+                    .constant_or_index(headers)
+                    .map_err(Error::MissingAmount)?,
+                account: self
+                    .account_column
+                    .constant_or_index(headers)
+                    .map_err(Error::MissingAccount)?,
+                description: self
+                    .description_column
+                    .constant_or_index(headers)
+                    .map_err(Error::MissingDescription)?,
+                time: self
+                    .time_column
+                    .constant_or_index(headers)
+                    .map_err(Error::MissingTime)?,
+            })
+        };
+
+        Ok(CsvTransactions {
+            records: reader.into_records(),
+            columns,
+            invert_amounts,
+            source,
+        })
+    }
+
+    fn map_csv<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        mut map: impl FnMut(CsvColumn, &str) -> String,
+    ) -> Result<(), Error> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(self.has_header)
+            .flexible(true)
+            .from_reader(reader);
+        let mut writer = WriterBuilder::new().flexible(true).from_writer(writer);
+        let headers = reader.headers()?.clone();
+        if self.has_header {
+            writer.write_record(&headers)?;
         }
 
-        // Find indexes of headers
-        let amount_constant_or_index = self
-            .amount_column
-            .constant_or_index(headers)
-            .map_err(Error::MissingAmount)?;
-        let account_constant_or_index = self
-            .account_column
-            .constant_or_index(headers)
-            .map_err(Error::MissingAccount)?;
-        let description_constant_or_index = self
-            .description_column
-            .constant_or_index(headers)
-            .map_err(Error::MissingDescription)?;
-        let time_constant_or_index = self
-            .time_column
-            .constant_or_index(headers)
-            .map_err(Error::MissingTime)?;
-
-        // Convert each row to a `Transaction` and add it to the list of transactions
-        for result in reader.records() {
-            let record = &result?;
-
-            // Get the &str for each column
-            let amount = amount_constant_or_index
-                .value(record)
-                .map_err(Error::MissingAmount)?;
-            let account = account_constant_or_index
-                .value(record)
-                .map_err(Error::MissingAccount)?;
-            let description = description_constant_or_index
-                .value(record)
-                .map_err(Error::MissingDescription)?;
-            let time = time_constant_or_index
-                .value(record)
-                .map_err(Error::MissingTime)?;
-
-            // Special parsing or conversion for each column
-            let mut amount = BigDecimal::from_str(amount)?;
-            if invert_amounts {
-                amount = -amount;
+        // Constant columns are not in the file
+        let mut columns = HashMap::new();
+        for (determiner, column) in [
+            (&self.amount_column, CsvColumn::Amount),
+            (&self.account_column, CsvColumn::Account),
+            (&self.description_column, CsvColumn::Description),
+            (&self.time_column, CsvColumn::Time),
+        ] {
+            if let Ok(ConstantOrIndex::Index(index)) = determiner.constant_or_index(&headers) {
+                columns.insert(index, column);
             }
-            let account = account.to_string();
-            let description = description.to_string();
-            let time = dateparser::parse_with_timezone(time, &Utc)?;
-
-            let transaction = Transaction {
-                amount,
-                account,
-                description,
-                time,
-            };
-            transactions.push(transaction);
         }
 
-        Ok(transactions)
+        for record in reader.records() {
+            let record = record?;
+            let mapped = record.iter().enumerate().map(|(index, value)| {
+                map(
+                    columns.get(&index).copied().unwrap_or(CsvColumn::Other),
+                    value,
+                )
+            });
+            writer.write_record(mapped.collect::<Vec<_>>())?;
+        }
+        writer.flush()?;
+        Ok(())
     }
 
     fn default_has_header() -> bool {
@@ -281,8 +505,74 @@ impl Default for TransactionParserCsv {
     }
 }
 
+/// Iterator of transactions parsed from the rows of a csv file
+pub struct CsvTransactions<'a, R> {
+    records: StringRecordsIntoIter<R>,
+    columns: Option<CsvColumns<'a>>,
+    invert_amounts: bool,
+    source: Option<&'a Path>,
+}
+
+impl<'a, R: Read> Iterator for CsvTransactions<'a, R> {
+    type Item = Result<Transaction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let columns = self.columns.as_ref()?;
+        let record = self.records.next()?;
+        Some(
+            record
+                .map_err(Into::into)
+                .and_then(|record| columns.transaction(&record, self.invert_amounts, self.source)),
+        )
+    }
+}
+
+/// Retrieve each column of a transaction from a csv row
+struct CsvColumns<'a> {
+    amount: ConstantOrIndex<'a>,
+    account: ConstantOrIndex<'a>,
+    description: ConstantOrIndex<'a>,
+    time: ConstantOrIndex<'a>,
+}
+
+impl<'a> CsvColumns<'a> {
+    /// Convert a row to a `Transaction`
+    fn transaction(
+        &self,
+        record: &StringRecord,
+        invert_amounts: bool,
+        source: Option<&Path>,
+    ) -> Result<Transaction, Error> {
+        // Get the &str for each column
+        let amount = self.amount.value(record).map_err(Error::MissingAmount)?;
+        let account = self.account.value(record).map_err(Error::MissingAccount)?;
+        let description = self
+            .description
+            .value(record)
+            .map_err(Error::MissingDescription)?;
+        let time = self.time.value(record).map_err(Error::MissingTime)?;
+
+        // Special parsing or conversion for each column
+        let mut amount = BigDecimal::from_str(amount)?;
+        if invert_amounts {
+            amount = -amount;
+        }
+        let account = account.to_string();
+        let description = description.to_string();
+        let time = dateparser::parse_with_timezone(time, &Utc)?;
+
+        Ok(Transaction {
+            amount,
+            account,
+            description,
+            time,
+            source: source.map(Into::into),
+        })
+    }
+}
+
 /// Determine if a columns values should be decided by a header, index, or constant
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ColumnDeterminer {
     /// Column is a constant value
@@ -294,7 +584,7 @@ pub enum ColumnDeterminer {
 }
 
 impl ColumnDeterminer {
-    fn constant_or_index(&self, headers: &StringRecord) -> Result<ConstantOrIndex, String> {
+    fn constant_or_index(&self, headers: &StringRecord) -> Result<ConstantOrIndex<'_>, String> {
         match self {
             Self::Constant(constant) => Ok(constant.as_str().into()),
             Self::Header(header) => headers
@@ -315,7 +605,10 @@ enum ConstantOrIndex<'a> {
 }
 
 impl<'a> ConstantOrIndex<'a> {
-    fn value(&self, row: &'a StringRecord) -> Result<&'a str, String> {
+    fn value<'r>(&self, row: &'r StringRecord) -> Result<&'r str, String>
+    where
+        'a: 'r,
+    {
         match *self {
             Self::Constant(constant) => Ok(constant),
             Self::Index(index) => row.get(index).ok_or_else(|| row.as_slice().into()),
@@ -1,7 +1,22 @@
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
 use bigdecimal::{BigDecimal, Signed};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, NaiveDate, Utc,
+};
 use derive_more::{From, Into};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use strum::EnumString;
 
 #[cfg(test)]
 mod tests;
@@ -11,43 +26,339 @@ mod tests;
 #[serde(deny_unknown_fields)]
 pub(crate) struct RegexSerde(#[serde(with = "serde_regex")] Regex);
 
+impl JsonSchema for RegexSerde {
+    fn schema_name() -> String {
+        "Regex".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("regex".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Wrapper type to deserialize `BigDecimal` from yaml numbers without binary float error
+///
+/// Floats are converted using their shortest decimal representation so `0.1` becomes exactly
+/// `0.1`. Strings are parsed as decimals.
+#[derive(Clone, Debug, Into, From, Serialize)]
+pub(crate) struct BigDecimalSerde(BigDecimal);
+
+impl JsonSchema for BigDecimalSerde {
+    fn schema_name() -> String {
+        "Decimal".to_string()
+    }
+
+    /// A number or a string of a decimal number
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(vec![InstanceType::Number, InstanceType::String].into()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl<'de> Deserialize<'de> for BigDecimalSerde {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = BigDecimalSerde;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a decimal number")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(BigDecimalSerde(value.into()))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(BigDecimalSerde(value.into()))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                self.visit_str(&value.to_string())
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                BigDecimal::from_str(value)
+                    .map(BigDecimalSerde)
+                    .map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// Normalize a transaction description into a payee name for grouping
+///
+/// Words containing digits (ie store numbers, dates, and reference codes) are dropped, punctuation
+/// is treated as whitespace, and the result is lowercase (ie `AMZN Mktp US*2K3 #123` becomes
+/// `amzn mktp us`).
+pub fn normalize_payee(description: &str) -> String {
+    description
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '&')
+        .filter(|word| !word.is_empty() && !word.chars().any(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Case insensitive regex matching the words of a normalized payee in order (ie
+/// `(?i)\bamzn\b.*\bmktp\b` for `amzn mktp`)
+pub fn payee_regex(payee: &str) -> String {
+    let words = payee
+        .split_whitespace()
+        .map(regex::escape)
+        .collect::<Vec<_>>();
+    format!(r"(?i)\b{}\b", words.join(r"\b.*\b"))
+}
+
+/// Single quoted yaml string, which does not treat backslashes as escapes
+pub(crate) fn yaml_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Rounding mode for ties
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, EnumString, Eq, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RoundingMode {
+    /// Round ties to the nearest even digit (ie banker's rounding)
+    #[default]
+    HalfEven,
+    /// Round ties away from zero
+    HalfUp,
+}
+
+impl From<RoundingMode> for bigdecimal::RoundingMode {
+    fn from(mode: RoundingMode) -> Self {
+        match mode {
+            RoundingMode::HalfEven => Self::HalfEven,
+            RoundingMode::HalfUp => Self::HalfUp,
+        }
+    }
+}
+
+/// Rounding policy for amounts
+#[derive(Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rounding {
+    /// Rounding mode for ties
+    #[serde(default)]
+    pub mode: RoundingMode,
+    /// Number of decimal places to round to
+    #[serde(default = "Rounding::default_decimal_places")]
+    pub decimal_places: i64,
+}
+
+impl Rounding {
+    /// Round the number to exactly `decimal_places` decimal places (ie `-1234.5` becomes
+    /// `-1234.50`)
+    pub fn round(&self, number: &BigDecimal) -> BigDecimal {
+        number.with_scale_round(self.decimal_places, self.mode.into())
+    }
+
+    fn default_decimal_places() -> i64 {
+        2
+    }
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            decimal_places: Self::default_decimal_places(),
+        }
+    }
+}
+
 /// Nicely format a bigdecimal value with two decimal places and commas
 pub fn format_bigdecimal(number: &BigDecimal) -> String {
-    let decimals = 2u8;
-    let number = number.round(decimals.into());
+    Formatter::default().format(number)
+}
 
-    // Extract integer and fractional parts
-    let negative = number.is_negative();
-    let s = number.abs().to_string();
-    let (integer, fractional) = s.split_once('.').unwrap_or((&s, ""));
+/// How negative amounts are displayed
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumString, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NegativeStyle {
+    /// Leading minus sign (ie `-1,234.56`)
+    #[default]
+    Minus,
+    /// Surrounding parentheses (ie `(1,234.56)`)
+    Parentheses,
+}
 
-    let mut formatted = String::new();
+/// Amount formatting with currency symbols, locale specific separators, and negative styles along
+/// with date formatting
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Formatter {
+    /// Currency symbol (ie `$`), empty for no symbol
+    #[serde(default)]
+    pub currency_symbol: String,
+    /// Place the currency symbol after the number separated by a space (ie `1.234,56 €`)
+    #[serde(default)]
+    pub currency_suffix: bool,
+    /// Separator between groups of three integer digits
+    #[serde(default = "Formatter::default_thousands_separator")]
+    pub thousands_separator: String,
+    /// Separator between the integer and fractional parts
+    #[serde(default = "Formatter::default_decimal_separator")]
+    pub decimal_separator: String,
+    /// Number of decimal places to round to
+    #[serde(default = "Formatter::default_decimal_places")]
+    pub decimal_places: u8,
+    /// How negative amounts are displayed
+    #[serde(default)]
+    pub negative_style: NegativeStyle,
+    /// `strftime` style format of dates in reports (ie `%Y-%m-%d` or `%m/%d/%Y`)
+    #[serde(default = "Formatter::default_date_format")]
+    pub date_format: String,
+}
+
+impl Formatter {
+    /// Create a formatter with the separators of a locale (ie `en-US`, `de-DE`, or `fr`)
+    ///
+    /// Returns `None` if the locale is not known.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        let (thousands_separator, decimal_separator) = match (language, locale) {
+            (_, "de-CH" | "de_CH") => ("\u{2019}", "."),
+            ("en" | "ja" | "zh" | "ko" | "th" | "he", _) => (",", "."),
+            ("de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr", _) => (".", ","),
+            ("fr" | "sv" | "nb" | "fi" | "cs" | "pl" | "ru" | "uk", _) => ("\u{202f}", ","),
+            _ => return None,
+        };
+        Some(Self {
+            thousands_separator: thousands_separator.into(),
+            decimal_separator: decimal_separator.into(),
+            ..Default::default()
+        })
+    }
+
+    /// Format a bigdecimal value
+    pub fn format(&self, number: &BigDecimal) -> String {
+        let decimals = self.decimal_places;
+        let number = number.round(decimals.into());
+
+        // Extract integer and fractional parts
+        let negative = number.is_negative();
+        let s = number.abs().to_string();
+        let (integer, fractional) = s.split_once('.').unwrap_or((&s, ""));
+
+        let mut formatted = String::new();
+
+        // Insert thousands separators every three digits
+        let mut count = 0;
+        for c in integer.chars().rev() {
+            if count == 3 {
+                formatted.insert_str(0, &self.thousands_separator);
+                count = 0;
+            }
+            formatted.insert(0, c);
+            count += 1;
+        }
+
+        // Insert decimal separator and fractional part padded with zeros
+        if decimals > 0 {
+            let decimals = usize::from(decimals);
+            formatted.push_str(&self.decimal_separator);
+            formatted.push_str(&fractional[..std::cmp::min(decimals, fractional.len())]);
+            formatted.extend(std::iter::repeat_n(
+                '0',
+                decimals.saturating_sub(fractional.len()),
+            ));
+        }
 
-    // Insert commas every three digits
-    let mut count = 0;
-    for c in integer.chars().rev() {
-        if count == 3 {
-            formatted.insert(0, ',');
-            count = 0;
+        // Insert currency symbol
+        if !self.currency_symbol.is_empty() {
+            if self.currency_suffix {
+                formatted.push(' ');
+                formatted.push_str(&self.currency_symbol);
+            } else {
+                formatted.insert_str(0, &self.currency_symbol);
+            }
+        }
+
+        // Insert sign
+        if negative {
+            match self.negative_style {
+                NegativeStyle::Minus => formatted.insert(0, '-'),
+                NegativeStyle::Parentheses => formatted = format!("({formatted})"),
+            }
+        }
+
+        formatted
+    }
+
+    /// Format a date, invalid date formats fall back to the default date format
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.format_datetime(date.format(&self.date_format), || date.to_string())
+    }
+
+    /// Format a time with the date format, invalid date formats fall back to the default date
+    /// format
+    pub fn format_time(&self, time: &DateTime<Utc>) -> String {
+        self.format_datetime(time.format(&self.date_format), || {
+            time.date_naive().to_string()
+        })
+    }
+
+    fn format_datetime(
+        &self,
+        formatted: impl fmt::Display,
+        fallback: impl Fn() -> String,
+    ) -> String {
+        let mut output = String::new();
+        match write!(output, "{formatted}") {
+            Ok(()) => output,
+            Err(_) => fallback(),
         }
-        formatted.insert(0, c);
-        count += 1;
     }
 
-    // Insert sign
-    if negative {
-        formatted.insert(0, '-');
+    /// Check if a date format only contains valid `strftime` specifiers
+    pub fn is_valid_date_format(date_format: &str) -> bool {
+        StrftimeItems::new(date_format).all(|item| !matches!(item, Item::Error))
     }
 
-    // Insert decimal point and fractional part
-    formatted.push('.');
-    formatted.push_str(&fractional[..std::cmp::min(decimals.into(), fractional.len())]);
-    let zero_fill = match fractional.len() {
-        0 => "00",
-        1 => "0",
-        _ => "",
-    };
-    formatted.push_str(zero_fill);
+    fn default_thousands_separator() -> String {
+        String::from(",")
+    }
+
+    fn default_decimal_separator() -> String {
+        String::from(".")
+    }
+
+    fn default_decimal_places() -> u8 {
+        2
+    }
 
-    formatted
+    fn default_date_format() -> String {
+        String::from("%Y-%m-%d")
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self {
+            currency_symbol: Default::default(),
+            currency_suffix: Default::default(),
+            thousands_separator: Self::default_thousands_separator(),
+            decimal_separator: Self::default_decimal_separator(),
+            decimal_places: Self::default_decimal_places(),
+            negative_style: Default::default(),
+            date_format: Self::default_date_format(),
+        }
+    }
 }
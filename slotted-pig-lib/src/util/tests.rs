@@ -1,7 +1,12 @@
+use std::str::FromStr;
+
 use bigdecimal::{BigDecimal, FromPrimitive};
 use test_case::test_case;
 
-use super::format_bigdecimal;
+use super::{
+    format_bigdecimal, normalize_payee, BigDecimalSerde, Formatter, NegativeStyle, Rounding,
+    RoundingMode,
+};
 
 #[test_case(BigDecimal::from_f64(1234567890.123456).unwrap(), "1,234,567,890.12")]
 #[test_case(BigDecimal::from_u32(123456).unwrap(), "123,456.00")]
@@ -16,3 +21,69 @@ fn test_format_bigdecimal(input: BigDecimal, expected_output: &str) {
     let formatted_output = format_bigdecimal(&input);
     assert_eq!(formatted_output, expected_output);
 }
+
+#[test_case(Formatter::from_locale("de-DE").unwrap(), "-1.234.567,89")]
+#[test_case(Formatter::from_locale("fr").unwrap(), "-1\u{202f}234\u{202f}567,89")]
+#[test_case(Formatter::from_locale("de-CH").unwrap(), "-1\u{2019}234\u{2019}567.89")]
+#[test_case(Formatter { currency_symbol: "$".into(), ..Default::default() }, "-$1,234,567.89")]
+#[test_case(Formatter { currency_symbol: "€".into(), currency_suffix: true, ..Formatter::from_locale("de").unwrap() }, "-1.234.567,89 €")]
+#[test_case(Formatter { negative_style: NegativeStyle::Parentheses, ..Default::default() }, "(1,234,567.89)")]
+#[test_case(Formatter { decimal_places: 0, ..Default::default() }, "-1,234,568")]
+#[test_case(Formatter { decimal_places: 4, ..Default::default() }, "-1,234,567.8900")]
+fn test_formatter(formatter: Formatter, expected_output: &str) {
+    let input = BigDecimal::from_f64(-1234567.89).unwrap();
+    assert_eq!(formatter.format(&input), expected_output);
+}
+
+#[test_case(RoundingMode::HalfEven, 2, "-1234.5", "-1234.50"; "pad")]
+#[test_case(RoundingMode::HalfEven, 2, "0.125", "0.12"; "half even")]
+#[test_case(RoundingMode::HalfUp, 2, "0.125", "0.13"; "half up")]
+#[test_case(RoundingMode::HalfUp, 2, "-0.125", "-0.13"; "half up negative")]
+#[test_case(RoundingMode::HalfEven, 0, "2.5", "2"; "half even integer")]
+#[test_case(RoundingMode::HalfUp, 0, "2.5", "3"; "half up integer")]
+fn test_rounding(mode: RoundingMode, decimal_places: i64, input: &str, expected_output: &str) {
+    let rounding = Rounding {
+        mode,
+        decimal_places,
+    };
+    let input = input.parse::<BigDecimal>().unwrap();
+    assert_eq!(rounding.round(&input).to_string(), expected_output);
+}
+
+#[test_case("STARBUCKS 123", "starbucks")]
+#[test_case("AMZN Mktp US*2K3 #123", "amzn mktp us")]
+#[test_case("Trader Joe's 02/24", "trader joe's")]
+#[test_case("  Netflix.com  ", "netflix com")]
+#[test_case("12345", "")]
+fn test_normalize_payee(description: &str, expected_output: &str) {
+    assert_eq!(normalize_payee(description), expected_output);
+}
+
+#[test_case("-120.35", "-120.35"; "float")]
+#[test_case("0.1", "0.1"; "fraction")]
+#[test_case("42", "42"; "integer")]
+#[test_case("\"12.345678901234567890\"", "12.345678901234567890"; "string")]
+fn test_bigdecimal_serde(yaml: &str, expected_output: &str) {
+    let number: BigDecimalSerde = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        BigDecimal::from(number),
+        BigDecimal::from_str(expected_output).unwrap()
+    );
+}
+
+#[test_case("%Y-%m-%d", "2024-02-24")]
+#[test_case("%m/%d/%Y", "02/24/2024")]
+#[test_case("%d.%m.%Y %H:%M", "24.02.2024 20:10")]
+#[test_case("%Q", "2024-02-24"; "invalid falls back")]
+fn test_format_time(date_format: &str, expected_output: &str) {
+    let formatter = Formatter {
+        date_format: date_format.into(),
+        ..Default::default()
+    };
+    let time = "2024-02-24T20:10:59Z".parse().unwrap();
+    assert_eq!(formatter.format_time(&time), expected_output);
+    assert_eq!(
+        Formatter::is_valid_date_format(date_format),
+        date_format != "%Q"
+    );
+}
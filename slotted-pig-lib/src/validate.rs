@@ -0,0 +1,272 @@
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+
+use crate::{
+    categorizer::{Categorizer, Category, CategoryChildren, TransactionMatcher},
+    config::Config,
+    diagnostic::suggestion,
+    transaction::{ColumnDeterminer, TransactionParser},
+};
+
+/// Problem found in a config file
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Issue {
+    /// Line of the problem starting at 1, `None` if unknown
+    pub line: Option<usize>,
+    /// Column of the problem starting at 1, `None` if unknown
+    pub column: Option<usize>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Issue {
+    fn new(location: Option<(usize, usize)>, message: impl Into<String>) -> Self {
+        let (line, column) = location.unzip();
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn from_yaml_error(error: &serde_yaml::Error) -> Self {
+        let mut message = error.to_string();
+        let location = error.location().map(|l| (l.line(), l.column()));
+        if let Some((line, column)) = location {
+            message = message.replace(&format!(" at line {line} column {column}"), "");
+        }
+        if let Some(suggestion) = suggestion(&message) {
+            message = format!("{message}, did you mean `{suggestion}`?");
+        }
+        Self::new(location, message)
+    }
+}
+
+/// Location prefix (ie `3:7: `) followed by the message
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{line}:{column}: {}", self.message),
+            (Some(line), None) => write!(f, "{line}: {}", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Check a categorizer yaml config for problems
+///
+/// Beyond failing to parse (ie invalid regex or unknown fields) this finds duplicate, empty, and
+/// unreachable categories. Locations of categories are found by matching `category` keys in the
+/// yaml in order, so categories written in flow style have no location.
+pub fn validate_categorizer(yaml: &str) -> Vec<Issue> {
+    match serde_yaml::from_str::<Categorizer>(yaml) {
+        Ok(categorizer) => check_categorizer(&categorizer, yaml),
+        Err(e) => vec![Issue::from_yaml_error(&e)],
+    }
+}
+
+/// Check a transaction parser yaml config for problems
+///
+/// Beyond failing to parse (ie invalid regex or unknown fields) this finds csv configs which can
+/// never be used or can never parse a file.
+pub fn validate_transaction_parser(yaml: &str) -> Vec<Issue> {
+    match serde_yaml::from_str::<TransactionParser>(yaml) {
+        Ok(transaction_parser) => check_transaction_parser(&transaction_parser, yaml),
+        Err(e) => vec![Issue::from_yaml_error(&e)],
+    }
+}
+
+/// Check a combined yaml config for the problems of both its transaction parser and categorizer
+pub fn validate_config(yaml: &str) -> Vec<Issue> {
+    match serde_yaml::from_str::<Config>(yaml) {
+        Ok(config) => {
+            let mut issues = check_transaction_parser(&config.transaction_parser, yaml);
+            issues.extend(check_categorizer(&config.categorizer, yaml));
+            issues.sort_by_key(|issue| issue.line);
+            issues
+        }
+        Err(e) => vec![Issue::from_yaml_error(&e)],
+    }
+}
+
+fn check_categorizer(categorizer: &Categorizer, yaml: &str) -> Vec<Issue> {
+    let mut validator = CategorizerValidator {
+        locations: key_locations(yaml, "category"),
+        index: 0,
+        issues: Vec::new(),
+    };
+    if categorizer
+        .transaction_filters
+        .as_ref()
+        .is_some_and(Vec::is_empty)
+    {
+        validator.issues.push(Issue::new(
+            key_locations(yaml, "transaction_filters").first().copied(),
+            "transaction_filters is empty so every transaction is filtered out",
+        ));
+    }
+    validator.categories(&categorizer.categories, &mut Vec::new());
+    let leaf_paths = categorizer
+        .leaf_category_paths()
+        .into_iter()
+        .map(|path| path.join(":"))
+        .collect::<HashSet<_>>();
+    for test in &categorizer.tests {
+        if let Some(expected) = test.expected.as_ref().filter(|e| !leaf_paths.contains(*e)) {
+            validator.issues.push(Issue::new(
+                None,
+                format!(
+                    "test `{}` expects `{expected}` which is not a leaf category",
+                    test.description
+                ),
+            ));
+        }
+    }
+    validator.issues.sort_by_key(|issue| issue.line);
+    validator.issues
+}
+
+fn check_transaction_parser(transaction_parser: &TransactionParser, yaml: &str) -> Vec<Issue> {
+    if transaction_parser.csv.is_empty() {
+        return vec![Issue::new(None, "no csv configs so no files can be parsed")];
+    }
+    let locations = key_locations(yaml, "filename_regex");
+    let mut issues = Vec::new();
+    let mut filename_regexes = HashSet::new();
+    for (i, csv) in transaction_parser.csv.iter().enumerate() {
+        let location = locations.get(i).copied();
+        let filename_regex = csv.filename_regex.as_str();
+        if !filename_regexes.insert(filename_regex) {
+            issues.push(Issue::new(
+                location,
+                format!(
+                    "filename_regex `{filename_regex}` is used by an earlier csv config so this \
+                     config is never used"
+                ),
+            ));
+        }
+        if !csv.has_header {
+            let columns = [
+                ("amount_column", &csv.amount_column),
+                ("account_column", &csv.account_column),
+                ("description_column", &csv.description_column),
+                ("time_column", &csv.time_column),
+            ];
+            for (name, column) in columns {
+                if let ColumnDeterminer::Header(header) = column {
+                    issues.push(Issue::new(
+                        location,
+                        format!("{name} uses header `{header}` but has_header is false"),
+                    ));
+                }
+            }
+        }
+    }
+    issues
+}
+
+struct CategorizerValidator {
+    /// Location of every `category` key in document order
+    locations: Vec<(usize, usize)>,
+    /// Index of the next category in document order
+    index: usize,
+    issues: Vec<Issue>,
+}
+
+impl CategorizerValidator {
+    fn categories<'a>(&mut self, categories: &'a [Category], path: &mut Vec<&'a str>) {
+        let mut names = HashSet::new();
+        for category in categories {
+            let location = self.locations.get(self.index).copied();
+            self.index += 1;
+            path.push(&category.category);
+            let joined = path.join(":");
+            if category.category.is_empty() {
+                self.issue(location, String::from("category name is empty"));
+            }
+            if category.category.contains(':') {
+                self.issue(
+                    location,
+                    format!(
+                        "category `{}` contains `:` which separates category paths",
+                        category.category
+                    ),
+                );
+            }
+            if !names.insert(category.category.as_str()) {
+                self.issue(location, format!("duplicate category `{joined}`"));
+            }
+            match &category.children {
+                CategoryChildren::Subcategories(subcategories) => {
+                    if subcategories.is_empty() {
+                        self.issue(
+                            location,
+                            format!("category `{joined}` has no subcategories"),
+                        );
+                    }
+                    self.categories(subcategories, path);
+                }
+                CategoryChildren::TransactionMatchers(matchers) => {
+                    if matchers.is_empty() {
+                        self.issue(
+                            location,
+                            format!(
+                                "category `{joined}` has no transaction matchers so no \
+                                 transactions are assigned to it"
+                            ),
+                        );
+                    }
+                    for matcher in matchers {
+                        if let Some(problem) = never_matches(matcher) {
+                            self.issue(
+                                location,
+                                format!(
+                                    "transaction matcher of `{joined}` never matches, {problem}"
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+
+    fn issue(&mut self, location: Option<(usize, usize)>, message: String) {
+        self.issues.push(Issue::new(location, message));
+    }
+}
+
+/// Reason a transaction matcher can never match, `None` if it can
+fn never_matches(matcher: &TransactionMatcher) -> Option<&'static str> {
+    if let (Some(min), Some(max)) = (&matcher.min, &matcher.max) {
+        if min > max {
+            return Some("min is greater than max");
+        }
+    }
+    if let (Some(begin), Some(end)) = (&matcher.begin, &matcher.end) {
+        if begin > end {
+            return Some("begin is after end");
+        }
+    }
+    None
+}
+
+/// Line and column starting at 1 of every block style occurrence of the key in document order
+fn key_locations(yaml: &str, key: &str) -> Vec<(usize, usize)> {
+    yaml.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let trimmed = line.trim_start();
+            let trimmed = trimmed
+                .strip_prefix('-')
+                .map(str::trim_start)
+                .unwrap_or(trimmed);
+            trimmed
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+                .then(|| (i + 1, line.len() - trimmed.len() + 1))
+        })
+        .collect()
+}
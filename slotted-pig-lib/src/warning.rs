@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use displaydoc::Display;
+use serde::Serialize;
+
+/// Problem which does not stop parsing or categorizing but likely means the output is not what was
+/// intended, returned to callers to show alongside the output
+#[derive(Clone, Debug, Display, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    /// the transaction filters filter out all {count} transactions
+    EveryTransactionFiltered { count: usize },
+    /// transaction override of `{description}` at {time} matches no transaction
+    UnmatchedOverride {
+        description: String,
+        time: DateTime<Utc>,
+    },
+}
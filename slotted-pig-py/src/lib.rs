@@ -0,0 +1,224 @@
+//! Python module of the transaction parser and categorizer so notebooks can categorize with the
+//! same yaml rules
+//!
+//! Amounts are `decimal.Decimal` and times are timezone aware `datetime`s, records are plain
+//! dicts so they load straight into a dataframe (ie `pandas.DataFrame(categorizer.categorize(t))`).
+
+// The pymethods macro converts every returned `PyErr` into itself
+#![allow(clippy::useless_conversion)]
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use slotted_pig_lib::{
+    categorizer::{self, Categorizer},
+    transaction::{self, Transaction, TransactionParser},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Python `ValueError` of an error and its sources (ie `serde_yaml: missing field ...`)
+fn value_error(e: &dyn Error) -> PyErr {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        message.push_str(&format!(": {e}"));
+        source = e.source();
+    }
+    PyValueError::new_err(message)
+}
+
+fn transaction_error(e: transaction::Error) -> PyErr {
+    value_error(&e)
+}
+
+fn categorizer_error(e: categorizer::Error) -> PyErr {
+    value_error(&e)
+}
+
+/// Python `decimal.Decimal` of an amount, floats would lose cents
+fn decimal<'py>(py: Python<'py>, amount: &BigDecimal) -> PyResult<Bound<'py, PyAny>> {
+    py.import_bound("decimal")?
+        .getattr("Decimal")?
+        .call1((amount.to_string(),))
+}
+
+/// Transaction
+#[pyclass(name = "Transaction", module = "slotted_pig", frozen)]
+#[derive(Clone)]
+struct PyTransaction(Transaction);
+
+#[pymethods]
+impl PyTransaction {
+    #[new]
+    #[pyo3(signature = (amount, account, description, time, source=None))]
+    fn new(
+        amount: &str,
+        account: String,
+        description: String,
+        time: DateTime<Utc>,
+        source: Option<PathBuf>,
+    ) -> PyResult<Self> {
+        let amount = amount
+            .parse()
+            .map_err(|e| PyValueError::new_err(format!("invalid amount {amount}: {e}")))?;
+        Ok(Self(Transaction {
+            amount,
+            account,
+            description,
+            time,
+            source,
+        }))
+    }
+
+    /// Amount of the transaction
+    #[getter]
+    fn amount<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        decimal(py, &self.0.amount)
+    }
+
+    /// Account the transaction applied to
+    #[getter]
+    fn account(&self) -> &str {
+        &self.0.account
+    }
+
+    /// Description of the transaction
+    #[getter]
+    fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    /// Time of the transaction
+    #[getter]
+    fn time(&self) -> DateTime<Utc> {
+        self.0.time
+    }
+
+    /// File the transaction was parsed from
+    #[getter]
+    fn source(&self) -> Option<&Path> {
+        self.0.source.as_deref()
+    }
+
+    /// Fields of the transaction as a dict
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("time", self.0.time)?;
+        dict.set_item("account", &self.0.account)?;
+        dict.set_item("description", &self.0.description)?;
+        dict.set_item("amount", decimal(py, &self.0.amount)?)?;
+        dict.set_item("source", self.0.source.as_deref())?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Transaction({} {} {:?} {})",
+            self.0.time, self.0.account, self.0.description, self.0.amount
+        )
+    }
+}
+
+/// Parser of bank csv files configured by a transaction parser yaml
+#[pyclass(name = "TransactionParser", module = "slotted_pig", frozen)]
+struct PyTransactionParser(TransactionParser);
+
+#[pymethods]
+impl PyTransactionParser {
+    /// Parse a transaction parser yaml
+    #[new]
+    fn new(yaml: &str) -> PyResult<Self> {
+        TransactionParser::from_yaml_buffer(yaml)
+            .map(Self)
+            .map_err(transaction_error)
+    }
+
+    /// Read a transaction parser yaml file
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        TransactionParser::from_yaml_file(path)
+            .map(Self)
+            .map_err(transaction_error)
+    }
+
+    /// Parse the transactions of csv files
+    fn parse_csvs(&self, paths: Vec<PathBuf>) -> PyResult<Vec<PyTransaction>> {
+        let transactions = self
+            .0
+            .parse_csvs(paths.iter().map(PathBuf::as_path))
+            .map_err(transaction_error)?;
+        Ok(transactions.into_iter().map(PyTransaction).collect())
+    }
+
+    /// Parse the transactions of csv contents, the file name picks the csv config
+    fn parse_csv_string(&self, contents: &str, file_name: &str) -> PyResult<Vec<PyTransaction>> {
+        let transactions = self
+            .0
+            .parse_csv_buffer(Path::new(file_name), contents)
+            .map_err(transaction_error)?;
+        Ok(transactions.into_iter().map(PyTransaction).collect())
+    }
+}
+
+/// Categorizer configured by a categorizer yaml
+#[pyclass(name = "Categorizer", module = "slotted_pig", frozen)]
+struct PyCategorizer(Categorizer);
+
+#[pymethods]
+impl PyCategorizer {
+    /// Parse a categorizer yaml
+    #[new]
+    fn new(yaml: &str) -> PyResult<Self> {
+        Categorizer::from_yaml_buffer(yaml)
+            .map(Self)
+            .map_err(categorizer_error)
+    }
+
+    /// Read a categorizer yaml file
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        Categorizer::from_yaml_file(path)
+            .map(Self)
+            .map_err(categorizer_error)
+    }
+
+    /// One record per transaction passing the transaction filters with its fields, its colon
+    /// separated category (`None` if uncategorized), and the matcher that assigned it
+    fn categorize<'py>(
+        &self,
+        py: Python<'py>,
+        transactions: Vec<PyTransaction>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| transaction.0)
+            .collect::<Vec<_>>();
+        let records = PyList::empty_bound(py);
+        for assignment in self.0.assign(&transactions) {
+            let record = PyTransaction(assignment.transaction.clone()).to_dict(py)?;
+            record.set_item("category", assignment.category_path_joined(":"))?;
+            record.set_item("matcher", assignment.matcher.map(ToString::to_string))?;
+            records.append(record)?;
+        }
+        Ok(records)
+    }
+}
+
+#[pymodule]
+fn slotted_pig(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyTransaction>()?;
+    module.add_class::<PyTransactionParser>()?;
+    module.add_class::<PyCategorizer>()?;
+    Ok(())
+}
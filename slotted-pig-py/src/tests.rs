@@ -0,0 +1,109 @@
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::{PyCategorizer, PyTransactionParser};
+
+const CSV: &str = include_str!("../../examples/transactions.csv");
+const TRANSACTION_PARSER: &str = include_str!("../../examples/transaction_parser.yaml");
+const CATEGORIZER: &str = include_str!("../../examples/categorizer.yaml");
+
+/// Python `decimal.Decimal` of a string
+fn decimal<'py>(py: Python<'py>, amount: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import_bound("decimal")?
+        .getattr("Decimal")?
+        .call1((amount,))
+}
+
+#[test]
+fn test_parse_csv_string() -> PyResult<()> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let parser = PyTransactionParser::new(TRANSACTION_PARSER)?;
+        let transactions = parser.parse_csv_string(CSV, "transactions.csv")?;
+        assert_eq!(transactions.len(), 6);
+        let transaction = &transactions[0];
+        assert!(transaction.amount(py)?.eq(decimal(py, "1000.00")?)?);
+        assert_eq!(transaction.account(), "Account1");
+        assert_eq!(transaction.description(), "Income");
+        assert_eq!(transaction.time().to_rfc3339(), "2023-05-10T08:00:00+00:00");
+        assert_eq!(
+            transaction.source().and_then(|s| s.to_str()),
+            Some("transactions.csv")
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn test_categorize_records() -> PyResult<()> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let parser = PyTransactionParser::new(TRANSACTION_PARSER)?;
+        let transactions = parser.parse_csv_string(CSV, "transactions.csv")?;
+        let categorizer = PyCategorizer::new(CATEGORIZER)?;
+        let records = categorizer.categorize(py, transactions)?;
+        assert_eq!(records.len(), 6);
+        let record = records.get_item(1)?.downcast_into::<PyDict>()?;
+        let item = |key: &str| -> PyResult<Bound<'_, PyAny>> {
+            Ok(record.get_item(key)?.expect("record has every key"))
+        };
+        // Amounts stay exact decimals instead of floats
+        assert!(item("amount")?.is_instance(&decimal(py, "0")?.get_type())?);
+        assert!(item("amount")?.eq(decimal(py, "-50.00")?)?);
+        assert_eq!(item("description")?.extract::<String>()?, "Groceries 1");
+        assert_eq!(item("category")?.extract::<String>()?, "Expenses:Food");
+        assert!(item("matcher")?.extract::<Option<String>>()?.is_some());
+        Ok(())
+    })
+}
+
+#[test]
+fn test_categorize_uncategorized_record() -> PyResult<()> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let parser = PyTransactionParser::new(TRANSACTION_PARSER)?;
+        let transactions = parser.parse_csv_string(CSV, "transactions.csv")?;
+        let categorizer = PyCategorizer::new("categories: []")?;
+        let records = categorizer.categorize(py, transactions)?;
+        let record = records.get_item(0)?.downcast_into::<PyDict>()?;
+        assert!(record.get_item("category")?.is_some_and(|c| c.is_none()));
+        assert!(record.get_item("matcher")?.is_some_and(|m| m.is_none()));
+        Ok(())
+    })
+}
+
+#[test]
+fn test_errors_are_value_errors() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let Err(error) = PyCategorizer::new("categories: [") else {
+            panic!("invalid yaml is an error");
+        };
+        assert!(error.is_instance_of::<PyValueError>(py));
+        // The message includes the sources of the error
+        assert!(error
+            .value_bound(py)
+            .to_string()
+            .starts_with("invalid yaml: did not find expected node content"));
+
+        let parser = PyTransactionParser::new(TRANSACTION_PARSER).expect("valid parser");
+        let Err(error) = parser.parse_csv_string(CSV, "transactions.txt") else {
+            panic!("a file name matching no csv config is an error");
+        };
+        assert!(error.is_instance_of::<PyValueError>(py));
+
+        let Err(error) = crate::PyTransaction::new(
+            "ten",
+            String::from("Account1"),
+            String::from("Income"),
+            chrono::Utc::now(),
+            None,
+        ) else {
+            panic!("an invalid amount is an error");
+        };
+        assert!(error.is_instance_of::<PyValueError>(py));
+        assert!(error
+            .value_bound(py)
+            .to_string()
+            .starts_with("invalid amount ten"));
+    });
+}
@@ -0,0 +1,197 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use slotted_pig_lib::{
+    categorizer::{CategorizedList, Categorizer},
+    fetch,
+    report::{Stats, Top, Totals},
+    transaction::Transaction,
+    validate::{validate_categorizer, validate_transaction_parser, Issue},
+    warning::Warning,
+};
+
+use crate::workspace::{ConfigKind, Error, Workspace};
+
+/// Largest transactions and merchants in the top report unless `n` is given
+const DEFAULT_TOP: usize = 10;
+
+/// JSON API of a workspace for every device of a self-hosted install
+///
+/// Statements and configs are uploaded as their raw contents, everything else is returned as
+/// JSON. Every request reads the workspace so edits from other devices are seen right away.
+pub fn router(workspace: Workspace) -> Router {
+    Router::new()
+        .route("/api/statements", get(statements))
+        .route(
+            "/api/statements/:name",
+            put(write_statement).delete(remove_statement),
+        )
+        .route("/api/config/:kind", get(config).put(write_config))
+        .route("/api/categorized", get(categorized))
+        .route("/api/uncategorized", get(uncategorized))
+        .route("/api/warnings", get(warnings))
+        .route("/api/reports/stats", get(stats))
+        .route("/api/reports/totals", get(totals))
+        .route("/api/reports/top", get(top))
+        .fallback(|| async { ApiError::new(StatusCode::NOT_FOUND, "no such endpoint") })
+        .with_state(workspace)
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    error: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, error: impl ToString) -> Self {
+        Self {
+            status,
+            error: error.to_string(),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::InvalidStatementName(_) => StatusCode::BAD_REQUEST,
+            Error::MissingStatement(_) => StatusCode::NOT_FOUND,
+            Error::MissingConfig(_) => StatusCode::CONFLICT,
+            Error::Io(_) | Error::Fetch(fetch::Error::Io(_) | fetch::Error::Join(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            Error::Transaction(_) | Error::Categorizer(_) | Error::Fetch(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+        };
+        // Include the sources, the variants alone only name the failing step
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(e) = source {
+            message.push_str(&format!(": {e}"));
+            source = e.source();
+        }
+        Self::new(status, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Parsed transactions of every statement with the categorizer
+async fn load(workspace: &Workspace) -> Result<(Categorizer, Vec<Transaction>), ApiError> {
+    Ok((workspace.categorizer()?, workspace.transactions().await?))
+}
+
+async fn statements(State(workspace): State<Workspace>) -> ApiResult<Vec<String>> {
+    Ok(Json(workspace.statements()?))
+}
+
+async fn write_statement(
+    State(workspace): State<Workspace>,
+    Path(name): Path<String>,
+    contents: String,
+) -> Result<StatusCode, ApiError> {
+    workspace.write_statement(&name, &contents)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_statement(
+    State(workspace): State<Workspace>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    workspace.remove_statement(&name)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn config(
+    State(workspace): State<Workspace>,
+    Path(kind): Path<ConfigKind>,
+) -> Result<String, ApiError> {
+    workspace
+        .config(kind)?
+        .ok_or_else(|| Error::MissingConfig(kind).into())
+}
+
+/// Replace a config, returning the problems found in it (ie unreachable categories)
+async fn write_config(
+    State(workspace): State<Workspace>,
+    Path(kind): Path<ConfigKind>,
+    yaml: String,
+) -> ApiResult<Vec<Issue>> {
+    workspace.write_config(kind, &yaml)?;
+    Ok(Json(match kind {
+        ConfigKind::TransactionParser => validate_transaction_parser(&yaml),
+        ConfigKind::Categorizer => validate_categorizer(&yaml),
+    }))
+}
+
+async fn categorized(State(workspace): State<Workspace>) -> ApiResult<CategorizedList> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    let (mut categorized, _uncategorized) = categorizer.categorize(&transactions);
+    categorizer.round_totals(&mut categorized);
+    Ok(Json(categorized))
+}
+
+async fn uncategorized(State(workspace): State<Workspace>) -> ApiResult<Vec<Transaction>> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    let (_categorized, uncategorized) = categorizer.categorize(&transactions);
+    Ok(Json(uncategorized.into_iter().cloned().collect()))
+}
+
+/// Problems categorizing which do not stop it (ie transaction filters which filter out every
+/// transaction)
+async fn warnings(State(workspace): State<Workspace>) -> ApiResult<Vec<Warning>> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    Ok(Json(categorizer.warnings(&transactions)))
+}
+
+async fn stats(State(workspace): State<Workspace>) -> ApiResult<Stats> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    Ok(Json(Stats::new(&categorizer, &transactions)))
+}
+
+#[derive(Deserialize)]
+struct TotalsQuery {
+    /// Deepest level of categories listed, every level if not given
+    depth: Option<usize>,
+}
+
+async fn totals(
+    State(workspace): State<Workspace>,
+    Query(query): Query<TotalsQuery>,
+) -> ApiResult<Totals> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    let (categorized, _uncategorized) = categorizer.categorize(&transactions);
+    Ok(Json(Totals::new(&categorized, query.depth)))
+}
+
+#[derive(Deserialize)]
+struct TopQuery {
+    /// Number of transactions and merchants
+    n: Option<usize>,
+    /// Colon separated category path or category name the transactions are limited to
+    category: Option<String>,
+}
+
+async fn top(State(workspace): State<Workspace>, Query(query): Query<TopQuery>) -> ApiResult<Top> {
+    let (categorizer, transactions) = load(&workspace).await?;
+    Ok(Json(Top::new(
+        &categorizer.assign(&transactions),
+        query.n.unwrap_or(DEFAULT_TOP),
+        query.category.as_deref(),
+        None,
+    )))
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+
+use crate::workspace::Workspace;
+
+mod api;
+#[cfg(test)]
+mod tests;
+mod workspace;
+
+/// Self-hosted JSON API of a workspace of statements and configs shared by every device
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, env = "SLOTTED_PIG_ADDRESS", default_value = "127.0.0.1:8080")]
+    address: String,
+    /// Directory the uploaded statements and configs are kept in
+    #[arg(long, env = "SLOTTED_PIG_DATA_DIR", default_value = "slotted-pig-data")]
+    data_dir: PathBuf,
+    /// Log level
+    #[arg(long, env = "SLOTTED_PIG_LOG_LEVEL")]
+    log_level: Option<LogLevel>,
+}
+
+/// Level of the log messages written to stderr
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => Self::TRACE,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Warning => Self::WARN,
+            LogLevel::Error | LogLevel::Critical => Self::ERROR,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    // The server logs and the diagnostics of the library are both `tracing` events
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::from(args.log_level.unwrap_or_default()))
+        .with_target(false)
+        .init();
+
+    let listener = tokio::net::TcpListener::bind(&args.address)
+        .await
+        .with_context(|| format!("failed to listen on {}", args.address))?;
+    tracing::info!(
+        "serving {} on http://{}",
+        args.data_dir.display(),
+        args.address
+    );
+    axum::serve(listener, api::router(Workspace::new(args.data_dir))).await?;
+    Ok(())
+}
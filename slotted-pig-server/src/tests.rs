@@ -0,0 +1,96 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+use crate::{api::router, workspace::Workspace};
+
+const CSV: &str = include_str!("../../examples/transactions.csv");
+const TRANSACTION_PARSER: &str = include_str!("../../examples/transaction_parser.yaml");
+const CATEGORIZER: &str = include_str!("../../examples/categorizer.yaml");
+
+async fn request(app: &Router, method: Method, uri: &str, body: &str) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn app() -> (TempDir, Router) {
+    let dir = TempDir::new().unwrap();
+    let app = router(Workspace::new(dir.path().to_path_buf()));
+    (dir, app)
+}
+
+#[tokio::test]
+async fn test_upload_and_categorize() {
+    let (_dir, app) = app();
+    for (uri, body) in [
+        ("/api/config/transaction-parser", TRANSACTION_PARSER),
+        ("/api/config/categorizer", CATEGORIZER),
+    ] {
+        let (status, issues) = request(&app, Method::PUT, uri, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(issues, "[]");
+    }
+    let (status, _) = request(&app, Method::PUT, "/api/statements/march.csv", CSV).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, statements) = request(&app, Method::GET, "/api/statements", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(statements, r#"["march.csv"]"#);
+
+    let (status, categorized) = request(&app, Method::GET, "/api/categorized", "").await;
+    assert_eq!(status, StatusCode::OK);
+    let categorized = serde_json::from_str::<Value>(&categorized).unwrap();
+    assert_eq!(categorized["categorized"][0]["category"], "Income");
+
+    let (status, uncategorized) = request(&app, Method::GET, "/api/uncategorized", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(uncategorized, "[]");
+
+    let (status, warnings) = request(&app, Method::GET, "/api/warnings", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(warnings, "[]");
+
+    let (status, _) = request(&app, Method::GET, "/api/reports/totals?depth=1", "").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = request(&app, Method::DELETE, "/api/statements/march.csv", "").await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, statements) = request(&app, Method::GET, "/api/statements", "").await;
+    assert_eq!(statements, "[]");
+}
+
+#[tokio::test]
+async fn test_invalid_requests() {
+    let (_dir, app) = app();
+    let (status, _) = request(&app, Method::PUT, "/api/statements/..%2Fescape.csv", CSV).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request(&app, Method::PUT, "/api/statements/notes.txt", CSV).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // A categorizer which does not parse is rejected and not saved
+    let (status, error) = request(&app, Method::PUT, "/api/config/categorizer", "nope: 1").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(error.contains("unknown field `nope`"), "{error}");
+    let (status, _) = request(&app, Method::GET, "/api/config/categorizer", "").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = request(&app, Method::GET, "/api/categorized", "").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = request(&app, Method::DELETE, "/api/statements/missing.csv", "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = request(&app, Method::GET, "/api/nothing", "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
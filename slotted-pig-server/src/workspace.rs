@@ -0,0 +1,170 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use displaydoc::Display;
+use serde::Deserialize;
+use slotted_pig_lib::{
+    categorizer::{self, Categorizer},
+    fetch::{self, read_transaction_dir, TRANSACTION_FILE_EXTENSIONS},
+    transaction::{self, Transaction, TransactionParser},
+};
+use thiserror::Error;
+
+/// Directory of the uploaded statements under the data directory
+const STATEMENTS_DIR: &str = "statements";
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// io
+    Io(#[from] io::Error),
+    /// transaction
+    Transaction(#[from] transaction::Error),
+    /// categorizer
+    Categorizer(#[from] categorizer::Error),
+    /// statements
+    Fetch(#[from] fetch::Error),
+    /// invalid statement name `{0}`, expected a file name ending in .csv, .ofx, .qfx, or .qif
+    InvalidStatementName(String),
+    /// no statement named `{0}`
+    MissingStatement(String),
+    /// no {0} has been uploaded
+    MissingConfig(ConfigKind),
+}
+
+/// Config file of a workspace
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigKind {
+    /// transaction parser
+    TransactionParser,
+    /// categorizer
+    Categorizer,
+}
+
+impl ConfigKind {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::TransactionParser => "transaction_parser.yaml",
+            Self::Categorizer => "categorizer.yaml",
+        }
+    }
+}
+
+/// Statements and configs of the server kept in a data directory so every device sees the same
+/// files
+///
+/// The layout is the same as a desktop workspace folder, `transaction_parser.yaml` and
+/// `categorizer.yaml` next to a `statements` directory of bank files.
+#[derive(Clone, Debug)]
+pub struct Workspace {
+    dir: PathBuf,
+}
+
+impl Workspace {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn statements_dir(&self) -> PathBuf {
+        self.dir.join(STATEMENTS_DIR)
+    }
+
+    /// Path of the statement, rejecting names which are not plain bank file names (ie `../x`)
+    fn statement_path(&self, name: &str) -> Result<PathBuf, Error> {
+        let path = Path::new(name);
+        let valid = path.file_name().is_some_and(|file_name| file_name == name)
+            && !name.starts_with('.')
+            && path
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .is_some_and(|extension| TRANSACTION_FILE_EXTENSIONS.contains(&extension.as_str()));
+        if !valid {
+            return Err(Error::InvalidStatementName(name.to_string()));
+        }
+        Ok(self.statements_dir().join(name))
+    }
+
+    /// Names of the uploaded statements in alphabetical order
+    pub fn statements(&self) -> Result<Vec<String>, Error> {
+        let entries = match fs::read_dir(self.statements_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Add a statement replacing the statement of the same name
+    pub fn write_statement(&self, name: &str, contents: &str) -> Result<(), Error> {
+        let path = self.statement_path(name)?;
+        fs::create_dir_all(self.statements_dir())?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn remove_statement(&self, name: &str) -> Result<(), Error> {
+        match fs::remove_file(self.statement_path(name)?) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Err(Error::MissingStatement(name.to_string()))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Yaml of the config, `None` if it has not been uploaded
+    pub fn config(&self, kind: ConfigKind) -> Result<Option<String>, Error> {
+        match fs::read_to_string(self.dir.join(kind.file_name())) {
+            Ok(yaml) => Ok(Some(yaml)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Replace the config, rejecting yaml which does not parse
+    pub fn write_config(&self, kind: ConfigKind, yaml: &str) -> Result<(), Error> {
+        match kind {
+            ConfigKind::TransactionParser => {
+                TransactionParser::from_yaml_buffer(yaml)?;
+            }
+            ConfigKind::Categorizer => {
+                Categorizer::from_yaml_buffer(yaml)?;
+            }
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(kind.file_name()), yaml)?;
+        Ok(())
+    }
+
+    pub fn categorizer(&self) -> Result<Categorizer, Error> {
+        let yaml = self
+            .config(ConfigKind::Categorizer)?
+            .ok_or(Error::MissingConfig(ConfigKind::Categorizer))?;
+        Ok(Categorizer::from_yaml_buffer(yaml)?)
+    }
+
+    /// Parse every statement without blocking, csv files with the transaction parser, merging
+    /// transactions in more than one statement (ie overlapping downloads)
+    pub async fn transactions(&self) -> Result<Vec<Transaction>, Error> {
+        let transaction_parser = self
+            .config(ConfigKind::TransactionParser)?
+            .map(TransactionParser::from_yaml_buffer)
+            .transpose()?
+            .map(Arc::new);
+        read_transaction_dir(transaction_parser, &self.statements_dir())
+            .await
+            .map_err(|e| match e {
+                fetch::Error::MissingTransactionParser(_) => {
+                    Error::MissingConfig(ConfigKind::TransactionParser)
+                }
+                e => e.into(),
+            })
+    }
+}
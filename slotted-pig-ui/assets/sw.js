@@ -0,0 +1,37 @@
+// Service worker caching the app so it works offline, uploaded files never leave the device so
+// only the app itself is cached
+const CACHE = "slotted-pig-v1";
+const PRECACHE = ["./", "./index.html", "./tailwind.css", "./manifest.webmanifest", "./icon.svg"];
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(PRECACHE)));
+    self.skipWaiting();
+});
+
+self.addEventListener("activate", (event) => {
+    // Drop the caches of older versions
+    event.waitUntil(
+        caches.keys().then((keys) =>
+            Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))),
+        ),
+    );
+    self.clients.claim();
+});
+
+// Answer from the cache when possible and refresh the cache from the network in the background
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) return;
+    event.respondWith(
+        caches.open(CACHE).then(async (cache) => {
+            const cached = await cache.match(request, { ignoreSearch: true });
+            const fetched = fetch(request)
+                .then((response) => {
+                    if (response.ok) cache.put(request, response.clone());
+                    return response;
+                })
+                .catch(() => cached);
+            return cached || fetched;
+        }),
+    );
+});
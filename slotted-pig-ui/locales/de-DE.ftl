@@ -0,0 +1,150 @@
+## Page
+
+categorized = Kategorisiert
+files = Dateien
+file-transactions = Buchungen
+file-transaction-parser = Buchungsparser
+file-categorizer = Kategorisierer
+file-overrides = Korrekturen
+transaction-files = Buchungsdateien:
+remove = entfernen
+drop-hint = oder ziehen Sie eine dieser Dateien irgendwo auf das Fenster
+unrecognized-file = { $name } ist weder eine Bankdatei noch ein Buchungsparser, Kategorisierer, Korrekturen oder eine kategorisierte YAML-Datei
+warning-every-transaction-filtered = Die Buchungsfilter filtern alle { $count } Buchungen heraus
+warning-unmatched-override = Die Korrektur von { $description } am { $time } passt zu keiner Buchung
+upload-to-categorize = Laden Sie Buchungen, einen Kategorisierer und für CSV-Dateien einen Buchungsparser hoch, um zu kategorisieren
+settings = Einstellungen
+accounts = Konten
+show-all = alle anzeigen
+
+## Settings
+
+language = Sprache
+theme = Design
+theme-light = hell
+theme-dark = dunkel
+accent = Akzent
+color-amounts = Beträge einfärben
+separators = Trennzeichen
+currency = Währung
+symbol-after = Symbol nachgestellt
+cents = Cent
+keyboard-help = Tasten: / Suche, s Kategorien sortieren, t Buchungen sortieren, u rückgängig, r wiederholen, ↑ ↓ bewegen, Enter umschalten, ← → zu- und aufklappen
+
+## Views and sorting
+
+view-by-month = nach Monat
+view-by-category = nach Kategorie
+view-drill-down = Drilldown
+view-cashflow = Geldfluss
+view-compare = Vergleich
+sort-categories = Kategorien
+sort-transactions = Buchungen
+sort-original-order = ursprüngliche Reihenfolge
+sort-total-descending = Summe absteigend
+sort-total-ascending = Summe aufsteigend
+sort-absolute-total-descending = absolute Summe absteigend
+sort-absolute-total-ascending = absolute Summe aufsteigend
+sort-name-descending = Name absteigend
+sort-name-ascending = Name aufsteigend
+sort-time-descending = Zeit absteigend
+sort-time-ascending = Zeit aufsteigend
+sort-amount-descending = Betrag absteigend
+sort-amount-ascending = Betrag aufsteigend
+sort-absolute-amount-descending = absoluter Betrag absteigend
+sort-absolute-amount-ascending = absoluter Betrag aufsteigend
+search = Suche
+from = Von
+to = bis
+all-dates = alle Daten
+expand-all = alle aufklappen
+collapse-all = alle zuklappen
+depth = Tiefe
+
+## Categories and months
+
+trend = Verlauf
+hide-trend = Verlauf ausblenden
+trend-title = Summe pro Monat
+copy = kopieren
+copy-markdown = als Markdown kopieren
+copy-title = Pfad, Anzahl, Summe und größte Buchungen der Kategorie kopieren
+copied = kopiert
+summary-heading = { $path }: { $count } Buchungen, Summe { $total }
+summary-largest = Größte Buchungen
+date = Datum
+load-more = { $count } weitere laden ({ $remaining } übrig)
+month = Monat
+income = Einnahmen
+expenses = Ausgaben
+net = Saldo
+all = alle
+period = Zeitraum
+sankey-budget = Budget
+sankey-savings = Ersparnis
+sankey-deficit = Defizit
+category = Kategorie
+change = Änderung
+
+## Uncategorized and rules
+
+uncategorized = Nicht kategorisiert ({ $transactions ->
+        [one] { $transactions } Buchung
+       *[other] { $transactions } Buchungen
+    }, { $payees ->
+        [one] { $payees } Empfänger
+       *[other] { $payees } Empfänger
+    })
+date-range = { $first } bis { $last }
+rule-add = Regel zu { $category } hinzufügen für
+rule-payee = den Empfänger
+rule-transaction = nur diese Buchung
+rule-matches = trifft auf { $count ->
+        [one] { $count } nicht kategorisierte Buchung
+       *[other] { $count } nicht kategorisierte Buchungen
+    } zu
+rule-missing-categorizer = laden Sie einen Kategorisierer hoch, um Regeln hinzuzufügen
+apply = übernehmen
+cancel = abbrechen
+undo = rückgängig
+redo = wiederholen
+download-categorizer = aktualisierten Kategorisierer herunterladen
+
+## Categorizer editor
+
+edit-categorizer = Kategorisierer bearbeiten
+close-editor = Kategorisierer-Editor schließen
+no-problems = keine Probleme
+changed-by-edit = Durch die letzte Änderung geändert
+
+## Transaction detail
+
+transaction = Buchung
+close = schließen
+amount = Betrag
+account = Konto
+description = Beschreibung
+time = Zeit
+source = Quelle
+unknown = unbekannt
+csv-row = CSV-Zeile
+csv-row-unavailable = nur für hochgeladene CSV-Dateien verfügbar
+categorization = Kategorisierung
+explain-missing-categorizer = laden Sie den Kategorisierer hoch, um die Kategorie zu erklären
+edit = bearbeiten
+save = speichern
+invalid-amount = der Betrag ist keine Zahl
+edited-from = bearbeitet von
+revert-edits = Änderungen zurücksetzen
+download-overrides = Korrekturen herunterladen
+
+## Desktop menu
+
+menu-file = Datei
+menu-open-workspace = Arbeitsbereich öffnen…
+menu-open-recent = Zuletzt geöffnet
+menu-open-transactions = Buchungen öffnen…
+menu-open-transaction-parser = Buchungsparser öffnen…
+menu-open-categorizer = Kategorisierer öffnen…
+menu-open-overrides = Korrekturen öffnen…
+menu-export = Kategorisierung exportieren…
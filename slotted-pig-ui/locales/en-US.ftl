@@ -0,0 +1,150 @@
+## Page
+
+categorized = Categorized
+files = Files
+file-transactions = Transactions
+file-transaction-parser = Transaction parser
+file-categorizer = Categorizer
+file-overrides = Overrides
+transaction-files = Transaction files:
+remove = remove
+drop-hint = or drop any of these files anywhere on the window
+unrecognized-file = { $name } is not a bank file, transaction parser, categorizer, overrides, or categorized yaml
+warning-every-transaction-filtered = The transaction filters filter out all { $count } transactions
+warning-unmatched-override = The override of { $description } on { $time } matches no transaction
+upload-to-categorize = Upload transactions, a categorizer, and a transaction parser for csv files to categorize
+settings = Settings
+accounts = Accounts
+show-all = show all
+
+## Settings
+
+language = Language
+theme = Theme
+theme-light = light
+theme-dark = dark
+accent = Accent
+color-amounts = color amounts
+separators = Separators
+currency = Currency
+symbol-after = symbol after
+cents = cents
+keyboard-help = keys: / search, s sort categories, t sort transactions, u undo, r redo, ↑ ↓ move, enter toggle, ← → collapse and expand
+
+## Views and sorting
+
+view-by-month = by month
+view-by-category = by category
+view-drill-down = drill down
+view-cashflow = cashflow
+view-compare = compare
+sort-categories = Categories
+sort-transactions = Transactions
+sort-original-order = original order
+sort-total-descending = total descending
+sort-total-ascending = total ascending
+sort-absolute-total-descending = absolute total descending
+sort-absolute-total-ascending = absolute total ascending
+sort-name-descending = name descending
+sort-name-ascending = name ascending
+sort-time-descending = time descending
+sort-time-ascending = time ascending
+sort-amount-descending = amount descending
+sort-amount-ascending = amount ascending
+sort-absolute-amount-descending = absolute amount descending
+sort-absolute-amount-ascending = absolute amount ascending
+search = Search
+from = From
+to = to
+all-dates = all dates
+expand-all = expand all
+collapse-all = collapse all
+depth = Depth
+
+## Categories and months
+
+trend = trend
+hide-trend = hide trend
+trend-title = Total per month
+copy = copy
+copy-markdown = copy markdown
+copy-title = Copy the path, count, total, and largest transactions of the category
+copied = copied
+summary-heading = { $path }: { $count } transactions, total { $total }
+summary-largest = Largest transactions
+date = date
+load-more = load { $count } more ({ $remaining } remaining)
+month = Month
+income = Income
+expenses = Expenses
+net = Net
+all = all
+period = Period
+sankey-budget = Budget
+sankey-savings = Savings
+sankey-deficit = Deficit
+category = Category
+change = Change
+
+## Uncategorized and rules
+
+uncategorized = Uncategorized ({ $transactions ->
+        [one] { $transactions } transaction
+       *[other] { $transactions } transactions
+    }, { $payees ->
+        [one] { $payees } payee
+       *[other] { $payees } payees
+    })
+date-range = { $first } to { $last }
+rule-add = Add a rule to { $category } for
+rule-payee = the payee
+rule-transaction = this transaction only
+rule-matches = matches { $count ->
+        [one] { $count } uncategorized transaction
+       *[other] { $count } uncategorized transactions
+    }
+rule-missing-categorizer = upload a categorizer to add rules
+apply = apply
+cancel = cancel
+undo = undo
+redo = redo
+download-categorizer = download updated categorizer
+
+## Categorizer editor
+
+edit-categorizer = edit categorizer
+close-editor = close categorizer editor
+no-problems = no problems
+changed-by-edit = Changed by the last edit
+
+## Transaction detail
+
+transaction = Transaction
+close = close
+amount = amount
+account = account
+description = description
+time = time
+source = source
+unknown = unknown
+csv-row = Csv row
+csv-row-unavailable = only available for uploaded csv files
+categorization = Categorization
+explain-missing-categorizer = upload the categorizer to explain the category
+edit = edit
+save = save
+invalid-amount = the amount is not a number
+edited-from = edited from
+revert-edits = revert edits
+download-overrides = download overrides
+
+## Desktop menu
+
+menu-file = File
+menu-open-workspace = Open workspace…
+menu-open-recent = Open recent
+menu-open-transactions = Open transactions…
+menu-open-transaction-parser = Open transaction parser…
+menu-open-categorizer = Open categorizer…
+menu-open-overrides = Open overrides…
+menu-export = Export categorized…
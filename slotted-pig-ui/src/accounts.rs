@@ -0,0 +1,56 @@
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+use slotted_pig_lib::{categorizer::CategorizedList, transaction::Transaction};
+
+use crate::i18n::tr;
+
+/// Every account of the categorized and uncategorized transactions in alphabetical order
+pub fn accounts(categorized_list: &CategorizedList, uncategorized: &[Transaction]) -> Vec<String> {
+    categorized_list
+        .assignments()
+        .into_iter()
+        .map(|assignment| assignment.transaction)
+        .chain(uncategorized)
+        .map(|transaction| transaction.account.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Sidebar of every account with a checkbox, the transactions of unchecked accounts are hidden
+/// and the totals recomputed without them
+#[component]
+pub fn AccountFilter(accounts: Vec<String>, hidden_accounts: Signal<BTreeSet<String>>) -> Element {
+    rsx!(
+        aside { class: "flex flex-col font-mono text-sm py-2 pr-4",
+            h2 { class: "font-bold", {tr("accounts")} }
+            for account in accounts {
+                label { key: "{account}",
+                    input {
+                        r#type: "checkbox",
+                        checked: !hidden_accounts.read().contains(&account),
+                        oninput: {
+                            let account = account.clone();
+                            move |event: FormEvent| {
+                                if event.value() == "true" {
+                                    hidden_accounts.write().remove(&account);
+                                } else {
+                                    hidden_accounts.write().insert(account.clone());
+                                }
+                            }
+                        }
+                    }
+                    " {account}"
+                }
+            }
+            if !hidden_accounts.read().is_empty() {
+                button {
+                    class: "underline text-left",
+                    onclick: move |_| hidden_accounts.write().clear(),
+                    {tr("show-all")}
+                }
+            }
+        }
+    )
+}
@@ -0,0 +1,38 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::{categorizer::Outcome, transaction::Transaction};
+
+use crate::session::SharedCategorizer;
+
+/// Badge of the leaf category and the number of its transaction matcher which claimed the
+/// transaction (ie `restaurants #2`), hovering it shows the matcher's rules
+#[component]
+pub fn MatcherBadge(transaction: Transaction) -> Element {
+    let categorizer = use_context::<Signal<SharedCategorizer>>();
+    let categorizer = categorizer.read().categorizer.clone()?;
+    let explanation = categorizer.explain(&transaction);
+    let assigned = explanation.assigned()?;
+
+    // Matchers are numbered within their category in the order they are tried
+    let number = explanation
+        .candidates
+        .iter()
+        .take_while(|c| c.outcome != Outcome::Assigned)
+        .filter(|c| c.category_path == assigned.category_path)
+        .count()
+        + 1;
+    let leaf = assigned.category_path.last().copied().unwrap_or_default();
+    let title = format!(
+        "{} [{}]",
+        assigned.category_path.join(":"),
+        assigned.matcher
+    );
+
+    rsx!(
+        span {
+            class: "font-mono text-xs px-1",
+            style: "border: 1px solid var(--accent); border-radius: 0.25rem",
+            title,
+            "{leaf} #{number}"
+        }
+    )
+}
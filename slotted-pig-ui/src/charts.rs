@@ -0,0 +1,356 @@
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::{Categorized, CategorizedChildren, CategorizedList},
+    report::{Bucket, Pivot},
+    util::Formatter,
+};
+
+use crate::{
+    drill::{category_path, drill, subcategories, Breadcrumbs},
+    session::SharedCategorizer,
+    Amount,
+};
+
+/// Colors of chart slices without a configured color, reused in order
+pub const COLORS: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// Radius of a circle with a circumference of 100 so slice lengths are percentages
+const RADIUS: f64 = 15.915_494_309_189_533;
+
+/// Donut of the absolute totals of one level of categories, clicking a slice or its legend entry
+/// drills into its subcategories
+#[component]
+pub fn Donut(categorized_list: Vec<Categorized>) -> Element {
+    let path = use_signal(Vec::<String>::new);
+
+    // Fall back to the top level if the categories changed and the path no longer exists
+    let (shown_path, categories) = match subcategories(&categorized_list, &path.read()) {
+        Some(categories) => (path.read().clone(), categories.to_vec()),
+        None => (Vec::new(), categorized_list.clone()),
+    };
+    let shared_categorizer = use_context::<Signal<SharedCategorizer>>();
+    let slices = slices(&categories, &shown_path, &shared_categorizer.read());
+
+    rsx!(
+        div { class: "font-mono text-sm w-64",
+            Breadcrumbs { path, shown_path: shown_path.clone() }
+            svg { view_box: "0 0 42 42", width: "100%",
+                circle {
+                    cx: "21",
+                    cy: "21",
+                    r: "{RADIUS}",
+                    fill: "none",
+                    stroke: "#eeeeee",
+                    stroke_width: "6"
+                }
+                for slice in slices.iter().cloned() {
+                    circle {
+                        key: "{slice.category}",
+                        class: if slice.drillable { "hover:cursor-pointer" } else { "" },
+                        cx: "21",
+                        cy: "21",
+                        r: "{RADIUS}",
+                        fill: "none",
+                        stroke: "{slice.color}",
+                        stroke_width: "6",
+                        stroke_dasharray: "{slice.percent} {100.0 - slice.percent}",
+                        // Slices start at the top and go clockwise
+                        stroke_dashoffset: "{25.0 - slice.offset}",
+                        onclick: {
+                            let shown_path = shown_path.clone();
+                            let category = slice.category.clone();
+                            move |_| {
+                                if slice.drillable {
+                                    drill(path, &shown_path, category.clone());
+                                }
+                            }
+                        },
+                        title { "{slice.category} {slice.percent:.1}%" }
+                    }
+                }
+            }
+            ul {
+                for slice in slices {
+                    li {
+                        key: "{slice.category}",
+                        class: if slice.drillable { "hover:cursor-pointer" } else { "" },
+                        onclick: {
+                            let shown_path = shown_path.clone();
+                            let category = slice.category.clone();
+                            move |_| {
+                                if slice.drillable {
+                                    drill(path, &shown_path, category.clone());
+                                }
+                            }
+                        },
+                        span { style: "color: {slice.color}", "■ " }
+                        if let Some(icon) = &slice.icon {
+                            "{icon} "
+                        }
+                        "{slice.category}"
+                        Amount { amount: slice.total }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Width of the stacked bars chart in SVG units
+const BARS_WIDTH: f64 = 600.0;
+/// Height of the stacked bars chart in SVG units
+const BARS_HEIGHT: f64 = 240.0;
+
+/// Bar of the spending of each month stacked by top level category, hovering a segment shows its
+/// amount
+#[component]
+pub fn StackedBars(categorized_list: Vec<Categorized>) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let shared_categorizer = use_context::<Signal<SharedCategorizer>>();
+    let categorized_list = CategorizedList {
+        categorized: categorized_list,
+    };
+    let pivot = Pivot::new(&categorized_list.assignments(), Bucket::Month, Some(1));
+    if pivot.periods.is_empty() {
+        return None;
+    }
+
+    // Spending is the negated total, months where a category has net income are left out
+    let spending = |total: &BigDecimal| {
+        if total < &BigDecimal::zero() {
+            -total
+        } else {
+            BigDecimal::zero()
+        }
+    };
+    let max = (0..pivot.periods.len())
+        .map(|i| {
+            pivot
+                .rows
+                .iter()
+                .map(|row| spending(&row.totals[i]).to_f64().unwrap_or_default())
+                .sum::<f64>()
+        })
+        .fold(0.0, f64::max);
+    let width = BARS_WIDTH / pivot.periods.len() as f64;
+    let mut segments = Vec::new();
+    for (i, period) in pivot.periods.iter().enumerate() {
+        let mut y = BARS_HEIGHT;
+        for (row_index, row) in pivot.rows.iter().enumerate() {
+            let amount = spending(&row.totals[i]);
+            let height = if max > 0.0 {
+                amount.to_f64().unwrap_or_default() / max * BARS_HEIGHT
+            } else {
+                0.0
+            };
+            if height <= 0.0 {
+                continue;
+            }
+            y -= height;
+            segments.push(Segment {
+                key: format!("{period} {}", row.category),
+                x: i as f64 * width,
+                y,
+                height,
+                color: shared_categorizer.read().color(&row.category, row_index),
+                tooltip: format!(
+                    "{period} {}: {}",
+                    row.category,
+                    formatter.read().format(&amount)
+                ),
+            });
+        }
+    }
+    let label_step = pivot.periods.len().div_ceil(12);
+
+    rsx!(
+        div { class: "font-mono text-sm",
+            svg {
+                view_box: "0 0 {BARS_WIDTH} {BARS_HEIGHT + 16.0}",
+                width: "100%",
+                for segment in segments {
+                    rect {
+                        key: "{segment.key}",
+                        x: "{segment.x + width * 0.1}",
+                        y: "{segment.y}",
+                        width: "{width * 0.8}",
+                        height: "{segment.height}",
+                        fill: "{segment.color}",
+                        title { "{segment.tooltip}" }
+                    }
+                }
+                for (i, period) in pivot.periods.iter().enumerate().step_by(label_step) {
+                    text {
+                        key: "{period}",
+                        x: "{(i as f64 + 0.5) * width}",
+                        y: "{BARS_HEIGHT + 12.0}",
+                        font_size: "10",
+                        text_anchor: "middle",
+                        "{period}"
+                    }
+                }
+            }
+            div { class: "flex gap-2",
+                for (i, row) in pivot.rows.iter().enumerate() {
+                    span { key: "{row.category}",
+                        span { style: "color: {shared_categorizer.read().color(&row.category, i)}",
+                            "■ "
+                        }
+                        "{row.category}"
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Width of the trend chart in SVG units
+const TREND_WIDTH: f64 = 400.0;
+/// Height of the trend chart in SVG units
+const TREND_HEIGHT: f64 = 120.0;
+
+/// Area chart of the total of a category for every month from its first to its last transaction
+#[component]
+pub fn Trend(categorized: Categorized) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let categorized_list = CategorizedList {
+        categorized: vec![categorized],
+    };
+    let pivot = Pivot::new(&categorized_list.assignments(), Bucket::Month, Some(1));
+    let Some(row) = pivot.rows.first() else {
+        return rsx!( span { class: "font-mono text-xs", "no transactions" } );
+    };
+
+    let totals = row
+        .totals
+        .iter()
+        .map(|total| total.to_f64().unwrap_or_default())
+        .collect::<Vec<_>>();
+    // Keep zero in range so the area is filled from zero
+    let max = totals.iter().copied().fold(0.0, f64::max);
+    let min = totals.iter().copied().fold(0.0, f64::min);
+    let range = if max > min { max - min } else { 1.0 };
+    let step = TREND_WIDTH / totals.len().max(2).saturating_sub(1) as f64;
+    let x = |i: usize| i as f64 * step;
+    let y = |total: f64| (max - total) / range * TREND_HEIGHT;
+    let line = totals
+        .iter()
+        .enumerate()
+        .map(|(i, total)| format!("{},{}", x(i), y(*total)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let area = format!(
+        "{},{} {line} {},{}",
+        x(0),
+        y(0.0),
+        x(totals.len() - 1),
+        y(0.0)
+    );
+    let points = pivot
+        .periods
+        .iter()
+        .zip(&row.totals)
+        .enumerate()
+        .map(|(i, (period, total))| {
+            (
+                period.clone(),
+                x(i),
+                y(totals[i]),
+                format!("{period}: {}", formatter.read().format(total)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        div { class: "font-mono text-xs",
+            svg {
+                view_box: "-4 -4 {TREND_WIDTH + 8.0} {TREND_HEIGHT + 8.0}",
+                width: "{TREND_WIDTH}",
+                polygon { points: "{area}", fill: COLORS[0], fill_opacity: "0.3" }
+                polyline { points: "{line}", fill: "none", stroke: COLORS[0], stroke_width: "2" }
+                line {
+                    x1: "0",
+                    y1: "{y(0.0)}",
+                    x2: "{TREND_WIDTH}",
+                    y2: "{y(0.0)}",
+                    stroke: "#999999",
+                    stroke_width: "0.5"
+                }
+                for (period, cx, cy, tooltip) in points {
+                    circle { key: "{period}", cx: "{cx}", cy: "{cy}", r: "3", fill: COLORS[0],
+                        title { "{tooltip}" }
+                    }
+                }
+            }
+            div { class: "flex justify-between",
+                span { "{pivot.periods[0]}" }
+                span { "{pivot.periods[pivot.periods.len() - 1]}" }
+            }
+        }
+    )
+}
+
+/// Category segment of a month's bar
+struct Segment {
+    key: String,
+    x: f64,
+    y: f64,
+    height: f64,
+    color: String,
+    tooltip: String,
+}
+
+/// Segment of the donut
+#[derive(Clone, Debug, PartialEq)]
+struct Slice {
+    category: String,
+    total: BigDecimal,
+    color: String,
+    icon: Option<String>,
+    /// Length of the slice as a percent of the circle
+    percent: f64,
+    /// Percent of the circle before the slice
+    offset: f64,
+    /// Whether the category has subcategories to drill into
+    drillable: bool,
+}
+
+fn slices(
+    categories: &[Categorized],
+    shown_path: &[String],
+    shared_categorizer: &SharedCategorizer,
+) -> Vec<Slice> {
+    let absolute_total = categories
+        .iter()
+        .filter_map(|c| c.absolute_total.to_f64())
+        .sum::<f64>();
+    let mut offset = 0.0;
+    categories
+        .iter()
+        .filter(|c| c.count > 0)
+        .enumerate()
+        .map(|(i, c)| {
+            let percent = if absolute_total > 0.0 {
+                c.absolute_total.to_f64().unwrap_or_default() / absolute_total * 100.0
+            } else {
+                0.0
+            };
+            let path = category_path(shown_path, &c.category);
+            let slice = Slice {
+                category: c.category.clone(),
+                total: c.total.clone(),
+                color: shared_categorizer.color(&path, i),
+                icon: shared_categorizer.icon(&path),
+                percent,
+                offset,
+                drillable: matches!(c.children, CategorizedChildren::Subcategories(_)),
+            };
+            offset += percent;
+            slice
+        })
+        .collect()
+}
@@ -0,0 +1,115 @@
+use bigdecimal::{BigDecimal, Zero};
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::{Categorized, CategorizedList, CategoryDiff},
+    report::DateRange,
+    util::Formatter,
+};
+
+use crate::{i18n::tr, months::month_ranges, Amount};
+
+/// Totals of every category in two months side by side with the change between them, changes are
+/// colored by sign so the categories that moved stand out
+///
+/// The latest month is compared to the month before it until other months are chosen.
+#[component]
+pub fn Comparison(categorized_list: Vec<Categorized>) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let previous_month = use_signal(String::new);
+    let current_month = use_signal(String::new);
+
+    let categorized_list = CategorizedList {
+        categorized: categorized_list,
+    };
+    let months = month_ranges(&categorized_list);
+    let chosen = |label: String, default: usize| {
+        months
+            .iter()
+            .find(|range| range.label == label)
+            .or_else(|| months.get(default))
+            .cloned()
+    };
+    let (Some(current), Some(previous)) = (chosen(current_month(), 0), chosen(previous_month(), 1))
+    else {
+        return None;
+    };
+    let within = |range: &DateRange| {
+        let mut categorized_list = categorized_list.clone();
+        categorized_list.retain_transactions(|t| range.contains(t.time.date_naive()));
+        categorized_list
+    };
+    let diffs = within(&current).diff(&within(&previous));
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            div { class: "flex gap-2",
+                MonthSelect { months: months.clone(), month: previous_month, selected: previous.label.clone() }
+                "→"
+                MonthSelect { months: months.clone(), month: current_month, selected: current.label.clone() }
+            }
+            table {
+                tr {
+                    th { class: "text-left pr-2", {tr("category")} }
+                    th { class: "text-right pr-2", "{previous.label}" }
+                    th { class: "text-right pr-2", "{current.label}" }
+                    th { class: "text-right pr-2", {tr("change")} }
+                    th { class: "text-right", "%" }
+                }
+                for (key, diff) in diffs.into_iter().map(|diff| (diff.path.join(":"), diff)) {
+                    tr { key: "{key}",
+                        td {
+                            class: "pr-2",
+                            style: "padding-left: {diff.path.len() - 1}rem",
+                            "{diff.path[diff.path.len() - 1]}"
+                        }
+                        td { class: "text-right pr-2", Amount { amount: diff.previous.clone() } }
+                        td { class: "text-right pr-2", Amount { amount: diff.current.clone() } }
+                        td { class: "text-right pr-2", style: change_style(&diff.change()),
+                            {formatter.read().format(&diff.change())}
+                        }
+                        td { class: "text-right", style: change_style(&diff.change()),
+                            {percent(&diff)}
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Select of the month on one side of the comparison
+#[component]
+fn MonthSelect(months: Vec<DateRange>, month: Signal<String>, selected: String) -> Element {
+    rsx!(
+        select { onchange: move |event| month.set(event.value()),
+            for range in months {
+                option {
+                    key: "{range.label}",
+                    value: "{range.label}",
+                    selected: range.label == selected,
+                    "{range.label}"
+                }
+            }
+        }
+    )
+}
+
+/// Signed percent change rounded to a tenth, empty if the previous total is zero
+fn percent(diff: &CategoryDiff) -> String {
+    match diff.percent_change() {
+        Some(percent) if percent > BigDecimal::zero() => format!("+{}%", percent.round(1)),
+        Some(percent) => format!("{}%", percent.round(1)),
+        None => String::new(),
+    }
+}
+
+/// Color of a change, unchanged totals are faded
+fn change_style(change: &BigDecimal) -> &'static str {
+    if change.is_zero() {
+        "opacity: 0.5"
+    } else if change > &BigDecimal::zero() {
+        "color: var(--positive); font-weight: bold"
+    } else {
+        "color: var(--negative); font-weight: bold"
+    }
+}
@@ -0,0 +1,233 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use slotted_pig_lib::categorizer::{
+    Categorized, CategorizedChildren, CategorySort, TransactionSort,
+};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+
+use crate::i18n::tr;
+
+/// Top level navigation of the categorization
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Default, Deserialize, EnumIter, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum View {
+    /// Months with their totals, each expanding into its category tree
+    #[default]
+    ByMonth,
+    /// Category tree of every transaction
+    ByCategory,
+    /// One level of categories at a time under a breadcrumb trail, for small screens
+    DrillDown,
+    /// Sankey diagram of income flowing into the categories
+    Cashflow,
+    /// Totals of two months side by side with their changes
+    Compare,
+}
+
+/// Buttons to switch between the views
+#[component]
+pub fn ViewControls(view: Signal<View>) -> Element {
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            for option in View::iter() {
+                button {
+                    key: "{option.as_ref()}",
+                    class: if view() == option { "font-bold" } else { "underline" },
+                    onclick: move |_| view.set(option),
+                    {label("view", option.as_ref())}
+                }
+            }
+        }
+    )
+}
+
+/// Dropdowns to sort the categories and transactions, the first choice keeps the original order
+#[component]
+pub fn SortControls(
+    category_sort: Signal<Option<CategorySort>>,
+    transaction_sort: Signal<Option<TransactionSort>>,
+) -> Element {
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            label {
+                {tr("sort-categories")}
+                " "
+                select {
+                    onchange: move |event| category_sort.set(CategorySort::from_str(&event.value()).ok()),
+                    option { value: "", {tr("sort-original-order")} }
+                    for sort in CategorySort::iter() {
+                        option {
+                            key: "{sort.as_ref()}",
+                            value: sort.as_ref(),
+                            selected: category_sort() == Some(sort),
+                            {label("sort", sort.as_ref())}
+                        }
+                    }
+                }
+            }
+            label {
+                {tr("sort-transactions")}
+                " "
+                select {
+                    onchange: move |event| {
+                        transaction_sort.set(TransactionSort::from_str(&event.value()).ok())
+                    },
+                    option { value: "", {tr("sort-original-order")} }
+                    for sort in TransactionSort::iter() {
+                        option {
+                            key: "{sort.as_ref()}",
+                            value: sort.as_ref(),
+                            selected: transaction_sort() == Some(sort),
+                            {label("sort", sort.as_ref())}
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Text input keeping only the transactions whose description contains the text, focused with `/`
+#[component]
+pub fn Search(search: Signal<String>) -> Element {
+    rsx!(
+        label { class: "font-mono text-sm",
+            {tr("search")}
+            " "
+            input {
+                id: "search",
+                r#type: "search",
+                value: "{search}",
+                oninput: move |event| search.set(event.value())
+            }
+        }
+    )
+}
+
+/// Days shown, unbounded on the sides without a date
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DateFilter {
+    /// First day shown
+    pub start: Option<NaiveDate>,
+    /// Last day shown
+    pub end: Option<NaiveDate>,
+}
+
+impl DateFilter {
+    /// Check if the filter shows every day
+    pub fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    /// Check if the date is shown
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start.is_none_or(|start| start <= date) && self.end.is_none_or(|end| date <= end)
+    }
+}
+
+/// Date inputs of the first and last day shown, hiding the transactions outside them
+#[component]
+pub fn DateFilterControls(date_filter: Signal<DateFilter>) -> Element {
+    let format = |date: Option<NaiveDate>| date.map(|d| d.to_string()).unwrap_or_default();
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            label {
+                {tr("from")}
+                " "
+                input {
+                    r#type: "date",
+                    value: "{format(date_filter().start)}",
+                    oninput: move |event| date_filter.write().start = event.value().parse().ok()
+                }
+            }
+            label {
+                {tr("to")}
+                " "
+                input {
+                    r#type: "date",
+                    value: "{format(date_filter().end)}",
+                    oninput: move |event| date_filter.write().end = event.value().parse().ok()
+                }
+            }
+            if !date_filter().is_empty() {
+                button {
+                    class: "underline",
+                    onclick: move |_| date_filter.set(DateFilter::default()),
+                    {tr("all-dates")}
+                }
+            }
+        }
+    )
+}
+
+/// Choice after the current one, cycling through the original order (`None`) after the last
+pub fn next_choice<T: IntoEnumIterator + PartialEq>(current: Option<T>) -> Option<T> {
+    let mut choices = T::iter();
+    match current {
+        None => choices.next(),
+        Some(current) => choices.skip_while(|c| *c != current).nth(1),
+    }
+}
+
+/// Translated label of a snake case choice (ie the `sort-total-descending` message of
+/// `total_descending`)
+fn label(prefix: &str, choice: &str) -> String {
+    tr(&format!("{prefix}-{}", choice.replace('_', "-")))
+}
+
+/// Depth the category tree is expanded to, set by the tree controls and provided as context
+///
+/// Every write re-applies the depth, even an unchanged one, to the categories toggled
+/// individually since.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Expansion {
+    /// Categories with fewer ancestors than the depth are expanded
+    pub depth: usize,
+}
+
+/// Buttons to expand or collapse every category and a slider of the expanded depth
+#[component]
+pub fn TreeControls(categorized_list: Vec<Categorized>) -> Element {
+    let mut expansion = use_context::<Signal<Expansion>>();
+    let max_depth = categorized_list.iter().map(depth).max().unwrap_or_default();
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            button { onclick: move |_| expansion.write().depth = max_depth, {tr("expand-all")} }
+            button { onclick: move |_| expansion.write().depth = 0, {tr("collapse-all")} }
+            label {
+                {tr("depth")}
+                " "
+                input {
+                    r#type: "range",
+                    min: "0",
+                    max: "{max_depth}",
+                    value: "{expansion().depth.min(max_depth)}",
+                    oninput: move |event| {
+                        if let Ok(depth) = event.value().parse() {
+                            expansion.write().depth = depth;
+                        }
+                    }
+                }
+                " {expansion().depth.min(max_depth)}"
+            }
+        }
+    )
+}
+
+/// Number of levels below and including the category, a category of transactions has a depth of 1
+fn depth(categorized: &Categorized) -> usize {
+    match &categorized.children {
+        CategorizedChildren::Transactions(_) => 1,
+        CategorizedChildren::Subcategories(subcategories) => {
+            1 + subcategories.iter().map(depth).max().unwrap_or_default()
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::{Categorized, CategorizedList},
+    report::Top,
+    util::Formatter,
+};
+
+use crate::i18n::{tr, tr_args};
+
+/// Number of the largest transactions listed in a copied summary
+const SUMMARY_TRANSACTIONS: usize = 5;
+
+/// Page script writing the text it is sent to the clipboard
+const CLIPBOARD_SCRIPT: &str = "navigator.clipboard.writeText(await dioxus.recv());";
+
+/// Summary of a category with its path, count, total, and largest transactions as plain text or
+/// Markdown for pasting into chats and notes
+pub fn summary(
+    path: &[String],
+    categorized: &Categorized,
+    formatter: &Formatter,
+    markdown: bool,
+) -> String {
+    let heading = tr_args(
+        "summary-heading",
+        [
+            ("path", path.join(":").into()),
+            ("count", categorized.count.into()),
+            ("total", formatter.format(&categorized.total).into()),
+        ],
+    );
+    let categorized_list = CategorizedList {
+        categorized: vec![categorized.clone()],
+    };
+    let top = Top::new(
+        &categorized_list.assignments(),
+        SUMMARY_TRANSACTIONS,
+        None,
+        None,
+    );
+    let rows = top.transactions.iter().map(|t| {
+        [
+            formatter.format_date(t.date),
+            t.description.clone(),
+            formatter.format(&t.amount),
+        ]
+    });
+
+    let mut lines = Vec::new();
+    if markdown {
+        lines.push(format!("**{heading}**"));
+        lines.push(String::new());
+        lines.push(format!(
+            "| {} | {} | {} |",
+            tr("date"),
+            tr("description"),
+            tr("amount")
+        ));
+        lines.push(String::from("| --- | --- | ---: |"));
+        for row in rows {
+            let cells = row.map(|cell| cell.replace('|', "\\|"));
+            lines.push(format!("| {} |", cells.join(" | ")));
+        }
+    } else {
+        lines.push(heading);
+        lines.push(format!("{}:", tr("summary-largest")));
+        for row in rows {
+            lines.push(format!("  {}", row.join("  ")));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Links copying the summary of a category to the clipboard as plain text or Markdown
+#[component]
+pub fn CopySummary(path: Vec<String>, categorized: Categorized) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let mut copied = use_signal(|| false);
+
+    rsx!(
+        for (markdown, label) in [(false, tr("copy")), (true, tr("copy-markdown"))] {
+            span {
+                key: "{label}",
+                class: "font-mono text-xs px-1 underline",
+                title: tr("copy-title"),
+                onclick: {
+                    let path = path.clone();
+                    let categorized = categorized.clone();
+                    move |event: MouseEvent| {
+                        event.stop_propagation();
+                        let text = summary(&path, &categorized, &formatter.read(), markdown);
+                        let _ = eval(CLIPBOARD_SCRIPT).send(text.into());
+                        copied.set(true);
+                    }
+                },
+                "{label}"
+            }
+        }
+        if copied() {
+            span { class: "font-mono text-xs px-1", {tr("copied")} }
+        }
+    )
+}
@@ -0,0 +1,285 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use dioxus::{desktop::window, prelude::*};
+use futures_channel::mpsc::unbounded;
+use futures_util::StreamExt;
+use log::warn;
+use muda::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use rfd::AsyncFileDialog;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    session::{Categorization, FileKind, UploadedFile},
+};
+
+/// Menu item id prefix of the open recent entries, followed by the workspace path
+const RECENT_PREFIX: &str = "recent:";
+/// Workspaces kept in the open recent menu
+const RECENT_LIMIT: usize = 10;
+/// Names of the config files loaded from a workspace folder
+const WORKSPACE_CONFIGS: [(&str, FileKind); 3] = [
+    ("transaction_parser.yaml", FileKind::TransactionParser),
+    ("categorizer.yaml", FileKind::Categorizer),
+    ("overrides.yaml", FileKind::Overrides),
+];
+
+/// Action chosen from the file menu
+enum MenuAction {
+    Open(FileKind),
+    OpenWorkspace,
+    OpenRecent(PathBuf),
+    Export,
+}
+
+impl MenuAction {
+    fn from_id(id: &str) -> Option<Self> {
+        if let Some(path) = id.strip_prefix(RECENT_PREFIX) {
+            return Some(Self::OpenRecent(PathBuf::from(path)));
+        }
+        Some(match id {
+            "open-transactions" => Self::Open(FileKind::Transactions),
+            "open-transaction-parser" => Self::Open(FileKind::TransactionParser),
+            "open-categorizer" => Self::Open(FileKind::Categorizer),
+            "open-overrides" => Self::Open(FileKind::Overrides),
+            "open-workspace" => Self::OpenWorkspace,
+            "export" => Self::Export,
+            _ => return None,
+        })
+    }
+}
+
+/// Workspace folders opened most recently first, saved next to the saved state
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+struct RecentWorkspaces(Vec<PathBuf>);
+
+impl RecentWorkspaces {
+    fn path() -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("slotted-pig").join("recent.yaml"))
+    }
+
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_yaml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| anyhow!("missing app data directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Move the workspace to the front, dropping the oldest beyond the limit
+    fn add(&mut self, workspace: &Path) {
+        self.0.retain(|path| path != workspace);
+        self.0.insert(0, workspace.to_path_buf());
+        self.0.truncate(RECENT_LIMIT);
+    }
+}
+
+fn recent_item(workspace: &Path) -> MenuItem {
+    MenuItem::with_id(
+        format!("{RECENT_PREFIX}{}", workspace.display()),
+        workspace.display().to_string(),
+        true,
+        None,
+    )
+}
+
+/// File menu of the window opening files and workspaces with native dialogs, replacing the
+/// default menu bar
+fn build_menu(recent: &RecentWorkspaces) -> Result<(Menu, Submenu)> {
+    let item = |id: &str, message: &str| MenuItem::with_id(id, tr(message), true, None);
+    let recent_menu = Submenu::new(tr("menu-open-recent"), true);
+    for workspace in &recent.0 {
+        recent_menu.append(&recent_item(workspace))?;
+    }
+    let file_menu = Submenu::new(tr("menu-file"), true);
+    file_menu.append_items(&[
+        &item("open-workspace", "menu-open-workspace"),
+        &recent_menu,
+        &PredefinedMenuItem::separator(),
+        &item("open-transactions", "menu-open-transactions"),
+        &item("open-transaction-parser", "menu-open-transaction-parser"),
+        &item("open-categorizer", "menu-open-categorizer"),
+        &item("open-overrides", "menu-open-overrides"),
+        &PredefinedMenuItem::separator(),
+        &item("export", "menu-export"),
+        &PredefinedMenuItem::separator(),
+        &PredefinedMenuItem::quit(None),
+    ])?;
+    let menu = Menu::new();
+    menu.append(&file_menu)?;
+
+    let desktop = window();
+    let window = &desktop.window;
+    #[cfg(target_os = "windows")]
+    {
+        use dioxus::desktop::tao::platform::windows::WindowExtWindows;
+        menu.init_for_hwnd(window.hwnd())?;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use dioxus::desktop::tao::platform::unix::WindowExtUnix;
+        menu.init_for_gtk_window(window.gtk_window(), window.default_vbox())?;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = window;
+        menu.init_for_nsapp();
+    }
+    Ok((menu, recent_menu))
+}
+
+/// Read the files as uploaded files named by their file name
+fn read_paths(paths: impl IntoIterator<Item = PathBuf>) -> Result<Vec<UploadedFile>> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Ok(UploadedFile { name, contents })
+        })
+        .collect()
+}
+
+/// Files of a workspace folder by kind, the bank files by extension and the configs by name
+fn read_workspace(folder: &Path) -> Result<Vec<(FileKind, Vec<UploadedFile>)>> {
+    let mut transaction_paths = Vec::new();
+    let mut configs = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if let Some((_, kind)) = WORKSPACE_CONFIGS.iter().find(|(config, _)| *config == name) {
+            configs.push((*kind, read_paths([path])?));
+        } else if has_extension(&path, FileKind::Transactions) {
+            transaction_paths.push(path);
+        }
+    }
+    transaction_paths.sort();
+    let mut files = vec![(FileKind::Transactions, read_paths(transaction_paths)?)];
+    files.extend(configs);
+    Ok(files)
+}
+
+/// Extensions of a file kind without their dots (ie `csv`)
+fn extensions(kind: FileKind) -> Vec<&'static str> {
+    kind.accept()
+        .split(',')
+        .map(|extension| extension.trim_start_matches('.'))
+        .collect()
+}
+
+fn has_extension(path: &Path, kind: FileKind) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| extensions(kind).iter().any(|e| *e == extension))
+}
+
+/// Add a file menu and run its actions, opening files through `upload` like the file inputs
+pub fn use_file_menu(
+    mut upload: impl FnMut(FileKind, Result<Vec<UploadedFile>>) + Copy + 'static,
+    categorization_result: Signal<Result<Categorization, String>>,
+) {
+    // The menu is kept for the life of the app, it is removed once dropped
+    let menu = use_hook(|| {
+        let recent = RecentWorkspaces::load();
+        build_menu(&recent)
+            .map_err(|e| warn!("failed to build the file menu: {e}"))
+            .ok()
+    });
+
+    use_future(move || {
+        let recent_menu = menu.as_ref().map(|(_, recent_menu)| recent_menu.clone());
+        async move {
+            let (sender, mut receiver) = unbounded();
+            MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+                let _ = sender.unbounded_send(event.id.0);
+            }));
+            let mut open_workspace = move |folder: PathBuf| {
+                match read_workspace(&folder) {
+                    Ok(files) => {
+                        for (kind, files) in files {
+                            upload(kind, Ok(files));
+                        }
+                    }
+                    Err(e) => upload(FileKind::Transactions, Err(e)),
+                }
+                let mut recent = RecentWorkspaces::load();
+                recent.add(&folder);
+                if let Err(e) = recent.save() {
+                    warn!("failed to save recent workspaces: {e}");
+                }
+                if let Some(recent_menu) = &recent_menu {
+                    let _ = recent_menu.prepend(&recent_item(&folder));
+                }
+            };
+            while let Some(id) = receiver.next().await {
+                match MenuAction::from_id(&id) {
+                    Some(MenuAction::Open(kind)) => {
+                        let dialog = AsyncFileDialog::new()
+                            .add_filter(tr(kind.message()), extensions(kind).as_slice());
+                        let handles = if kind == FileKind::Transactions {
+                            dialog.pick_files().await
+                        } else {
+                            dialog.pick_file().await.map(|handle| vec![handle])
+                        };
+                        if let Some(handles) = handles {
+                            let paths = handles.iter().map(|handle| handle.path().to_path_buf());
+                            upload(kind, read_paths(paths));
+                        }
+                    }
+                    Some(MenuAction::OpenWorkspace) => {
+                        if let Some(folder) = AsyncFileDialog::new().pick_folder().await {
+                            open_workspace(folder.path().to_path_buf());
+                        }
+                    }
+                    Some(MenuAction::OpenRecent(folder)) => open_workspace(folder),
+                    Some(MenuAction::Export) => {
+                        let contents = match &*categorization_result.read() {
+                            Ok(categorization) => {
+                                serde_yaml::to_string(&categorization.categorized)
+                            }
+                            Err(_) => continue,
+                        };
+                        let contents = match contents {
+                            Ok(contents) => contents,
+                            Err(e) => {
+                                warn!("failed to serialize the categorization: {e}");
+                                continue;
+                            }
+                        };
+                        let file = AsyncFileDialog::new()
+                            .add_filter("yaml", &["yaml", "yml"])
+                            .set_file_name("categorized.yaml")
+                            .save_file()
+                            .await;
+                        if let Some(file) = file {
+                            if let Err(e) = file.write(contents.as_bytes()).await {
+                                warn!("failed to export the categorization: {e}");
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,255 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use dioxus::prelude::*;
+use slotted_pig_lib::transaction::Transaction;
+
+use crate::{
+    download::DownloadLink,
+    i18n::tr,
+    keyboard::TREE_ROW,
+    session::{Categorization, Session},
+    undo::History,
+    Amount,
+};
+
+/// Transaction whose details are shown, `None` when the detail panel is closed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectedTransaction(pub Option<Transaction>);
+
+/// Fields of the transaction being edited as typed
+#[derive(Clone, Debug, PartialEq)]
+struct EditDraft {
+    amount: String,
+    account: String,
+    description: String,
+}
+
+/// Panel over the page with every field of the selected transaction, the csv row it was parsed
+/// from, and how the categorizer categorized it, clicking outside the panel or escape closes it
+///
+/// Transactions of uploaded files can be edited, the edits are saved as overrides which are
+/// applied before categorizing and can be downloaded.
+#[component]
+pub fn TransactionDetail(
+    session: Signal<Session>,
+    categorization_result: Signal<Result<Categorization, String>>,
+) -> Element {
+    let mut selected = use_context::<Signal<SelectedTransaction>>();
+    let mut history = use_context::<Signal<History>>();
+    let mut draft = use_signal(|| None::<EditDraft>);
+    let mut error = use_signal(|| None::<String>);
+    let transaction = selected().0?;
+
+    let mut close = move || {
+        selected.set(SelectedTransaction(None));
+        draft.set(None);
+        error.set(None);
+    };
+    // Edits are re-categorized right away so the panel follows the edited transaction
+    let mut save = move |transaction: Transaction, edited: Transaction| {
+        let before = session();
+        if let Err(e) = session.write().edit_transaction(&transaction, &edited) {
+            error.set(Some(e));
+            return;
+        }
+        history.write().record(before);
+        if let Some(result) = session.read().categorize() {
+            categorization_result.set(result);
+        }
+        selected.set(SelectedTransaction(Some(edited)));
+        draft.set(None);
+        error.set(None);
+    };
+
+    let source = transaction
+        .source
+        .as_ref()
+        .map_or_else(|| tr("unknown"), |s| s.display().to_string());
+    let original = session.read().original(&transaction);
+    let editable = session.read().is_complete();
+    let overrides = session.read().overrides.clone();
+    let csv_row = session.read().csv_row(&transaction);
+    let explanation = session
+        .read()
+        .categorizer()
+        .map(|categorizer| categorizer.explain(&transaction).to_string());
+
+    rsx!(
+        div {
+            style: "position: fixed; inset: 0; background: rgba(0, 0, 0, 0.4); display: flex; align-items: center; justify-content: center",
+            onclick: move |_| close(),
+            div {
+                class: "font-mono text-sm {TREE_ROW}",
+                style: "background: var(--background); padding: 1rem; max-width: 48rem; max-height: 80vh; overflow: auto",
+                tabindex: "0",
+                onclick: move |event| event.stop_propagation(),
+                onkeydown: move |event| {
+                    if event.key() == Key::Escape {
+                        close();
+                    }
+                },
+                div { class: "flex justify-between",
+                    h2 { class: "text-base", {tr("transaction")} }
+                    div { class: "flex gap-2",
+                        if editable && draft.read().is_none() {
+                            button {
+                                onclick: {
+                                    let transaction = transaction.clone();
+                                    move |_| {
+                                        draft.set(Some(EditDraft {
+                                            amount: transaction.amount.to_string(),
+                                            account: transaction.account.clone(),
+                                            description: transaction.description.clone(),
+                                        }));
+                                    }
+                                },
+                                {tr("edit")}
+                            }
+                        }
+                        button { onclick: move |_| close(), {tr("close")} }
+                    }
+                }
+                match draft() {
+                    Some(fields) => rsx!(
+                        table {
+                            tr {
+                                th { class: "text-left pr-2", {tr("amount")} }
+                                td {
+                                    input {
+                                        value: "{fields.amount}",
+                                        oninput: move |event| {
+                                            if let Some(fields) = draft.write().as_mut() {
+                                                fields.amount = event.value();
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("account")} }
+                                td {
+                                    input {
+                                        value: "{fields.account}",
+                                        oninput: move |event| {
+                                            if let Some(fields) = draft.write().as_mut() {
+                                                fields.account = event.value();
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("description")} }
+                                td {
+                                    input {
+                                        class: "w-full",
+                                        value: "{fields.description}",
+                                        oninput: move |event| {
+                                            if let Some(fields) = draft.write().as_mut() {
+                                                fields.description = event.value();
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "flex gap-2 py-1",
+                            button {
+                                class: "underline",
+                                onclick: {
+                                    let transaction = transaction.clone();
+                                    let invalid_amount = tr("invalid-amount");
+                                    move |_| {
+                                        let Ok(amount) = BigDecimal::from_str(fields.amount.trim()) else {
+                                            error.set(Some(invalid_amount.clone()));
+                                            return;
+                                        };
+                                        let edited = Transaction {
+                                            amount,
+                                            account: fields.account.clone(),
+                                            description: fields.description.clone(),
+                                            ..transaction.clone()
+                                        };
+                                        save(transaction.clone(), edited);
+                                    }
+                                },
+                                {tr("save")}
+                            }
+                            button { class: "underline", onclick: move |_| draft.set(None), {tr("cancel")} }
+                        }
+                    ),
+                    None => rsx!(
+                        table {
+                            tr {
+                                th { class: "text-left pr-2", {tr("amount")} }
+                                td { Amount { amount: transaction.amount.clone() } }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("account")} }
+                                td { "{transaction.account}" }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("description")} }
+                                td { "{transaction.description}" }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("time")} }
+                                td { "{transaction.time.to_rfc3339()}" }
+                            }
+                            tr {
+                                th { class: "text-left pr-2", {tr("source")} }
+                                td { "{source}" }
+                            }
+                        }
+                    ),
+                }
+                if let Some(e) = error() {
+                    div { class: "text-red-600", "{e}" }
+                }
+                if original != transaction {
+                    div { class: "flex gap-2 py-1",
+                        span { class: "text-xs", {tr("edited-from")} " {original.amount} | {original.account} | {original.description}" }
+                        button {
+                            class: "underline",
+                            onclick: {
+                                let transaction = transaction.clone();
+                                let original = original.clone();
+                                move |_| save(transaction.clone(), original.clone())
+                            },
+                            {tr("revert-edits")}
+                        }
+                    }
+                }
+                if let Some(file) = overrides {
+                    DownloadLink { file, label: tr("download-overrides") }
+                }
+                h3 { class: "pt-2", {tr("csv-row")} }
+                match csv_row {
+                    Some(row) => rsx!(
+                        table {
+                            if !row.headers.is_empty() {
+                                tr {
+                                    for (i, header) in row.headers.iter().enumerate() {
+                                        th { key: "{i}", class: "text-left pr-2", "{header}" }
+                                    }
+                                }
+                            }
+                            tr {
+                                for (i, value) in row.values.iter().enumerate() {
+                                    td { key: "{i}", class: "pr-2", "{value}" }
+                                }
+                            }
+                        }
+                    ),
+                    None => rsx!( div { {tr("csv-row-unavailable")} } ),
+                }
+                h3 { class: "pt-2", {tr("categorization")} }
+                match explanation {
+                    Ok(explanation) => rsx!( pre { class: "text-xs", "{explanation}" } ),
+                    Err(_) => rsx!( div { {tr("explain-missing-categorizer")} } ),
+                }
+            }
+        }
+    )
+}
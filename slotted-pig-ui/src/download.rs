@@ -0,0 +1,29 @@
+use dioxus::prelude::*;
+
+use crate::session::UploadedFile;
+
+/// Link saving the contents of a file under its name
+#[component]
+pub fn DownloadLink(file: UploadedFile, label: String) -> Element {
+    rsx!(
+        a {
+            class: "underline",
+            href: "{data_url(&file.contents)}",
+            download: "{file.name}",
+            "{label}"
+        }
+    )
+}
+
+/// Data url of text, percent encoding everything but unreserved characters
+fn data_url(contents: &str) -> String {
+    let mut url = String::from("data:text/plain;charset=utf-8,");
+    for byte in contents.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            url.push(char::from(byte));
+        } else {
+            url.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    url
+}
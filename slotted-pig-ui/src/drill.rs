@@ -0,0 +1,138 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::categorizer::{Categorized, CategorizedChildren};
+
+use crate::{i18n::tr, session::SharedCategorizer, Amount, Transactions};
+
+/// One level of categories at a time under a breadcrumb trail, clicking a category drills into
+/// its subcategories or transactions
+#[component]
+pub fn DrillDown(categorized_list: Vec<Categorized>) -> Element {
+    let path = use_signal(Vec::<String>::new);
+    let shared_categorizer = use_context::<Signal<SharedCategorizer>>();
+
+    // Fall back to the top level if the categories changed and the path no longer exists
+    let (shown_path, children) = match category_at(&categorized_list, &path.read()) {
+        Some(category) => (path.read().clone(), category.children.clone()),
+        None => (
+            Vec::new(),
+            CategorizedChildren::Subcategories(categorized_list.clone()),
+        ),
+    };
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            Breadcrumbs { path, shown_path: shown_path.clone() }
+            match children {
+                CategorizedChildren::Subcategories(categories) => rsx!(
+                    ul {
+                        for (i, category) in categories.into_iter().enumerate() {
+                            li {
+                                key: "{category.category}",
+                                class: "flex gap-2 py-1 hover:cursor-pointer drill-row",
+                                tabindex: "0",
+                                onclick: {
+                                    let shown_path = shown_path.clone();
+                                    let name = category.category.clone();
+                                    move |_| drill(path, &shown_path, name.clone())
+                                },
+                                onkeydown: {
+                                    let shown_path = shown_path.clone();
+                                    let name = category.category.clone();
+                                    move |event: KeyboardEvent| {
+                                        if event.key() == Key::Enter {
+                                            drill(path, &shown_path, name.clone());
+                                        }
+                                    }
+                                },
+                                span {
+                                    style: "color: {shared_categorizer.read().color(&category_path(&shown_path, &category.category), i)}",
+                                    "■"
+                                }
+                                if let Some(icon) = shared_categorizer.read().icon(&category_path(&shown_path, &category.category)) {
+                                    span { "{icon}" }
+                                }
+                                span { class: "flex-1", "{category.category}" }
+                                span { "[{category.count}]" }
+                                Amount { amount: category.total }
+                                span { "›" }
+                            }
+                        }
+                    }
+                ),
+                CategorizedChildren::Transactions(transactions) => rsx!(Transactions { transactions }),
+            }
+        }
+    )
+}
+
+/// Trail of the drilled into categories, clicking a category goes back up to it
+#[component]
+pub fn Breadcrumbs(path: Signal<Vec<String>>, shown_path: Vec<String>) -> Element {
+    rsx!(
+        div { class: "flex gap-1",
+            span {
+                class: "hover:cursor-pointer underline",
+                onclick: move |_| path.write().clear(),
+                {tr("all")}
+            }
+            for (i, category) in shown_path.iter().enumerate() {
+                span { key: "{i}", "›" }
+                span {
+                    class: "hover:cursor-pointer underline",
+                    onclick: {
+                        let shown_path = shown_path.clone();
+                        move |_| path.set(shown_path[..=i].to_vec())
+                    },
+                    "{category}"
+                }
+            }
+        }
+    )
+}
+
+/// Drill into a category of the shown level
+pub fn drill(mut path: Signal<Vec<String>>, shown_path: &[String], category: String) {
+    let mut new_path = shown_path.to_vec();
+    new_path.push(category);
+    path.set(new_path);
+}
+
+/// Category at the path of category names, `None` if the path is empty or does not exist
+pub fn category_at<'a>(
+    mut categories: &'a [Categorized],
+    path: &[String],
+) -> Option<&'a Categorized> {
+    let (last, ancestors) = path.split_last()?;
+    for name in ancestors {
+        match &categories.iter().find(|c| &c.category == name)?.children {
+            CategorizedChildren::Subcategories(subcategories) => categories = subcategories,
+            CategorizedChildren::Transactions(_) => return None,
+        }
+    }
+    categories.iter().find(|c| &c.category == last)
+}
+
+/// Subcategories at the path of category names, `None` if the path does not exist or ends at a
+/// category of transactions
+pub fn subcategories<'a>(
+    categories: &'a [Categorized],
+    path: &[String],
+) -> Option<&'a [Categorized]> {
+    if path.is_empty() {
+        return Some(categories);
+    }
+    match &category_at(categories, path)?.children {
+        CategorizedChildren::Subcategories(subcategories) => Some(subcategories),
+        CategorizedChildren::Transactions(_) => None,
+    }
+}
+
+/// Colon separated path of a category shown under a path
+pub fn category_path(shown_path: &[String], category: &str) -> String {
+    shown_path
+        .iter()
+        .map(String::as_str)
+        .chain([category])
+        .collect::<Vec<_>>()
+        .join(":")
+}
@@ -0,0 +1,115 @@
+use bigdecimal::Zero;
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::CategoryDiff,
+    validate::{validate_categorizer, Issue},
+};
+
+use crate::{
+    i18n::tr,
+    session::{Categorization, Session, UploadedFile},
+    undo::History,
+    Amount,
+};
+
+/// Categorizer yaml used when editing without an uploaded categorizer
+const EMPTY_CATEGORIZER: &str = "categories: []\n";
+
+/// YAML editor of the session's categorizer which recategorizes the transactions on every edit
+/// that parses, listing the categories whose totals the edit changed
+#[component]
+pub fn CategorizerEditor(
+    session: Signal<Session>,
+    categorization_result: Signal<Result<Categorization, String>>,
+) -> Element {
+    let mut open = use_signal(|| false);
+    let mut history = use_context::<Signal<History>>();
+    // The edits of one opening of the editor are undone together instead of keystroke by keystroke
+    let mut recorded = use_signal(|| false);
+    let mut issues = use_signal(Vec::<Issue>::new);
+    let mut changes = use_signal(Vec::<CategoryDiff>::new);
+
+    let yaml = session
+        .read()
+        .categorizer
+        .as_ref()
+        .map_or_else(|| String::from(EMPTY_CATEGORIZER), |c| c.contents.clone());
+    let yaml_to_validate = yaml.clone();
+    let mut edit = move |yaml: String| {
+        if !recorded() {
+            history.write().record(session());
+            recorded.set(true);
+        }
+        issues.set(validate_categorizer(&yaml));
+        let name = session
+            .read()
+            .categorizer
+            .as_ref()
+            .map_or_else(|| String::from("categorizer.yaml"), |c| c.name.clone());
+        session.write().categorizer = Some(UploadedFile {
+            name,
+            contents: yaml,
+        });
+        // Keep showing the last categorization while the yaml does not parse
+        let Some(Ok(categorization)) = session.read().categorize() else {
+            return;
+        };
+        if let Ok(previous) = &*categorization_result.read() {
+            changes.set(
+                categorization
+                    .categorized
+                    .diff(&previous.categorized)
+                    .into_iter()
+                    .filter(|diff| !diff.change().is_zero())
+                    .collect(),
+            );
+        }
+        categorization_result.set(Ok(categorization));
+    };
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            button {
+                onclick: move |_| {
+                    if !open() {
+                        issues.set(validate_categorizer(&yaml_to_validate));
+                    }
+                    open.set(!open());
+                    recorded.set(false);
+                },
+                if open() { {tr("close-editor")} } else { {tr("edit-categorizer")} }
+            }
+            if open() {
+                textarea {
+                    class: "w-full font-mono text-xs",
+                    rows: "20",
+                    spellcheck: "false",
+                    value: "{yaml}",
+                    oninput: move |event| edit(event.value())
+                }
+                if issues.read().is_empty() {
+                    div { {tr("no-problems")} }
+                } else {
+                    ul {
+                        for (i, issue) in issues.read().iter().enumerate() {
+                            li { key: "{i}", class: "text-red-600", "{issue}" }
+                        }
+                    }
+                }
+                if !changes.read().is_empty() {
+                    div { {tr("changed-by-edit")} }
+                    ul { class: "list-disc pl-4",
+                        for (category, diff) in changes.read().iter().map(|d| (d.path.join(":"), d.clone())) {
+                            li { key: "{category}",
+                                "{category} "
+                                Amount { amount: diff.previous.clone() }
+                                "→"
+                                Amount { amount: diff.current.clone() }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
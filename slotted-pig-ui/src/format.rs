@@ -0,0 +1,79 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::util::Formatter;
+
+use crate::i18n::tr;
+
+/// Locales with distinct separators and an example of their amounts
+const LOCALES: [(&str, &str); 4] = [
+    ("en-US", "1,234.56"),
+    ("de-DE", "1.234,56"),
+    ("fr-FR", "1\u{202f}234,56"),
+    ("de-CH", "1’234.56"),
+];
+
+/// Settings of the currency symbol, separators, and cents of every amount in the app
+#[component]
+pub fn FormatControls() -> Element {
+    let mut formatter = use_context::<Signal<Formatter>>();
+    let selected_locale = LOCALES.iter().map(|(locale, _)| *locale).find(|locale| {
+        Formatter::from_locale(locale).is_some_and(|f| {
+            f.thousands_separator == formatter.read().thousands_separator
+                && f.decimal_separator == formatter.read().decimal_separator
+        })
+    });
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            label {
+                {tr("separators")}
+                " "
+                select {
+                    onchange: move |event| {
+                        if let Some(locale) = Formatter::from_locale(&event.value()) {
+                            let mut formatter = formatter.write();
+                            formatter.thousands_separator = locale.thousands_separator;
+                            formatter.decimal_separator = locale.decimal_separator;
+                        }
+                    },
+                    for (locale, example) in LOCALES {
+                        option {
+                            key: "{locale}",
+                            value: locale,
+                            selected: selected_locale == Some(locale),
+                            "{example}"
+                        }
+                    }
+                }
+            }
+            label {
+                {tr("currency")}
+                " "
+                input {
+                    class: "w-12",
+                    value: "{formatter.read().currency_symbol}",
+                    oninput: move |event| formatter.write().currency_symbol = event.value()
+                }
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: formatter.read().currency_suffix,
+                    oninput: move |event| formatter.write().currency_suffix = event.value() == "true"
+                }
+                " "
+                {tr("symbol-after")}
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: formatter.read().decimal_places > 0,
+                    oninput: move |event| {
+                        formatter.write().decimal_places = if event.value() == "true" { 2 } else { 0 };
+                    }
+                }
+                " "
+                {tr("cents")}
+            }
+        }
+    )
+}
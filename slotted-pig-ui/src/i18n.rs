@@ -0,0 +1,155 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use dioxus::prelude::*;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use slotted_pig_lib::util::Formatter;
+use strum::{AsRefStr, EnumIter, EnumString, IntoEnumIterator};
+use unic_langid::LanguageIdentifier;
+
+/// Languages the UI is translated to, named by their locale
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en-US")]
+    #[strum(serialize = "en-US")]
+    English,
+    #[serde(rename = "de-DE")]
+    #[strum(serialize = "de-DE")]
+    German,
+}
+
+impl Language {
+    /// Name of the language in the language itself
+    fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    /// Fluent messages of the language
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../locales/en-US.ftl"),
+            Self::German => include_str!("../locales/de-DE.ftl"),
+        }
+    }
+
+    /// `strftime` style date format of the language
+    fn date_format(self) -> &'static str {
+        match self {
+            Self::English => "%Y-%m-%d",
+            Self::German => "%d.%m.%Y",
+        }
+    }
+
+    /// Use the separators and date format of the language, keeping the currency and cents
+    pub fn localize(self, formatter: &mut Formatter) {
+        if let Some(locale) = Formatter::from_locale(self.as_ref()) {
+            formatter.thousands_separator = locale.thousands_separator;
+            formatter.decimal_separator = locale.decimal_separator;
+        }
+        formatter.date_format = self.date_format().to_string();
+    }
+
+    /// Translate the message, falling back to English and then the message id if the language
+    /// does not have it
+    pub fn translate(self, id: &str, args: Option<&FluentArgs>) -> String {
+        [self, Self::English]
+            .into_iter()
+            .find_map(|language| {
+                let bundle = language.bundle();
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(
+                    bundle
+                        .format_pattern(pattern, args, &mut errors)
+                        .into_owned(),
+                )
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn bundle(self) -> &'static FluentBundle<FluentResource> {
+        static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+        let bundles = BUNDLES.get_or_init(|| {
+            Language::iter()
+                .map(|language| {
+                    let locale = LanguageIdentifier::from_str(language.as_ref())
+                        .expect("language is a valid locale");
+                    let resource = FluentResource::try_new(language.source().to_string())
+                        .expect("failed to parse messages");
+                    let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+                    // Isolation marks show up as stray characters in monospace text
+                    bundle.set_use_isolating(false);
+                    bundle
+                        .add_resource(resource)
+                        .expect("messages have unique ids");
+                    bundle
+                })
+                .collect()
+        });
+        &bundles[self as usize]
+    }
+}
+
+/// Translate the message to the language of the app, only call while rendering a component
+pub fn tr(id: &str) -> String {
+    let language = consume_context::<Signal<Language>>();
+    let language = language();
+    language.translate(id, None)
+}
+
+/// Translate the message with its arguments (ie `tr_args("rule-matches", [("count", 2.into())])`)
+/// to the language of the app, only call while rendering a component
+pub fn tr_args<'a>(id: &str, args: impl IntoIterator<Item = (&'a str, FluentValue<'a>)>) -> String {
+    let language = consume_context::<Signal<Language>>();
+    let language = language();
+    language.translate(id, Some(&args.into_iter().collect()))
+}
+
+/// Select of the language, which also switches the separators and date format of amounts and
+/// dates
+#[component]
+pub fn LanguageControls() -> Element {
+    let mut language = use_context::<Signal<Language>>();
+    let mut formatter = use_context::<Signal<Formatter>>();
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            label {
+                {tr("language")}
+                " "
+                select {
+                    onchange: move |event| {
+                        if let Ok(choice) = Language::from_str(&event.value()) {
+                            language.set(choice);
+                            choice.localize(&mut formatter.write());
+                        }
+                    },
+                    for choice in Language::iter() {
+                        option {
+                            key: "{choice.as_ref()}",
+                            value: choice.as_ref(),
+                            selected: language() == choice,
+                            "{choice.name()}"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
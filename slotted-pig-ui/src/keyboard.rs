@@ -0,0 +1,57 @@
+use dioxus::prelude::*;
+
+use crate::i18n::tr;
+
+/// Page listener of the shortcut keys, keys typed in form controls or with modifiers are ignored
+///
+/// `/` is handled in the page so it focuses the search without being typed into it, the other
+/// shortcuts are sent to the app.
+pub const SHORTCUTS_SCRIPT: &str = r#"
+document.addEventListener("keydown", (event) => {
+    if (event.ctrlKey || event.metaKey || event.altKey) return;
+    if (event.target.closest("input, textarea, select")) return;
+    if (event.key === "/") {
+        event.preventDefault();
+        document.getElementById("search")?.focus();
+    } else if (["s", "t", "u", "r"].includes(event.key)) {
+        dioxus.send(event.key);
+    }
+});
+"#;
+
+/// Class of the rows of the category tree and months reachable with the arrow keys
+pub const TREE_ROW: &str = "tree-row";
+
+/// Move the focus to the visible row `offset` rows from the focused row
+fn focus_row(offset: i32) {
+    let _ = eval(&format!(
+        r#"
+const rows = [...document.querySelectorAll(".{TREE_ROW}")].filter((row) => row.offsetParent !== null);
+rows[rows.indexOf(document.activeElement) + {offset}]?.focus();
+"#
+    ));
+}
+
+/// Handle the keys of a focused tree row, enter toggles it, left and right collapse and expand
+/// it, and up and down move to the neighboring rows
+pub fn tree_row_keydown(event: KeyboardEvent, mut hidden: Signal<bool>) {
+    match event.key() {
+        Key::Enter => hidden.set(!hidden()),
+        Key::ArrowLeft => hidden.set(true),
+        Key::ArrowRight => hidden.set(false),
+        Key::ArrowUp => focus_row(-1),
+        Key::ArrowDown => focus_row(1),
+        _ => return,
+    }
+    event.stop_propagation();
+}
+
+/// Summary of the keyboard shortcuts
+#[component]
+pub fn KeyboardHelp() -> Element {
+    rsx!(
+        div { class: "font-mono text-xs py-1",
+            {tr("keyboard-help")}
+        }
+    )
+}
@@ -0,0 +1,41 @@
+use crate::controls::View;
+
+#[cfg(feature = "web")]
+/// Widest screen (in CSS pixels) laid out for phones
+const NARROW_WIDTH: f64 = 640.0;
+
+/// Stylesheet of the responsive layout, on narrow screens rows wrap, the account sidebar stacks
+/// above the content, rows are tall enough to tap, and the donut is hidden
+pub const LAYOUT_CSS: &str = "
+.panel > summary { cursor: pointer; padding: 0.5rem 0; font-family: monospace; }
+.sidebar-layout { display: flex; }
+.sidebar-layout > .content { flex: 1; min-width: 0; }
+@media (max-width: 640px) {
+  .theme { padding: 0 0.5rem; }
+  .theme .flex { flex-wrap: wrap; }
+  .sidebar-layout { flex-direction: column; }
+  .tree-row, .drill-row { min-height: 2.75rem; align-items: center; }
+  .wide-only { display: none; }
+}";
+
+/// Check if the app is shown on a narrow screen (ie a phone), always `false` on desktop
+pub fn is_narrow() -> bool {
+    #[cfg(feature = "web")]
+    {
+        web_sys::window()
+            .and_then(|window| window.inner_width().ok())
+            .and_then(|width| width.as_f64())
+            .is_some_and(|width| width <= NARROW_WIDTH)
+    }
+    #[cfg(not(feature = "web"))]
+    false
+}
+
+/// View shown before one is chosen, drill down on narrow screens
+pub fn default_view() -> View {
+    if is_narrow() {
+        View::DrillDown
+    } else {
+        View::default()
+    }
+}
@@ -1,23 +1,86 @@
+// The app is only launched with the `desktop` or `web` feature
+#![cfg_attr(not(any(feature = "desktop", feature = "web")), allow(dead_code))]
+
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
-use dioxus::{html::FileEngine, prelude::*};
-use log::{info, LevelFilter};
+use dioxus::{
+    html::{FileEngine, HasFileData},
+    prelude::*,
+};
+use log::{info, warn, LevelFilter};
 use serde::de::DeserializeOwned;
 use slotted_pig_lib::{
     categorizer::{Categorized, CategorizedChildren, CategorizedList},
     transaction::Transaction,
-    util::format_bigdecimal,
+    util::Formatter,
+    warning::Warning,
 };
 
+use crate::{
+    accounts::{accounts, AccountFilter},
+    badge::MatcherBadge,
+    charts::{Donut, StackedBars, Trend},
+    compare::Comparison,
+    controls::{
+        next_choice, DateFilterControls, Expansion, Search, SortControls, TreeControls, View,
+        ViewControls,
+    },
+    copy::CopySummary,
+    detail::{SelectedTransaction, TransactionDetail},
+    drill::DrillDown,
+    editor::CategorizerEditor,
+    format::FormatControls,
+    i18n::{tr, tr_args, LanguageControls},
+    keyboard::{tree_row_keydown, KeyboardHelp, SHORTCUTS_SCRIPT, TREE_ROW},
+    layout::{default_view, is_narrow, LAYOUT_CSS},
+    months::Months,
+    persist::SavedState,
+    rules::{RuleConfirmation, RuleDraft},
+    sankey::Cashflow,
+    session::{Categorization, FileKind, SharedCategorizer, UploadedFile},
+    theme::{Theme, ThemeControls},
+    uncategorized::Uncategorized,
+    undo::{History, UndoControls},
+};
+
+mod accounts;
+mod badge;
+mod charts;
+mod compare;
+mod controls;
+mod copy;
+#[cfg(feature = "desktop")]
+mod desktop;
+mod detail;
+mod download;
+mod drill;
+mod editor;
+mod format;
+mod i18n;
+mod keyboard;
+mod layout;
+mod months;
+mod persist;
+#[cfg(feature = "web")]
+mod pwa;
+mod rules;
+mod sankey;
+mod session;
+mod theme;
+mod uncategorized;
+mod undo;
+
 fn main() {
     dioxus_logger::init(LevelFilter::Info).expect("failed to init logger");
 
     #[cfg(feature = "desktop")]
     {
+        // The file menu replaces the default menu bar
         let cfg = dioxus::desktop::Config::new()
+            .with_default_menu_bar(false)
             .with_custom_head(r#"<link rel="stylesheet" href="assets/tailwind.css">"#.to_string());
         LaunchBuilder::desktop().with_cfg(cfg).launch(App)
     }
@@ -30,32 +93,291 @@ fn main() {
 fn App() -> Element {
     info!("slotted-pig");
 
-    let mut categorized_list_result = use_signal(|| {
-        let categorized = include_str!("../../examples/categorized.yaml");
-        Ok::<_, String>(
-            serde_yaml::from_str::<CategorizedList>(categorized).expect("failed to parse YAML"),
-        )
+    #[cfg(feature = "web")]
+    use_hook(pwa::install);
+    let saved = use_hook(SavedState::load);
+
+    let formatter = use_context_provider(|| Signal::new(saved.formatter.clone()));
+    let theme = use_context_provider(|| Signal::new(saved.theme.clone()));
+    let language = use_context_provider(|| Signal::new(saved.language));
+    let expansion = use_context_provider(|| Signal::new(saved.expansion));
+    use_context_provider(|| Signal::new(RuleDraft::default()));
+    use_context_provider(|| Signal::new(SelectedTransaction::default()));
+    let mut history = use_context_provider(|| Signal::new(History::default()));
+
+    let mut session = use_signal(|| saved.session.clone());
+    // Parse the categorizer once per change instead of in every transaction row
+    let mut shared_categorizer = use_context_provider(|| Signal::new(SharedCategorizer::default()));
+    use_effect(move || {
+        let categorizer = session.read().categorizer().ok();
+        shared_categorizer.set(SharedCategorizer::new(categorizer));
+    });
+    // Start from the saved session, falling back to the example categorization
+    let mut categorization_result = use_signal(|| {
+        session.read().categorize().unwrap_or_else(|| {
+            let categorized = include_str!("../../examples/categorized.yaml");
+            Ok(Categorization {
+                categorized: serde_yaml::from_str::<CategorizedList>(categorized)
+                    .expect("failed to parse YAML"),
+                ..Default::default()
+            })
+        })
     });
 
+    let view = use_signal(|| saved.view.unwrap_or_else(default_view));
+    let mut category_sort = use_signal(|| saved.category_sort);
+    let mut transaction_sort = use_signal(|| saved.transaction_sort);
+    let search = use_signal(String::new);
+    let hidden_accounts = use_signal(|| saved.hidden_accounts.clone());
+    let date_filter = use_signal(|| saved.date_filter);
+
+    use_future(move || async move {
+        let mut shortcuts = eval(SHORTCUTS_SCRIPT);
+        while let Ok(key) = shortcuts.recv().await {
+            match key.as_str() {
+                Some("s") => category_sort.set(next_choice(category_sort())),
+                Some("t") => transaction_sort.set(next_choice(transaction_sort())),
+                Some("u") => undo::step(history, session, categorization_result, false),
+                Some("r") => undo::step(history, session, categorization_result, true),
+                _ => {}
+            }
+        }
+    });
+
+    // Save whenever the uploads, view, sorts, expansion, theme, formatting, language, account
+    // filter, or date range change
+    use_effect(move || {
+        let state = SavedState {
+            session: session(),
+            theme: theme(),
+            formatter: formatter(),
+            language: language(),
+            hidden_accounts: hidden_accounts(),
+            date_filter: date_filter(),
+            view: Some(view()),
+            category_sort: category_sort(),
+            transaction_sort: transaction_sort(),
+            expansion: expansion(),
+        };
+        if let Err(e) = state.save() {
+            warn!("failed to save state: {e}");
+        }
+    });
+    // Rerun the pipeline whenever a raw file is uploaded or removed
+    let mut upload = move |kind: FileKind, files: Result<Vec<UploadedFile>>| match files {
+        Ok(files) => {
+            history.write().clear();
+            session.write().upload(kind, files);
+            if let Some(result) = session.read().categorize() {
+                *categorization_result.write() = result;
+            }
+        }
+        Err(e) => *categorization_result.write() = Err(e.to_string()),
+    };
+    let mut remove = move |name: &str| {
+        history.write().clear();
+        session.write().remove_transaction_file(name);
+        // Clear the categorization of the removed files once none are left
+        let result = session
+            .read()
+            .categorize()
+            .unwrap_or_else(|| Ok(Categorization::default()));
+        *categorization_result.write() = result;
+    };
+    #[cfg(feature = "desktop")]
+    desktop::use_file_menu(upload, categorization_result);
+    // Files dropped anywhere on the window are sorted by their contents, drags of transactions
+    // onto categories carry no files and are left to the categories
+    let drop_files = move |event: DragEvent| async move {
+        if event.files().is_none_or(|engine| engine.files().is_empty()) {
+            return;
+        }
+        let files = match read_files(event.files()).await {
+            Ok(files) => files,
+            Err(e) => return upload(FileKind::Transactions, Err(e)),
+        };
+        let mut transaction_files = Vec::new();
+        for file in files {
+            match FileKind::sniff(&file) {
+                Some(FileKind::Transactions) => transaction_files.push(file),
+                Some(kind) => upload(kind, Ok(vec![file])),
+                // A categorized yaml replaces the categorization like its own input
+                None => match serde_yaml::from_str(&file.contents) {
+                    Ok(categorized) => categorization_result.set(Ok(Categorization {
+                        categorized,
+                        ..Default::default()
+                    })),
+                    Err(_) => categorization_result.set(Err(tr_args(
+                        "unrecognized-file",
+                        [("name", file.name.into())],
+                    ))),
+                },
+            }
+        }
+        if !transaction_files.is_empty() {
+            upload(FileKind::Transactions, Ok(transaction_files));
+        }
+    };
+    let transaction_files = session
+        .read()
+        .transaction_files
+        .iter()
+        .map(|file| file.name.clone())
+        .collect::<Vec<_>>();
+
     rsx! {
-        div { class: "max-w-screen-lg mx-auto",
+        style { "{theme.read().css()}" }
+        style { {LAYOUT_CSS} }
+        div {
+            class: "theme max-w-screen-lg mx-auto",
+            // Fill the window so files can be dropped below the content too
+            style: "min-height: 100vh",
+            prevent_default: "ondragover ondrop",
+            ondrop: drop_files,
             div { class: "flex justify-between",
                 h1 { class: "font-mono text-2xl", "Slotted Pig" }
-                input {
-                    r#type: "file",
-                    accept: ".yaml",
-                    multiple: false,
-                    oninput: move |event| {
-                        async move {
-                            *categorized_list_result
-                                .write() = read_first_file(event.files()).await.map_err(|e| e.to_string());
+                label { class: "font-mono text-sm",
+                    {tr("categorized")}
+                    " "
+                    input {
+                        r#type: "file",
+                        accept: ".yaml",
+                        multiple: false,
+                        oninput: move |event| {
+                            async move {
+                                *categorization_result
+                                    .write() = read_first_file(event.files())
+                                    .await
+                                    .map(|categorized| Categorization {
+                                        categorized,
+                                        ..Default::default()
+                                    })
+                                    .map_err(|e| e.to_string());
+                            }
                         }
                     }
                 }
             }
-            match categorized_list_result.read().clone(){
-                Ok(categorized_list) =>  {
-                    rsx!(CategorizedList { categorized_list: categorized_list.categorized })
+            details { class: "panel", open: session.read().is_empty(),
+                summary { {tr("files")} }
+                div { class: "flex justify-between font-mono text-sm py-2",
+                    for kind in [FileKind::Transactions, FileKind::TransactionParser, FileKind::Categorizer, FileKind::Overrides] {
+                        label { key: "{kind.message()}",
+                            {tr(kind.message())}
+                            " "
+                            input {
+                                r#type: "file",
+                                accept: kind.accept(),
+                                multiple: kind == FileKind::Transactions,
+                                oninput: move |event| {
+                                    async move {
+                                        upload(kind, read_files(event.files()).await);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "font-mono text-xs", {tr("drop-hint")} }
+                if !transaction_files.is_empty() {
+                    div { class: "flex flex-wrap gap-2 font-mono text-xs",
+                        {tr("transaction-files")}
+                        for name in transaction_files {
+                            span { key: "{name}",
+                                "{name} "
+                                button { class: "underline", onclick: move |_| remove(&name), {tr("remove")} }
+                            }
+                        }
+                    }
+                }
+            }
+            if !session.read().is_empty() && !session.read().is_complete() {
+                span { class: "font-mono text-sm",
+                    {tr("upload-to-categorize")}
+                }
+            }
+            if !session.read().transaction_files.is_empty() && !session.read().needs_transaction_parser() {
+                CategorizerEditor { session, categorization_result }
+            }
+            UndoControls { session, categorization_result }
+            details { class: "panel",
+                summary { {tr("settings")} }
+                LanguageControls {}
+                ThemeControls {}
+                FormatControls {}
+                SortControls { category_sort, transaction_sort }
+                KeyboardHelp {}
+            }
+            ViewControls { view }
+            Search { search }
+            DateFilterControls { date_filter }
+            TransactionDetail { session, categorization_result }
+            match categorization_result.read().clone(){
+                Ok(Categorization { categorized: mut categorized_list, mut uncategorized, warnings }) =>  {
+                    let accounts = accounts(&categorized_list, &uncategorized);
+                    if !hidden_accounts.read().is_empty() {
+                        let shown = |t: &Transaction| !hidden_accounts.read().contains(&t.account);
+                        categorized_list.retain_transactions(shown);
+                        uncategorized.retain(shown);
+                    }
+                    if !date_filter().is_empty() {
+                        let shown = |t: &Transaction| date_filter().contains(t.time.date_naive());
+                        categorized_list.retain_transactions(shown);
+                        uncategorized.retain(shown);
+                    }
+                    let query = search().to_lowercase();
+                    if !query.is_empty() {
+                        let matches = |t: &Transaction| t.description.to_lowercase().contains(&query);
+                        categorized_list.retain_transactions(matches);
+                        uncategorized.retain(matches);
+                    }
+                    if let Some(sort) = category_sort() {
+                        categorized_list.sort_subcategories(sort);
+                    }
+                    if let Some(sort) = transaction_sort() {
+                        categorized_list.sort_transactions(sort);
+                    }
+                    rsx!(
+                        div { class: "sidebar-layout",
+                            details { class: "panel", open: !is_narrow(),
+                                summary { {tr("accounts")} }
+                                AccountFilter { accounts, hidden_accounts }
+                            }
+                            div { class: "content",
+                                Warnings { warnings }
+                                TreeControls { categorized_list: categorized_list.categorized.clone() }
+                                match view() {
+                                    View::ByMonth => rsx!(
+                                        Months { categorized_list: categorized_list.categorized.clone() }
+                                    ),
+                                    View::ByCategory => rsx!(
+                                        div { class: "flex gap-4",
+                                            div { class: "flex-1",
+                                                CategorizedList { categorized_list: categorized_list.categorized.clone(), ancestors: Vec::new() }
+                                            }
+                                            div { class: "wide-only",
+                                                Donut { categorized_list: categorized_list.categorized.clone() }
+                                            }
+                                        }
+                                    ),
+                                    View::DrillDown => rsx!(
+                                        DrillDown { categorized_list: categorized_list.categorized.clone() }
+                                    ),
+                                    View::Cashflow => rsx!(
+                                        Cashflow { categorized_list: categorized_list.categorized.clone() }
+                                    ),
+                                    View::Compare => rsx!(
+                                        Comparison { categorized_list: categorized_list.categorized.clone() }
+                                    ),
+                                }
+                                StackedBars { categorized_list: categorized_list.categorized }
+                                if !uncategorized.is_empty() {
+                                    RuleConfirmation { session, categorization_result }
+                                    Uncategorized { transactions: uncategorized }
+                                }
+                            }
+                        }
+                    )
                 }
                 Err(e) => rsx!(
                     span {"{e}"}
@@ -65,22 +387,34 @@ fn App() -> Element {
     }
 }
 
+/// Categories below the `ancestors` categories
 #[component]
-fn CategorizedList(categorized_list: Vec<Categorized>) -> Element {
+fn CategorizedList(categorized_list: Vec<Categorized>, ancestors: Vec<String>) -> Element {
     rsx!(
         ul { class: "list-disc pl-4",
             for categorized in categorized_list {
                 li { key: "{categorized.category}",
-                    Categorized { categorized }
+                    Categorized { categorized, ancestors: ancestors.clone() }
                 }
             }
         }
     )
 }
 
+/// Category of the tree, uncategorized transactions can be dropped on leaf categories to add a
+/// rule
 #[component]
-fn Categorized(categorized: Categorized) -> Element {
+fn Categorized(categorized: Categorized, ancestors: Vec<String>) -> Element {
+    let expansion = use_context::<Signal<Expansion>>();
+    let mut draft = use_context::<Signal<RuleDraft>>();
+    let shared_categorizer = use_context::<Signal<SharedCategorizer>>();
     let mut hidden = use_signal(|| true);
+    let mut trend = use_signal(|| false);
+    let mut over = use_signal(|| false);
+
+    // Follow the tree controls, clicking the category still toggles it on its own
+    let level = ancestors.len();
+    use_effect(move || hidden.set(level >= expansion().depth));
 
     let Categorized {
         category,
@@ -88,69 +422,210 @@ fn Categorized(categorized: Categorized) -> Element {
         total,
         children,
         ..
-    } = categorized;
+    } = categorized.clone();
+    let mut path = ancestors;
+    path.push(category.clone());
+    let droppable = matches!(children, CategorizedChildren::Transactions(_))
+        && draft.read().transaction.is_some();
+    // Only configured colors mark the row, the palette is left to the charts
+    let category_style = shared_categorizer
+        .read()
+        .styles
+        .get(&path.join(":"))
+        .cloned()
+        .unwrap_or_default();
+    let border = category_style
+        .color
+        .map(|color| format!("border-left: 4px solid {color};"))
+        .unwrap_or_default();
+    let background = if droppable && over() {
+        "background: #e5e7eb;"
+    } else {
+        ""
+    };
 
     rsx!(
-        div { class: "hover:cursor-pointer", onclick: move |_| *hidden.write() = !hidden(),
+        div {
+            class: "hover:cursor-pointer {TREE_ROW}",
+            tabindex: "0",
+            style: "{border}{background}",
+            prevent_default: if droppable { "ondragover ondrop" } else { "" },
+            onclick: move |_| *hidden.write() = !hidden(),
+            onkeydown: move |event| tree_row_keydown(event, hidden),
+            ondragover: move |_| {
+                // Dragover fires continuously, only rerender when the highlight changes
+                if droppable && !over() {
+                    over.set(true);
+                }
+            },
+            ondragleave: move |_| over.set(false),
+            ondrop: {
+                let path = path.clone();
+                move |_| {
+                    over.set(false);
+                    if droppable {
+                        draft.write().category_path = Some(path.clone());
+                    }
+                }
+            },
+            if let Some(icon) = category_style.icon {
+                span { class: "px-1", "{icon}" }
+            }
             span { class: "font-mono text-base px-1", "{category}" }
             span { class: "font-mono text-sm px-1", "[{count}]" }
             Amount { amount: total }
+            span {
+                class: "font-mono text-xs px-1 underline",
+                title: tr("trend-title"),
+                onclick: move |event| {
+                    event.stop_propagation();
+                    *trend.write() = !trend();
+                },
+                if trend() { {tr("hide-trend")} } else { {tr("trend")} }
+            }
+            CopySummary { path: path.clone(), categorized: categorized.clone() }
+        }
+        if trend() {
+            Trend { categorized }
         }
-        div { class: if hidden() { "hidden" } else { "" },
+        // Children are only rendered once expanded so large trees stay cheap to render
+        if !hidden() {
             match children {
                 CategorizedChildren::Transactions(transactions) => {
                     rsx!(Transactions{transactions})
                 },
                 CategorizedChildren::Subcategories(categorized_list) => {
-                    rsx!(CategorizedList{categorized_list})
+                    rsx!(CategorizedList{categorized_list, ancestors: path.clone()})
                 },
             }
         }
     )
 }
 
+/// Number of transactions rendered at first and added by each load more
+const TRANSACTIONS_PAGE_SIZE: usize = 100;
+
+/// List of transactions rendered a page at a time, draggable ones can be dropped on a leaf
+/// category to add a rule
 #[component]
-fn Transactions(transactions: Vec<Transaction>) -> Element {
+fn Transactions(transactions: Vec<Transaction>, #[props(default)] draggable: bool) -> Element {
+    let mut draft = use_context::<Signal<RuleDraft>>();
+    let mut shown = use_signal(|| TRANSACTIONS_PAGE_SIZE);
+    let remaining = transactions.len().saturating_sub(shown());
     rsx!(
         ul { class: "list-disc pl-4",
-            for transaction in transactions {
+            for transaction in transactions.into_iter().take(shown()) {
                 li {
-                    Transaction { transaction }
+                    draggable: "{draggable}",
+                    class: if draggable { "hover:cursor-grab" } else { "" },
+                    ondragstart: {
+                        let transaction = transaction.clone();
+                        move |_| {
+                            if draggable {
+                                draft.set(RuleDraft {
+                                    transaction: Some(transaction.clone()),
+                                    category_path: None,
+                                });
+                            }
+                        }
+                    },
+                    Transaction { transaction: transaction.clone() }
+                }
+            }
+            if remaining > 0 {
+                li { class: "list-none",
+                    button {
+                        class: "font-mono text-xs underline",
+                        onclick: move |_| shown += TRANSACTIONS_PAGE_SIZE,
+                        {tr_args("load-more", [("count", remaining.min(TRANSACTIONS_PAGE_SIZE).into()), ("remaining", remaining.into())])}
+                    }
                 }
             }
         }
     )
 }
 
+/// Transaction summary, clicking it shows its details
 #[component]
 fn Transaction(transaction: Transaction) -> Element {
+    let mut selected = use_context::<Signal<SelectedTransaction>>();
     let Transaction {
         amount,
         time,
         description,
         ..
-    } = transaction;
+    } = transaction.clone();
     rsx!(
-        Amount { amount: amount }
-        " | "
-        Time { time }
-        " | "
-        span { class: "font-mono text-xs", "{description}" }
+        span {
+            class: "hover:cursor-pointer",
+            onclick: move |event| {
+                event.stop_propagation();
+                selected.set(SelectedTransaction(Some(transaction.clone())));
+            },
+            Amount { amount }
+            " | "
+            Time { time }
+            " | "
+            span { class: "font-mono text-xs", "{description}" }
+            MatcherBadge { transaction: transaction.clone() }
+        }
     )
 }
 
 #[component]
 fn Amount(amount: BigDecimal) -> Element {
-    let amount = format_bigdecimal(&amount);
-    rsx!( span { class: "font-mono text-sm px-1", "{amount}" } )
+    let formatter = use_context::<Signal<Formatter>>();
+    let theme = use_context::<Signal<Theme>>();
+    let style = theme.read().amount_style(&amount);
+    let amount = formatter.read().format(&amount);
+    rsx!( span { class: "font-mono text-sm px-1", style, "{amount}" } )
 }
 
 #[component]
 fn Time(time: DateTime<Utc>) -> Element {
-    let time = time.format("%Y-%m-%d");
+    let formatter = use_context::<Signal<Formatter>>();
+    let time = formatter.read().format_time(&time);
     rsx!( span { class: "font-mono text-sm px-1", "{time}" } )
 }
 
+/// Problems categorizing which did not stop it
+#[component]
+fn Warnings(warnings: Vec<Warning>) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let message = |warning: &Warning| match warning {
+        Warning::EveryTransactionFiltered { count } => tr_args(
+            "warning-every-transaction-filtered",
+            [("count", (*count).into())],
+        ),
+        Warning::UnmatchedOverride { description, time } => tr_args(
+            "warning-unmatched-override",
+            [
+                ("description", description.clone().into()),
+                ("time", formatter.read().format_time(time).into()),
+            ],
+        ),
+    };
+    rsx!(
+        for warning in warnings {
+            p { class: "text-sm", style: "color: var(--negative)", {message(&warning)} }
+        }
+    )
+}
+
+/// Read every selected file as a string
+pub async fn read_files(file_engine: Option<Arc<dyn FileEngine>>) -> Result<Vec<UploadedFile>> {
+    let file_engine = file_engine.ok_or_else(|| anyhow!("missing file engine"))?;
+    let mut files = Vec::new();
+    for name in file_engine.files() {
+        let contents = file_engine
+            .read_file_to_string(&name)
+            .await
+            .ok_or_else(|| anyhow!("failed to read {name} as string"))?;
+        files.push(UploadedFile { name, contents });
+    }
+    Ok(files)
+}
+
 pub async fn read_first_file<T: DeserializeOwned>(
     file_engine: Option<Arc<dyn FileEngine>>,
 ) -> Result<T> {
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use bigdecimal::{BigDecimal, Zero};
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::Categorized,
+    report::{Bucket, DateRange, Period},
+};
+
+use crate::{
+    i18n::tr,
+    keyboard::{tree_row_keydown, TREE_ROW},
+    Amount, CategorizedList,
+};
+
+/// Income, expenses, and net of every month, newest first, clicking a month shows its own
+/// category tree
+#[component]
+pub fn Months(categorized_list: Vec<Categorized>) -> Element {
+    let months = months(&categorized_list);
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            div { class: "flex gap-4 font-bold",
+                span { class: "w-40", {tr("month")} }
+                span { class: "w-32", {tr("income")} }
+                span { class: "w-32", {tr("expenses")} }
+                span { class: "w-32", {tr("net")} }
+            }
+            for month in months {
+                Month { key: "{month.range.label}", month, categorized_list: categorized_list.clone() }
+            }
+        }
+    )
+}
+
+#[component]
+fn Month(month: MonthTotals, categorized_list: Vec<Categorized>) -> Element {
+    let mut hidden = use_signal(|| true);
+
+    // Only build the month's tree once it is shown
+    let categorized_list = (!hidden()).then(|| {
+        let mut categorized_list = CategorizedList {
+            categorized: categorized_list,
+        };
+        categorized_list.retain_transactions(|t| month.range.contains(t.time.date_naive()));
+        categorized_list.categorized
+    });
+
+    rsx!(
+        div {
+            class: "flex gap-4 hover:cursor-pointer {TREE_ROW}",
+            tabindex: "0",
+            onclick: move |_| *hidden.write() = !hidden(),
+            onkeydown: move |event| tree_row_keydown(event, hidden),
+            span { class: "w-40", "{month.range.label} [{month.count}]" }
+            span { class: "w-32", Amount { amount: month.income } }
+            span { class: "w-32", Amount { amount: month.expenses } }
+            span { class: "w-32", Amount { amount: month.net } }
+        }
+        if let Some(categorized_list) = categorized_list {
+            CategorizedList { categorized_list, ancestors: Vec::new() }
+        }
+    )
+}
+
+/// Totals of the transactions of a month
+#[derive(Clone, Debug, PartialEq)]
+struct MonthTotals {
+    range: DateRange,
+    count: u64,
+    /// Sum of the positive amounts
+    income: BigDecimal,
+    /// Sum of the negative amounts
+    expenses: BigDecimal,
+    net: BigDecimal,
+}
+
+fn months(categorized: &[Categorized]) -> Vec<MonthTotals> {
+    let categorized_list = CategorizedList {
+        categorized: categorized.to_vec(),
+    };
+    let mut months = BTreeMap::new();
+    for assignment in categorized_list.assignments() {
+        let amount = &assignment.transaction.amount;
+        let range = Period::bucket(Bucket::Month, assignment.transaction.time.date_naive()).current;
+        let month = months.entry(range.start).or_insert_with(|| MonthTotals {
+            range,
+            count: 0,
+            income: BigDecimal::zero(),
+            expenses: BigDecimal::zero(),
+            net: BigDecimal::zero(),
+        });
+        month.count += 1;
+        if amount > &BigDecimal::zero() {
+            month.income += amount;
+        } else {
+            month.expenses += amount;
+        }
+        month.net += amount;
+    }
+    months.into_values().rev().collect()
+}
+
+/// Every month with transactions, newest first
+pub fn month_ranges(categorized_list: &CategorizedList) -> Vec<DateRange> {
+    let mut months = BTreeMap::new();
+    for assignment in categorized_list.assignments() {
+        let range = Period::bucket(Bucket::Month, assignment.transaction.time.date_naive()).current;
+        months.entry(range.start).or_insert(range);
+    }
+    months.into_values().rev().collect()
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use slotted_pig_lib::{
+    categorizer::{CategorySort, TransactionSort},
+    util::Formatter,
+};
+
+use crate::{
+    controls::{DateFilter, Expansion, View},
+    i18n::Language,
+    session::Session,
+    theme::Theme,
+};
+
+/// Key of the saved state in local storage
+#[cfg(feature = "web")]
+const STORAGE_KEY: &str = "slotted-pig";
+
+/// UI state saved between runs so reopening the app does not require uploading everything again
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SavedState {
+    pub session: Session,
+    /// View chosen by the user, `None` until one is chosen
+    pub view: Option<View>,
+    pub category_sort: Option<CategorySort>,
+    pub transaction_sort: Option<TransactionSort>,
+    pub expansion: Expansion,
+    pub theme: Theme,
+    pub formatter: Formatter,
+    pub language: Language,
+    /// Accounts unchecked in the account filter
+    pub hidden_accounts: BTreeSet<String>,
+    /// Last used date range
+    pub date_filter: DateFilter,
+}
+
+impl SavedState {
+    /// Load the saved state, the default state if nothing was saved or it cannot be read
+    pub fn load() -> Self {
+        read()
+            .and_then(|contents| serde_yaml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the state in local storage (web) or the app data directory (desktop)
+    pub fn save(&self) -> Result<(), String> {
+        let contents = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+        write(&contents)
+    }
+}
+
+#[cfg(feature = "web")]
+fn read() -> Option<String> {
+    web_sys::window()?
+        .local_storage()
+        .ok()??
+        .get_item(STORAGE_KEY)
+        .ok()?
+}
+
+#[cfg(feature = "web")]
+fn write(contents: &str) -> Result<(), String> {
+    web_sys::window()
+        .ok_or("missing window")?
+        .local_storage()
+        .map_err(|e| format!("{e:?}"))?
+        .ok_or("missing local storage")?
+        .set_item(STORAGE_KEY, contents)
+        .map_err(|e| format!("failed to write local storage: {e:?}"))
+}
+
+/// Saved state file in the app data directory
+#[cfg(all(feature = "desktop", not(feature = "web")))]
+fn path() -> Option<std::path::PathBuf> {
+    Some(dirs::data_dir()?.join("slotted-pig").join("state.yaml"))
+}
+
+#[cfg(all(feature = "desktop", not(feature = "web")))]
+fn read() -> Option<String> {
+    std::fs::read_to_string(path()?).ok()
+}
+
+#[cfg(all(feature = "desktop", not(feature = "web")))]
+fn write(contents: &str) -> Result<(), String> {
+    let path = path().ok_or("missing app data directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, contents).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+#[cfg(not(any(feature = "desktop", feature = "web")))]
+fn read() -> Option<String> {
+    None
+}
+
+#[cfg(not(any(feature = "desktop", feature = "web")))]
+fn write(_contents: &str) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use dioxus::prelude::*;
+
+/// Link the manifest so the app can be installed and register the service worker that caches it
+/// for offline use, paths are relative so they work under the base path
+const INSTALL_SCRIPT: &str = r#"
+if (!document.querySelector("link[rel=manifest]")) {
+    const manifest = document.createElement("link");
+    manifest.rel = "manifest";
+    manifest.href = "./manifest.webmanifest";
+    document.head.appendChild(manifest);
+}
+if ("serviceWorker" in navigator) {
+    navigator.serviceWorker.register("./sw.js", { scope: "./" });
+}
+"#;
+
+/// Make the web build installable and usable offline
+pub fn install() {
+    let _ = eval(INSTALL_SCRIPT);
+}
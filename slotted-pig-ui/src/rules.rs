@@ -0,0 +1,133 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::TransactionMatcher, edit::add_transaction_matcher, transaction::Transaction,
+};
+
+use crate::{
+    download::DownloadLink,
+    i18n::{tr, tr_args},
+    session::{Categorization, Session, UploadedFile},
+    undo::History,
+};
+
+/// Rule being built by dragging an uncategorized transaction onto a leaf category
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleDraft {
+    /// Uncategorized transaction being dragged
+    pub transaction: Option<Transaction>,
+    /// Path of the leaf category the transaction was dropped on
+    pub category_path: Option<Vec<String>>,
+}
+
+/// Transactions the suggested matcher matches
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RuleScope {
+    /// Every transaction of the payee with the same sign
+    Payee,
+    /// Only the dropped transaction
+    Transaction,
+}
+
+impl RuleScope {
+    fn matcher(self, transaction: &Transaction) -> TransactionMatcher {
+        match self {
+            Self::Payee => TransactionMatcher::for_payee(transaction),
+            Self::Transaction => TransactionMatcher::for_transaction(transaction),
+        }
+    }
+}
+
+/// Confirmation of the matcher suggested for a transaction dropped on a category, applying it
+/// adds it to the session's categorizer and recategorizes
+#[component]
+pub fn RuleConfirmation(
+    session: Signal<Session>,
+    categorization_result: Signal<Result<Categorization, String>>,
+) -> Element {
+    let mut draft = use_context::<Signal<RuleDraft>>();
+    let mut history = use_context::<Signal<History>>();
+    let mut scope = use_signal(|| RuleScope::Payee);
+    let mut applied = use_signal(|| false);
+
+    let download = session
+        .read()
+        .categorizer
+        .clone()
+        .filter(|_| applied())
+        .map(|file| {
+            rsx!(DownloadLink {
+                file,
+                label: tr("download-categorizer")
+            })
+        });
+    let RuleDraft {
+        transaction: Some(transaction),
+        category_path: Some(category_path),
+    } = draft()
+    else {
+        return rsx!( div { class: "font-mono text-sm py-2", {download} } );
+    };
+
+    let matcher = scope().matcher(&transaction);
+    let matches = match &*categorization_result.read() {
+        Ok(categorization) => categorization
+            .uncategorized
+            .iter()
+            .filter(|t| matcher.matches(t))
+            .count(),
+        Err(_) => 0,
+    };
+    let category = category_path.join(":");
+    let path = category_path.iter().map(String::as_str).collect::<Vec<_>>();
+    let edited = match &session.read().categorizer {
+        Some(categorizer) => add_transaction_matcher(&categorizer.contents, &path, &matcher)
+            .map(|contents| UploadedFile {
+                name: categorizer.name.clone(),
+                contents,
+            })
+            .map_err(|e| e.to_string()),
+        None => Err(tr("rule-missing-categorizer")),
+    };
+
+    rsx!(
+        div { class: "font-mono text-sm py-2 border p-2",
+            div { {tr_args("rule-add", [("category", category.into())])} }
+            crate::Transaction { transaction }
+            div { class: "flex gap-2",
+                for (option , label) in [(RuleScope::Payee, tr("rule-payee")), (RuleScope::Transaction, tr("rule-transaction"))] {
+                    label { key: "{label}",
+                        input {
+                            r#type: "radio",
+                            name: "rule-scope",
+                            checked: scope() == option,
+                            oninput: move |_| scope.set(option)
+                        }
+                        " {label}"
+                    }
+                }
+            }
+            div { class: "text-xs", "{matcher}" }
+            div { class: "text-xs", {tr_args("rule-matches", [("count", matches.into())])} }
+            match edited {
+                Ok(file) => rsx!(
+                    button {
+                        class: "underline pr-2",
+                        onclick: move |_| {
+                            history.write().record(session());
+                            session.write().categorizer = Some(file.clone());
+                            if let Some(result) = session.read().categorize() {
+                                categorization_result.set(result);
+                            }
+                            draft.set(RuleDraft::default());
+                            applied.set(true);
+                        },
+                        {tr("apply")}
+                    }
+                ),
+                Err(e) => rsx!( div { class: "text-red-600", "{e}" } ),
+            }
+            button { class: "underline", onclick: move |_| draft.set(RuleDraft::default()), {tr("cancel")} }
+            {download}
+        }
+    )
+}
@@ -0,0 +1,233 @@
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    categorizer::{Categorized, CategorizedList},
+    report::Sankey,
+    util::Formatter,
+};
+
+use crate::{i18n::tr, months::month_ranges, session::SharedCategorizer};
+
+const WIDTH: f64 = 960.0;
+const HEIGHT: f64 = 480.0;
+const NODE_WIDTH: f64 = 12.0;
+/// Vertical space between the nodes of a column
+const NODE_GAP: f64 = 8.0;
+
+/// Sankey diagram of income flowing through the budget into the top level and leaf categories
+/// of every transaction or the transactions of one month
+///
+/// Top level categories with a positive total are income.
+#[component]
+pub fn Cashflow(categorized_list: Vec<Categorized>) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let mut month = use_signal(String::new);
+
+    let mut categorized_list = CategorizedList {
+        categorized: categorized_list,
+    };
+    let months = month_ranges(&categorized_list);
+    if let Some(range) = months.iter().find(|range| range.label == month()) {
+        categorized_list.retain_transactions(|t| range.contains(t.time.date_naive()));
+    }
+    let income_categories = categorized_list
+        .categorized
+        .iter()
+        .filter(|category| category.total > BigDecimal::zero())
+        .map(|category| category.category.as_str())
+        .collect::<Vec<_>>();
+    let sankey = Sankey::new(&categorized_list, &income_categories);
+    let shared_categorizer = use_context::<Signal<SharedCategorizer>>();
+    let (nodes, links) = layout(&sankey, &shared_categorizer.read());
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            label {
+                {tr("period")}
+                " "
+                select {
+                    onchange: move |event| month.set(event.value()),
+                    option { value: "", {tr("all")} }
+                    for range in months.iter() {
+                        option {
+                            key: "{range.label}",
+                            value: "{range.label}",
+                            selected: range.label == month(),
+                            "{range.label}"
+                        }
+                    }
+                }
+            }
+            svg { view_box: "0 0 {WIDTH} {HEIGHT}", width: "100%",
+                for (i, link) in links.into_iter().enumerate() {
+                    path {
+                        key: "{i}",
+                        d: "M{link.x0},{link.y0} C{link.xm},{link.y0} {link.xm},{link.y1} {link.x1},{link.y1}",
+                        fill: "none",
+                        stroke: "{link.color}",
+                        stroke_opacity: "0.4",
+                        stroke_width: "{link.thickness}",
+                        title { "{link.label} {formatter.read().format(&link.value)}" }
+                    }
+                }
+                for node in nodes {
+                    g { key: "{node.name}",
+                        rect {
+                            x: "{node.x}",
+                            y: "{node.y}",
+                            width: "{NODE_WIDTH}",
+                            height: "{node.height}",
+                            fill: "{node.color}",
+                            title { "{node.name} {formatter.read().format(&node.value)}" }
+                        }
+                        text {
+                            // Labels of the last column are left of their nodes to stay in view
+                            x: if node.last { "{node.x - 4.0}" } else { "{node.x + NODE_WIDTH + 4.0}" },
+                            y: "{node.y + node.height / 2.0}",
+                            text_anchor: if node.last { "end" } else { "start" },
+                            dominant_baseline: "middle",
+                            font_size: "10",
+                            fill: "currentColor",
+                            "{node.label}"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Node placed in the diagram
+#[derive(Clone, Debug, PartialEq)]
+struct NodeShape {
+    name: String,
+    label: String,
+    value: BigDecimal,
+    color: String,
+    x: f64,
+    y: f64,
+    height: f64,
+    /// Node is in the last column
+    last: bool,
+}
+
+/// Link placed in the diagram from the right edge of its source to the left edge of its target
+#[derive(Clone, Debug, PartialEq)]
+struct LinkShape {
+    label: String,
+    value: BigDecimal,
+    color: String,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    /// Horizontal middle of the curve
+    xm: f64,
+    thickness: f64,
+}
+
+/// Place the nodes in columns by their longest path from a source and the links between them,
+/// node heights and link thicknesses are proportional to their values
+fn layout(
+    sankey: &Sankey,
+    shared_categorizer: &SharedCategorizer,
+) -> (Vec<NodeShape>, Vec<LinkShape>) {
+    let count = sankey.nodes.len();
+    // Nodes form a tree around the budget so relaxing every link once per node is enough
+    let mut columns = vec![0; count];
+    for _ in 0..count {
+        for link in &sankey.links {
+            columns[link.target] = columns[link.target].max(columns[link.source] + 1);
+        }
+    }
+    let column_count = columns.iter().max().map_or(1, |max| max + 1);
+
+    let mut inflows = vec![BigDecimal::zero(); count];
+    let mut outflows = vec![BigDecimal::zero(); count];
+    for link in &sankey.links {
+        outflows[link.source] += &link.value;
+        inflows[link.target] += &link.value;
+    }
+    let values = inflows
+        .into_iter()
+        .zip(outflows)
+        .map(|(inflow, outflow)| inflow.max(outflow))
+        .collect::<Vec<_>>();
+
+    // Scale every column by the fullest one so values are comparable across columns
+    let mut column_totals = vec![0.0; column_count];
+    let mut column_sizes = vec![0; column_count];
+    for (column, value) in columns.iter().zip(&values) {
+        column_totals[*column] += value.to_f64().unwrap_or_default();
+        column_sizes[*column] += 1;
+    }
+    let scale = column_totals
+        .iter()
+        .zip(&column_sizes)
+        .map(|(total, size)| (HEIGHT - NODE_GAP * (*size as f64 - 1.0).max(0.0)) / total)
+        .filter(|scale| scale.is_finite())
+        .fold(f64::INFINITY, f64::min);
+    let scale = if scale.is_finite() { scale } else { 0.0 };
+
+    let column_width = (WIDTH - NODE_WIDTH) / (column_count as f64 - 1.0).max(1.0);
+    let mut column_offsets = vec![0.0; column_count];
+    let mut nodes = Vec::with_capacity(count);
+    for (i, (node, value)) in sankey.nodes.iter().zip(&values).enumerate() {
+        let column = columns[i];
+        let height = value.to_f64().unwrap_or_default() * scale;
+        // The nodes added by the diagram are not categories so they are translated
+        let label = match node.name.as_str() {
+            Sankey::BUDGET => tr("sankey-budget"),
+            Sankey::SAVINGS => tr("sankey-savings"),
+            Sankey::DEFICIT => tr("sankey-deficit"),
+            _ => node.label.clone(),
+        };
+        nodes.push(NodeShape {
+            name: node.name.clone(),
+            label,
+            value: value.clone(),
+            color: shared_categorizer.color(&node.name, i),
+            x: column as f64 * column_width,
+            y: column_offsets[column],
+            height,
+            last: column + 1 == column_count && column_count > 1,
+        });
+        column_offsets[column] += height + NODE_GAP;
+    }
+
+    // Links leave and enter their nodes stacked in order
+    let mut source_offsets = vec![0.0; count];
+    let mut target_offsets = vec![0.0; count];
+    let links = sankey
+        .links
+        .iter()
+        .map(|link| {
+            let source = &nodes[link.source];
+            let target = &nodes[link.target];
+            let thickness = link.value.to_f64().unwrap_or_default() * scale;
+            let y0 = source.y + source_offsets[link.source] + thickness / 2.0;
+            let y1 = target.y + target_offsets[link.target] + thickness / 2.0;
+            source_offsets[link.source] += thickness;
+            target_offsets[link.target] += thickness;
+            let x0 = source.x + NODE_WIDTH;
+            let x1 = target.x;
+            LinkShape {
+                label: format!("{} → {}", source.label, target.label),
+                value: link.value.clone(),
+                // Color by the node away from the budget so each category keeps its color
+                color: if source.name == Sankey::BUDGET {
+                    target.color.clone()
+                } else {
+                    source.color.clone()
+                },
+                x0,
+                y0,
+                x1,
+                y1,
+                xm: (x0 + x1) / 2.0,
+                thickness,
+            }
+        })
+        .collect();
+    (nodes, links)
+}
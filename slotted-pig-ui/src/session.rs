@@ -0,0 +1,313 @@
+use std::{collections::BTreeMap, path::Path, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+use slotted_pig_lib::{
+    categorizer::{CategorizedList, Categorizer, CategoryStyle},
+    import::{parse_ofx, parse_qif, ImportFormat},
+    overrides::TransactionOverrides,
+    transaction::{CsvRow, Transaction, TransactionParser},
+    warning::Warning,
+};
+
+use crate::charts::COLORS;
+
+/// File uploaded through a file input
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct UploadedFile {
+    pub name: String,
+    pub contents: String,
+}
+
+impl UploadedFile {
+    /// Format of a transaction file from its extension or contents
+    pub fn format(&self) -> ImportFormat {
+        ImportFormat::detect(Path::new(&self.name), &self.contents)
+    }
+}
+
+/// Categorized and uncategorized transactions shown in the UI
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Categorization {
+    pub categorized: CategorizedList,
+    /// Transactions no category matched, empty for an uploaded categorization
+    pub uncategorized: Vec<Transaction>,
+    /// Problems categorizing which did not stop it (ie overrides matching no transaction)
+    pub warnings: Vec<Warning>,
+}
+
+/// Categorizer of the session shared with every row and chart so it is only parsed once per
+/// change
+#[derive(Clone, Default)]
+pub struct SharedCategorizer {
+    /// `None` without an uploaded categorizer
+    pub categorizer: Option<Rc<Categorizer>>,
+    /// Configured colors and icons keyed by colon separated category path
+    pub styles: Rc<BTreeMap<String, CategoryStyle>>,
+}
+
+impl SharedCategorizer {
+    pub fn new(categorizer: Option<Categorizer>) -> Self {
+        let styles = categorizer
+            .as_ref()
+            .map(Categorizer::category_styles)
+            .unwrap_or_default();
+        Self {
+            categorizer: categorizer.map(Rc::new),
+            styles: Rc::new(styles),
+        }
+    }
+
+    /// Configured color of the colon separated category path, a color of the palette by index
+    /// if it has none
+    pub fn color(&self, path: &str, index: usize) -> String {
+        self.styles
+            .get(path)
+            .and_then(|style| style.color.clone())
+            .unwrap_or_else(|| String::from(COLORS[index % COLORS.len()]))
+    }
+
+    /// Configured icon of the colon separated category path
+    pub fn icon(&self, path: &str) -> Option<String> {
+        self.styles.get(path).and_then(|style| style.icon.clone())
+    }
+}
+
+/// Raw files uploaded to run the full parse and categorize pipeline in the browser
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Session {
+    /// Bank csv, OFX, and QIF files
+    pub transaction_files: Vec<UploadedFile>,
+    /// Transaction parser yaml
+    pub transaction_parser: Option<UploadedFile>,
+    /// Categorizer yaml
+    pub categorizer: Option<UploadedFile>,
+    /// Transaction overrides yaml of the transactions edited in the UI
+    pub overrides: Option<UploadedFile>,
+}
+
+/// Kind of file uploaded to a session
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileKind {
+    Transactions,
+    TransactionParser,
+    Categorizer,
+    Overrides,
+}
+
+impl FileKind {
+    /// Message id of the label of the file input
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Transactions => "file-transactions",
+            Self::TransactionParser => "file-transaction-parser",
+            Self::Categorizer => "file-categorizer",
+            Self::Overrides => "file-overrides",
+        }
+    }
+
+    /// File extensions accepted by the file input
+    pub fn accept(self) -> &'static str {
+        match self {
+            Self::Transactions => ".csv,.ofx,.qfx,.qif",
+            Self::TransactionParser | Self::Categorizer | Self::Overrides => ".yaml,.yml",
+        }
+    }
+
+    /// Kind of a dropped file, bank files by their extension and configs by their top level key,
+    /// `None` if it is neither
+    pub fn sniff(file: &UploadedFile) -> Option<Self> {
+        let extension = Path::new(&file.name)
+            .extension()?
+            .to_string_lossy()
+            .to_lowercase();
+        let has_extension = |kind: Self| {
+            kind.accept()
+                .split(',')
+                .any(|accepted| accepted.trim_start_matches('.') == extension)
+        };
+        if has_extension(Self::Transactions) {
+            return Some(Self::Transactions);
+        }
+        if !has_extension(Self::Categorizer) {
+            return None;
+        }
+        let config = serde_yaml::from_str::<serde_yaml::Mapping>(&file.contents).ok()?;
+        [
+            ("csv", Self::TransactionParser),
+            ("categories", Self::Categorizer),
+            ("overrides", Self::Overrides),
+        ]
+        .into_iter()
+        .find(|(key, _)| config.contains_key(key))
+        .map(|(_, kind)| kind)
+    }
+}
+
+impl Session {
+    /// Add transaction files replacing files of the same name, or replace a config with the first
+    /// file
+    pub fn upload(&mut self, kind: FileKind, files: Vec<UploadedFile>) {
+        match kind {
+            FileKind::Transactions => {
+                for file in files {
+                    match self
+                        .transaction_files
+                        .iter_mut()
+                        .find(|f| f.name == file.name)
+                    {
+                        Some(existing) => *existing = file,
+                        None => self.transaction_files.push(file),
+                    }
+                }
+            }
+            FileKind::TransactionParser => self.transaction_parser = files.into_iter().next(),
+            FileKind::Categorizer => self.categorizer = files.into_iter().next(),
+            FileKind::Overrides => self.overrides = files.into_iter().next(),
+        }
+    }
+
+    /// Remove the transaction file with the name
+    pub fn remove_transaction_file(&mut self, name: &str) {
+        self.transaction_files.retain(|f| f.name != name);
+    }
+
+    /// Check if any file has been uploaded
+    pub fn is_empty(&self) -> bool {
+        self.transaction_files.is_empty()
+            && self.transaction_parser.is_none()
+            && self.categorizer.is_none()
+            && self.overrides.is_none()
+    }
+
+    /// Check if every file the pipeline needs has been uploaded
+    pub fn is_complete(&self) -> bool {
+        !self.transaction_files.is_empty()
+            && !self.needs_transaction_parser()
+            && self.categorizer.is_some()
+    }
+
+    /// Check if a csv file was uploaded without a transaction parser, OFX and QIF files do not
+    /// need one
+    pub fn needs_transaction_parser(&self) -> bool {
+        self.transaction_parser.is_none()
+            && self
+                .transaction_files
+                .iter()
+                .any(|file| file.format() == ImportFormat::Csv)
+    }
+
+    /// Parse the transaction files, csv files with the transaction parser, merging transactions in
+    /// more than one file (ie overlapping downloads), the overrides are not applied
+    fn parsed_transactions(&self) -> Result<Vec<Transaction>, String> {
+        let transaction_parser = self
+            .transaction_parser
+            .as_ref()
+            .map(|parser| {
+                TransactionParser::from_yaml_buffer(&parser.contents)
+                    .map_err(|e| format!("failed to parse transaction parser {}: {e}", parser.name))
+            })
+            .transpose()?;
+        let batches = self
+            .transaction_files
+            .iter()
+            .map(|file| {
+                let path = Path::new(&file.name);
+                match file.format() {
+                    ImportFormat::Csv => transaction_parser
+                        .as_ref()
+                        .ok_or("missing transaction parser")?
+                        .parse_csv_buffer(path, &file.contents)
+                        .map_err(|e| format!("failed to parse transactions: {e}")),
+                    ImportFormat::Ofx => parse_ofx(path, &file.contents)
+                        .map_err(|e| format!("failed to parse {}: {e}", file.name)),
+                    ImportFormat::Qif => parse_qif(path, &file.contents)
+                        .map_err(|e| format!("failed to parse {}: {e}", file.name)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Transaction::merge(batches.iter().map(Vec::as_slice)))
+    }
+
+    /// Overrides parsed from the uploaded or edited yaml, no overrides if there is none
+    pub fn overrides(&self) -> Result<TransactionOverrides, String> {
+        self.overrides
+            .as_ref()
+            .map(|overrides| {
+                serde_yaml::from_str(&overrides.contents)
+                    .map_err(|e| format!("failed to parse overrides {}: {e}", overrides.name))
+            })
+            .unwrap_or_else(|| Ok(TransactionOverrides::default()))
+    }
+
+    /// Override the fields of a shown transaction with the edited fields, editing it again
+    /// replaces the earlier edit
+    pub fn edit_transaction(
+        &mut self,
+        transaction: &Transaction,
+        edited: &Transaction,
+    ) -> Result<(), String> {
+        let mut overrides = self.overrides()?;
+        overrides.set(&overrides.original(transaction), edited);
+        let name = self
+            .overrides
+            .as_ref()
+            .map_or_else(|| String::from("overrides.yaml"), |o| o.name.clone());
+        self.overrides = Some(UploadedFile {
+            name,
+            contents: serde_yaml::to_string(&overrides).map_err(|e| e.to_string())?,
+        });
+        Ok(())
+    }
+
+    /// Parsed transaction of a shown transaction, which differs if it was edited
+    pub fn original(&self, transaction: &Transaction) -> Transaction {
+        self.overrides()
+            .map(|overrides| overrides.original(transaction))
+            .unwrap_or_else(|_| transaction.clone())
+    }
+
+    /// Csv row the transaction was parsed from, `None` if it is not from an uploaded csv file
+    pub fn csv_row(&self, transaction: &Transaction) -> Option<CsvRow> {
+        let transaction = &self.original(transaction);
+        let source = transaction.source.as_ref()?;
+        let file = self
+            .transaction_files
+            .iter()
+            .find(|f| Path::new(&f.name) == source && f.format() == ImportFormat::Csv)?;
+        let parser = self.transaction_parser.as_ref()?;
+        TransactionParser::from_yaml_buffer(&parser.contents)
+            .ok()?
+            .parse_csv_rows(source, &file.contents)
+            .ok()?
+            .into_iter()
+            .find(|row| &row.transaction == transaction)
+    }
+
+    /// Categorizer parsed from the uploaded yaml
+    pub fn categorizer(&self) -> Result<Categorizer, String> {
+        let categorizer = self.categorizer.as_ref().ok_or("missing categorizer")?;
+        Categorizer::from_yaml_buffer(&categorizer.contents)
+            .map_err(|e| format!("failed to parse categorizer {}: {e}", categorizer.name))
+    }
+
+    /// Parse and categorize the transactions, `None` until every file is uploaded
+    pub fn categorize(&self) -> Option<Result<Categorization, String>> {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(self.parsed_transactions().and_then(|parsed| {
+            let overrides = self.overrides()?;
+            let transactions = overrides.apply(&parsed);
+            let categorizer = self.categorizer()?;
+            let mut warnings = overrides.warnings(&parsed);
+            warnings.extend(categorizer.warnings(&transactions));
+            let (categorized, uncategorized) = categorizer.categorize(&transactions);
+            Ok(Categorization {
+                categorized,
+                uncategorized: uncategorized.into_iter().cloned().collect(),
+                warnings,
+            })
+        }))
+    }
+}
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Zero};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter, EnumString, IntoEnumIterator};
+
+use crate::i18n::tr;
+
+/// Light or dark colors of the page
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl ThemeMode {
+    /// Background, text, surface (ie inputs), and border colors
+    fn colors(self) -> [&'static str; 4] {
+        match self {
+            Self::Light => ["#ffffff", "#1f2937", "#f3f4f6", "#d1d5db"],
+            Self::Dark => ["#111827", "#e5e7eb", "#1f2937", "#4b5563"],
+        }
+    }
+
+    /// Colors of positive and negative amounts readable on the background
+    fn amount_colors(self) -> [&'static str; 2] {
+        match self {
+            Self::Light => ["#15803d", "#b91c1c"],
+            Self::Dark => ["#4ade80", "#f87171"],
+        }
+    }
+}
+
+/// Colors of the UI, applied with CSS variables so they do not depend on the generated Tailwind
+/// classes
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    /// Color of buttons, links, and form controls
+    pub accent: String,
+    /// Color positive amounts green and negative amounts red
+    pub color_amounts: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::default(),
+            accent: String::from("#4e79a7"),
+            color_amounts: false,
+        }
+    }
+}
+
+impl Theme {
+    /// Stylesheet of the page background and the `.theme` root element
+    pub fn css(&self) -> String {
+        let [background, text, surface, border] = self.mode.colors();
+        let [positive, negative] = self.mode.amount_colors();
+        let accent = &self.accent;
+        format!(
+            "body {{ background: {background}; color: {text}; }}
+.theme {{ --background: {background}; --accent: {accent}; --positive: {positive}; \
+             --negative: {negative}; \
+             accent-color: {accent}; }}
+.theme button, .theme a {{ color: {accent}; }}
+.theme input, .theme select, .theme textarea {{ background: {surface}; color: {text}; \
+             border: 1px solid {border}; }}"
+        )
+    }
+
+    /// Inline style of an amount, empty unless amounts are colored
+    pub fn amount_style(&self, amount: &BigDecimal) -> &'static str {
+        if !self.color_amounts || amount.is_zero() {
+            ""
+        } else if amount > &BigDecimal::zero() {
+            "color: var(--positive)"
+        } else {
+            "color: var(--negative)"
+        }
+    }
+}
+
+/// Select of the mode, accent color picker, and toggle of amount coloring
+#[component]
+pub fn ThemeControls() -> Element {
+    let mut theme = use_context::<Signal<Theme>>();
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            label {
+                {tr("theme")}
+                " "
+                select {
+                    onchange: move |event| {
+                        if let Ok(mode) = ThemeMode::from_str(&event.value()) {
+                            theme.write().mode = mode;
+                        }
+                    },
+                    for mode in ThemeMode::iter() {
+                        option {
+                            key: "{mode.as_ref()}",
+                            value: mode.as_ref(),
+                            selected: theme.read().mode == mode,
+                            {tr(&format!("theme-{}", mode.as_ref()))}
+                        }
+                    }
+                }
+            }
+            label {
+                {tr("accent")}
+                " "
+                input {
+                    r#type: "color",
+                    value: "{theme.read().accent}",
+                    oninput: move |event| theme.write().accent = event.value()
+                }
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: theme.read().color_amounts,
+                    oninput: move |event| theme.write().color_amounts = event.value() == "true"
+                }
+                " "
+                {tr("color-amounts")}
+            }
+        }
+    )
+}
@@ -0,0 +1,57 @@
+use dioxus::prelude::*;
+use slotted_pig_lib::{
+    report::{UncategorizedPayee, UncategorizedPayees},
+    transaction::Transaction,
+    util::Formatter,
+};
+
+use crate::{i18n::tr_args, Amount, Transactions};
+
+/// Transactions no category matched grouped by payee, clicking a payee lists its transactions
+/// which can be dragged onto a category to add a rule
+#[component]
+pub fn Uncategorized(transactions: Vec<Transaction>) -> Element {
+    let payees = UncategorizedPayees::new(&transactions).payees;
+
+    rsx!(
+        div { class: "font-mono text-sm py-2",
+            h2 { class: "text-base",
+                {tr_args("uncategorized", [("transactions", transactions.len().into()), ("payees", payees.len().into())])}
+            }
+            ul { class: "list-disc pl-4",
+                for payee in payees {
+                    li { key: "{payee.payee}",
+                        Payee {
+                            transactions: transactions
+                                .iter()
+                                .filter(|t| payee.descriptions.contains(&t.description))
+                                .cloned()
+                                .collect::<Vec<_>>(),
+                            payee
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn Payee(payee: UncategorizedPayee, transactions: Vec<Transaction>) -> Element {
+    let formatter = use_context::<Signal<Formatter>>();
+    let mut hidden = use_signal(|| true);
+
+    rsx!(
+        div { class: "hover:cursor-pointer", onclick: move |_| *hidden.write() = !hidden(),
+            span { class: "px-1", "{payee.payee}" }
+            span { class: "px-1", "[{payee.count}]" }
+            Amount { amount: payee.total }
+            span { class: "text-xs px-1",
+                {tr_args("date-range", [("first", formatter.read().format_date(payee.first_date).into()), ("last", formatter.read().format_date(payee.last_date).into())])}
+            }
+        }
+        if !hidden() {
+            Transactions { transactions, draggable: true }
+        }
+    )
+}
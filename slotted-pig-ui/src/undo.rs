@@ -0,0 +1,107 @@
+use dioxus::prelude::*;
+
+use crate::{
+    i18n::tr,
+    session::{Categorization, Session},
+};
+
+/// Most edits kept to undo, the oldest are dropped first
+const HISTORY_LIMIT: usize = 50;
+
+/// Sessions before the rule, categorizer, and transaction edits of the UI so they can be undone
+/// and redone
+///
+/// Uploading or removing a file clears the history so an undo never drops an upload.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    undo: Vec<Session>,
+    redo: Vec<Session>,
+}
+
+impl History {
+    /// Record the session before an edit, clearing the edits that were undone
+    pub fn record(&mut self, before: Session) {
+        self.undo.push(before);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Session before the last edit, `current` can be redone
+    fn undo(&mut self, current: Session) -> Option<Session> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Session after the last undone edit, `current` can be undone again
+    fn redo(&mut self, current: Session) -> Option<Session> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
+
+/// Undo the last edit (or redo the last undone edit) and recategorize
+pub fn step(
+    mut history: Signal<History>,
+    mut session: Signal<Session>,
+    mut categorization_result: Signal<Result<Categorization, String>>,
+    redo: bool,
+) {
+    let current = session();
+    let restored = if redo {
+        history.write().redo(current)
+    } else {
+        history.write().undo(current)
+    };
+    let Some(restored) = restored else {
+        return;
+    };
+    session.set(restored);
+    if let Some(result) = session.read().categorize() {
+        categorization_result.set(result);
+    }
+}
+
+/// Undo and redo buttons of the edits, disabled when there is nothing to undo or redo
+#[component]
+pub fn UndoControls(
+    session: Signal<Session>,
+    categorization_result: Signal<Result<Categorization, String>>,
+) -> Element {
+    let history = use_context::<Signal<History>>();
+
+    rsx!(
+        div { class: "flex gap-2 font-mono text-sm py-2",
+            button {
+                class: "underline",
+                style: if !history.read().can_undo() { "opacity: 0.5" } else { "" },
+                disabled: !history.read().can_undo(),
+                onclick: move |_| step(history, session, categorization_result, false),
+                {tr("undo")}
+            }
+            button {
+                class: "underline",
+                style: if !history.read().can_redo() { "opacity: 0.5" } else { "" },
+                disabled: !history.read().can_redo(),
+                onclick: move |_| step(history, session, categorization_result, true),
+                {tr("redo")}
+            }
+        }
+    )
+}
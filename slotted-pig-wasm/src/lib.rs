@@ -0,0 +1,84 @@
+//! JavaScript API of the parse and categorize pipeline for web apps embedding the engine
+//!
+//! Every function takes and returns strings, configs as yaml and transactions and results as
+//! JSON, so callers need no bindings beyond `JSON.parse`.
+
+use std::path::Path;
+
+use displaydoc::Display;
+use serde::Serialize;
+use slotted_pig_lib::{
+    categorizer::{self, Categorized, Categorizer},
+    transaction::{self, Transaction, TransactionParser},
+};
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// File name csv configs are matched against when the caller does not pass one
+const DEFAULT_FILE_NAME: &str = "transactions.csv";
+
+#[derive(Error, Debug, Display)]
+pub enum Error {
+    /// transaction: {0}
+    Transaction(#[from] transaction::Error),
+    /// categorizer: {0}
+    Categorizer(#[from] categorizer::Error),
+    /// json: {0}
+    Json(#[from] serde_json::Error),
+}
+
+/// Categorized and uncategorized transactions returned by `categorize`
+#[derive(Serialize)]
+struct Categorization<'a> {
+    categorized: Vec<Categorized>,
+    uncategorized: Vec<&'a Transaction>,
+}
+
+/// Parse the transactions of a csv file with a transaction parser yaml, returning them as a JSON
+/// array
+///
+/// The csv config is chosen by matching the file name (`transactions.csv` if none is given)
+/// against the `filename_regex` of each config.
+#[wasm_bindgen(js_name = parseTransactions)]
+pub fn parse_transactions(
+    csv: &str,
+    parser_config: &str,
+    file_name: Option<String>,
+) -> Result<String, JsError> {
+    Ok(parse_transactions_json(
+        csv,
+        parser_config,
+        file_name.as_deref().unwrap_or(DEFAULT_FILE_NAME),
+    )?)
+}
+
+/// Categorize a JSON array of transactions (ie the output of `parseTransactions`) with a
+/// categorizer yaml, returning `{ categorized, uncategorized }` as JSON
+#[wasm_bindgen]
+pub fn categorize(transactions: &str, categorizer_yaml: &str) -> Result<String, JsError> {
+    Ok(categorize_json(transactions, categorizer_yaml)?)
+}
+
+pub fn parse_transactions_json(
+    csv: &str,
+    parser_config: &str,
+    file_name: &str,
+) -> Result<String, Error> {
+    let parser = TransactionParser::from_yaml_buffer(parser_config)?;
+    let transactions = parser.parse_csv_buffer(Path::new(file_name), csv)?;
+    Ok(serde_json::to_string(&transactions)?)
+}
+
+pub fn categorize_json(transactions: &str, categorizer_yaml: &str) -> Result<String, Error> {
+    let transactions = serde_json::from_str::<Vec<Transaction>>(transactions)?;
+    let categorizer = Categorizer::from_yaml_buffer(categorizer_yaml)?;
+    let (mut categorized, uncategorized) = categorizer.categorize(&transactions);
+    categorizer.round_totals(&mut categorized);
+    Ok(serde_json::to_string(&Categorization {
+        categorized: categorized.categorized,
+        uncategorized,
+    })?)
+}
@@ -0,0 +1,28 @@
+use insta::assert_json_snapshot;
+use serde_json::Value;
+
+use crate::{categorize_json, parse_transactions_json, Error, DEFAULT_FILE_NAME};
+
+const CSV: &str = include_str!("../../examples/transactions.csv");
+const TRANSACTION_PARSER: &str = include_str!("../../examples/transaction_parser.yaml");
+const CATEGORIZER: &str = include_str!("../../examples/categorizer.yaml");
+
+#[test]
+fn test_parse_transactions() -> Result<(), Error> {
+    let transactions = parse_transactions_json(CSV, TRANSACTION_PARSER, DEFAULT_FILE_NAME)?;
+    assert_json_snapshot!(serde_json::from_str::<Value>(&transactions)?);
+    Ok(())
+}
+
+#[test]
+fn test_categorize() -> Result<(), Error> {
+    let transactions = parse_transactions_json(CSV, TRANSACTION_PARSER, DEFAULT_FILE_NAME)?;
+    let categorization = categorize_json(&transactions, CATEGORIZER)?;
+    assert_json_snapshot!(serde_json::from_str::<Value>(&categorization)?);
+    Ok(())
+}
+
+#[test]
+fn test_parse_transactions_unmatched_file_name() {
+    assert!(parse_transactions_json(CSV, TRANSACTION_PARSER, "transactions.txt").is_err());
+}